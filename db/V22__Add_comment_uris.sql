@@ -0,0 +1,8 @@
+ALTER TABLE comments ADD COLUMN uri VARCHAR(2048) NULL;
+ALTER TABLE comments ADD COLUMN is_external BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE comments SET uri = '/feed/' || post_id || '/comments/' || comment_id;
+
+ALTER TABLE comments ALTER COLUMN uri SET NOT NULL;
+
+CREATE UNIQUE INDEX comments_uq_uri_idx ON comments(uri);
@@ -0,0 +1,17 @@
+CREATE TABLE "deliveries" (
+  "delivery_id" uuid NOT NULL,
+  "job_id" uuid REFERENCES jobs(job_id) ON DELETE SET NULL,
+  "created_at" timestamptz NOT NULL DEFAULT now(),
+  "updated_at" timestamptz NOT NULL DEFAULT now(),
+  "inbox_uri" varchar(2048) NOT NULL,
+  "actor_fediverse_uri" varchar(2048) NOT NULL,
+  "activity" jsonb NOT NULL,
+  "status" varchar(20) NOT NULL,
+  "attempts" int4 NOT NULL DEFAULT 0,
+  "next_attempt_at" timestamptz NOT NULL DEFAULT now(),
+  "last_error" text,
+  PRIMARY KEY ("delivery_id")
+);
+
+CREATE INDEX deliveries_status_next_attempt_at_idx ON deliveries(status, next_attempt_at);
+CREATE INDEX deliveries_job_id_idx ON deliveries(job_id);
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN ext_apub_shared_inbox_uri VARCHAR(2048) NULL;
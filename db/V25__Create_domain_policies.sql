@@ -0,0 +1,12 @@
+CREATE TABLE "domain_policies" (
+  "domain_policy_id" uuid NOT NULL,
+  "created_at" timestamptz NOT NULL DEFAULT now(),
+  "updated_at" timestamptz NOT NULL DEFAULT now(),
+  "created_by_id" uuid REFERENCES users(user_id) ON DELETE SET NULL,
+  "domain" varchar(253) NOT NULL,
+  "policy" varchar(20) NOT NULL,
+  "reason" text,
+  PRIMARY KEY ("domain_policy_id")
+);
+
+CREATE UNIQUE INDEX domain_policies_uq_domain_idx ON domain_policies(domain);
@@ -0,0 +1,12 @@
+CREATE TABLE blocks (
+  block_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  blocked_user_id uuid NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT blocks_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT blocks_blocked_user_id_fkey FOREIGN KEY (blocked_user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (block_id)
+);
+
+CREATE UNIQUE INDEX blocks_user_blocked_idx ON blocks(user_id, blocked_user_id);
+CREATE INDEX blocks_blocked_user_idx ON blocks(blocked_user_id);
@@ -0,0 +1,38 @@
+CREATE TABLE user_mutes (
+  user_mute_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  muted_user_id uuid NOT NULL,
+  boosts_only bool NOT NULL DEFAULT false,
+  expires_at timestamptz NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT user_mutes_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT user_mutes_muted_user_id_fkey FOREIGN KEY (muted_user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (user_mute_id)
+);
+
+CREATE UNIQUE INDEX user_mutes_user_muted_idx ON user_mutes(user_id, muted_user_id);
+
+CREATE TABLE orbit_mutes (
+  orbit_mute_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  orbit_id uuid NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT orbit_mutes_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT orbit_mutes_orbit_id_fkey FOREIGN KEY (orbit_id) REFERENCES orbits(orbit_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (orbit_mute_id)
+);
+
+CREATE UNIQUE INDEX orbit_mutes_user_orbit_idx ON orbit_mutes(user_id, orbit_id);
+
+CREATE TABLE keyword_filters (
+  keyword_filter_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  phrase varchar(256) NOT NULL,
+  is_regex bool NOT NULL DEFAULT false,
+  expires_at timestamptz NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT keyword_filters_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (keyword_filter_id)
+);
+
+CREATE INDEX keyword_filters_user_idx ON keyword_filters(user_id);
@@ -0,0 +1,29 @@
+CREATE TABLE reports (
+  report_id uuid NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  reporter_id uuid NULL,
+  target_user_id uuid NULL,
+  post_id uuid NULL,
+  comment_id uuid NULL,
+  orbit_id uuid NULL,
+  moderator_orbit_id uuid NULL,
+  reason text NOT NULL,
+  status varchar(20) NOT NULL DEFAULT 'open',
+  resolved_by_id uuid NULL,
+  resolution_note text NULL,
+  is_external bool NOT NULL DEFAULT false,
+  forwarded bool NOT NULL DEFAULT false,
+  uri varchar(2048) NULL,
+  CONSTRAINT reports_reporter_id_fkey FOREIGN KEY (reporter_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  CONSTRAINT reports_target_user_id_fkey FOREIGN KEY (target_user_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  CONSTRAINT reports_post_id_fkey FOREIGN KEY (post_id) REFERENCES posts(post_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  CONSTRAINT reports_comment_id_fkey FOREIGN KEY (comment_id) REFERENCES comments(comment_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  CONSTRAINT reports_orbit_id_fkey FOREIGN KEY (orbit_id) REFERENCES orbits(orbit_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  CONSTRAINT reports_moderator_orbit_id_fkey FOREIGN KEY (moderator_orbit_id) REFERENCES orbits(orbit_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  CONSTRAINT reports_resolved_by_id_fkey FOREIGN KEY (resolved_by_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  PRIMARY KEY (report_id)
+);
+
+CREATE INDEX reports_status_idx ON reports(status, created_at);
+CREATE INDEX reports_moderator_orbit_idx ON reports(moderator_orbit_id, status);
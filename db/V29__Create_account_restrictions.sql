@@ -0,0 +1,15 @@
+CREATE TABLE account_restrictions (
+  account_restriction_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  created_by_id uuid NULL,
+  restriction varchar(20) NOT NULL,
+  reason text NOT NULL,
+  expires_at timestamptz NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT account_restrictions_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT account_restrictions_created_by_id_fkey FOREIGN KEY (created_by_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  PRIMARY KEY (account_restriction_id)
+);
+
+CREATE UNIQUE INDEX account_restrictions_user_restriction_idx ON account_restrictions(user_id, restriction);
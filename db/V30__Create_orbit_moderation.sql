@@ -0,0 +1,37 @@
+CREATE TABLE orbit_bans (
+  orbit_ban_id uuid NOT NULL,
+  orbit_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  created_by_id uuid NULL,
+  reason text NULL,
+  expires_at timestamptz NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT orbit_bans_orbit_id_fkey FOREIGN KEY (orbit_id) REFERENCES orbits(orbit_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT orbit_bans_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT orbit_bans_created_by_id_fkey FOREIGN KEY (created_by_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  PRIMARY KEY (orbit_ban_id)
+);
+
+CREATE UNIQUE INDEX orbit_bans_orbit_user_idx ON orbit_bans(orbit_id, user_id);
+
+-- NOTE: Removed posts and comments are gone by the time anyone reads the log, so they aren't foreign keys
+CREATE TABLE orbit_modlog (
+  orbit_modlog_id uuid NOT NULL,
+  orbit_id uuid NOT NULL,
+  moderator_id uuid NULL,
+  action varchar(20) NOT NULL,
+  target_user_id uuid NULL,
+  post_id uuid NULL,
+  comment_id uuid NULL,
+  reason text NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT orbit_modlog_orbit_id_fkey FOREIGN KEY (orbit_id) REFERENCES orbits(orbit_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT orbit_modlog_moderator_id_fkey FOREIGN KEY (moderator_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  CONSTRAINT orbit_modlog_target_user_id_fkey FOREIGN KEY (target_user_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  PRIMARY KEY (orbit_modlog_id)
+);
+
+CREATE INDEX orbit_modlog_orbit_id_idx ON orbit_modlog(orbit_id, created_at DESC);
+
+ALTER TABLE posts ADD COLUMN is_locked bool NOT NULL DEFAULT false;
+ALTER TABLE posts ADD COLUMN pinned_at timestamptz NULL;
@@ -0,0 +1,29 @@
+ALTER TABLE orbits ADD COLUMN join_policy varchar(20) NOT NULL DEFAULT 'open';
+
+-- NOTE: activity holds the Follow/Join sent by a remote user, so it can be embedded in the eventual Accept/Reject
+CREATE TABLE orbit_join_requests (
+  orbit_join_request_id uuid NOT NULL,
+  orbit_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  activity text NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT orbit_join_requests_orbit_id_fkey FOREIGN KEY (orbit_id) REFERENCES orbits(orbit_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT orbit_join_requests_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (orbit_join_request_id)
+);
+
+CREATE UNIQUE INDEX orbit_join_requests_orbit_user_idx ON orbit_join_requests(orbit_id, user_id);
+
+CREATE TABLE orbit_invites (
+  orbit_invite_id uuid NOT NULL,
+  orbit_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  created_by_id uuid NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT orbit_invites_orbit_id_fkey FOREIGN KEY (orbit_id) REFERENCES orbits(orbit_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT orbit_invites_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT orbit_invites_created_by_id_fkey FOREIGN KEY (created_by_id) REFERENCES users(user_id) ON DELETE SET NULL ON UPDATE CASCADE,
+  PRIMARY KEY (orbit_invite_id)
+);
+
+CREATE UNIQUE INDEX orbit_invites_orbit_user_idx ON orbit_invites(orbit_id, user_id);
@@ -0,0 +1,7 @@
+ALTER TABLE users ADD COLUMN manually_approves_followers boolean NOT NULL DEFAULT false;
+
+-- NOTE: activity holds the Follow sent by a remote user, so it can be embedded in the eventual Accept/Reject
+ALTER TABLE followers ADD COLUMN is_pending boolean NOT NULL DEFAULT false;
+ALTER TABLE followers ADD COLUMN activity text NULL;
+
+CREATE INDEX followers_following_user_pending_idx ON followers(following_user_id) WHERE is_pending;
@@ -0,0 +1,31 @@
+-- NOTE: Notifications mirror the shape of events, but are addressed to the single local user being interacted with
+-- rather than fanned out to timelines
+CREATE TABLE notifications (
+  notification_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  source_user_id uuid NOT NULL,
+  notification_type varchar(32) NOT NULL,
+  post_id uuid NULL,
+  comment_id uuid NULL,
+  orbit_id uuid NULL,
+  read_at timestamptz NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT notifications_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT notifications_source_user_id_fkey FOREIGN KEY (source_user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT notifications_post_id_fkey FOREIGN KEY (post_id) REFERENCES posts(post_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT notifications_comment_id_fkey FOREIGN KEY (comment_id) REFERENCES comments(comment_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT notifications_orbit_id_fkey FOREIGN KEY (orbit_id) REFERENCES orbits(orbit_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (notification_id)
+);
+
+CREATE INDEX notifications_user_created_idx ON notifications(user_id, created_at DESC, notification_id DESC);
+
+-- NOTE: Repeating an interaction (e.g. liking, unliking and liking again) shouldn't notify the user twice
+CREATE UNIQUE INDEX notifications_uq_notification_idx ON notifications(
+  user_id,
+  source_user_id,
+  notification_type,
+  COALESCE(post_id, '00000000-0000-0000-0000-000000000000'),
+  COALESCE(comment_id, '00000000-0000-0000-0000-000000000000'),
+  COALESCE(orbit_id, '00000000-0000-0000-0000-000000000000')
+);
@@ -0,0 +1,12 @@
+-- NOTE: Mentions are recorded for both local and remote users so that outgoing posts can tag and address everyone they
+-- name, while only local users are ever notified
+CREATE TABLE post_mentions (
+  post_id uuid NOT NULL,
+  user_id uuid NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT post_mentions_post_id_fkey FOREIGN KEY (post_id) REFERENCES posts(post_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT post_mentions_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (post_id, user_id)
+);
+
+CREATE INDEX post_mentions_user_id_idx ON post_mentions(user_id);
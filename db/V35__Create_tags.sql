@@ -0,0 +1,19 @@
+-- NOTE: Tag names are stored normalized to lowercase so that #Rust and #rust share a timeline
+CREATE TABLE tags (
+  tag_id uuid NOT NULL,
+  name varchar(128) NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT tags_name_key UNIQUE (name),
+  PRIMARY KEY (tag_id)
+);
+
+CREATE TABLE post_tags (
+  post_id uuid NOT NULL,
+  tag_id uuid NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT post_tags_post_id_fkey FOREIGN KEY (post_id) REFERENCES posts(post_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT post_tags_tag_id_fkey FOREIGN KEY (tag_id) REFERENCES tags(tag_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  PRIMARY KEY (post_id, tag_id)
+);
+
+CREATE INDEX post_tags_tag_id_idx ON post_tags(tag_id);
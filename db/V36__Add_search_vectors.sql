@@ -0,0 +1,22 @@
+-- NOTE: Posts aren't tagged with a language, so the simple configuration is used to avoid stemming with the wrong
+-- dictionary. Titles, handles and names are weighted above body text so they rank first.
+ALTER TABLE posts ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', COALESCE(title, '')), 'A') || setweight(to_tsvector('simple', content_md), 'B')
+) STORED;
+
+ALTER TABLE comments ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+  to_tsvector('simple', content_md)
+) STORED;
+
+ALTER TABLE users ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', handle), 'A') || setweight(to_tsvector('simple', COALESCE(intro_md, '')), 'B')
+) STORED;
+
+ALTER TABLE orbits ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+  setweight(to_tsvector('simple', name), 'A') || setweight(to_tsvector('simple', description_md), 'B')
+) STORED;
+
+CREATE INDEX posts_search_vector_idx ON posts USING GIN (search_vector);
+CREATE INDEX comments_search_vector_idx ON comments USING GIN (search_vector);
+CREATE INDEX users_search_vector_idx ON users USING GIN (search_vector);
+CREATE INDEX orbits_search_vector_idx ON orbits USING GIN (search_vector);
@@ -0,0 +1,4 @@
+-- NOTE: Apps registered before scopes existed were given full access, so they keep it. Apps can now register several
+-- redirect URIs, separated by whitespace.
+ALTER TABLE apps ADD COLUMN scopes VARCHAR(255) NOT NULL DEFAULT 'read write follow push admin:read admin:write';
+ALTER TABLE apps ALTER COLUMN redirect_uri TYPE VARCHAR(4096);
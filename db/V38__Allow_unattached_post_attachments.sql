@@ -0,0 +1,5 @@
+-- NOTE: Mastodon clients upload media before the post it belongs to exists, so attachments are created without a post
+-- and are attached to one once it's made
+ALTER TABLE post_attachments ALTER COLUMN post_id DROP NOT NULL;
+
+CREATE INDEX post_attachments_unattached_idx ON post_attachments(user_id) WHERE post_id IS NULL;
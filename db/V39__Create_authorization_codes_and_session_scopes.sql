@@ -0,0 +1,15 @@
+CREATE TABLE authorization_codes (
+  "code" VARCHAR(64) NOT NULL,
+  "user_id" uuid NOT NULL,
+  "app_id" uuid NOT NULL,
+  "redirect_uri" VARCHAR(4096) NOT NULL,
+  "scopes" VARCHAR(255) NOT NULL,
+  "created_at" timestamptz NOT NULL DEFAULT now(),
+  "expires_at" timestamptz NOT NULL,
+  PRIMARY KEY ("code"),
+  CONSTRAINT fk_authorization_code_app FOREIGN KEY(app_id) REFERENCES apps(app_id) ON DELETE CASCADE ON UPDATE CASCADE,
+  CONSTRAINT fk_authorization_code_user FOREIGN KEY(user_id) REFERENCES users(user_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- NOTE: Sessions created before scopes were enforced had full access, so they keep it until they expire
+ALTER TABLE sessions ADD COLUMN scopes VARCHAR(255) NOT NULL DEFAULT 'read write follow push admin:read admin:write';
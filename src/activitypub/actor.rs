@@ -32,4 +32,6 @@ pub struct ActorProps {
   pub sign_client_key: Option<String>,
   #[serde(rename = "sharedInbox", skip_serializing_if = "Option::is_none")]
   pub shared_inbox: Option<Reference<Object>>,
+  #[serde(rename = "manuallyApprovesFollowers", skip_serializing_if = "Option::is_none")]
+  pub manually_approves_followers: Option<bool>,
 }
@@ -1,6 +1,8 @@
+use uuid::Uuid;
+
 use crate::{
   helpers::api::relative_to_absolute_uri,
-  model::{event_type::EventType, post_event::PostEvent},
+  model::{event_type::EventType, page_cursor::PageCursor, post_event::PostEvent},
   settings::SETTINGS,
 };
 
@@ -14,41 +16,70 @@ use super::{
   reference::Reference,
 };
 
-pub fn create_activitypub_ordered_collection_page_feed(
+/// Links to the numbered pages either side of a collection page, as `(prev, next)`
+pub fn numbered_page_links(
   base_uri: &str,
-  page: i32,
-  page_size: i32,
-  total_items: i32,
-  posts: Vec<PostEvent>,
-) -> ActivityPubDocument {
+  page: i64,
+  page_size: i64,
+  total_items: i64,
+) -> (Option<String>, Option<String>) {
   let prev = match page {
     0 => None,
-    _ => Some(Reference::Remote::<Object>(format!(
-      "{}?page={}&page_size={}",
-      base_uri,
-      page - 1,
-      page_size
-    ))),
+    _ => Some(format!("{}?page={}&page_size={}", base_uri, page - 1, page_size)),
   };
 
   let next = match (page + 1) * page_size > total_items {
     true => None,
-    false => Some(Reference::Remote::<Object>(format!(
-      "{}?page={}&page_size={}",
-      base_uri,
-      page + 1,
-      page_size
-    ))),
+    false => Some(format!("{}?page={}&page_size={}", base_uri, page + 1, page_size)),
+  };
+
+  (prev, next)
+}
+
+/// Links to the pages either side of a collection page walked by cursor, as `(prev, next)`. `oldest_first`
+/// collections page forward with `min_id` rather than `max_id`.
+pub fn cursor_page_links<T>(
+  base_uri: &str,
+  cursor: &PageCursor,
+  page_size: i64,
+  items: &[T],
+  id: impl Fn(&T) -> Uuid,
+  oldest_first: bool,
+) -> (Option<String>, Option<String>) {
+  let (prev_param, next_param) = match oldest_first {
+    true => ("max_id", "min_id"),
+    false => ("min_id", "max_id"),
+  };
+
+  // The first page has nothing before it
+  let prev = match cursor.is_empty() {
+    true => None,
+    false => items
+      .first()
+      .map(|item| format!("{}?{}={}&page_size={}", base_uri, prev_param, id(item), page_size)),
   };
+  let next = items
+    .last()
+    .map(|item| format!("{}?{}={}&page_size={}", base_uri, next_param, id(item), page_size));
+
+  (prev, next)
+}
 
+pub fn create_activitypub_ordered_collection_page_feed(
+  base_uri: &str,
+  prev: Option<String>,
+  next: Option<String>,
+  posts: Vec<PostEvent>,
+) -> ActivityPubDocument {
   let posts = posts
     .into_iter()
     .filter_map(
       |p| match p.to_object(&format!("{}/user/{}", SETTINGS.server.api_fqdn, p.user_id)) {
         Some(post_obj) => {
-          let activity = match p.event_type {
-            EventType::Boost => ActivityType::Announce,
-            _ => ActivityType::Create,
+          // Boosts are announced by the user who boosted the post rather than its author
+          let (activity, actor_uri) = match p.event_type {
+            EventType::Boost => (ActivityType::Announce, &p.event_user_fediverse_uri),
+            _ => (ActivityType::Create, &p.user_fediverse_uri),
           };
 
           let id = relative_to_absolute_uri(&p.uri);
@@ -61,7 +92,7 @@ pub fn create_activitypub_ordered_collection_page_feed(
             .published(post_obj.published)
             .activity(Some(
               ActivityProps::builder()
-                .actor(Some(Reference::Remote(relative_to_absolute_uri(&p.user_fediverse_uri))))
+                .actor(Some(Reference::Remote(relative_to_absolute_uri(actor_uri))))
                 .object(Some(Reference::Embedded(Box::new(post_obj))))
                 .build(),
             ))
@@ -79,8 +110,8 @@ pub fn create_activitypub_ordered_collection_page_feed(
     .collection_page(Some(
       CollectionPageProps::builder()
         .part_of(Some(Reference::Remote(base_uri.to_string())))
-        .prev(prev)
-        .next(next)
+        .prev(prev.map(Reference::Remote))
+        .next(next.map(Reference::Remote))
         .build(),
     ))
     .collection(Some(
@@ -95,32 +126,11 @@ pub fn create_activitypub_ordered_collection_page_feed(
 
 pub fn create_activitypub_ordered_collection_page_specific_feed(
   base_uri: &str,
-  page: i32,
-  page_size: i32,
-  total_items: i32,
+  prev: Option<String>,
+  next: Option<String>,
   posts: Vec<PostEvent>,
   activity: ActivityType,
 ) -> ActivityPubDocument {
-  let prev = match page {
-    0 => None,
-    _ => Some(Reference::Remote::<Object>(format!(
-      "{}?page={}&page_size={}",
-      base_uri,
-      page - 1,
-      page_size
-    ))),
-  };
-
-  let next = match (page + 1) * page_size > total_items {
-    true => None,
-    false => Some(Reference::Remote::<Object>(format!(
-      "{}?page={}&page_size={}",
-      base_uri,
-      page + 1,
-      page_size
-    ))),
-  };
-
   let posts = posts
     .into_iter()
     .filter_map(
@@ -152,8 +162,8 @@ pub fn create_activitypub_ordered_collection_page_specific_feed(
     .collection_page(Some(
       CollectionPageProps::builder()
         .part_of(Some(Reference::Remote(base_uri.to_string())))
-        .prev(prev)
-        .next(next)
+        .prev(prev.map(Reference::Remote))
+        .next(next.map(Reference::Remote))
         .build(),
     ))
     .collection(Some(
@@ -168,32 +178,11 @@ pub fn create_activitypub_ordered_collection_page_specific_feed(
 
 pub fn create_activitypub_ordered_collection_page<T: ActivityConvertible>(
   base_uri: &str,
-  page: i32,
-  page_size: i32,
-  total_items: i32,
+  prev: Option<String>,
+  next: Option<String>,
   entities: Vec<T>,
   actor: Option<String>,
 ) -> ActivityPubDocument {
-  let prev = match page {
-    0 => None,
-    _ => Some(Reference::Remote::<Object>(format!(
-      "{}?page={}&page_size={}",
-      base_uri,
-      page - 1,
-      page_size
-    ))),
-  };
-
-  let next = match (page + 1) * page_size > total_items {
-    true => None,
-    false => Some(Reference::Remote::<Object>(format!(
-      "{}?page={}&page_size={}",
-      base_uri,
-      page + 1,
-      page_size
-    ))),
-  };
-
   let actor_ref = &actor.unwrap_or_default();
 
   let entities = entities
@@ -210,8 +199,8 @@ pub fn create_activitypub_ordered_collection_page<T: ActivityConvertible>(
     .collection_page(Some(
       CollectionPageProps::builder()
         .part_of(Some(Reference::Remote(base_uri.to_string())))
-        .prev(prev)
-        .next(next)
+        .prev(prev.map(Reference::Remote))
+        .next(next.map(Reference::Remote))
         .build(),
     ))
     .collection(Some(
@@ -223,3 +212,56 @@ pub fn create_activitypub_ordered_collection_page<T: ActivityConvertible>(
 
   ActivityPubDocument::new(obj)
 }
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use crate::{activitypub::helpers::cursor_page_links, model::page_cursor::PageCursor};
+
+  const BASE_URI: &str = "https://example.com/api/user/a/outbox";
+
+  #[test]
+  fn test_cursor_page_links_first_page_has_no_prev() {
+    let ids = [Uuid::new_v4(), Uuid::new_v4()];
+    let cursor = PageCursor {
+      max_id: None,
+      since_id: None,
+      min_id: None,
+    };
+
+    assert_eq!(
+      cursor_page_links(BASE_URI, &cursor, 20, &ids, |id| *id, false),
+      (None, Some(format!("{}?max_id={}&page_size=20", BASE_URI, ids[1])))
+    );
+  }
+
+  #[test]
+  fn test_cursor_page_links() {
+    let ids = [Uuid::new_v4(), Uuid::new_v4()];
+    let cursor = PageCursor {
+      max_id: Some(Uuid::new_v4()),
+      since_id: None,
+      min_id: None,
+    };
+
+    assert_eq!(
+      cursor_page_links(BASE_URI, &cursor, 20, &ids, |id| *id, false),
+      (
+        Some(format!("{}?min_id={}&page_size=20", BASE_URI, ids[0])),
+        Some(format!("{}?max_id={}&page_size=20", BASE_URI, ids[1]))
+      )
+    );
+    assert_eq!(
+      cursor_page_links(BASE_URI, &cursor, 20, &ids, |id| *id, true),
+      (
+        Some(format!("{}?max_id={}&page_size=20", BASE_URI, ids[0])),
+        Some(format!("{}?min_id={}&page_size=20", BASE_URI, ids[1]))
+      )
+    );
+    assert_eq!(
+      cursor_page_links(BASE_URI, &cursor, 20, &[] as &[Uuid], |id| *id, false),
+      (None, None)
+    );
+  }
+}
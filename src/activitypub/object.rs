@@ -42,6 +42,7 @@ pub enum ObjectType {
   Person,
   Service,
   Mention,
+  Hashtag,
   OrderedCollection,
   OrderedCollectionPage,
 }
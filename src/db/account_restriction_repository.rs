@@ -0,0 +1,114 @@
+use super::FromRow;
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::account_restriction::{AccountRestriction, AccountRestrictionType},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait AccountRestrictionRepo {
+  /// Fetches the active restrictions, optionally only those on a single account
+  async fn fetch_restrictions(&self, user_id: &Option<Uuid>) -> Result<Vec<AccountRestriction>, LogicErr>;
+  async fn user_has_restriction(&self, user_id: &Uuid, restriction: &AccountRestrictionType) -> Result<bool, LogicErr>;
+  /// Places a restriction on an account, replacing the reason and expiry if it already had one of the same kind
+  async fn upsert_restriction(
+    &self,
+    user_id: &Uuid,
+    restriction: &AccountRestrictionType,
+    reason: &str,
+    expires_at: &Option<DateTime<Utc>>,
+    created_by_id: &Uuid,
+  ) -> Result<(), LogicErr>;
+  async fn delete_restriction(&self, user_id: &Uuid, restriction: &AccountRestrictionType) -> Result<(), LogicErr>;
+}
+
+pub type AccountRestrictionPool = Arc<dyn AccountRestrictionRepo + Send + Sync>;
+
+pub struct DbAccountRestrictionRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl AccountRestrictionRepo for DbAccountRestrictionRepo {
+  async fn fetch_restrictions(&self, user_id: &Option<Uuid>) -> Result<Vec<AccountRestriction>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT ar.*, u.fediverse_id AS user_fediverse_id FROM account_restrictions ar
+        INNER JOIN users u ON u.user_id = ar.user_id
+        WHERE ($1::uuid IS NULL OR ar.user_id = $1)
+        AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+        ORDER BY ar.created_at DESC"#,
+        &[&user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(AccountRestriction::from_row).collect())
+  }
+
+  async fn user_has_restriction(&self, user_id: &Uuid, restriction: &AccountRestrictionType) -> Result<bool, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        r#"SELECT COUNT(*) > 0 FROM account_restrictions WHERE user_id = $1 AND restriction = $2
+        AND (expires_at IS NULL OR expires_at > NOW())"#,
+        &[&user_id, &restriction.to_string()],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn upsert_restriction(
+    &self,
+    user_id: &Uuid,
+    restriction: &AccountRestrictionType,
+    reason: &str,
+    expires_at: &Option<DateTime<Utc>>,
+    created_by_id: &Uuid,
+  ) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      r#"INSERT INTO account_restrictions (account_restriction_id, user_id, created_by_id, restriction, reason, expires_at)
+      VALUES ($1, $2, $3, $4, $5, $6)
+      ON CONFLICT (user_id, restriction) DO UPDATE SET created_by_id = $3, reason = $5, expires_at = $6,
+      updated_at = NOW()"#,
+      &[
+        &Uuid::new_v4(),
+        &user_id,
+        &created_by_id,
+        &restriction.to_string(),
+        &reason,
+        &expires_at,
+      ],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn delete_restriction(&self, user_id: &Uuid, restriction: &AccountRestrictionType) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM account_restrictions WHERE user_id = $1 AND restriction = $2",
+      &[&user_id, &restriction.to_string()],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+}
@@ -36,8 +36,8 @@ impl AppRepo for DbAppRepo {
   async fn create(&self, app: &App) -> Result<(), LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     db.execute(
-      r#"INSERT INTO apps (app_id, name, description, owner_name, owner_uri, redirect_uri, client_id, client_secret)
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+      r#"INSERT INTO apps (app_id, name, description, owner_name, owner_uri, redirect_uri, client_id, client_secret, scopes)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
       &[
         &app.app_id,
         &app.name,
@@ -47,6 +47,7 @@ impl AppRepo for DbAppRepo {
         &app.redirect_uri,
         &app.client_id,
         &app.client_secret,
+        &app.scopes,
       ],
     )
     .await
@@ -0,0 +1,62 @@
+use crate::{helpers::api::map_db_err, logic::LogicErr, model::authorization_code::AuthorizationCode};
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use deadpool_postgres::Pool;
+#[cfg(test)]
+use mockall::automock;
+
+use super::FromRow;
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait AuthorizationCodeRepo {
+  async fn create(&self, code: &AuthorizationCode) -> Result<(), LogicErr>;
+  /// Removes the code and returns it, so that it can only ever be redeemed once
+  async fn take(&self, code: &str) -> Result<Option<AuthorizationCode>, LogicErr>;
+}
+
+pub type AuthorizationCodePool = Arc<dyn AuthorizationCodeRepo + Send + Sync>;
+
+pub struct DbAuthorizationCodeRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl AuthorizationCodeRepo for DbAuthorizationCodeRepo {
+  async fn create(&self, code: &AuthorizationCode) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+
+    // Codes that were never redeemed are cleaned up as new ones are issued
+    db.execute("DELETE FROM authorization_codes WHERE expires_at < NOW()", &[])
+      .await
+      .map_err(map_db_err)?;
+
+    db.execute(
+      r#"INSERT INTO authorization_codes (code, user_id, app_id, redirect_uri, scopes, created_at, expires_at)
+    VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+      &[
+        &code.code,
+        &code.user_id,
+        &code.app_id,
+        &code.redirect_uri,
+        &code.scopes,
+        &code.created_at,
+        &code.expires_at,
+      ],
+    )
+    .await
+    .map_err(map_db_err)?;
+    Ok(())
+  }
+
+  async fn take(&self, code: &str) -> Result<Option<AuthorizationCode>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt("DELETE FROM authorization_codes WHERE code = $1 RETURNING *", &[&code])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.and_then(AuthorizationCode::from_row))
+  }
+}
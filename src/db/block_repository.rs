@@ -0,0 +1,79 @@
+use crate::{helpers::api::map_db_err, logic::LogicErr};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait BlockRepo {
+  async fn create_block(&self, user_id: &Uuid, blocked_user_id: &Uuid) -> Result<Uuid, LogicErr>;
+  async fn delete_block(&self, user_id: &Uuid, blocked_user_id: &Uuid) -> Result<(), LogicErr>;
+  async fn block_exists_between(&self, user_id: &Uuid, other_user_id: &Uuid) -> bool;
+}
+
+pub type BlockPool = Arc<dyn BlockRepo + Send + Sync>;
+
+pub struct DbBlockRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl BlockRepo for DbBlockRepo {
+  async fn create_block(&self, user_id: &Uuid, blocked_user_id: &Uuid) -> Result<Uuid, LogicErr> {
+    let block_id = Uuid::new_v4();
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "INSERT INTO blocks (block_id, user_id, blocked_user_id) VALUES ($1, $2, $3) \
+         ON CONFLICT (user_id, blocked_user_id) DO UPDATE SET created_at = blocks.created_at \
+         RETURNING block_id",
+        &[&block_id, &user_id, &blocked_user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn delete_block(&self, user_id: &Uuid, blocked_user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM blocks WHERE user_id = $1 AND blocked_user_id = $2",
+      &[&user_id, &blocked_user_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  /// Fetches a boolean indicator of if either user has blocked the other
+  async fn block_exists_between(&self, user_id: &Uuid, other_user_id: &Uuid) -> bool {
+    let db = match self.db.get().await.map_err(map_db_err) {
+      Ok(db) => db,
+      Err(_) => return false,
+    };
+
+    let row = match db
+      .query_one(
+        r#"SELECT count(*) >= 1 AS blocked FROM blocks
+        WHERE (user_id = $1 AND blocked_user_id = $2)
+        OR (user_id = $2 AND blocked_user_id = $1)"#,
+        &[&user_id, &other_user_id],
+      )
+      .await
+      .map_err(map_db_err)
+    {
+      Ok(row) => row,
+      Err(_) => return false,
+    };
+
+    row.get(0)
+  }
+}
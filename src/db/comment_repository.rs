@@ -1,7 +1,12 @@
-use crate::{helpers::api::map_db_err, logic::LogicErr, model::comment_pub::CommentPub};
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::{comment_pub::CommentPub, page_cursor::PageCursor},
+};
 
 use super::FromRow;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use deadpool_postgres::Pool;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -12,10 +17,12 @@ use mockall::automock;
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait CommentRepo {
+  /// Fetches a post's comments oldest first, starting after `cursor` when one is given
   async fn fetch_comments(
     &self,
     post_id: &Uuid,
     own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<CommentPub>, LogicErr>;
@@ -26,12 +33,24 @@ pub trait CommentRepo {
     content_md: &str,
     content_html: &str,
   ) -> Result<Uuid, LogicErr>;
+  async fn create_external_comment(
+    &self,
+    user_id: &Uuid,
+    post_id: &Uuid,
+    uri: &str,
+    content_md: &str,
+    content_html: &str,
+    created_at: &DateTime<Utc>,
+  ) -> Result<Uuid, LogicErr>;
   async fn delete_comment(&self, user_id: &Uuid, post_id: &Uuid, comment_id: &Uuid) -> Result<(), LogicErr>;
+  async fn delete_comment_from_uri(&self, uri: &str, user_id: &Uuid) -> Result<(), LogicErr>;
   async fn fetch_comments_count(&self, post_id: &Uuid, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr>;
   async fn create_comment_like(&self, user_id: &Uuid, comment_id: &Uuid, post_id: &Uuid) -> Result<(), LogicErr>;
   async fn delete_comment_like(&self, user_id: &Uuid, comment_id: &Uuid, post_id: &Uuid) -> Result<(), LogicErr>;
   async fn fetch_comment_count(&self) -> i64;
   async fn fetch_comment(&self, post_id: &Uuid, comment_id: &Uuid, own_user_id: &Option<Uuid>) -> Option<CommentPub>;
+  async fn fetch_comment_from_uri(&self, uri: &str) -> Option<CommentPub>;
+  async fn fetch_comment_post_id(&self, comment_id: &Uuid) -> Option<Uuid>;
 }
 
 pub type CommentPool = Arc<dyn CommentRepo + Send + Sync>;
@@ -46,6 +65,7 @@ impl CommentRepo for DbCommentRepo {
     &self,
     post_id: &Uuid,
     own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<CommentPub>, LogicErr> {
@@ -53,12 +73,25 @@ impl CommentRepo for DbCommentRepo {
     let rows = db
       .query(
         include_str!("./sql/fetch_post_comments.sql"),
-        &[&own_user_id, &post_id, &limit, &skip],
+        &[
+          &own_user_id,
+          &post_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_newest_first(),
+        ],
       )
       .await
       .map_err(map_db_err)?;
 
-    Ok(rows.into_iter().flat_map(CommentPub::from_row).collect())
+    let mut comments: Vec<CommentPub> = rows.into_iter().flat_map(CommentPub::from_row).collect();
+    if cursor.walks_newest_first() {
+      comments.reverse();
+    }
+
+    Ok(comments)
   }
 
   async fn create_comment(
@@ -69,15 +102,46 @@ impl CommentRepo for DbCommentRepo {
     content_html: &str,
   ) -> Result<Uuid, LogicErr> {
     let comment_id = Uuid::new_v4();
+    let uri = format!("/feed/{}/comments/{}", post_id, comment_id);
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db.query_one("INSERT INTO comments (comment_id, user_id, post_id, content_md, content_html, uri) VALUES ($1, $2, $3, $4, $5, $6) RETURNING comment_id",
+      &[
+        &comment_id,
+        &user_id,
+        &post_id,
+        &content_md,
+        &content_html,
+        &uri,
+      ],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn create_external_comment(
+    &self,
+    user_id: &Uuid,
+    post_id: &Uuid,
+    uri: &str,
+    content_md: &str,
+    content_html: &str,
+    created_at: &DateTime<Utc>,
+  ) -> Result<Uuid, LogicErr> {
+    let comment_id = Uuid::new_v4();
 
     let db = self.db.get().await.map_err(map_db_err)?;
-    let row = db.query_one("INSERT INTO comments (comment_id, user_id, post_id, content_md, content_html) VALUES ($1, $2, $3, $4, $5) RETURNING comment_id",
+    let row = db.query_one("INSERT INTO comments (comment_id, user_id, post_id, content_md, content_html, uri, is_external, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, TRUE, $7, $7) RETURNING comment_id",
       &[
         &comment_id,
         &user_id,
         &post_id,
         &content_md,
         &content_html,
+        &uri,
+        &created_at,
       ],
     )
     .await
@@ -98,6 +162,18 @@ impl CommentRepo for DbCommentRepo {
     Ok(())
   }
 
+  async fn delete_comment_from_uri(&self, uri: &str, user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM comments WHERE uri = $1 AND user_id = $2",
+      &[&uri, &user_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
   async fn fetch_comments_count(&self, post_id: &Uuid, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let row = db
@@ -175,4 +251,37 @@ impl CommentRepo for DbCommentRepo {
 
     row.and_then(CommentPub::from_row)
   }
+
+  async fn fetch_comment_from_uri(&self, uri: &str) -> Option<CommentPub> {
+    let db = match self.db.get().await.map_err(map_db_err) {
+      Ok(db) => db,
+      Err(_) => return None,
+    };
+
+    let row = match db
+      .query_opt(include_str!("./sql/fetch_comment_from_uri.sql"), &[&uri])
+      .await
+      .map_err(map_db_err)
+    {
+      Ok(row) => row,
+      Err(_) => return None,
+    };
+
+    row.and_then(CommentPub::from_row)
+  }
+
+  async fn fetch_comment_post_id(&self, comment_id: &Uuid) -> Option<Uuid> {
+    let db = match self.db.get().await.map_err(map_db_err) {
+      Ok(db) => db,
+      Err(_) => return None,
+    };
+
+    match db
+      .query_opt("SELECT post_id FROM comments WHERE comment_id = $1", &[&comment_id])
+      .await
+    {
+      Ok(row) => row.map(|row| row.get("post_id")),
+      Err(_) => None,
+    }
+  }
 }
@@ -0,0 +1,181 @@
+use super::FromRow;
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::{
+    delivery::{Delivery, DeliveryStatus},
+    job::JobStatus,
+  },
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait DeliveryRepo {
+  async fn create_delivery(
+    &self,
+    inbox_uri: &str,
+    actor_fediverse_uri: &str,
+    activity: &Value,
+    next_attempt_at: &DateTime<Utc>,
+    last_error: &str,
+  ) -> Result<Uuid, LogicErr>;
+  async fn claim_due_deliveries(&self, limit: i64) -> Result<Vec<Delivery>, LogicErr>;
+  async fn mark_delivered(&self, delivery_id: &Uuid) -> Result<(), LogicErr>;
+  async fn reschedule_delivery(
+    &self,
+    delivery_id: &Uuid,
+    next_attempt_at: &DateTime<Utc>,
+    last_error: &str,
+  ) -> Result<(), LogicErr>;
+  async fn mark_dead(&self, delivery_id: &Uuid, last_error: &str) -> Result<(), LogicErr>;
+  async fn purge_delivered_deliveries(&self) -> Result<(), LogicErr>;
+}
+
+pub type DeliveryPool = Arc<dyn DeliveryRepo + Send + Sync>;
+
+pub struct DbDeliveryRepo {
+  pub db: Pool,
+}
+
+impl DbDeliveryRepo {
+  async fn update_attempt(
+    &self,
+    delivery_id: &Uuid,
+    status: DeliveryStatus,
+    next_attempt_at: &DateTime<Utc>,
+    last_error: Option<&str>,
+    job_status: JobStatus,
+    failed: bool,
+  ) -> Result<(), LogicErr> {
+    let failed_increment: i32 = match failed {
+      true => 1,
+      false => 0,
+    };
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      include_str!("./sql/update_delivery_attempt.sql"),
+      &[
+        &delivery_id,
+        &status.to_string(),
+        &next_attempt_at,
+        &last_error,
+        &job_status.to_string(),
+        &failed_increment,
+      ],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+}
+
+#[async_trait]
+impl DeliveryRepo for DbDeliveryRepo {
+  async fn create_delivery(
+    &self,
+    inbox_uri: &str,
+    actor_fediverse_uri: &str,
+    activity: &Value,
+    next_attempt_at: &DateTime<Utc>,
+    last_error: &str,
+  ) -> Result<Uuid, LogicErr> {
+    let delivery_id = Uuid::new_v4();
+    let job_id = Uuid::new_v4();
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        include_str!("./sql/create_delivery.sql"),
+        &[
+          &delivery_id,
+          &job_id,
+          &inbox_uri,
+          &actor_fediverse_uri,
+          &activity,
+          &next_attempt_at,
+          &last_error,
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn claim_due_deliveries(&self, limit: i64) -> Result<Vec<Delivery>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(include_str!("./sql/claim_due_deliveries.sql"), &[&limit])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(Delivery::from_row).collect())
+  }
+
+  async fn mark_delivered(&self, delivery_id: &Uuid) -> Result<(), LogicErr> {
+    self
+      .update_attempt(
+        delivery_id,
+        DeliveryStatus::Delivered,
+        &Utc::now(),
+        None,
+        JobStatus::Done,
+        false,
+      )
+      .await
+  }
+
+  async fn reschedule_delivery(
+    &self,
+    delivery_id: &Uuid,
+    next_attempt_at: &DateTime<Utc>,
+    last_error: &str,
+  ) -> Result<(), LogicErr> {
+    self
+      .update_attempt(
+        delivery_id,
+        DeliveryStatus::Pending,
+        next_attempt_at,
+        Some(last_error),
+        JobStatus::InProgress,
+        true,
+      )
+      .await
+  }
+
+  async fn mark_dead(&self, delivery_id: &Uuid, last_error: &str) -> Result<(), LogicErr> {
+    self
+      .update_attempt(
+        delivery_id,
+        DeliveryStatus::Dead,
+        &Utc::now(),
+        Some(last_error),
+        JobStatus::Failed,
+        true,
+      )
+      .await
+  }
+
+  async fn purge_delivered_deliveries(&self) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM deliveries WHERE status = 'delivered' AND updated_at < NOW() - INTERVAL '1 day'",
+      &[],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+}
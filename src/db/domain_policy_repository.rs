@@ -0,0 +1,85 @@
+use super::FromRow;
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::domain_policy::{DomainPolicy, DomainPolicyType},
+};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait DomainPolicyRepo {
+  async fn fetch_domain_policies(&self) -> Result<Vec<DomainPolicy>, LogicErr>;
+  async fn upsert_domain_policy(
+    &self,
+    domain: &str,
+    policy: &DomainPolicyType,
+    reason: &Option<String>,
+    created_by_id: &Uuid,
+  ) -> Result<DomainPolicy, LogicErr>;
+  async fn delete_domain_policy(&self, domain: &str) -> Result<(), LogicErr>;
+}
+
+pub type DomainPolicyPool = Arc<dyn DomainPolicyRepo + Send + Sync>;
+
+pub struct DbDomainPolicyRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl DomainPolicyRepo for DbDomainPolicyRepo {
+  async fn fetch_domain_policies(&self) -> Result<Vec<DomainPolicy>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query("SELECT * FROM domain_policies ORDER BY domain", &[])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(DomainPolicy::from_row).collect())
+  }
+
+  async fn upsert_domain_policy(
+    &self,
+    domain: &str,
+    policy: &DomainPolicyType,
+    reason: &Option<String>,
+    created_by_id: &Uuid,
+  ) -> Result<DomainPolicy, LogicErr> {
+    let domain_policy_id = Uuid::new_v4();
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "INSERT INTO domain_policies (domain_policy_id, domain, policy, reason, created_by_id) VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (domain) DO UPDATE SET policy = $3, reason = $4, created_by_id = $5, updated_at = NOW() \
+         RETURNING *",
+        &[&domain_policy_id, &domain, &policy.to_string(), &reason, &created_by_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    match DomainPolicy::from_row(row) {
+      Some(domain_policy) => Ok(domain_policy),
+      None => Err(LogicErr::InternalError("Failed to read back domain policy".to_string())),
+    }
+  }
+
+  async fn delete_domain_policy(&self, domain: &str) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let count = db
+      .execute("DELETE FROM domain_policies WHERE domain = $1", &[&domain])
+      .await
+      .map_err(map_db_err)?;
+
+    match count {
+      0 => Err(LogicErr::MissingRecord),
+      _ => Ok(()),
+    }
+  }
+}
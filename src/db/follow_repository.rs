@@ -1,4 +1,8 @@
-use crate::{helpers::api::map_db_err, logic::LogicErr, model::follow::Follow};
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::follow::{Follow, FollowRequest},
+};
 
 use async_trait::async_trait;
 use deadpool_postgres::Pool;
@@ -17,6 +21,27 @@ pub trait FollowRepo {
   async fn user_follows_poster(&self, post_id: &Uuid, user_id: &Uuid) -> bool;
   async fn user_follows_user(&self, following_user_id: &Uuid, followed_user_id: &Uuid) -> bool;
   async fn fetch_user_followers(&self, user_id: &Uuid) -> Option<Vec<Follow>>;
+  /// Records a follow that only takes effect once the followed user accepts it, keeping the remote activity that
+  /// asked for it if there was one
+  async fn create_follow_request(
+    &self,
+    user_id: &Uuid,
+    following_user_id: &Uuid,
+    activity: &Option<String>,
+  ) -> Result<(), LogicErr>;
+  async fn accept_follow_request(&self, user_id: &Uuid, following_user_id: &Uuid) -> Result<(), LogicErr>;
+  async fn fetch_follow_request(
+    &self,
+    user_id: &Uuid,
+    following_user_id: &Uuid,
+  ) -> Result<Option<FollowRequest>, LogicErr>;
+  async fn fetch_follow_requests(
+    &self,
+    following_user_id: &Uuid,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<FollowRequest>, LogicErr>;
+  async fn count_follow_requests(&self, following_user_id: &Uuid) -> Result<i64, LogicErr>;
 }
 
 pub type FollowPool = Arc<dyn FollowRepo + Send + Sync>;
@@ -67,7 +92,8 @@ impl FollowRepo for DbFollowRepo {
       INNER JOIN posts p
       ON p.user_id = f.following_user_id
       WHERE p.post_id = $1
-      AND f.user_id = $2"#,
+      AND f.user_id = $2
+      AND f.is_pending = false"#,
         &[&post_id, &user_id],
       )
       .await
@@ -91,7 +117,8 @@ impl FollowRepo for DbFollowRepo {
       .query_one(
         r#"SELECT count(*) >= 1 AS following FROM followers
         WHERE user_id = $1
-        AND following_user_id = $2"#,
+        AND following_user_id = $2
+        AND is_pending = false"#,
         &[&following_user_id, &followed_user_id],
       )
       .await
@@ -112,7 +139,7 @@ impl FollowRepo for DbFollowRepo {
 
     let rows = match db
       .query(
-        "SELECT * FROM followers WHERE following_user_id = $1 AND user_id != following_user_id",
+        "SELECT * FROM followers WHERE following_user_id = $1 AND user_id != following_user_id AND is_pending = false",
         &[&user_id],
       )
       .await
@@ -124,4 +151,92 @@ impl FollowRepo for DbFollowRepo {
 
     Some(rows.into_iter().flat_map(Follow::from_row).collect())
   }
+
+  async fn create_follow_request(
+    &self,
+    user_id: &Uuid,
+    following_user_id: &Uuid,
+    activity: &Option<String>,
+  ) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      r#"INSERT INTO followers (follower_id, user_id, following_user_id, is_pending, activity)
+      VALUES ($1, $2, $3, true, $4)
+      ON CONFLICT (user_id, following_user_id) DO UPDATE SET activity = $4"#,
+      &[&Uuid::new_v4(), &user_id, &following_user_id, &activity],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn accept_follow_request(&self, user_id: &Uuid, following_user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "UPDATE followers SET is_pending = false, activity = NULL WHERE user_id = $1 AND following_user_id = $2",
+      &[&user_id, &following_user_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn fetch_follow_request(
+    &self,
+    user_id: &Uuid,
+    following_user_id: &Uuid,
+  ) -> Result<Option<FollowRequest>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt(
+        r#"SELECT f.*, u.fediverse_id AS user_fediverse_id FROM followers f
+        INNER JOIN users u ON u.user_id = f.user_id
+        WHERE f.user_id = $1
+        AND f.following_user_id = $2
+        AND f.is_pending = true"#,
+        &[&user_id, &following_user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.and_then(FollowRequest::from_row))
+  }
+
+  async fn fetch_follow_requests(
+    &self,
+    following_user_id: &Uuid,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<FollowRequest>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT f.*, u.fediverse_id AS user_fediverse_id FROM followers f
+        INNER JOIN users u ON u.user_id = f.user_id
+        WHERE f.following_user_id = $1
+        AND f.is_pending = true
+        ORDER BY f.created_at ASC
+        LIMIT $2 OFFSET $3"#,
+        &[&following_user_id, &limit, &skip],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(FollowRequest::from_row).collect())
+  }
+
+  async fn count_follow_requests(&self, following_user_id: &Uuid) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "SELECT COUNT(*) FROM followers WHERE following_user_id = $1 AND is_pending = true",
+        &[&following_user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
 }
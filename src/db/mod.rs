@@ -1,16 +1,29 @@
+pub mod account_restriction_repository;
 pub mod app_repository;
+pub mod authorization_code_repository;
+pub mod block_repository;
 pub mod comment_repository;
+pub mod delivery_repository;
+pub mod domain_policy_repository;
 pub mod event_repository;
 pub mod follow_repository;
 pub mod job_repository;
 pub mod like_repository;
+pub mod mute_repository;
+pub mod notification_repository;
+pub mod orbit_membership_repository;
+pub mod orbit_moderation_repository;
 pub mod orbit_moderator_repository;
 pub mod orbit_repository;
 pub mod post_attachment_repository;
+pub mod post_mention_repository;
 pub mod post_repository;
+pub mod report_repository;
 pub mod repositories;
 pub mod repository;
+pub mod search_repository;
 pub mod session_repository;
+pub mod tag_repository;
 pub mod tombstone_repository;
 pub mod traits;
 pub mod user_orbit_repository;
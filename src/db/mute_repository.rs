@@ -0,0 +1,219 @@
+use super::FromRow;
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::mute::{KeywordFilter, OrbitMute, UserMute},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait MuteRepo {
+  async fn fetch_user_mutes(&self, user_id: &Uuid) -> Result<Vec<UserMute>, LogicErr>;
+  async fn create_user_mute(
+    &self,
+    user_id: &Uuid,
+    muted_user_id: &Uuid,
+    boosts_only: bool,
+    expires_at: &Option<DateTime<Utc>>,
+  ) -> Result<Uuid, LogicErr>;
+  async fn delete_user_mute(&self, user_id: &Uuid, muted_user_id: &Uuid) -> Result<(), LogicErr>;
+  async fn fetch_orbit_mutes(&self, user_id: &Uuid) -> Result<Vec<OrbitMute>, LogicErr>;
+  async fn create_orbit_mute(&self, user_id: &Uuid, orbit_id: &Uuid) -> Result<Uuid, LogicErr>;
+  async fn delete_orbit_mute(&self, user_id: &Uuid, orbit_id: &Uuid) -> Result<(), LogicErr>;
+  async fn fetch_keyword_filters(&self, user_id: &Uuid) -> Result<Vec<KeywordFilter>, LogicErr>;
+  async fn create_keyword_filter(
+    &self,
+    user_id: &Uuid,
+    phrase: &str,
+    is_regex: bool,
+    expires_at: &Option<DateTime<Utc>>,
+  ) -> Result<KeywordFilter, LogicErr>;
+  async fn delete_keyword_filter(&self, user_id: &Uuid, keyword_filter_id: &Uuid) -> Result<(), LogicErr>;
+  /// Checks a pattern against Postgres' own regex engine, since that's the one the feed queries evaluate it with
+  async fn keyword_filter_pattern_valid(&self, pattern: &str) -> bool;
+}
+
+pub type MutePool = Arc<dyn MuteRepo + Send + Sync>;
+
+pub struct DbMuteRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl MuteRepo for DbMuteRepo {
+  async fn fetch_user_mutes(&self, user_id: &Uuid) -> Result<Vec<UserMute>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT um.*, u.handle AS muted_user_handle, u.fediverse_id AS muted_user_fediverse_id FROM user_mutes um
+        INNER JOIN users u
+        ON u.user_id = um.muted_user_id
+        WHERE um.user_id = $1
+        AND (um.expires_at IS NULL OR um.expires_at > NOW())
+        ORDER BY um.created_at DESC"#,
+        &[&user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(UserMute::from_row).collect())
+  }
+
+  async fn create_user_mute(
+    &self,
+    user_id: &Uuid,
+    muted_user_id: &Uuid,
+    boosts_only: bool,
+    expires_at: &Option<DateTime<Utc>>,
+  ) -> Result<Uuid, LogicErr> {
+    let user_mute_id = Uuid::new_v4();
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "INSERT INTO user_mutes (user_mute_id, user_id, muted_user_id, boosts_only, expires_at) VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (user_id, muted_user_id) DO UPDATE SET boosts_only = $4, expires_at = $5 \
+         RETURNING user_mute_id",
+        &[&user_mute_id, &user_id, &muted_user_id, &boosts_only, &expires_at],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn delete_user_mute(&self, user_id: &Uuid, muted_user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM user_mutes WHERE user_id = $1 AND muted_user_id = $2",
+      &[&user_id, &muted_user_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn fetch_orbit_mutes(&self, user_id: &Uuid) -> Result<Vec<OrbitMute>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT om.*, o.name AS orbit_name, o.shortcode AS orbit_shortcode FROM orbit_mutes om
+        INNER JOIN orbits o
+        ON o.orbit_id = om.orbit_id
+        WHERE om.user_id = $1
+        ORDER BY om.created_at DESC"#,
+        &[&user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(OrbitMute::from_row).collect())
+  }
+
+  async fn create_orbit_mute(&self, user_id: &Uuid, orbit_id: &Uuid) -> Result<Uuid, LogicErr> {
+    let orbit_mute_id = Uuid::new_v4();
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "INSERT INTO orbit_mutes (orbit_mute_id, user_id, orbit_id) VALUES ($1, $2, $3) \
+         ON CONFLICT (user_id, orbit_id) DO UPDATE SET created_at = orbit_mutes.created_at \
+         RETURNING orbit_mute_id",
+        &[&orbit_mute_id, &user_id, &orbit_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn delete_orbit_mute(&self, user_id: &Uuid, orbit_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM orbit_mutes WHERE user_id = $1 AND orbit_id = $2",
+      &[&user_id, &orbit_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn fetch_keyword_filters(&self, user_id: &Uuid) -> Result<Vec<KeywordFilter>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT * FROM keyword_filters
+        WHERE user_id = $1
+        AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY created_at DESC"#,
+        &[&user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(KeywordFilter::from_row).collect())
+  }
+
+  async fn create_keyword_filter(
+    &self,
+    user_id: &Uuid,
+    phrase: &str,
+    is_regex: bool,
+    expires_at: &Option<DateTime<Utc>>,
+  ) -> Result<KeywordFilter, LogicErr> {
+    let keyword_filter_id = Uuid::new_v4();
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "INSERT INTO keyword_filters (keyword_filter_id, user_id, phrase, is_regex, expires_at) VALUES ($1, $2, $3, $4, $5) \
+         RETURNING *",
+        &[&keyword_filter_id, &user_id, &phrase, &is_regex, &expires_at],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    match KeywordFilter::from_row(row) {
+      Some(keyword_filter) => Ok(keyword_filter),
+      None => Err(LogicErr::InternalError(
+        "Failed to read back keyword filter".to_string(),
+      )),
+    }
+  }
+
+  async fn delete_keyword_filter(&self, user_id: &Uuid, keyword_filter_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let count = db
+      .execute(
+        "DELETE FROM keyword_filters WHERE user_id = $1 AND keyword_filter_id = $2",
+        &[&user_id, &keyword_filter_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    match count {
+      0 => Err(LogicErr::MissingRecord),
+      _ => Ok(()),
+    }
+  }
+
+  async fn keyword_filter_pattern_valid(&self, pattern: &str) -> bool {
+    let db = match self.db.get().await.map_err(map_db_err) {
+      Ok(db) => db,
+      Err(_) => return false,
+    };
+
+    db.query_one("SELECT '' ~* $1", &[&pattern]).await.is_ok()
+  }
+}
@@ -0,0 +1,230 @@
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::{
+    notification::{NewNotification, Notification, NotificationFilter},
+    notification_type::NotificationType,
+  },
+};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+use super::FromRow;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait NotificationRepo {
+  /// Notifies a local user of someone else's interaction. Nothing is recorded for remote users, for users interacting
+  /// with themselves, or between users who've blocked or muted one another. Returns the new notification's ID if one
+  /// was recorded.
+  async fn create_notification(&self, notification: NewNotification) -> Result<Option<Uuid>, LogicErr>;
+  /// Notifies every local moderator of an orbit, returning the user and notification IDs of each notification recorded
+  async fn create_orbit_notification(
+    &self,
+    orbit_id: &Uuid,
+    source_user_id: &Uuid,
+    notification_type: NotificationType,
+  ) -> Result<Vec<(Uuid, Uuid)>, LogicErr>;
+  async fn fetch_notification(&self, user_id: &Uuid, notification_id: &Uuid) -> Result<Option<Notification>, LogicErr>;
+  async fn fetch_notifications(
+    &self,
+    user_id: &Uuid,
+    filter: &NotificationFilter,
+    limit: i64,
+  ) -> Result<Vec<Notification>, LogicErr>;
+  async fn count_unread_notifications(&self, user_id: &Uuid) -> Result<i64, LogicErr>;
+  async fn mark_notification_read(&self, user_id: &Uuid, notification_id: &Uuid) -> Result<(), LogicErr>;
+  /// Marks every notification read, or only those at or before `max_id` if one is given
+  async fn mark_notifications_read(&self, user_id: &Uuid, max_id: &Option<Uuid>) -> Result<(), LogicErr>;
+  async fn delete_notification(&self, user_id: &Uuid, notification_id: &Uuid) -> Result<(), LogicErr>;
+  async fn delete_notifications(&self, user_id: &Uuid) -> Result<(), LogicErr>;
+}
+
+pub type NotificationPool = Arc<dyn NotificationRepo + Send + Sync>;
+
+pub struct DbNotificationRepo {
+  pub db: Pool,
+}
+
+fn notification_types_to_strings(types: &[NotificationType]) -> Vec<String> {
+  types.iter().map(|t| t.to_string()).collect()
+}
+
+#[async_trait]
+impl NotificationRepo for DbNotificationRepo {
+  async fn create_notification(&self, notification: NewNotification) -> Result<Option<Uuid>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt(
+        include_str!("./sql/create_notification.sql"),
+        &[
+          &Uuid::new_v4(),
+          &notification.user_id,
+          &notification.source_user_id,
+          &notification.notification_type.to_string(),
+          &notification.post_id,
+          &notification.comment_id,
+          &notification.orbit_id,
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.map(|row| row.get("notification_id")))
+  }
+
+  async fn create_orbit_notification(
+    &self,
+    orbit_id: &Uuid,
+    source_user_id: &Uuid,
+    notification_type: NotificationType,
+  ) -> Result<Vec<(Uuid, Uuid)>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT om.user_id FROM orbit_moderators om
+        INNER JOIN users u ON u.user_id = om.user_id
+        WHERE om.orbit_id = $1
+        AND u.is_external = false"#,
+        &[&orbit_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    let mut created = vec![];
+
+    for row in rows {
+      let user_id: Uuid = row.get("user_id");
+      let notification_id = self
+        .create_notification(NewNotification {
+          user_id,
+          source_user_id: *source_user_id,
+          notification_type,
+          post_id: None,
+          comment_id: None,
+          orbit_id: Some(*orbit_id),
+        })
+        .await?;
+
+      if let Some(notification_id) = notification_id {
+        created.push((user_id, notification_id));
+      }
+    }
+
+    Ok(created)
+  }
+
+  async fn fetch_notification(&self, user_id: &Uuid, notification_id: &Uuid) -> Result<Option<Notification>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt(
+        r#"SELECT n.*, u.handle AS source_user_handle, u.fediverse_id AS source_user_fediverse_id,
+        u.avatar_url AS source_user_avatar_url
+        FROM notifications n
+        INNER JOIN users u
+        ON u.user_id = n.source_user_id
+        WHERE n.user_id = $1
+        AND n.notification_id = $2"#,
+        &[&user_id, &notification_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.and_then(Notification::from_row))
+  }
+
+  async fn fetch_notifications(
+    &self,
+    user_id: &Uuid,
+    filter: &NotificationFilter,
+    limit: i64,
+  ) -> Result<Vec<Notification>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        include_str!("./sql/fetch_notifications.sql"),
+        &[
+          &user_id,
+          &notification_types_to_strings(&filter.types),
+          &notification_types_to_strings(&filter.exclude_types),
+          &filter.unread_only,
+          &filter.max_id,
+          &filter.since_id,
+          &limit,
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(Notification::from_row).collect())
+  }
+
+  async fn count_unread_notifications(&self, user_id: &Uuid) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+        &[&user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn mark_notification_read(&self, user_id: &Uuid, notification_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND notification_id = $2 AND read_at IS NULL",
+      &[&user_id, &notification_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn mark_notifications_read(&self, user_id: &Uuid, max_id: &Option<Uuid>) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      r#"UPDATE notifications n SET read_at = NOW()
+      WHERE n.user_id = $1
+      AND n.read_at IS NULL
+      AND ($2::uuid IS NULL OR (n.created_at, n.notification_id) <= (
+        SELECT n2.created_at, n2.notification_id FROM notifications n2 WHERE n2.notification_id = $2
+      ))"#,
+      &[&user_id, &max_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn delete_notification(&self, user_id: &Uuid, notification_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM notifications WHERE user_id = $1 AND notification_id = $2",
+      &[&user_id, &notification_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn delete_notifications(&self, user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute("DELETE FROM notifications WHERE user_id = $1", &[&user_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(())
+  }
+}
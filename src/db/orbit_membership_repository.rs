@@ -0,0 +1,219 @@
+use super::FromRow;
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::orbit_membership::{OrbitInvite, OrbitJoinRequest},
+};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait OrbitMembershipRepo {
+  async fn fetch_join_requests(
+    &self,
+    orbit_id: &Uuid,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<OrbitJoinRequest>, LogicErr>;
+  async fn count_join_requests(&self, orbit_id: &Uuid) -> Result<i64, LogicErr>;
+  async fn fetch_join_request(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<Option<OrbitJoinRequest>, LogicErr>;
+  /// Queues a user to be let into an orbit, keeping the remote activity that asked for it if there was one
+  async fn create_join_request(
+    &self,
+    orbit_id: &Uuid,
+    user_id: &Uuid,
+    activity: &Option<String>,
+  ) -> Result<(), LogicErr>;
+  async fn delete_join_request(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr>;
+  async fn fetch_invites(&self, orbit_id: &Uuid) -> Result<Vec<OrbitInvite>, LogicErr>;
+  async fn create_invite(&self, orbit_id: &Uuid, user_id: &Uuid, created_by_id: &Uuid) -> Result<(), LogicErr>;
+  async fn delete_invite(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr>;
+  async fn user_is_invited(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr>;
+  /// Checks whether the user may post in an orbit: anyone can in open orbits, otherwise only members
+  async fn user_can_post(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr>;
+  /// Checks whether the user is a member of the orbit the post was made in
+  async fn user_is_member_for_post(&self, post_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr>;
+}
+
+pub type OrbitMembershipPool = Arc<dyn OrbitMembershipRepo + Send + Sync>;
+
+pub struct DbOrbitMembershipRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl OrbitMembershipRepo for DbOrbitMembershipRepo {
+  async fn fetch_join_requests(
+    &self,
+    orbit_id: &Uuid,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<OrbitJoinRequest>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT jr.*, u.fediverse_id AS user_fediverse_id FROM orbit_join_requests jr
+        INNER JOIN users u ON u.user_id = jr.user_id
+        WHERE jr.orbit_id = $1
+        ORDER BY jr.created_at ASC
+        LIMIT $2 OFFSET $3"#,
+        &[&orbit_id, &limit, &skip],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(OrbitJoinRequest::from_row).collect())
+  }
+
+  async fn count_join_requests(&self, orbit_id: &Uuid) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "SELECT COUNT(*) FROM orbit_join_requests WHERE orbit_id = $1",
+        &[&orbit_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn fetch_join_request(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<Option<OrbitJoinRequest>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt(
+        r#"SELECT jr.*, u.fediverse_id AS user_fediverse_id FROM orbit_join_requests jr
+        INNER JOIN users u ON u.user_id = jr.user_id
+        WHERE jr.orbit_id = $1 AND jr.user_id = $2"#,
+        &[&orbit_id, &user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.and_then(OrbitJoinRequest::from_row))
+  }
+
+  async fn create_join_request(
+    &self,
+    orbit_id: &Uuid,
+    user_id: &Uuid,
+    activity: &Option<String>,
+  ) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      r#"INSERT INTO orbit_join_requests (orbit_join_request_id, orbit_id, user_id, activity)
+      VALUES ($1, $2, $3, $4)
+      ON CONFLICT (orbit_id, user_id) DO UPDATE SET activity = $4"#,
+      &[&Uuid::new_v4(), &orbit_id, &user_id, &activity],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn delete_join_request(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM orbit_join_requests WHERE orbit_id = $1 AND user_id = $2",
+      &[&orbit_id, &user_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn fetch_invites(&self, orbit_id: &Uuid) -> Result<Vec<OrbitInvite>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT oi.*, u.fediverse_id AS user_fediverse_id FROM orbit_invites oi
+        INNER JOIN users u ON u.user_id = oi.user_id
+        WHERE oi.orbit_id = $1
+        ORDER BY oi.created_at DESC"#,
+        &[&orbit_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(OrbitInvite::from_row).collect())
+  }
+
+  async fn create_invite(&self, orbit_id: &Uuid, user_id: &Uuid, created_by_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      r#"INSERT INTO orbit_invites (orbit_invite_id, orbit_id, user_id, created_by_id)
+      VALUES ($1, $2, $3, $4)
+      ON CONFLICT (orbit_id, user_id) DO NOTHING"#,
+      &[&Uuid::new_v4(), &orbit_id, &user_id, &created_by_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn delete_invite(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM orbit_invites WHERE orbit_id = $1 AND user_id = $2",
+      &[&orbit_id, &user_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn user_is_invited(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "SELECT COUNT(*) > 0 FROM orbit_invites WHERE orbit_id = $1 AND user_id = $2",
+        &[&orbit_id, &user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn user_can_post(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        r#"SELECT COUNT(*) > 0 FROM orbits o
+        WHERE o.orbit_id = $1
+        AND (o.join_policy = 'open'
+        OR EXISTS (SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = o.orbit_id AND uo.user_id = $2))"#,
+        &[&orbit_id, &user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn user_is_member_for_post(&self, post_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        r#"SELECT COUNT(*) > 0 FROM user_orbits uo
+        INNER JOIN posts p ON p.orbit_id = uo.orbit_id
+        WHERE p.post_id = $1 AND uo.user_id = $2"#,
+        &[&post_id, &user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+}
@@ -0,0 +1,223 @@
+use super::FromRow;
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::orbit_moderation::{NewOrbitModlogEntry, OrbitBan, OrbitModlogEntry},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait OrbitModerationRepo {
+  /// Fetches the bans in an orbit that haven't expired yet
+  async fn fetch_bans(&self, orbit_id: &Uuid) -> Result<Vec<OrbitBan>, LogicErr>;
+  async fn user_is_banned(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr>;
+  /// Checks whether the user is banned from the orbit the post was made in, if any
+  async fn user_is_banned_for_post(&self, post_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr>;
+  /// Bans a user from an orbit, replacing the reason and expiry if they were already banned
+  async fn upsert_ban(
+    &self,
+    orbit_id: &Uuid,
+    user_id: &Uuid,
+    reason: &Option<String>,
+    expires_at: &Option<DateTime<Utc>>,
+    created_by_id: &Uuid,
+  ) -> Result<(), LogicErr>;
+  async fn delete_ban(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr>;
+  async fn post_is_locked(&self, post_id: &Uuid) -> Result<bool, LogicErr>;
+  async fn update_post_locked(&self, post_id: &Uuid, is_locked: bool) -> Result<(), LogicErr>;
+  async fn update_post_pinned(&self, post_id: &Uuid, is_pinned: bool) -> Result<(), LogicErr>;
+  async fn create_modlog_entry(&self, entry: &NewOrbitModlogEntry) -> Result<(), LogicErr>;
+  async fn fetch_modlog(&self, orbit_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<OrbitModlogEntry>, LogicErr>;
+  async fn fetch_modlog_count(&self, orbit_id: &Uuid) -> Result<i64, LogicErr>;
+}
+
+pub type OrbitModerationPool = Arc<dyn OrbitModerationRepo + Send + Sync>;
+
+pub struct DbOrbitModerationRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl OrbitModerationRepo for DbOrbitModerationRepo {
+  async fn fetch_bans(&self, orbit_id: &Uuid) -> Result<Vec<OrbitBan>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT ob.*, u.fediverse_id AS user_fediverse_id FROM orbit_bans ob
+        INNER JOIN users u ON u.user_id = ob.user_id
+        WHERE ob.orbit_id = $1
+        AND (ob.expires_at IS NULL OR ob.expires_at > NOW())
+        ORDER BY ob.created_at DESC"#,
+        &[&orbit_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(OrbitBan::from_row).collect())
+  }
+
+  async fn user_is_banned(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        r#"SELECT COUNT(*) > 0 FROM orbit_bans WHERE orbit_id = $1 AND user_id = $2
+        AND (expires_at IS NULL OR expires_at > NOW())"#,
+        &[&orbit_id, &user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn user_is_banned_for_post(&self, post_id: &Uuid, user_id: &Uuid) -> Result<bool, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        r#"SELECT COUNT(*) > 0 FROM orbit_bans ob
+        INNER JOIN posts p ON p.orbit_id = ob.orbit_id
+        WHERE p.post_id = $1 AND ob.user_id = $2
+        AND (ob.expires_at IS NULL OR ob.expires_at > NOW())"#,
+        &[&post_id, &user_id],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn upsert_ban(
+    &self,
+    orbit_id: &Uuid,
+    user_id: &Uuid,
+    reason: &Option<String>,
+    expires_at: &Option<DateTime<Utc>>,
+    created_by_id: &Uuid,
+  ) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      r#"INSERT INTO orbit_bans (orbit_ban_id, orbit_id, user_id, created_by_id, reason, expires_at)
+      VALUES ($1, $2, $3, $4, $5, $6)
+      ON CONFLICT (orbit_id, user_id) DO UPDATE SET created_by_id = $4, reason = $5, expires_at = $6,
+      created_at = NOW()"#,
+      &[
+        &Uuid::new_v4(),
+        &orbit_id,
+        &user_id,
+        &created_by_id,
+        &reason,
+        &expires_at,
+      ],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn delete_ban(&self, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "DELETE FROM orbit_bans WHERE orbit_id = $1 AND user_id = $2",
+      &[&orbit_id, &user_id],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn post_is_locked(&self, post_id: &Uuid) -> Result<bool, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt("SELECT is_locked FROM posts WHERE post_id = $1", &[&post_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.map(|row| row.get("is_locked")).unwrap_or(false))
+  }
+
+  async fn update_post_locked(&self, post_id: &Uuid, is_locked: bool) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "UPDATE posts SET is_locked = $2 WHERE post_id = $1",
+      &[&post_id, &is_locked],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn update_post_pinned(&self, post_id: &Uuid, is_pinned: bool) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "UPDATE posts SET pinned_at = CASE WHEN $2 THEN NOW() ELSE NULL END WHERE post_id = $1",
+      &[&post_id, &is_pinned],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn create_modlog_entry(&self, entry: &NewOrbitModlogEntry) -> Result<(), LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      r#"INSERT INTO orbit_modlog (orbit_modlog_id, orbit_id, moderator_id, action, target_user_id, post_id, comment_id,
+      reason) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+      &[
+        &Uuid::new_v4(),
+        &entry.orbit_id,
+        &entry.moderator_id,
+        &entry.action.to_string(),
+        &entry.target_user_id,
+        &entry.post_id,
+        &entry.comment_id,
+        &entry.reason,
+      ],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn fetch_modlog(&self, orbit_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<OrbitModlogEntry>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT ml.*, m.fediverse_id AS moderator_fediverse_id, t.fediverse_id AS target_user_fediverse_id
+        FROM orbit_modlog ml
+        LEFT JOIN users m ON m.user_id = ml.moderator_id
+        LEFT JOIN users t ON t.user_id = ml.target_user_id
+        WHERE ml.orbit_id = $1
+        ORDER BY ml.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        &[&orbit_id, &limit, &skip],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(OrbitModlogEntry::from_row).collect())
+  }
+
+  async fn fetch_modlog_count(&self, orbit_id: &Uuid) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one("SELECT COUNT(*) FROM orbit_modlog WHERE orbit_id = $1", &[&orbit_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+}
@@ -2,7 +2,7 @@ use super::FromRow;
 use crate::{
   helpers::api::map_db_err,
   logic::LogicErr,
-  model::{orbit::Orbit, orbit_pub::OrbitPub},
+  model::{orbit::Orbit, orbit_join_policy::OrbitJoinPolicy, orbit_pub::OrbitPub},
   settings::SETTINGS,
 };
 
@@ -42,6 +42,7 @@ pub trait OrbitRepo {
     priv_key: &str,
     pub_key: &str,
     uri: &str,
+    join_policy: &OrbitJoinPolicy,
   ) -> Result<Uuid, LogicErr>;
   async fn create_from(&self, orbit: &Orbit) -> Result<Orbit, LogicErr>;
   async fn update_orbit(
@@ -53,6 +54,7 @@ pub trait OrbitRepo {
     avatar_uri: &Option<String>,
     banner_uri: &Option<String>,
     is_external: bool,
+    join_policy: &OrbitJoinPolicy,
   ) -> Result<(), LogicErr>;
   async fn orbit_is_external(&self, orbit_id: &Uuid) -> Result<bool, LogicErr>;
   async fn update_orbit_from(&self, orbit: &Orbit) -> Result<(), LogicErr>;
@@ -220,12 +222,14 @@ impl OrbitRepo for DbOrbitRepo {
     let db = self.db.get().await.map_err(map_db_err)?;
     let row = db
       .query_opt(
-        r#"SELECT o.*, COUNT(uo.*) >= 1 AS joined, COUNT(om.*) >= 1 AS moderating
+        r#"SELECT o.*, COUNT(uo.*) >= 1 AS joined, COUNT(om.*) >= 1 AS moderating, COUNT(jr.*) >= 1 AS requested
       FROM orbits o
       LEFT OUTER JOIN user_orbits uo
       ON uo.orbit_id = o.orbit_id AND uo.user_id = $2
       LEFT OUTER JOIN orbit_moderators om
       ON om.orbit_id = o.orbit_id AND om.user_id = $2
+      LEFT OUTER JOIN orbit_join_requests jr
+      ON jr.orbit_id = o.orbit_id AND jr.user_id = $2
       WHERE o.orbit_id = $1
       GROUP BY o.orbit_id"#,
         &[&orbit_id, &user_id],
@@ -284,6 +288,7 @@ impl OrbitRepo for DbOrbitRepo {
     priv_key: &str,
     pub_key: &str,
     uri: &str,
+    join_policy: &OrbitJoinPolicy,
   ) -> Result<Uuid, LogicErr> {
     let orbit_id = Uuid::new_v4();
     let fediverse_uri = format!("/orbit/{}", orbit_id);
@@ -292,9 +297,9 @@ impl OrbitRepo for DbOrbitRepo {
     let db = self.db.get().await.map_err(map_db_err)?;
     let row = db
       .query_one(
-        r#"INSERT INTO orbits (orbit_id, name, description_md, description_html, avatar_uri, banner_uri, uri, fediverse_uri, is_external, shortcode, private_key, public_key, fediverse_id)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) RETURNING orbit_id"#,
-        &[&orbit_id, &name, &description_md, &description_html, &avatar_uri, &banner_uri, &uri, &fediverse_uri, &is_external, &shortcode, &priv_key, &pub_key, &fediverse_id],
+        r#"INSERT INTO orbits (orbit_id, name, description_md, description_html, avatar_uri, banner_uri, uri, fediverse_uri, is_external, shortcode, private_key, public_key, fediverse_id, join_policy)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) RETURNING orbit_id"#,
+        &[&orbit_id, &name, &description_md, &description_html, &avatar_uri, &banner_uri, &uri, &fediverse_uri, &is_external, &shortcode, &priv_key, &pub_key, &fediverse_id, &join_policy.to_string()],
       )
       .await
       .map_err(map_db_err)?;
@@ -306,9 +311,9 @@ impl OrbitRepo for DbOrbitRepo {
     let db = self.db.get().await.map_err(map_db_err)?;
     db
       .execute(
-        r#"INSERT INTO orbits (orbit_id, shortcode, name, description_md, description_html, avatar_uri, banner_uri, uri, fediverse_uri, is_external, ext_apub_inbox_uri, ext_apub_outbox_uri, ext_apub_followers_uri, private_key, public_key, fediverse_id)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)"#,
-        &[&orbit.orbit_id, &orbit.shortcode, &orbit.name, &orbit.description_md, &orbit.description_html, &orbit.avatar_uri, &orbit.banner_uri, &orbit.uri, &orbit.fediverse_uri, &orbit.is_external, &orbit.ext_apub_inbox_uri, &orbit.ext_apub_outbox_uri, &orbit.ext_apub_followers_uri, &orbit.private_key, &orbit.public_key, &orbit.fediverse_id],
+        r#"INSERT INTO orbits (orbit_id, shortcode, name, description_md, description_html, avatar_uri, banner_uri, uri, fediverse_uri, is_external, ext_apub_inbox_uri, ext_apub_outbox_uri, ext_apub_followers_uri, private_key, public_key, fediverse_id, join_policy)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)"#,
+        &[&orbit.orbit_id, &orbit.shortcode, &orbit.name, &orbit.description_md, &orbit.description_html, &orbit.avatar_uri, &orbit.banner_uri, &orbit.uri, &orbit.fediverse_uri, &orbit.is_external, &orbit.ext_apub_inbox_uri, &orbit.ext_apub_outbox_uri, &orbit.ext_apub_followers_uri, &orbit.private_key, &orbit.public_key, &orbit.fediverse_id, &orbit.join_policy.to_string()],
       )
       .await
       .map_err(map_db_err)?;
@@ -325,11 +330,12 @@ impl OrbitRepo for DbOrbitRepo {
     avatar_uri: &Option<String>,
     banner_uri: &Option<String>,
     is_external: bool,
+    join_policy: &OrbitJoinPolicy,
   ) -> Result<(), LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     db.execute(
-      "UPDATE orbits SET name = $2, description_md = $3, description_html = $4, avatar_uri = $5, banner_uri = $6, is_external = $7, updated_at = NOW() WHERE orbit_id = $1",
-      &[&orbit_id, &name, &description_md, &description_html, &avatar_uri, &banner_uri, &is_external],
+      "UPDATE orbits SET name = $2, description_md = $3, description_html = $4, avatar_uri = $5, banner_uri = $6, is_external = $7, join_policy = $8, updated_at = NOW() WHERE orbit_id = $1",
+      &[&orbit_id, &name, &description_md, &description_html, &avatar_uri, &banner_uri, &is_external, &join_policy.to_string()],
     )
     .await
     .map_err(map_db_err)?;
@@ -350,7 +356,7 @@ impl OrbitRepo for DbOrbitRepo {
   async fn update_orbit_from(&self, orbit: &Orbit) -> Result<(), LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     db.execute(
-      "UPDATE orbits SET shortcode = $2, name = $3, description_md = $4, description_html = $5, avatar_uri = $6, banner_uri = $7, uri = $8, fediverse_uri = $9, private_key = $10, public_key = $11, is_external = $12, ext_apub_inbox_uri = $13, ext_apub_outbox_uri = $14, ext_apub_followers_uri = $15, fediverse_id = $16, updated_at = NOW() WHERE orbit_id = $1",
+      "UPDATE orbits SET shortcode = $2, name = $3, description_md = $4, description_html = $5, avatar_uri = $6, banner_uri = $7, uri = $8, fediverse_uri = $9, private_key = $10, public_key = $11, is_external = $12, ext_apub_inbox_uri = $13, ext_apub_outbox_uri = $14, ext_apub_followers_uri = $15, fediverse_id = $16, join_policy = $17, updated_at = NOW() WHERE orbit_id = $1",
       &[
         &orbit.orbit_id,
         &orbit.shortcode,
@@ -368,6 +374,7 @@ impl OrbitRepo for DbOrbitRepo {
         &orbit.ext_apub_outbox_uri,
         &orbit.ext_apub_followers_uri,
         &orbit.fediverse_id,
+        &orbit.join_policy.to_string(),
       ],
     )
     .await
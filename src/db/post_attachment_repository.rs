@@ -15,6 +15,11 @@ pub trait PostAttachmentRepo {
   async fn create_attachment_from(&self, attachment: PostAttachment) -> Result<(), LogicErr>;
   async fn update_attachment(&self, attachment: PostAttachment) -> Result<(), LogicErr>;
   async fn fetch_by_post_id(&self, post_id: &Uuid) -> Result<Vec<PostAttachment>, LogicErr>;
+  /// Counts how many of the given uploads belong to a user and aren't attached to a post yet
+  async fn count_unattached(&self, user_id: &Uuid, attachment_ids: &[Uuid]) -> Result<i64, LogicErr>;
+  /// Attaches a user's unattached uploads to one of their posts, returning how many were attached. Nothing is attached
+  /// unless every one of them is available.
+  async fn attach_to_post(&self, user_id: &Uuid, post_id: &Uuid, attachment_ids: &[Uuid]) -> Result<u64, LogicErr>;
 }
 
 pub type PostAttachmentPool = Arc<dyn PostAttachmentRepo + Send + Sync>;
@@ -81,4 +86,29 @@ impl PostAttachmentRepo for DbPostAttachmentRepo {
 
     Ok(rows.into_iter().flat_map(PostAttachment::from_row).collect())
   }
+
+  async fn count_unattached(&self, user_id: &Uuid, attachment_ids: &[Uuid]) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        "SELECT COUNT(*) FROM post_attachments WHERE user_id = $1 AND post_id IS NULL AND attachment_id = ANY($2)",
+        &[&user_id, &attachment_ids],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn attach_to_post(&self, user_id: &Uuid, post_id: &Uuid, attachment_ids: &[Uuid]) -> Result<u64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute(
+      "UPDATE post_attachments SET post_id = $2 WHERE user_id = $1 AND post_id IS NULL AND attachment_id = ANY($3)
+        AND (SELECT COUNT(*) FROM post_attachments
+          WHERE user_id = $1 AND post_id IS NULL AND attachment_id = ANY($3)) = CARDINALITY($3)",
+      &[&user_id, &post_id, &attachment_ids],
+    )
+    .await
+    .map_err(map_db_err)
+  }
 }
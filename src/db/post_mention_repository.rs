@@ -0,0 +1,51 @@
+use crate::{helpers::api::map_db_err, logic::LogicErr, model::post_mention::PostMention};
+
+use super::FromRow;
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait PostMentionRepo {
+  /// Records mentions of the given users in a post, returning only those that weren't already recorded
+  async fn create_post_mentions(&self, post_id: &Uuid, user_ids: &[Uuid]) -> Result<Vec<Uuid>, LogicErr>;
+  async fn fetch_post_mentions(&self, post_id: &Uuid) -> Result<Vec<PostMention>, LogicErr>;
+}
+
+pub type PostMentionPool = Arc<dyn PostMentionRepo + Send + Sync>;
+
+pub struct DbPostMentionRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl PostMentionRepo for DbPostMentionRepo {
+  async fn create_post_mentions(&self, post_id: &Uuid, user_ids: &[Uuid]) -> Result<Vec<Uuid>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        "INSERT INTO post_mentions (post_id, user_id) SELECT $1, UNNEST($2::uuid[])
+          ON CONFLICT DO NOTHING RETURNING user_id",
+        &[&post_id, &user_ids],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().map(|row| row.get("user_id")).collect())
+  }
+
+  async fn fetch_post_mentions(&self, post_id: &Uuid) -> Result<Vec<PostMention>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(include_str!("./sql/fetch_post_mentions.sql"), &[&post_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(PostMention::from_row).collect())
+  }
+}
@@ -1,7 +1,10 @@
 use crate::{
   helpers::api::map_db_err,
   logic::LogicErr,
-  model::{access_type::AccessType, post::Post, post_event::PostEvent},
+  model::{
+    access_type::AccessType, event_type::EventType, page_cursor::PageCursor, post::Post, post_event::PostEvent,
+    post_mention::PostMention, stream_type::StreamType,
+  },
 };
 
 use async_trait::async_trait;
@@ -13,16 +16,35 @@ use uuid::Uuid;
 use mockall::automock;
 
 use super::{FromRow, FromRows};
+/// Feeds are fetched newest first, starting after `cursor` when one is given
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait PostRepo {
-  async fn fetch_user_own_feed(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr>;
+  async fn fetch_user_own_feed(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr>;
   /// Fetches the count of the posts in the user's feed from their own perspective, i.e. all of the posts they have submitted
   async fn count_user_own_feed(&self, user_id: &Uuid) -> Result<i64, LogicErr>;
-  async fn fetch_user_friends_feed(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr>;
+  async fn fetch_user_friends_feed(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr>;
   async fn count_user_friends_feed(&self, user_id: &Uuid) -> Result<i64, LogicErr>;
   /// Fetches the user's federated feed, i.e. what users on any server can see
-  async fn fetch_user_federated_feed(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr>;
+  async fn fetch_user_federated_feed(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr>;
   /// Fetches the count of the user's posts in their federated feed, i.e.
   /// what users on any server can see
   async fn count_user_federated_feed(&self, user_id: &Uuid) -> Result<i64, LogicErr>;
@@ -32,6 +54,7 @@ pub trait PostRepo {
     &self,
     target_user_id: &Uuid,
     own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<PostEvent>, LogicErr>;
@@ -39,22 +62,75 @@ pub trait PostRepo {
   /// user can see, or alternatively all the user's public posts
   async fn count_user_public_feed(&self, target_user_id: &Uuid, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr>;
   /// Fetches the global federated feed, i.e. what users not signed into this instance can see
-  async fn fetch_global_federated_feed(&self, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr>;
+  async fn fetch_global_federated_feed(
+    &self,
+    own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr>;
   /// Fetches the post count for the global federated feed, i.e. what users not signed into this instance can see
-  async fn count_global_federated_feed(&self) -> Result<i64, LogicErr>;
+  async fn count_global_federated_feed(&self, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr>;
+  /// Fetches the public posts carrying a tag, with the same visibility rules as the global federated feed
+  async fn fetch_tag_feed(
+    &self,
+    tag: &str,
+    own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr>;
+  /// Fetches the count of public posts carrying a tag
+  async fn count_tag_feed(&self, tag: &str, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr>;
   /// Fetches the global federated orbit feed
   async fn fetch_global_federated_orbit_feed(
     &self,
     orbit_id: &Uuid,
+    own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<PostEvent>, LogicErr>;
   /// Fetches the post count for the global federated orbit feed
-  async fn count_global_federated_orbit_feed(&self, orbit_id: &Uuid) -> Result<i64, LogicErr>;
+  async fn count_global_federated_orbit_feed(
+    &self,
+    orbit_id: &Uuid,
+    own_user_id: &Option<Uuid>,
+  ) -> Result<i64, LogicErr>;
+  /// Fetches the user's ActivityPub outbox, i.e. the posts and boosts they've made that federate to other servers
+  async fn fetch_user_outbox(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr>;
+  /// Fetches the count of activities in the user's ActivityPub outbox
+  async fn count_user_outbox(&self, user_id: &Uuid) -> Result<i64, LogicErr>;
+  /// Fetches the orbit's ActivityPub outbox, i.e. the posts made in the orbit that federate to other servers
+  async fn fetch_orbit_outbox(
+    &self,
+    orbit_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr>;
+  /// Fetches the count of activities in the orbit's ActivityPub outbox
+  async fn count_orbit_outbox(&self, orbit_id: &Uuid) -> Result<i64, LogicErr>;
   async fn fetch_by_id(&self, id: &Uuid) -> Result<Post, LogicErr>;
-  /// Fetches the specified post from a user's own perspective
+  /// Fetches the specified post from a user's own perspective, along with the users it mentions and its tags
   async fn fetch_post(&self, post_id: &Uuid, user_id: &Option<Uuid>) -> Result<Option<PostEvent>, LogicErr>;
   async fn fetch_post_from_uri(&self, post_uri: &str, user_id: &Option<Uuid>) -> Result<Option<PostEvent>, LogicErr>;
+  /// Fetches a post as it should be streamed to a user, or nothing if the user's blocks, mutes or filters hide it.
+  /// Silenced accounts are only hidden from the federated stream, as they are from the federated feed.
+  async fn fetch_stream_post(
+    &self,
+    post_id: &Uuid,
+    source_user_id: &Uuid,
+    event_type: &EventType,
+    user_id: &Option<Uuid>,
+    stream: &StreamType,
+  ) -> Result<Option<PostEvent>, LogicErr>;
   async fn create_post(
     &self,
     user_id: &Uuid,
@@ -79,6 +155,7 @@ pub trait PostRepo {
     &self,
     target_user_id: &Uuid,
     own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<PostEvent>, LogicErr>;
@@ -101,17 +178,35 @@ pub struct DbPostRepo {
 
 #[async_trait]
 impl PostRepo for DbPostRepo {
-  async fn fetch_user_own_feed(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr> {
+  async fn fetch_user_own_feed(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
       .query(
         include_str!("./sql/fetch_user_own_feed.sql"),
-        &[&user_id, &limit, &skip],
+        &[
+          &user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
       )
       .await
       .map_err(map_db_err)?;
 
-    PostEvent::from_rows(rows)
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
   }
 
   async fn count_user_own_feed(&self, user_id: &Uuid) -> Result<i64, LogicErr> {
@@ -124,17 +219,35 @@ impl PostRepo for DbPostRepo {
     Ok(row.get(0))
   }
 
-  async fn fetch_user_friends_feed(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr> {
+  async fn fetch_user_friends_feed(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
       .query(
         include_str!("./sql/fetch_user_friend_feed.sql"),
-        &[&user_id, &limit, &skip],
+        &[
+          &user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
       )
       .await
       .map_err(map_db_err)?;
 
-    PostEvent::from_rows(rows)
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
   }
 
   async fn count_user_friends_feed(&self, user_id: &Uuid) -> Result<i64, LogicErr> {
@@ -147,17 +260,35 @@ impl PostRepo for DbPostRepo {
     Ok(row.get(0))
   }
 
-  async fn fetch_user_federated_feed(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr> {
+  async fn fetch_user_federated_feed(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
       .query(
         include_str!("./sql/fetch_user_federated_feed.sql"),
-        &[&user_id, &limit, &skip],
+        &[
+          &user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
       )
       .await
       .map_err(map_db_err)?;
 
-    PostEvent::from_rows(rows)
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
   }
 
   async fn count_user_federated_feed(&self, user_id: &Uuid) -> Result<i64, LogicErr> {
@@ -174,6 +305,7 @@ impl PostRepo for DbPostRepo {
     &self,
     target_user_id: &Uuid,
     own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<PostEvent>, LogicErr> {
@@ -181,12 +313,25 @@ impl PostRepo for DbPostRepo {
     let rows = db
       .query(
         include_str!("./sql/fetch_user_public_feed.sql"),
-        &[&target_user_id, &own_user_id, &limit, &skip],
+        &[
+          &target_user_id,
+          &own_user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
       )
       .await
       .map_err(map_db_err)?;
 
-    PostEvent::from_rows(rows)
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
   }
 
   async fn count_user_public_feed(&self, target_user_id: &Uuid, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr> {
@@ -202,20 +347,84 @@ impl PostRepo for DbPostRepo {
     Ok(row.get(0))
   }
 
-  async fn fetch_global_federated_feed(&self, limit: i64, skip: i64) -> Result<Vec<PostEvent>, LogicErr> {
+  async fn fetch_global_federated_feed(
+    &self,
+    own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
-      .query(include_str!("./sql/fetch_global_federated_feed.sql"), &[&limit, &skip])
+      .query(
+        include_str!("./sql/fetch_global_federated_feed.sql"),
+        &[
+          &own_user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
+      )
       .await
       .map_err(map_db_err)?;
 
-    PostEvent::from_rows(rows)
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
   }
 
-  async fn count_global_federated_feed(&self) -> Result<i64, LogicErr> {
+  async fn count_global_federated_feed(&self, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let row = db
-      .query_one(include_str!("./sql/count_global_federated_feed.sql"), &[])
+      .query_one(include_str!("./sql/count_global_federated_feed.sql"), &[&own_user_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn fetch_tag_feed(
+    &self,
+    tag: &str,
+    own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        include_str!("./sql/fetch_tag_feed.sql"),
+        &[
+          &tag,
+          &own_user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
+  }
+
+  async fn count_tag_feed(&self, tag: &str, own_user_id: &Option<Uuid>) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(include_str!("./sql/count_tag_feed.sql"), &[&tag, &own_user_id])
       .await
       .map_err(map_db_err)?;
 
@@ -225,22 +434,126 @@ impl PostRepo for DbPostRepo {
   async fn fetch_global_federated_orbit_feed(
     &self,
     orbit_id: &Uuid,
+    own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        include_str!("./sql/fetch_orbit_feed.sql"),
+        &[
+          &orbit_id,
+          &own_user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
+  }
+
+  async fn count_global_federated_orbit_feed(
+    &self,
+    orbit_id: &Uuid,
+    own_user_id: &Option<Uuid>,
+  ) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(include_str!("./sql/count_orbit_feed.sql"), &[&orbit_id, &own_user_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn fetch_user_outbox(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<PostEvent>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
-      .query(include_str!("./sql/fetch_orbit_feed.sql"), &[&orbit_id, &limit, &skip])
+      .query(
+        include_str!("./sql/fetch_user_outbox.sql"),
+        &[
+          &user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
+      )
       .await
       .map_err(map_db_err)?;
 
-    PostEvent::from_rows(rows)
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
   }
 
-  async fn count_global_federated_orbit_feed(&self, orbit_id: &Uuid) -> Result<i64, LogicErr> {
+  async fn count_user_outbox(&self, user_id: &Uuid) -> Result<i64, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let row = db
-      .query_one(include_str!("./sql/count_orbit_feed.sql"), &[&orbit_id])
+      .query_one(include_str!("./sql/count_user_outbox.sql"), &[&user_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn fetch_orbit_outbox(
+    &self,
+    orbit_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<PostEvent>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        include_str!("./sql/fetch_orbit_outbox.sql"),
+        &[
+          &orbit_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
+  }
+
+  async fn count_orbit_outbox(&self, orbit_id: &Uuid) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(include_str!("./sql/count_orbit_outbox.sql"), &[&orbit_id])
       .await
       .map_err(map_db_err)?;
 
@@ -271,6 +584,54 @@ impl PostRepo for DbPostRepo {
 
     let mut posts = PostEvent::from_rows(rows)?;
 
+    let mut post = match posts.len() {
+      1 => posts.remove(0),
+      _ => return Ok(None),
+    };
+
+    let rows = db
+      .query(include_str!("./sql/fetch_post_mentions.sql"), &[&post_id])
+      .await
+      .map_err(map_db_err)?;
+
+    post.mentions = rows.into_iter().flat_map(PostMention::from_row).collect();
+
+    let rows = db
+      .query(include_str!("./sql/fetch_post_tags.sql"), &[&post_id])
+      .await
+      .map_err(map_db_err)?;
+
+    post.tags = rows.into_iter().map(|row| row.get("name")).collect();
+
+    Ok(Some(post))
+  }
+
+  async fn fetch_stream_post(
+    &self,
+    post_id: &Uuid,
+    source_user_id: &Uuid,
+    event_type: &EventType,
+    user_id: &Option<Uuid>,
+    stream: &StreamType,
+  ) -> Result<Option<PostEvent>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+
+    let rows = db
+      .query(
+        include_str!("./sql/fetch_stream_post.sql"),
+        &[
+          &post_id,
+          &source_user_id,
+          &event_type.to_string(),
+          &user_id,
+          &stream.to_string(),
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    let mut posts = PostEvent::from_rows(rows)?;
+
     match posts.len() {
       1 => Ok(Some(posts.remove(0))),
       _ => Ok(None),
@@ -503,6 +864,7 @@ impl PostRepo for DbPostRepo {
     &self,
     target_user_id: &Uuid,
     own_user_id: &Option<Uuid>,
+    cursor: &PageCursor,
     limit: i64,
     skip: i64,
   ) -> Result<Vec<PostEvent>, LogicErr> {
@@ -510,12 +872,25 @@ impl PostRepo for DbPostRepo {
     let rows = db
       .query(
         include_str!("./sql/fetch_user_public_likes_feed.sql"),
-        &[&target_user_id, &own_user_id, &limit, &skip],
+        &[
+          &target_user_id,
+          &own_user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
       )
       .await
       .map_err(map_db_err)?;
 
-    PostEvent::from_rows(rows)
+    let mut posts = PostEvent::from_rows(rows)?;
+    if cursor.walks_oldest_first() {
+      posts.reverse();
+    }
+
+    Ok(posts)
   }
 
   async fn count_user_public_likes_feed(
@@ -0,0 +1,162 @@
+use super::FromRow;
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::report::{NewReport, Report, ReportStatus},
+};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait ReportRepo {
+  async fn create_report(&self, report: &NewReport) -> Result<Report, LogicErr>;
+  async fn fetch_report(&self, report_id: &Uuid) -> Result<Option<Report>, LogicErr>;
+  /// Fetches a moderation queue, either the one for an orbit's moderators or, when no orbit is given, the admins' one
+  async fn fetch_reports(
+    &self,
+    moderator_orbit_id: &Option<Uuid>,
+    status: &Option<ReportStatus>,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<Report>, LogicErr>;
+  async fn count_reports(
+    &self,
+    moderator_orbit_id: &Option<Uuid>,
+    status: &Option<ReportStatus>,
+  ) -> Result<i64, LogicErr>;
+  async fn update_report_status(
+    &self,
+    report_id: &Uuid,
+    status: &ReportStatus,
+    resolved_by_id: &Uuid,
+    resolution_note: &Option<String>,
+  ) -> Result<Report, LogicErr>;
+}
+
+pub type ReportPool = Arc<dyn ReportRepo + Send + Sync>;
+
+pub struct DbReportRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl ReportRepo for DbReportRepo {
+  async fn create_report(&self, report: &NewReport) -> Result<Report, LogicErr> {
+    let report_id = Uuid::new_v4();
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        r#"INSERT INTO reports (report_id, reporter_id, target_user_id, post_id, comment_id, orbit_id, moderator_orbit_id,
+        reason, is_external, forwarded, uri) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *"#,
+        &[
+          &report_id,
+          &report.reporter_id,
+          &report.target_user_id,
+          &report.post_id,
+          &report.comment_id,
+          &report.orbit_id,
+          &report.moderator_orbit_id,
+          &report.reason,
+          &report.is_external,
+          &report.forwarded,
+          &report.uri,
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    match Report::from_row(row) {
+      Some(report) => Ok(report),
+      None => Err(LogicErr::InternalError("Failed to read back report".to_string())),
+    }
+  }
+
+  async fn fetch_report(&self, report_id: &Uuid) -> Result<Option<Report>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt("SELECT * FROM reports WHERE report_id = $1", &[&report_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.and_then(Report::from_row))
+  }
+
+  async fn fetch_reports(
+    &self,
+    moderator_orbit_id: &Option<Uuid>,
+    status: &Option<ReportStatus>,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<Report>, LogicErr> {
+    let status = status.map(|status| status.to_string());
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT * FROM reports
+        WHERE moderator_orbit_id IS NOT DISTINCT FROM $1
+        AND ($2::varchar IS NULL OR status = $2)
+        ORDER BY created_at DESC
+        LIMIT $3
+        OFFSET $4"#,
+        &[&moderator_orbit_id, &status, &limit, &skip],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(Report::from_row).collect())
+  }
+
+  async fn count_reports(
+    &self,
+    moderator_orbit_id: &Option<Uuid>,
+    status: &Option<ReportStatus>,
+  ) -> Result<i64, LogicErr> {
+    let status = status.map(|status| status.to_string());
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_one(
+        r#"SELECT COUNT(*) FROM reports
+        WHERE moderator_orbit_id IS NOT DISTINCT FROM $1
+        AND ($2::varchar IS NULL OR status = $2)"#,
+        &[&moderator_orbit_id, &status],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+
+  async fn update_report_status(
+    &self,
+    report_id: &Uuid,
+    status: &ReportStatus,
+    resolved_by_id: &Uuid,
+    resolution_note: &Option<String>,
+  ) -> Result<Report, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let row = db
+      .query_opt(
+        r#"UPDATE reports SET status = $2, resolved_by_id = $3, resolution_note = COALESCE($4, resolution_note),
+        updated_at = NOW() WHERE report_id = $1 RETURNING *"#,
+        &[&report_id, &status.to_string(), &resolved_by_id, &resolution_note],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    match row.and_then(Report::from_row) {
+      Some(report) => Ok(report),
+      None => Err(LogicErr::MissingRecord),
+    }
+  }
+}
@@ -1,28 +1,45 @@
 use deadpool_postgres::Pool;
 
 use super::{
-  app_repository::AppPool, comment_repository::CommentPool, event_repository::EventPool, follow_repository::FollowPool,
-  job_repository::JobPool, like_repository::LikePool, orbit_moderator_repository::OrbitModeratorPool,
-  orbit_repository::OrbitPool, post_attachment_repository::PostAttachmentPool, post_repository::PostPool,
-  repository::Repository, session_repository::SessionPool, tombstone_repository::TombstonePool,
-  user_orbit_repository::UserOrbitPool, user_repository::UserPool, user_stats_repository::UserStatsPool,
+  account_restriction_repository::AccountRestrictionPool, app_repository::AppPool, block_repository::BlockPool,
+  comment_repository::CommentPool, delivery_repository::DeliveryPool, domain_policy_repository::DomainPolicyPool,
+  event_repository::EventPool, follow_repository::FollowPool, job_repository::JobPool, like_repository::LikePool,
+  mute_repository::MutePool, notification_repository::NotificationPool,
+  orbit_membership_repository::OrbitMembershipPool, orbit_moderation_repository::OrbitModerationPool,
+  orbit_moderator_repository::OrbitModeratorPool, orbit_repository::OrbitPool,
+  post_attachment_repository::PostAttachmentPool, post_mention_repository::PostMentionPool, post_repository::PostPool,
+  report_repository::ReportPool, repository::Repository, search_repository::SearchPool,
+  session_repository::SessionPool, tombstone_repository::TombstonePool, user_orbit_repository::UserOrbitPool,
+  user_repository::UserPool, user_stats_repository::UserStatsPool,
 };
 
 #[derive(Clone)]
 pub struct Repositories {
   pool: Pool,
+  pub account_restrictions: AccountRestrictionPool,
   pub apps: AppPool,
+  pub blocks: BlockPool,
   pub comments: CommentPool,
+  pub deliveries: DeliveryPool,
+  pub domain_policies: DomainPolicyPool,
   pub events: EventPool,
   pub follows: FollowPool,
   pub jobs: JobPool,
   pub likes: LikePool,
+  pub mutes: MutePool,
+  pub notifications: NotificationPool,
   pub posts: PostPool,
   pub post_attachments: PostAttachmentPool,
+  pub post_mentions: PostMentionPool,
+  pub reports: ReportPool,
+  pub searches: SearchPool,
   pub sessions: SessionPool,
+  pub tags: TagPool,
   pub users: UserPool,
   pub user_stats: UserStatsPool,
   pub orbits: OrbitPool,
+  pub orbit_membership: OrbitMembershipPool,
+  pub orbit_moderation: OrbitModerationPool,
   pub orbit_moderators: OrbitModeratorPool,
   pub user_orbits: UserOrbitPool,
   pub tombstones: TombstonePool,
@@ -31,18 +48,30 @@ pub struct Repositories {
 impl Repositories {
   pub fn new(db: Pool) -> Self {
     Repositories {
+      account_restrictions: Repository::new_account_restriction_pool(&db),
       apps: Repository::new_app_pool(&db),
+      blocks: Repository::new_block_pool(&db),
       comments: Repository::new_comment_pool(&db),
+      deliveries: Repository::new_delivery_pool(&db),
+      domain_policies: Repository::new_domain_policy_pool(&db),
       events: Repository::new_event_pool(&db),
       follows: Repository::new_follow_pool(&db),
       jobs: Repository::new_job_pool(&db),
       likes: Repository::new_like_pool(&db),
+      mutes: Repository::new_mute_pool(&db),
+      notifications: Repository::new_notification_pool(&db),
       posts: Repository::new_post_pool(&db),
       post_attachments: Repository::new_post_attachment_pool(&db),
+      post_mentions: Repository::new_post_mention_pool(&db),
+      reports: Repository::new_report_pool(&db),
+      searches: Repository::new_search_pool(&db),
       sessions: Repository::new_session_pool(&db),
+      tags: Repository::new_tag_pool(&db),
       users: Repository::new_user_pool(&db),
       user_stats: Repository::new_user_stats_pool(&db),
       orbits: Repository::new_orbit_pool(&db),
+      orbit_membership: Repository::new_orbit_membership_pool(&db),
+      orbit_moderation: Repository::new_orbit_moderation_pool(&db),
       orbit_moderators: Repository::new_orbit_moderator_pool(&db),
       user_orbits: Repository::new_user_orbit_pool(&db),
       tombstones: Repository::new_tombstone_pool(&db),
@@ -3,17 +3,30 @@ use std::sync::Arc;
 use deadpool_postgres::Pool;
 
 use super::{
+  account_restriction_repository::{AccountRestrictionPool, DbAccountRestrictionRepo},
   app_repository::{AppPool, DbAppRepo},
+  authorization_code_repository::{AuthorizationCodePool, DbAuthorizationCodeRepo},
+  block_repository::{BlockPool, DbBlockRepo},
   comment_repository::{CommentPool, DbCommentRepo},
+  delivery_repository::{DbDeliveryRepo, DeliveryPool},
+  domain_policy_repository::{DbDomainPolicyRepo, DomainPolicyPool},
   event_repository::{DbEventRepo, EventPool},
   follow_repository::{DbFollowRepo, FollowPool},
   job_repository::{DbJobRepo, JobPool},
   like_repository::{DbLikeRepo, LikePool},
+  mute_repository::{DbMuteRepo, MutePool},
+  notification_repository::{DbNotificationRepo, NotificationPool},
+  orbit_membership_repository::{DbOrbitMembershipRepo, OrbitMembershipPool},
+  orbit_moderation_repository::{DbOrbitModerationRepo, OrbitModerationPool},
   orbit_moderator_repository::{DbOrbitModeratorRepo, OrbitModeratorPool},
   orbit_repository::{DbOrbitRepo, OrbitPool},
   post_attachment_repository::{DbPostAttachmentRepo, PostAttachmentPool},
+  post_mention_repository::{DbPostMentionRepo, PostMentionPool},
   post_repository::{DbPostRepo, PostPool},
+  report_repository::{DbReportRepo, ReportPool},
+  search_repository::{DbSearchRepo, SearchPool},
   session_repository::{DbSessionRepo, SessionPool},
+  tag_repository::{DbTagRepo, TagPool},
   tombstone_repository::{DbTombstoneRepo, TombstonePool},
   user_orbit_repository::{DbUserOrbitRepo, UserOrbitPool},
   user_repository::{DbUserRepo, UserPool},
@@ -23,14 +36,34 @@ use super::{
 pub struct Repository {}
 
 impl Repository {
+  pub fn new_account_restriction_pool(db: &Pool) -> AccountRestrictionPool {
+    Arc::new(DbAccountRestrictionRepo { db: db.clone() })
+  }
+
   pub fn new_app_pool(db: &Pool) -> AppPool {
     Arc::new(DbAppRepo { db: db.clone() })
   }
 
+  pub fn new_authorization_code_pool(db: &Pool) -> AuthorizationCodePool {
+    Arc::new(DbAuthorizationCodeRepo { db: db.clone() })
+  }
+
+  pub fn new_block_pool(db: &Pool) -> BlockPool {
+    Arc::new(DbBlockRepo { db: db.clone() })
+  }
+
   pub fn new_comment_pool(db: &Pool) -> CommentPool {
     Arc::new(DbCommentRepo { db: db.clone() })
   }
 
+  pub fn new_delivery_pool(db: &Pool) -> DeliveryPool {
+    Arc::new(DbDeliveryRepo { db: db.clone() })
+  }
+
+  pub fn new_domain_policy_pool(db: &Pool) -> DomainPolicyPool {
+    Arc::new(DbDomainPolicyRepo { db: db.clone() })
+  }
+
   pub fn new_event_pool(db: &Pool) -> EventPool {
     Arc::new(DbEventRepo { db: db.clone() })
   }
@@ -55,6 +88,10 @@ impl Repository {
     Arc::new(DbPostAttachmentRepo { db: db.clone() })
   }
 
+  pub fn new_report_pool(db: &Pool) -> ReportPool {
+    Arc::new(DbReportRepo { db: db.clone() })
+  }
+
   pub fn new_session_pool(db: &Pool) -> SessionPool {
     Arc::new(DbSessionRepo { db: db.clone() })
   }
@@ -71,6 +108,26 @@ impl Repository {
     Arc::new(DbOrbitRepo { db: db.clone() })
   }
 
+  pub fn new_mute_pool(db: &Pool) -> MutePool {
+    Arc::new(DbMuteRepo { db: db.clone() })
+  }
+
+  pub fn new_notification_pool(db: &Pool) -> NotificationPool {
+    Arc::new(DbNotificationRepo { db: db.clone() })
+  }
+
+  pub fn new_post_mention_pool(db: &Pool) -> PostMentionPool {
+    Arc::new(DbPostMentionRepo { db: db.clone() })
+  }
+
+  pub fn new_orbit_membership_pool(db: &Pool) -> OrbitMembershipPool {
+    Arc::new(DbOrbitMembershipRepo { db: db.clone() })
+  }
+
+  pub fn new_orbit_moderation_pool(db: &Pool) -> OrbitModerationPool {
+    Arc::new(DbOrbitModerationRepo { db: db.clone() })
+  }
+
   pub fn new_orbit_moderator_pool(db: &Pool) -> OrbitModeratorPool {
     Arc::new(DbOrbitModeratorRepo { db: db.clone() })
   }
@@ -79,6 +136,14 @@ impl Repository {
     Arc::new(DbUserOrbitRepo { db: db.clone() })
   }
 
+  pub fn new_search_pool(db: &Pool) -> SearchPool {
+    Arc::new(DbSearchRepo { db: db.clone() })
+  }
+
+  pub fn new_tag_pool(db: &Pool) -> TagPool {
+    Arc::new(DbTagRepo { db: db.clone() })
+  }
+
   pub fn new_tombstone_pool(db: &Pool) -> TombstonePool {
     Arc::new(DbTombstoneRepo { db: db.clone() })
   }
@@ -0,0 +1,85 @@
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::{search_hit::SearchHit, search_result_type::SearchResultType},
+};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+use super::FromRow;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait SearchRepo {
+  /// Ranks the posts, comments, users and orbits matching a full-text search term, leaving out anything the searching
+  /// user isn't allowed to see. Only results of `result_type` are returned if one is given.
+  async fn search(
+    &self,
+    term: &str,
+    own_user_id: &Option<Uuid>,
+    result_type: &Option<SearchResultType>,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<SearchHit>, LogicErr>;
+  async fn count_search(
+    &self,
+    term: &str,
+    own_user_id: &Option<Uuid>,
+    result_type: &Option<SearchResultType>,
+  ) -> Result<i64, LogicErr>;
+}
+
+pub type SearchPool = Arc<dyn SearchRepo + Send + Sync>;
+
+pub struct DbSearchRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl SearchRepo for DbSearchRepo {
+  async fn search(
+    &self,
+    term: &str,
+    own_user_id: &Option<Uuid>,
+    result_type: &Option<SearchResultType>,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<SearchHit>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let result_type = result_type.map(|t| t.to_string());
+    let rows = db
+      .query(
+        include_str!("./sql/search_hits.sql"),
+        &[&term, &own_user_id, &result_type, &limit, &skip],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().flat_map(SearchHit::from_row).collect())
+  }
+
+  async fn count_search(
+    &self,
+    term: &str,
+    own_user_id: &Option<Uuid>,
+    result_type: &Option<SearchResultType>,
+  ) -> Result<i64, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let result_type = result_type.map(|t| t.to_string());
+    let row = db
+      .query_one(
+        include_str!("./sql/count_search_hits.sql"),
+        &[&term, &own_user_id, &result_type],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(row.get(0))
+  }
+}
@@ -13,7 +13,8 @@ use crate::logic::LogicErr;
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait SessionRepo {
-  async fn query_session_exists_for_refresh_token(&self, refresh_token: &str) -> bool;
+  /// Fetches the scopes granted to the session a refresh token belongs to, if it can still be refreshed
+  async fn fetch_refresh_token_scopes(&self, refresh_token: &str) -> Option<String>;
   async fn insert_session(
     &self,
     session_id: &Uuid,
@@ -22,9 +23,11 @@ pub trait SessionRepo {
     refresh_token: &str,
     access_expires_at: &DateTime<Utc>,
     refresh_expires_at: &DateTime<Utc>,
+    scopes: &str,
   ) -> Result<(), LogicErr>;
   async fn delete_session(&self, user_id: &Uuid, app_id: &Uuid, refresh_token: &str) -> Result<(), LogicErr>;
-  async fn query_session_exists(&self, session_id: &Uuid) -> bool;
+  /// Fetches the scopes granted to a session, if it's still active
+  async fn fetch_session_scopes(&self, session_id: &Uuid) -> Option<String>;
 }
 
 pub type SessionPool = Arc<dyn SessionRepo + Send + Sync>;
@@ -35,25 +38,29 @@ pub struct DbSessionRepo {
 
 #[async_trait]
 impl SessionRepo for DbSessionRepo {
-  async fn query_session_exists_for_refresh_token(&self, refresh_token: &str) -> bool {
+  async fn fetch_refresh_token_scopes(&self, refresh_token: &str) -> Option<String> {
     let db = match self.db.get().await.map_err(map_db_err) {
       Ok(db) => db,
-      Err(_) => return false,
+      Err(_) => return None,
     };
 
     let row = match db
-      .query_one(
-        "SELECT COUNT(*) > 0 from sessions WHERE refresh_token = $1 AND refresh_expires_at > NOW()",
+      .query_opt(
+        r#"SELECT s.scopes FROM sessions s WHERE s.refresh_token = $1 AND s.refresh_expires_at > NOW()
+        AND NOT EXISTS (
+          SELECT 1 FROM account_restrictions ar WHERE ar.user_id = s.user_id AND ar.restriction = 'suspend'
+          AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+        )"#,
         &[&refresh_token],
       )
       .await
       .map_err(map_db_err)
     {
       Ok(row) => row,
-      Err(_) => return false,
+      Err(_) => return None,
     };
 
-    row.get(0)
+    row.map(|row| row.get(0))
   }
 
   async fn insert_session(
@@ -64,11 +71,12 @@ impl SessionRepo for DbSessionRepo {
     refresh_token: &str,
     access_expires_at: &DateTime<Utc>,
     refresh_expires_at: &DateTime<Utc>,
+    scopes: &str,
   ) -> Result<(), LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     db.execute(
-      r#"INSERT INTO sessions (session_id, user_id, app_id, refresh_token, access_expires_at, refresh_expires_at)
-      VALUES ($1, $2, $3, $4, $5, $6)"#,
+      r#"INSERT INTO sessions (session_id, user_id, app_id, refresh_token, access_expires_at, refresh_expires_at, scopes)
+      VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
       &[
         &session_id,
         &user_id,
@@ -76,6 +84,7 @@ impl SessionRepo for DbSessionRepo {
         &refresh_token,
         &access_expires_at,
         &refresh_expires_at,
+        &scopes,
       ],
     )
     .await
@@ -96,24 +105,28 @@ impl SessionRepo for DbSessionRepo {
     Ok(())
   }
 
-  async fn query_session_exists(&self, session_id: &Uuid) -> bool {
+  async fn fetch_session_scopes(&self, session_id: &Uuid) -> Option<String> {
     let db = match self.db.get().await.map_err(map_db_err) {
       Ok(db) => db,
-      Err(_) => return false,
+      Err(_) => return None,
     };
 
     let row = match db
-      .query_one(
-        "SELECT COUNT(*) > 0 from sessions WHERE session_id = $1 AND access_expires_at > NOW()",
+      .query_opt(
+        r#"SELECT s.scopes FROM sessions s WHERE s.session_id = $1 AND s.access_expires_at > NOW()
+        AND NOT EXISTS (
+          SELECT 1 FROM account_restrictions ar WHERE ar.user_id = s.user_id AND ar.restriction = 'suspend'
+          AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+        )"#,
         &[&session_id],
       )
       .await
       .map_err(map_db_err)
     {
       Ok(row) => row,
-      Err(_) => return false,
+      Err(_) => return None,
     };
 
-    row.get(0)
+    row.map(|row| row.get(0))
   }
 }
@@ -0,0 +1,9 @@
+UPDATE deliveries SET next_attempt_at = now() + INTERVAL '15 minutes', updated_at = now()
+WHERE delivery_id IN (
+  SELECT delivery_id FROM deliveries
+  WHERE status = 'pending' AND next_attempt_at <= now()
+  ORDER BY next_attempt_at ASC
+  LIMIT $1
+  FOR UPDATE SKIP LOCKED
+)
+RETURNING *
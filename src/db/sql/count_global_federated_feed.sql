@@ -1,3 +1,48 @@
 SELECT COUNT(DISTINCT e.post_id) FROM events e
+INNER JOIN posts p
+ON p.post_id = e.post_id
+INNER JOIN users u
+ON u.user_id = p.user_id
+INNER JOIN users u2
+ON u2.user_id = e.source_user_id
 WHERE e.target_user_id IS NULL
 AND e.visibility IN ('public_federated', 'public_local')
+AND NOT EXISTS (
+  SELECT 1 FROM domain_policies dp
+  CROSS JOIN (VALUES (u.fediverse_uri), (u2.fediverse_uri)) AS actor(fediverse_uri)
+  CROSS JOIN LATERAL (SELECT LOWER(SUBSTRING(actor.fediverse_uri FROM '^https?://([^/:]+)')) AS host) h
+  WHERE dp.policy = 'silence'
+  AND (h.host = dp.domain OR h.host LIKE '%.' || dp.domain)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $1 AND b.blocked_user_id IN (p.user_id, e.source_user_id))
+  OR (b.blocked_user_id = $1 AND b.user_id IN (p.user_id, e.source_user_id))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $1
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+  AND ((um.muted_user_id = p.user_id AND NOT um.boosts_only)
+    OR (um.muted_user_id = e.source_user_id AND e.event_type = 'boost'))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM orbit_mutes om
+  WHERE om.user_id = $1
+  AND om.orbit_id = p.orbit_id
+)
+AND NOT EXISTS (
+  SELECT 1 FROM keyword_filters kf
+  WHERE kf.user_id = $1
+  AND (kf.expires_at IS NULL OR kf.expires_at > NOW())
+  AND CASE
+    WHEN kf.is_regex THEN (COALESCE(p.title, '') || ' ' || p.content_md) ~* kf.phrase
+    ELSE POSITION(LOWER(kf.phrase) IN LOWER(COALESCE(p.title, '') || ' ' || p.content_md)) > 0
+  END
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id IN (p.user_id, e.source_user_id)
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction IN ('silence', 'shadow') AND ar.user_id IS DISTINCT FROM $1))
+)
@@ -1,2 +1,31 @@
-SELECT COUNT(*) FROM posts WHERE orbit_id = $1
-AND visibility IN ('public_federated', 'public_local')
+SELECT COUNT(*) FROM posts p WHERE p.orbit_id = $1
+AND (p.visibility IN ('public_federated', 'public_local')
+  OR (p.visibility = 'members_only' AND EXISTS (
+    SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $2
+  )))
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $2 AND b.blocked_user_id = p.user_id)
+  OR (b.blocked_user_id = $2 AND b.user_id = p.user_id)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $2
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+  AND ((um.muted_user_id = p.user_id AND NOT um.boosts_only))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM keyword_filters kf
+  WHERE kf.user_id = $2
+  AND (kf.expires_at IS NULL OR kf.expires_at > NOW())
+  AND CASE
+    WHEN kf.is_regex THEN (COALESCE(p.title, '') || ' ' || p.content_md) ~* kf.phrase
+    ELSE POSITION(LOWER(kf.phrase) IN LOWER(COALESCE(p.title, '') || ' ' || p.content_md)) > 0
+  END
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id = p.user_id
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction = 'shadow' AND ar.user_id IS DISTINCT FROM $2))
+)
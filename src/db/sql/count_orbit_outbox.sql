@@ -0,0 +1,8 @@
+SELECT COUNT(*) FROM posts p WHERE p.orbit_id = $1
+AND p.visibility IN ('public_federated', 'unlisted')
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id = p.user_id
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND ar.restriction IN ('suspend', 'shadow')
+)
@@ -0,0 +1,113 @@
+SELECT COUNT(*) FROM (
+WITH q AS (SELECT websearch_to_tsquery('simple', $1) AS query)
+SELECT 'post' AS result_type, p.post_id AS id, NULL::uuid AS post_id, ts_rank(p.search_vector, q.query) AS rank
+FROM posts p
+CROSS JOIN q
+INNER JOIN users u
+ON u.user_id = p.user_id
+WHERE ($3::text IS NULL OR $3 = 'post')
+AND p.search_vector @@ q.query
+AND (p.visibility IN ('public_federated', 'public_local')
+  OR p.user_id = $2
+  OR (p.visibility = 'followers_only' AND EXISTS (
+    SELECT 1 FROM followers f WHERE f.user_id = $2 AND f.following_user_id = p.user_id AND f.is_pending = false
+  ))
+  OR (p.visibility = 'members_only' AND EXISTS (
+    SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $2
+  )))
+AND NOT EXISTS (
+  SELECT 1 FROM domain_policies dp
+  CROSS JOIN LATERAL (SELECT LOWER(SUBSTRING(u.fediverse_uri FROM '^https?://([^/:]+)')) AS host) h
+  WHERE dp.policy = 'silence'
+  AND (h.host = dp.domain OR h.host LIKE '%.' || dp.domain)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $2 AND b.blocked_user_id = p.user_id)
+  OR (b.blocked_user_id = $2 AND b.user_id = p.user_id)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $2
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+  AND um.muted_user_id = p.user_id
+  AND NOT um.boosts_only
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id = p.user_id
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction IN ('silence', 'shadow') AND ar.user_id IS DISTINCT FROM $2))
+)
+UNION ALL
+SELECT 'comment' AS result_type, c.comment_id AS id, c.post_id, ts_rank(c.search_vector, q.query) AS rank
+FROM comments c
+CROSS JOIN q
+INNER JOIN posts p
+ON p.post_id = c.post_id
+INNER JOIN users u
+ON u.user_id = c.user_id
+WHERE ($3::text IS NULL OR $3 = 'comment')
+AND c.search_vector @@ q.query
+AND (p.visibility IN ('public_federated', 'public_local')
+  OR p.user_id = $2
+  OR (p.visibility = 'followers_only' AND EXISTS (
+    SELECT 1 FROM followers f WHERE f.user_id = $2 AND f.following_user_id = p.user_id AND f.is_pending = false
+  ))
+  OR (p.visibility = 'members_only' AND EXISTS (
+    SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $2
+  )))
+AND NOT EXISTS (
+  SELECT 1 FROM domain_policies dp
+  CROSS JOIN LATERAL (SELECT LOWER(SUBSTRING(u.fediverse_uri FROM '^https?://([^/:]+)')) AS host) h
+  WHERE dp.policy = 'silence'
+  AND (h.host = dp.domain OR h.host LIKE '%.' || dp.domain)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $2 AND b.blocked_user_id IN (p.user_id, c.user_id))
+  OR (b.blocked_user_id = $2 AND b.user_id IN (p.user_id, c.user_id))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $2
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+  AND um.muted_user_id IN (p.user_id, c.user_id)
+  AND NOT um.boosts_only
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id IN (p.user_id, c.user_id)
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction IN ('silence', 'shadow') AND ar.user_id IS DISTINCT FROM $2))
+)
+UNION ALL
+SELECT 'user' AS result_type, u.user_id AS id, NULL::uuid AS post_id, ts_rank(u.search_vector, q.query) AS rank
+FROM users u
+CROSS JOIN q
+WHERE ($3::text IS NULL OR $3 = 'user')
+AND u.search_vector @@ q.query
+AND NOT EXISTS (
+  SELECT 1 FROM domain_policies dp
+  CROSS JOIN LATERAL (SELECT LOWER(SUBSTRING(u.fediverse_uri FROM '^https?://([^/:]+)')) AS host) h
+  WHERE dp.policy = 'silence'
+  AND (h.host = dp.domain OR h.host LIKE '%.' || dp.domain)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $2 AND b.blocked_user_id = u.user_id)
+  OR (b.blocked_user_id = $2 AND b.user_id = u.user_id)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id = u.user_id
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction IN ('silence', 'shadow') AND ar.user_id IS DISTINCT FROM $2))
+)
+UNION ALL
+SELECT 'orbit' AS result_type, o.orbit_id AS id, NULL::uuid AS post_id, ts_rank(o.search_vector, q.query) AS rank
+FROM orbits o
+CROSS JOIN q
+WHERE ($3::text IS NULL OR $3 = 'orbit')
+AND o.search_vector @@ q.query
+) AS hits
@@ -1,4 +1,12 @@
 SELECT COUNT(DISTINCT e.post_id) FROM events e
+INNER JOIN posts p
+ON p.post_id = e.post_id
 WHERE e.source_user_id = $1
 AND e.target_user_id IS NULL
 AND e.visibility IN ('public_federated', 'public_local')
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id IN (p.user_id, e.source_user_id)
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND ar.restriction IN ('suspend', 'shadow')
+)
@@ -0,0 +1,13 @@
+SELECT COUNT(*) FROM (
+  SELECT DISTINCT e.post_id, e.event_type FROM events e
+  INNER JOIN posts p
+  ON p.post_id = e.post_id
+  WHERE e.source_user_id = $1
+  AND e.visibility IN ('public_federated', 'unlisted')
+  AND NOT EXISTS (
+    SELECT 1 FROM account_restrictions ar
+    WHERE ar.user_id IN (p.user_id, e.source_user_id)
+    AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+    AND ar.restriction IN ('suspend', 'shadow')
+  )
+) AS outbox
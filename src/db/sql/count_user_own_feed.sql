@@ -1,3 +1,37 @@
 SELECT COUNT(DISTINCT e.post_id) FROM events e
-WHERE (e.source_user_id = $1 AND e.visibility IN ('public_federated', 'public_local', 'followers_only', 'private', 'unlisted'))
-OR (e.target_user_id = $1 AND e.visibility IN ('public_federated', 'public_local', 'followers_only'))
+INNER JOIN posts p
+ON p.post_id = e.post_id
+WHERE ((e.source_user_id = $1 AND e.visibility IN ('public_federated', 'public_local', 'followers_only', 'members_only', 'private', 'unlisted', 'shadow'))
+OR (e.target_user_id = $1 AND e.visibility IN ('public_federated', 'public_local', 'followers_only', 'members_only')))
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $1 AND b.blocked_user_id IN (p.user_id, e.source_user_id))
+  OR (b.blocked_user_id = $1 AND b.user_id IN (p.user_id, e.source_user_id))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $1
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+  AND ((um.muted_user_id = p.user_id AND NOT um.boosts_only)
+    OR (um.muted_user_id = e.source_user_id AND e.event_type = 'boost'))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM orbit_mutes om
+  WHERE om.user_id = $1
+  AND om.orbit_id = p.orbit_id
+)
+AND NOT EXISTS (
+  SELECT 1 FROM keyword_filters kf
+  WHERE kf.user_id = $1
+  AND (kf.expires_at IS NULL OR kf.expires_at > NOW())
+  AND CASE
+    WHEN kf.is_regex THEN (COALESCE(p.title, '') || ' ' || p.content_md) ~* kf.phrase
+    ELSE POSITION(LOWER(kf.phrase) IN LOWER(COALESCE(p.title, '') || ' ' || p.content_md)) > 0
+  END
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id IN (p.user_id, e.source_user_id)
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction = 'shadow' AND ar.user_id IS DISTINCT FROM $1))
+)
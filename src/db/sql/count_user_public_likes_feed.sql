@@ -1,4 +1,6 @@
 SELECT COUNT(DISTINCT e.post_id) FROM events e
+INNER JOIN posts p
+ON p.post_id = e.post_id
 INNER JOIN likes l
 ON l.post_id = e.post_id
 AND l.user_id = $1
@@ -7,3 +9,14 @@ AND (
   (e.target_user_id = $2 AND e.visibility IN ('public_federated', 'public_local', 'followers_only') OR 
   (e.visibility IN ('public_federated', 'public_local'))
 ))
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $2 AND b.blocked_user_id IN (p.user_id, e.source_user_id))
+  OR (b.blocked_user_id = $2 AND b.user_id IN (p.user_id, e.source_user_id))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id IN (p.user_id, e.source_user_id)
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction = 'shadow' AND ar.user_id IS DISTINCT FROM $2))
+)
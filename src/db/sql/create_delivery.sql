@@ -0,0 +1,7 @@
+WITH job AS (
+  INSERT INTO jobs (job_id, status, failed_count) VALUES ($2, 'in_progress', 1)
+  RETURNING job_id
+)
+INSERT INTO deliveries (delivery_id, job_id, inbox_uri, actor_fediverse_uri, activity, status, attempts, next_attempt_at, last_error)
+SELECT $1, job.job_id, $3, $4, $5, 'pending', 1, $6, $7 FROM job
+RETURNING delivery_id
@@ -0,0 +1,19 @@
+INSERT INTO notifications (notification_id, user_id, source_user_id, notification_type, post_id, comment_id, orbit_id)
+SELECT $1, u.user_id, $3, $4, $5, $6, $7 FROM users u
+WHERE u.user_id = $2
+AND u.user_id != $3
+AND u.is_external = false -- remote users are notified by their own instance
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $2 AND b.blocked_user_id = $3)
+  OR (b.user_id = $3 AND b.blocked_user_id = $2)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $2
+  AND um.muted_user_id = $3
+  AND (um.boosts_only = false OR $4 = 'boost')
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+)
+ON CONFLICT DO NOTHING
+RETURNING notification_id
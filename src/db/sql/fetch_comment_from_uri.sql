@@ -0,0 +1,10 @@
+SELECT DISTINCT c.*, FALSE AS liked, count(DISTINCT ul2.comment_like_id) as likes, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, u.fediverse_uri AS user_fediverse_uri, u.avatar_url AS user_avatar_url, p.visibility as visibility, p.uri AS post_uri FROM comments c
+INNER JOIN posts p
+ON p.post_id = c.post_id
+INNER JOIN users u
+ON u.user_id = c.user_id
+LEFT OUTER JOIN (SELECT DISTINCT comment_id, comment_like_id FROM comment_likes) AS ul2
+ON ul2.comment_id = c.comment_id
+WHERE c.uri = $1
+GROUP BY c.comment_id, u.user_id, p.post_id
+LIMIT 1
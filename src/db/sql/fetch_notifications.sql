@@ -0,0 +1,17 @@
+SELECT n.*, u.handle AS source_user_handle, u.fediverse_id AS source_user_fediverse_id,
+u.avatar_url AS source_user_avatar_url
+FROM notifications n
+INNER JOIN users u
+ON u.user_id = n.source_user_id
+WHERE n.user_id = $1
+AND (cardinality($2::varchar[]) = 0 OR n.notification_type = ANY($2))
+AND NOT (n.notification_type = ANY($3::varchar[]))
+AND ($4 = false OR n.read_at IS NULL)
+AND ($5::uuid IS NULL OR (n.created_at, n.notification_id) < (
+  SELECT n2.created_at, n2.notification_id FROM notifications n2 WHERE n2.notification_id = $5
+))
+AND ($6::uuid IS NULL OR (n.created_at, n.notification_id) > (
+  SELECT n2.created_at, n2.notification_id FROM notifications n2 WHERE n2.notification_id = $6
+))
+ORDER BY n.created_at DESC, n.notification_id DESC
+LIMIT $7
@@ -1,11 +1,14 @@
-SELECT DISTINCT 'post' as event_type, p.*, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, 
+SELECT page.*, pa.attachment_id, pa.user_id as attachment_user_id, pa.post_id as attachment_post_id, pa.uri as attachment_uri, pa.width as attachment_width,
+pa.height as attachment_height, pa.content_type as attachment_content_type, pa.storage_ref as attachment_storage_ref,
+pa.blurhash as attachment_blurhash, pa.created_at as attachment_created_at
+FROM (
+SELECT 'post' as event_type, p.*, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, 
+EXISTS (SELECT 1 FROM account_restrictions ar WHERE ar.user_id = p.user_id AND ar.restriction = 'force_sensitive'
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())) AS sensitive,
 u.fediverse_uri AS user_fediverse_uri, u.avatar_url AS user_avatar_url, COUNT(DISTINCT l.like_id) AS likes, FALSE AS liked,
 COUNT(DISTINCT c.comment_id) AS comments, u.handle AS event_user_handle, u.fediverse_id AS event_user_fediverse_id, 
 u.fediverse_uri AS event_user_fediverse_uri, u.avatar_url AS event_user_avatar_url, 
-ob.name as orbit_name, ob.shortcode as orbit_shortcode, ob.uri as orbit_uri, ob.fediverse_uri as orbit_fediverse_uri, ob.avatar_uri as orbit_avatar_uri, 
-pa.attachment_id, pa.user_id as attachment_user_id,  pa.post_id as attachment_post_id, pa.uri as attachment_uri, pa.width as attachment_width, 
-pa.height as attachment_height, pa.content_type as attachment_content_type, pa.storage_ref as attachment_storage_ref, 
-pa.blurhash as attachment_blurhash, pa.created_at as attachment_created_at
+ob.name as orbit_name, ob.shortcode as orbit_shortcode, ob.uri as orbit_uri, ob.fediverse_uri as orbit_fediverse_uri, ob.avatar_uri as orbit_avatar_uri
 FROM posts p
 INNER JOIN users u
 ON u.user_id = p.user_id
@@ -13,13 +16,51 @@ LEFT OUTER JOIN likes l
 ON l.post_id = p.post_id
 LEFT OUTER JOIN comments c
 ON c.post_id = p.post_id
-LEFT OUTER JOIN post_attachments pa
-ON pa.post_id = p.post_id
 LEFT OUTER JOIN orbits ob
 ON ob.orbit_id = p.orbit_id
-WHERE p.visibility IN ('public_federated', 'public_local')
+WHERE (p.visibility IN ('public_federated', 'public_local')
+  OR (p.visibility = 'members_only' AND EXISTS (
+    SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $2
+  )))
 AND ob.orbit_id = $1
-GROUP BY p.post_id, u.user_id, pa.attachment_id, ob.orbit_id
-ORDER BY p.created_at DESC
-LIMIT $2
-OFFSET $3
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $2 AND b.blocked_user_id = p.user_id)
+  OR (b.blocked_user_id = $2 AND b.user_id = p.user_id)
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $2
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+  AND ((um.muted_user_id = p.user_id AND NOT um.boosts_only))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM keyword_filters kf
+  WHERE kf.user_id = $2
+  AND (kf.expires_at IS NULL OR kf.expires_at > NOW())
+  AND CASE
+    WHEN kf.is_regex THEN (COALESCE(p.title, '') || ' ' || p.content_md) ~* kf.phrase
+    ELSE POSITION(LOWER(kf.phrase) IN LOWER(COALESCE(p.title, '') || ' ' || p.content_md)) > 0
+  END
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id = p.user_id
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction = 'shadow' AND ar.user_id IS DISTINCT FROM $2))
+)
+AND ($5::uuid IS NULL OR (p.created_at, p.post_id) < (
+  SELECT p2.created_at, p2.post_id FROM posts p2 WHERE p2.post_id = $5
+))
+AND ($6::uuid IS NULL OR (p.created_at, p.post_id) > (
+  SELECT p2.created_at, p2.post_id FROM posts p2 WHERE p2.post_id = $6
+))
+AND (p.pinned_at IS NULL OR ($5::uuid IS NULL AND $6::uuid IS NULL))
+GROUP BY p.post_id, u.user_id, ob.orbit_id
+ORDER BY CASE WHEN $7 THEN p.created_at END ASC, CASE WHEN $7 THEN p.post_id END ASC, p.pinned_at DESC NULLS LAST, p.created_at DESC, p.post_id DESC
+LIMIT $3
+OFFSET $4
+) page
+LEFT OUTER JOIN post_attachments pa
+ON pa.post_id = page.post_id
+ORDER BY CASE WHEN $7 THEN page.created_at END ASC, CASE WHEN $7 THEN page.post_id END ASC, page.pinned_at DESC NULLS LAST, page.created_at DESC, page.post_id DESC, pa.created_at ASC
@@ -1,4 +1,6 @@
 SELECT DISTINCT 'post' as event_type, p.*, u.user_id, u.handle as user_handle, u.fediverse_id as user_fediverse_id, 
+EXISTS (SELECT 1 FROM account_restrictions ar WHERE ar.user_id = p.user_id AND ar.restriction = 'force_sensitive'
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())) AS sensitive,
 u.fediverse_uri AS user_fediverse_uri, u.avatar_url as user_avatar_url, u.handle as event_user_handle, 
 u.fediverse_id as event_user_fediverse_id, u.fediverse_uri AS event_user_fediverse_uri, 
 u.avatar_url as event_user_avatar_url, COUNT(DISTINCT l.like_id) AS likes, count(l2.like_id) >= 1 AS liked, 
@@ -23,4 +25,10 @@ ON pa.post_id = p.post_id
 LEFT OUTER JOIN orbits ob
 ON ob.orbit_id = p.orbit_id
 WHERE p.post_id = $1
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id = p.user_id
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.restriction = 'shadow' AND ar.user_id IS DISTINCT FROM $2))
+)
 GROUP BY p.post_id, u.user_id, pa.attachment_id, ob.orbit_id
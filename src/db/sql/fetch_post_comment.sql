@@ -1,4 +1,4 @@
-SELECT DISTINCT c.*, count(ul.comment_like_id) >= 1 AS liked, count(DISTINCT ul2.comment_like_id) as likes, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, u.fediverse_uri AS user_fediverse_uri, u.avatar_url AS user_avatar_url, p.visibility as visibility FROM comments c
+SELECT DISTINCT c.*, count(ul.comment_like_id) >= 1 AS liked, count(DISTINCT ul2.comment_like_id) as likes, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, u.fediverse_uri AS user_fediverse_uri, u.avatar_url AS user_avatar_url, p.visibility as visibility, p.uri AS post_uri FROM comments c
 INNER JOIN posts p
 ON p.post_id = c.post_id
 INNER JOIN users u
@@ -11,6 +11,7 @@ LEFT OUTER JOIN (
   INNER JOIN users u2
   ON u2.user_id = f.following_user_id
   WHERE u1.user_id = $1 -- the user viewing the post's comments
+  AND f.is_pending = false
   GROUP BY f.user_id, f.following_user_id
 ) AS ff
 ON ff.following_user_id = p.user_id
@@ -22,7 +23,10 @@ WHERE c.post_id = $2 -- the post id of this comment collection
 AND c.comment_id = $3
 AND (
   (p.visibility IN ('public_local', 'public_federated'))
-    OR (following IS TRUE AND p.visibility = 'followers_only'))
+    OR (following IS TRUE AND p.visibility = 'followers_only')
+    OR (p.visibility = 'members_only' AND EXISTS (
+      SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $1
+    )))
 GROUP BY c.comment_id, u.user_id, p.post_id
 ORDER BY c.created_at DESC
 LIMIT 1
@@ -1,4 +1,4 @@
-SELECT DISTINCT c.*, count(ul.comment_like_id) >= 1 AS liked, count(DISTINCT ul2.comment_like_id) as likes, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, u.fediverse_uri AS user_fediverse_uri, u.avatar_url AS user_avatar_url, p.visibility as visibility FROM comments c
+SELECT c.*, count(ul.comment_like_id) >= 1 AS liked, count(DISTINCT ul2.comment_like_id) as likes, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, u.fediverse_uri AS user_fediverse_uri, u.avatar_url AS user_avatar_url, p.visibility as visibility, p.uri AS post_uri FROM comments c
 INNER JOIN posts p
 ON p.post_id = c.post_id
 INNER JOIN users u
@@ -11,6 +11,7 @@ LEFT OUTER JOIN (
   INNER JOIN users u2
   ON u2.user_id = f.following_user_id
   WHERE u1.user_id = $1 -- the user viewing the post's comments
+  AND f.is_pending = false
   GROUP BY f.user_id, f.following_user_id
 ) AS ff
 ON ff.following_user_id = p.user_id
@@ -21,8 +22,22 @@ ON ul2.comment_id = c.comment_id
 WHERE c.post_id = $2 -- the post id of this comment collection
 AND (
   (p.visibility IN ('public_local', 'public_federated'))
-    OR (following IS TRUE AND p.visibility = 'followers_only'))
+    OR (following IS TRUE AND p.visibility = 'followers_only')
+    OR (p.visibility = 'members_only' AND EXISTS (
+      SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $1
+    )))
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $1 AND b.blocked_user_id = c.user_id)
+  OR (b.blocked_user_id = $1 AND b.user_id = c.user_id)
+)
+AND ($5::uuid IS NULL OR (c.created_at, c.comment_id) < (
+  SELECT c2.created_at, c2.comment_id FROM comments c2 WHERE c2.comment_id = $5
+))
+AND ($6::uuid IS NULL OR (c.created_at, c.comment_id) > (
+  SELECT c2.created_at, c2.comment_id FROM comments c2 WHERE c2.comment_id = $6
+))
 GROUP BY c.comment_id, u.user_id, p.post_id
-ORDER BY c.created_at ASC
+ORDER BY CASE WHEN $7 THEN c.created_at END DESC, CASE WHEN $7 THEN c.comment_id END DESC, c.created_at ASC, c.comment_id ASC
 LIMIT $3
 OFFSET $4
@@ -9,10 +9,19 @@ LEFT OUTER JOIN (
   INNER JOIN users u2
   ON u2.user_id = f.following_user_id
   WHERE u1.user_id = $1 -- the user viewing the post's comments
+  AND f.is_pending = false
   GROUP BY f.user_id, f.following_user_id
 ) AS ff
 ON ff.following_user_id = p.user_id
 WHERE c.post_id = $2 -- the post id of this comment collection
 AND (
   (p.visibility IN ('public_local', 'public_federated'))
-    OR (following IS TRUE AND p.visibility = 'followers_only'))
+    OR (following IS TRUE AND p.visibility = 'followers_only')
+    OR (p.visibility = 'members_only' AND EXISTS (
+      SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $1
+    )))
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $1 AND b.blocked_user_id = c.user_id)
+  OR (b.blocked_user_id = $1 AND b.user_id = c.user_id)
+)
@@ -0,0 +1,11 @@
+SELECT
+  u.user_id,
+  u.handle,
+  u.fediverse_id,
+  u.fediverse_uri,
+  u.is_external
+FROM post_mentions m
+INNER JOIN users u
+  ON u.user_id = m.user_id
+WHERE m.post_id = $1
+ORDER BY m.created_at ASC, u.handle ASC
@@ -0,0 +1,6 @@
+SELECT t.name
+FROM post_tags pt
+INNER JOIN tags t
+  ON t.tag_id = pt.tag_id
+WHERE pt.post_id = $1
+ORDER BY t.name ASC
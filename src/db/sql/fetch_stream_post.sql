@@ -0,0 +1,64 @@
+SELECT DISTINCT $3::text AS event_type, p.*, u.handle AS user_handle, u.fediverse_id AS user_fediverse_id, 
+EXISTS (SELECT 1 FROM account_restrictions ar WHERE ar.user_id = p.user_id AND ar.restriction = 'force_sensitive'
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())) AS sensitive,
+u.fediverse_uri AS user_fediverse_uri, u.avatar_url AS user_avatar_url, COUNT(DISTINCT l.like_id) AS likes,
+COUNT(l2.like_id) >= 1 AS liked, COUNT(DISTINCT c.comment_id) AS comments, u2.handle AS event_user_handle,
+u2.fediverse_id AS event_user_fediverse_id, u2.fediverse_uri AS event_user_fediverse_uri, u2.avatar_url AS event_user_avatar_url, 
+ob.name as orbit_name, ob.shortcode as orbit_shortcode, ob.uri as orbit_uri, ob.fediverse_uri as orbit_fediverse_uri, ob.avatar_uri as orbit_avatar_uri, 
+pa.attachment_id, pa.user_id as attachment_user_id,  pa.post_id as attachment_post_id, pa.uri as attachment_uri, pa.width as attachment_width, 
+pa.height as attachment_height, pa.content_type as attachment_content_type, pa.storage_ref as attachment_storage_ref, 
+pa.blurhash as attachment_blurhash, pa.created_at as attachment_created_at
+FROM posts p
+INNER JOIN users u
+ON u.user_id = p.user_id
+INNER JOIN users u2
+ON u2.user_id = $2
+LEFT OUTER JOIN likes l
+ON l.post_id = p.post_id
+LEFT OUTER JOIN likes l2
+ON l2.post_id = p.post_id
+AND l2.user_id = $4
+LEFT OUTER JOIN comments c
+ON c.post_id = p.post_id
+LEFT OUTER JOIN post_attachments pa
+ON pa.post_id = p.post_id
+LEFT OUTER JOIN orbits ob
+ON ob.orbit_id = p.orbit_id
+WHERE p.post_id = $1
+AND (p.visibility != 'members_only' OR EXISTS (
+  SELECT 1 FROM user_orbits uo WHERE uo.orbit_id = p.orbit_id AND uo.user_id = $4
+))
+AND NOT EXISTS (
+  SELECT 1 FROM blocks b
+  WHERE (b.user_id = $4 AND b.blocked_user_id IN (p.user_id, u2.user_id))
+  OR (b.blocked_user_id = $4 AND b.user_id IN (p.user_id, u2.user_id))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM user_mutes um
+  WHERE um.user_id = $4
+  AND (um.expires_at IS NULL OR um.expires_at > NOW())
+  AND ((um.muted_user_id = p.user_id AND NOT um.boosts_only)
+    OR (um.muted_user_id = u2.user_id AND $3 = 'boost'))
+)
+AND NOT EXISTS (
+  SELECT 1 FROM orbit_mutes om
+  WHERE om.user_id = $4
+  AND om.orbit_id = p.orbit_id
+)
+AND NOT EXISTS (
+  SELECT 1 FROM keyword_filters kf
+  WHERE kf.user_id = $4
+  AND (kf.expires_at IS NULL OR kf.expires_at > NOW())
+  AND CASE
+    WHEN kf.is_regex THEN (COALESCE(p.title, '') || ' ' || p.content_md) ~* kf.phrase
+    ELSE POSITION(LOWER(kf.phrase) IN LOWER(COALESCE(p.title, '') || ' ' || p.content_md)) > 0
+  END
+)
+AND NOT EXISTS (
+  SELECT 1 FROM account_restrictions ar
+  WHERE ar.user_id IN (p.user_id, u2.user_id)
+  AND (ar.expires_at IS NULL OR ar.expires_at > NOW())
+  AND (ar.restriction = 'suspend' OR (ar.user_id IS DISTINCT FROM $4
+    AND (ar.restriction = 'shadow' OR (ar.restriction = 'silence' AND $5::text = 'federated'))))
+)
+GROUP BY p.post_id, u.user_id, u2.user_id, pa.attachment_id, ob.orbit_id
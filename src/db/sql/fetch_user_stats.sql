@@ -5,6 +5,7 @@ SELECT
   ON u.user_id = f.user_id
   WHERE u.handle = $1
   AND following_user_id != u.user_id
+  AND f.is_pending = false
 ) AS following_count,
 
 (
@@ -13,6 +14,7 @@ SELECT
   ON u.user_id = f.following_user_id
   WHERE u.handle = $1
   AND f.user_id != u.user_id
+  AND f.is_pending = false
 ) AS followers_count,
 
 (
@@ -21,8 +23,18 @@ SELECT
   ON u.user_id = f.following_user_id
   WHERE u.handle = $1
   AND f.user_id = $2
+  AND f.is_pending = false
 ) AS following_user,
 
+(
+  SELECT COUNT(*) >= 1 FROM followers f
+  INNER JOIN users u
+  ON u.user_id = f.following_user_id
+  WHERE u.handle = $1
+  AND f.user_id = $2
+  AND f.is_pending = true
+) AS follow_requested,
+
 (
   SELECT COUNT(*) >= 1 FROM users u
   WHERE u.handle = $1
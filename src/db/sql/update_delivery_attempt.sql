@@ -0,0 +1,7 @@
+WITH delivery AS (
+  UPDATE deliveries SET status = $2, attempts = attempts + 1, next_attempt_at = $3, last_error = $4, updated_at = now()
+  WHERE delivery_id = $1
+  RETURNING job_id
+)
+UPDATE jobs SET status = $5, failed_count = failed_count + $6, updated_at = now()
+WHERE job_id IN (SELECT job_id FROM delivery)
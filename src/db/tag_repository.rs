@@ -0,0 +1,67 @@
+use crate::{helpers::api::map_db_err, logic::LogicErr};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait TagRepo {
+  /// Replaces a post's tags with the given normalized tag names, creating any tags that don't exist yet
+  async fn set_post_tags(&self, post_id: &Uuid, names: &[String]) -> Result<(), LogicErr>;
+  async fn fetch_post_tags(&self, post_id: &Uuid) -> Result<Vec<String>, LogicErr>;
+}
+
+pub type TagPool = Arc<dyn TagRepo + Send + Sync>;
+
+pub struct DbTagRepo {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl TagRepo for DbTagRepo {
+  async fn set_post_tags(&self, post_id: &Uuid, names: &[String]) -> Result<(), LogicErr> {
+    let mut db = self.db.get().await.map_err(map_db_err)?;
+    let tx = db.transaction().await.map_err(map_db_err)?;
+
+    for name in names {
+      tx.execute(
+        "INSERT INTO tags (tag_id, name) VALUES ($1, $2) ON CONFLICT (name) DO NOTHING",
+        &[&Uuid::new_v4(), &name],
+      )
+      .await
+      .map_err(map_db_err)?;
+    }
+
+    tx.execute(
+      "DELETE FROM post_tags pt USING tags t WHERE pt.tag_id = t.tag_id AND pt.post_id = $1 AND NOT t.name = ANY($2)",
+      &[&post_id, &names],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    tx.execute(
+      "INSERT INTO post_tags (post_id, tag_id) SELECT $1, t.tag_id FROM tags t WHERE t.name = ANY($2)
+        ON CONFLICT DO NOTHING",
+      &[&post_id, &names],
+    )
+    .await
+    .map_err(map_db_err)?;
+
+    tx.commit().await.map_err(map_db_err)
+  }
+
+  async fn fetch_post_tags(&self, post_id: &Uuid) -> Result<Vec<String>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(include_str!("./sql/fetch_post_tags.sql"), &[&post_id])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(rows.into_iter().map(|row| row.get("name")).collect())
+  }
+}
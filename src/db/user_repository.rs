@@ -1,5 +1,9 @@
 use super::FromRow;
-use crate::{helpers::api::map_db_err, logic::LogicErr, model::user::User};
+use crate::{
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::{page_cursor::PageCursor, user::User},
+};
 
 use async_trait::async_trait;
 use deadpool_postgres::Pool;
@@ -20,8 +24,23 @@ pub trait UserRepo {
   async fn fetch_password_hash(&self, handle: &str) -> Result<Option<String>, LogicErr>;
   async fn fetch_fediverse_id_by_handle(&self, fediverse_id: &str) -> Option<String>;
   async fn fetch_user_count(&self) -> i64;
-  async fn fetch_followers(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<User>, LogicErr>;
-  async fn fetch_following(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<User>, LogicErr>;
+  /// Fetches a user's followers, most recent first. The cursor refers to the followers by their user IDs.
+  async fn fetch_followers(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<User>, LogicErr>;
+  /// Fetches who a user follows, most recently followed first. The cursor refers to them by their user IDs.
+  async fn fetch_following(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<User>, LogicErr>;
+  async fn fetch_external_followers(&self, user_id: &Uuid) -> Result<Vec<User>, LogicErr>;
   async fn fetch_followers_count(&self, user_id: &Uuid) -> i64;
   async fn fetch_following_count(&self, user_id: &Uuid) -> i64;
   async fn fetch_by_fediverse_uri(&self, fediverse_uri: &str) -> Option<User>;
@@ -43,6 +62,7 @@ pub trait UserRepo {
   async fn delete_user(&self, id: &Uuid) -> Result<(), LogicErr>;
   async fn delete_external_user(&self, id: &Uuid) -> Result<(), LogicErr>;
   async fn user_is_external(&self, user_id: &Uuid) -> bool;
+  async fn user_is_admin(&self, user_id: &Uuid) -> bool;
 }
 
 pub type UserPool = Arc<dyn UserRepo + Send + Sync>;
@@ -164,27 +184,95 @@ impl UserRepo for DbUserRepo {
     row.get(0)
   }
 
-  async fn fetch_followers(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<User>, LogicErr> {
+  async fn fetch_followers(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<User>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
       .query(
         r#"SELECT u.* FROM users u INNER JOIN followers f ON f.user_id = u.user_id
-        WHERE f.following_user_id = $1 AND f.user_id != following_user_id LIMIT $2 OFFSET $3"#,
-        &[&user_id, &limit, &skip],
+        WHERE f.following_user_id = $1 AND f.user_id != following_user_id AND f.is_pending = false
+        AND ($4::uuid IS NULL OR (f.created_at, f.user_id) < (
+          SELECT f2.created_at, f2.user_id FROM followers f2 WHERE f2.following_user_id = $1 AND f2.user_id = $4
+        ))
+        AND ($5::uuid IS NULL OR (f.created_at, f.user_id) > (
+          SELECT f2.created_at, f2.user_id FROM followers f2 WHERE f2.following_user_id = $1 AND f2.user_id = $5
+        ))
+        ORDER BY CASE WHEN $6 THEN f.created_at END ASC, CASE WHEN $6 THEN f.user_id END ASC,
+        f.created_at DESC, f.user_id DESC
+        LIMIT $2 OFFSET $3"#,
+        &[
+          &user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
       )
       .await
       .map_err(map_db_err)?;
 
-    Ok(rows.into_iter().flat_map(User::from_row).collect())
+    let mut users: Vec<User> = rows.into_iter().flat_map(User::from_row).collect();
+    if cursor.walks_oldest_first() {
+      users.reverse();
+    }
+
+    Ok(users)
   }
 
-  async fn fetch_following(&self, user_id: &Uuid, limit: i64, skip: i64) -> Result<Vec<User>, LogicErr> {
+  async fn fetch_following(
+    &self,
+    user_id: &Uuid,
+    cursor: &PageCursor,
+    limit: i64,
+    skip: i64,
+  ) -> Result<Vec<User>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
       .query(
         r#"SELECT u.* FROM users u INNER JOIN followers f ON f.following_user_id = u.user_id
-        WHERE f.user_id = $1 AND f.user_id != following_user_id LIMIT $2 OFFSET $3"#,
-        &[&user_id, &limit, &skip],
+        WHERE f.user_id = $1 AND f.user_id != following_user_id AND f.is_pending = false
+        AND ($4::uuid IS NULL OR (f.created_at, f.following_user_id) < (
+          SELECT f2.created_at, f2.following_user_id FROM followers f2 WHERE f2.user_id = $1 AND f2.following_user_id = $4
+        ))
+        AND ($5::uuid IS NULL OR (f.created_at, f.following_user_id) > (
+          SELECT f2.created_at, f2.following_user_id FROM followers f2 WHERE f2.user_id = $1 AND f2.following_user_id = $5
+        ))
+        ORDER BY CASE WHEN $6 THEN f.created_at END ASC, CASE WHEN $6 THEN f.following_user_id END ASC,
+        f.created_at DESC, f.following_user_id DESC
+        LIMIT $2 OFFSET $3"#,
+        &[
+          &user_id,
+          &limit,
+          &skip,
+          &cursor.max_id,
+          &cursor.lower_id(),
+          &cursor.walks_oldest_first(),
+        ],
+      )
+      .await
+      .map_err(map_db_err)?;
+
+    let mut users: Vec<User> = rows.into_iter().flat_map(User::from_row).collect();
+    if cursor.walks_oldest_first() {
+      users.reverse();
+    }
+
+    Ok(users)
+  }
+
+  async fn fetch_external_followers(&self, user_id: &Uuid) -> Result<Vec<User>, LogicErr> {
+    let db = self.db.get().await.map_err(map_db_err)?;
+    let rows = db
+      .query(
+        r#"SELECT u.* FROM users u INNER JOIN followers f ON f.user_id = u.user_id
+        WHERE f.following_user_id = $1 AND f.user_id != following_user_id AND f.is_pending = false AND u.is_external = true"#,
+        &[&user_id],
       )
       .await
       .map_err(map_db_err)?;
@@ -199,7 +287,7 @@ impl UserRepo for DbUserRepo {
     };
     let row = match db
       .query_one(
-        "SELECT COUNT(*) FROM followers WHERE following_user_id = $1 AND user_id != following_user_id",
+        "SELECT COUNT(*) FROM followers WHERE following_user_id = $1 AND user_id != following_user_id AND is_pending = false",
         &[&user_id],
       )
       .await
@@ -219,7 +307,7 @@ impl UserRepo for DbUserRepo {
     };
     let row = match db
       .query_one(
-        "SELECT COUNT(*) FROM followers WHERE user_id = $1 AND user_id != following_user_id ",
+        "SELECT COUNT(*) FROM followers WHERE user_id = $1 AND user_id != following_user_id AND is_pending = false",
         &[&user_id],
       )
       .await
@@ -289,8 +377,8 @@ impl UserRepo for DbUserRepo {
     let db = self.db.get().await.map_err(map_db_err)?;
     db.execute(r#"INSERT INTO users (user_id, handle, fediverse_id, fediverse_uri, avatar_url, email, password_hash, is_external, 
       url_1, url_2, url_3, url_4, url_5, url_1_title, url_2_title, url_3_title, url_4_title, url_5_title, intro_md, intro_html, private_key, public_key, 
-      ext_apub_followers_uri, ext_apub_following_uri, ext_apub_inbox_uri, ext_apub_outbox_uri) 
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26) RETURNING user_id"#,
+      ext_apub_followers_uri, ext_apub_following_uri, ext_apub_inbox_uri, ext_apub_outbox_uri, ext_apub_shared_inbox_uri, manually_approves_followers) 
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28) RETURNING user_id"#,
       &[
         &user.user_id,
         &user.handle,
@@ -318,6 +406,8 @@ impl UserRepo for DbUserRepo {
         &user.ext_apub_following_uri,
         &user.ext_apub_inbox_uri,
         &user.ext_apub_outbox_uri,
+        &user.ext_apub_shared_inbox_uri,
+        &user.manually_approves_followers,
       ],
     )
     .await
@@ -330,7 +420,7 @@ impl UserRepo for DbUserRepo {
     let db = self.db.get().await.map_err(map_db_err)?;
     db.execute(r#"UPDATE users SET handle = $2, fediverse_id = $3, fediverse_uri = $4, avatar_url = $5, email = $6, password_hash = $7, is_external = $8, 
     url_1 = $9, url_2 = $10, url_3 = $11, url_4 = $12, url_5 = $13, url_1_title = $14, url_2_title = $15, url_3_title = $16, url_4_title = $17, url_5_title = $18, intro_md = $19, intro_html = $20, private_key = $21, public_key = $22, 
-    ext_apub_followers_uri = $23, ext_apub_following_uri = $24, ext_apub_inbox_uri = $25, ext_apub_outbox_uri = $26, updated_at = NOW() WHERE user_id = $1"#,
+    ext_apub_followers_uri = $23, ext_apub_following_uri = $24, ext_apub_inbox_uri = $25, ext_apub_outbox_uri = $26, ext_apub_shared_inbox_uri = $27, manually_approves_followers = $28, updated_at = NOW() WHERE user_id = $1"#,
       &[
         &user.user_id,
         &user.handle,
@@ -358,6 +448,8 @@ impl UserRepo for DbUserRepo {
         &user.ext_apub_following_uri,
         &user.ext_apub_inbox_uri,
         &user.ext_apub_outbox_uri,
+        &user.ext_apub_shared_inbox_uri,
+        &user.manually_approves_followers,
       ],
     )
     .await
@@ -413,6 +505,26 @@ impl UserRepo for DbUserRepo {
     row.get(0)
   }
 
+  async fn user_is_admin(&self, user_id: &Uuid) -> bool {
+    let db = match self.db.get().await.map_err(map_db_err) {
+      Ok(db) => db,
+      Err(_) => return false,
+    };
+    let row = match db
+      .query_one(
+        "SELECT COUNT(*) >= 1 FROM users WHERE user_id = $1 AND is_admin = TRUE AND is_external = FALSE",
+        &[&user_id],
+      )
+      .await
+      .map_err(map_db_err)
+    {
+      Ok(row) => row,
+      Err(_) => return false,
+    };
+
+    row.get(0)
+  }
+
   async fn fetch_outdated_external_users(&self) -> Result<Vec<Uuid>, LogicErr> {
     let db = self.db.get().await.map_err(map_db_err)?;
     let rows = db
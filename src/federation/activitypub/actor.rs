@@ -15,13 +15,14 @@ use crate::{
     reference::Reference,
   },
   db::{orbit_repository::OrbitPool, user_repository::UserPool},
+  federation::domain_policy::{uri_federation_allowed, uri_rejects_media},
   helpers::api::map_ext_err,
   logic::LogicErr,
-  model::{orbit::Orbit, user::User, webfinger::WebfingerRecord},
+  model::{orbit::Orbit, orbit_join_policy::OrbitJoinPolicy, user::User, webfinger::WebfingerRecord},
   settings::SETTINGS,
 };
 
-use super::util::{activitypub_ref_to_uri_opt, deref_activitypub_ref};
+use super::util::{activitypub_ref_to_uri_opt, activitypub_shared_inbox_uri, deref_activitypub_ref};
 
 lazy_static! {
   pub static ref BACKOFF_POLICY: ExponentialBackoff = {
@@ -86,6 +87,18 @@ async fn query_activitypub_orbit_ref(obj_ref: &Option<Reference<Object>>, orbits
   orbits.fetch_by_fediverse_uri(&uri).await
 }
 
+async fn federate_actor_media_uri(actor_uri: &str, media_ref: &Option<Reference<Object>>) -> Option<String> {
+  // Actors on domains that have their media rejected keep their profiles, just without the images
+  if uri_rejects_media(actor_uri) {
+    return None;
+  }
+
+  match deref_activitypub_ref(media_ref).await {
+    Some(obj) => activitypub_ref_to_uri_opt(&obj.url),
+    None => None,
+  }
+}
+
 pub async fn federate_user_actor(actor_ref: &Option<Reference<Object>>, users: &UserPool) -> Result<User, LogicErr> {
   if let Some(user) = query_activitypub_user_ref(actor_ref, users).await {
     return Ok(user);
@@ -126,6 +139,8 @@ pub async fn federate_user_actor(actor_ref: &Option<Reference<Object>>, users: &
     None => return Err(LogicErr::InvalidData),
   };
 
+  let shared_inbox_uri = activitypub_shared_inbox_uri(&actor.endpoints);
+
   let public_key = match actor_obj.key {
     Some(k) => match k.public_key_pem {
       Some(k) => k,
@@ -147,10 +162,7 @@ pub async fn federate_user_actor(actor_ref: &Option<Reference<Object>>, users: &
     None => return Err(LogicErr::InvalidData),
   };
 
-  let avatar_url = match deref_activitypub_ref(&actor_obj.icon).await {
-    Some(obj) => activitypub_ref_to_uri_opt(&obj.url),
-    None => None,
-  };
+  let avatar_url = federate_actor_media_uri(&fediverse_uri.to_string(), &actor_obj.icon).await;
 
   let user = User {
     user_id: Uuid::new_v4(),
@@ -180,6 +192,8 @@ pub async fn federate_user_actor(actor_ref: &Option<Reference<Object>>, users: &
     ext_apub_following_uri: Some(following_uri),
     ext_apub_inbox_uri: Some(inbox_uri),
     ext_apub_outbox_uri: Some(outbox_uri),
+    ext_apub_shared_inbox_uri: shared_inbox_uri,
+    manually_approves_followers: actor.manually_approves_followers.unwrap_or(false),
     created_at: Utc::now(),
     updated_at: Utc::now(),
   };
@@ -197,6 +211,10 @@ pub async fn federate_user_actor_from_webfinger(
     false => format!("https://{}/.well-known/webfinger?resource={}", domain, webfinger_query),
   };
 
+  if !uri_federation_allowed(&uri) {
+    return Ok(None);
+  }
+
   let result: Result<Response, reqwest::Error> = retry(BACKOFF_POLICY.clone(), || async {
     Ok(HTTP_CLIENT.get(&uri).send().await?)
   })
@@ -292,6 +310,8 @@ pub async fn federate_update_user_actor(
     None => return Ok(user),
   };
 
+  let shared_inbox_uri = activitypub_shared_inbox_uri(&actor.endpoints);
+
   let public_key = match actor_obj.key {
     Some(k) => match k.public_key_pem {
       Some(k) => k,
@@ -313,10 +333,7 @@ pub async fn federate_update_user_actor(
     None => return Ok(user),
   };
 
-  let avatar_url = match deref_activitypub_ref(&actor_obj.icon).await {
-    Some(obj) => activitypub_ref_to_uri_opt(&obj.url),
-    None => None,
-  };
+  let avatar_url = federate_actor_media_uri(&fediverse_uri.to_string(), &actor_obj.icon).await;
 
   user.fediverse_id = format!("@{}@{}", handle, fediverse_uri_host);
   user.avatar_url = avatar_url;
@@ -325,6 +342,8 @@ pub async fn federate_update_user_actor(
   user.ext_apub_following_uri = Some(following_uri);
   user.ext_apub_inbox_uri = Some(inbox_uri);
   user.ext_apub_outbox_uri = Some(outbox_uri);
+  user.ext_apub_shared_inbox_uri = shared_inbox_uri;
+  user.manually_approves_followers = actor.manually_approves_followers.unwrap_or(false);
 
   users.update_from(&user).await
 }
@@ -406,17 +425,16 @@ pub async fn federate_orbit_group(
     None => return Err(LogicErr::InvalidData),
   };
 
+  let join_policy = match actor.manually_approves_followers {
+    Some(true) => OrbitJoinPolicy::ApprovalRequired,
+    _ => OrbitJoinPolicy::Open,
+  };
+
   let fediverse_id = format!("o/{}@{}", shortcode, fediverse_uri.host().unwrap_or_default());
 
-  let avatar_url = match deref_activitypub_ref(&actor_obj.icon).await {
-    Some(obj) => activitypub_ref_to_uri_opt(&obj.url),
-    None => None,
-  };
+  let avatar_url = federate_actor_media_uri(&fediverse_uri.to_string(), &actor_obj.icon).await;
 
-  let banner_url = match deref_activitypub_ref(&actor_obj.image).await {
-    Some(obj) => activitypub_ref_to_uri_opt(&obj.url),
-    None => None,
-  };
+  let banner_url = federate_actor_media_uri(&fediverse_uri.to_string(), &actor_obj.image).await;
 
   let orbit = Orbit {
     orbit_id: Uuid::new_v4(),
@@ -437,6 +455,7 @@ pub async fn federate_orbit_group(
     ext_apub_inbox_uri: Some(inbox_uri),
     ext_apub_outbox_uri: Some(outbox_uri),
     ext_apub_followers_uri: Some(followers_uri),
+    join_policy,
   };
 
   orbits.create_from(&orbit).await
@@ -452,6 +471,10 @@ pub async fn federate_orbit_group_from_webfinger(
     false => format!("https://{}/.well-known/webfinger?resource={}", domain, webfinger_query),
   };
 
+  if !uri_federation_allowed(&uri) {
+    return Ok(None);
+  }
+
   let result: Result<Option<WebfingerRecord>, reqwest::Error> = retry(BACKOFF_POLICY.clone(), || async {
     Ok(HTTP_CLIENT.get(&uri).send().await?.json().await?)
   })
@@ -571,17 +594,16 @@ pub async fn federate_update_orbit_group(
     None => return Err(LogicErr::InvalidData),
   };
 
+  let join_policy = match actor.manually_approves_followers {
+    Some(true) => OrbitJoinPolicy::ApprovalRequired,
+    _ => OrbitJoinPolicy::Open,
+  };
+
   let fediverse_id = format!("o/{}@{}", shortcode, fediverse_uri.host().unwrap_or_default());
 
-  let avatar_url = match deref_activitypub_ref(&actor_obj.icon).await {
-    Some(obj) => activitypub_ref_to_uri_opt(&obj.url),
-    None => None,
-  };
+  let avatar_url = federate_actor_media_uri(&fediverse_uri.to_string(), &actor_obj.icon).await;
 
-  let banner_url = match deref_activitypub_ref(&actor_obj.image).await {
-    Some(obj) => activitypub_ref_to_uri_opt(&obj.url),
-    None => None,
-  };
+  let banner_url = federate_actor_media_uri(&fediverse_uri.to_string(), &actor_obj.image).await;
 
   orbit.shortcode = shortcode;
   orbit.name = name;
@@ -596,6 +618,7 @@ pub async fn federate_update_orbit_group(
   orbit.ext_apub_inbox_uri = Some(inbox_uri);
   orbit.ext_apub_outbox_uri = Some(outbox_uri);
   orbit.ext_apub_followers_uri = Some(followers_uri);
+  orbit.join_policy = join_policy;
 
   orbits.update_orbit_from(&orbit).await?;
 
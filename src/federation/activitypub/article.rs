@@ -11,11 +11,12 @@ use crate::{
     reference::Reference,
   },
   db::{
-    job_repository::JobPool, orbit_repository::OrbitPool, post_attachment_repository::PostAttachmentPool,
-    post_repository::PostPool,
+    delivery_repository::DeliveryPool, job_repository::JobPool, orbit_repository::OrbitPool,
+    post_attachment_repository::PostAttachmentPool, post_repository::PostPool, tag_repository::TagPool,
   },
-  helpers::api::map_db_err,
-  logic::LogicErr,
+  federation::domain_policy::uri_rejects_media,
+  helpers::api::{map_db_err, relative_to_absolute_uri},
+  logic::{tag::record_tags, LogicErr},
   model::{
     access_type::AccessType,
     job::{JobStatus, NewJob},
@@ -31,6 +32,7 @@ use crate::{
 
 use super::{
   actor::federate_orbit_group,
+  hashtag::federate_hashtags,
   util::{activitypub_ref_to_uri_opt, deref_activitypub_ref_list, send_activitypub_object, FederateResult},
 };
 
@@ -38,22 +40,30 @@ pub async fn federate_create_article(
   activity_object: Object,
   actor: &User,
   posts: &PostPool,
+  tags: &TagPool,
   jobs: &JobPool,
   post_attachments: &PostAttachmentPool,
   orbits: &OrbitPool,
   queue: &Queue,
 ) -> Result<FederateResult, LogicErr> {
   let orbit = federate_orbit_group(&activity_object.audience, orbits).await?;
+  let hashtags = federate_hashtags(&activity_object.tag);
 
   let uri = match activity_object.id {
     Some(uri) => uri,
     None => return Err(LogicErr::InvalidData),
   };
 
+  let reject_media = uri_rejects_media(&uri);
+
   let attachments: Vec<Object> = match deref_activitypub_ref_list(&activity_object.attachment).await {
     Some(obj) => obj
       .into_iter()
       .filter(|obj| {
+        if reject_media {
+          return false;
+        }
+
         let obj_type = match ObjectType::from_str_opt(&obj.kind) {
           Some(t) => t,
           None => return false,
@@ -144,6 +154,10 @@ pub async fn federate_create_article(
 
   posts.create_post_from(post).await?;
 
+  if !hashtags.is_empty() {
+    record_tags(tags, &post_id, &hashtags).await;
+  }
+
   for attachment_obj in attachments {
     let image_content_type = match attachment_obj.media_type {
       Some(val) => val,
@@ -168,7 +182,7 @@ pub async fn federate_create_article(
     let attachment = PostAttachment {
       attachment_id: Uuid::new_v4(),
       user_id: actor.user_id,
-      post_id,
+      post_id: Some(post_id),
       uri: Some(image_uri),
       width: image_width,
       height: image_height,
@@ -209,7 +223,10 @@ pub async fn federate_update_article(
   actor: &User,
   access: AccessType,
   posts: &PostPool,
+  tags: &TagPool,
 ) -> Result<FederateResult, LogicErr> {
+  let hashtags = federate_hashtags(&activity_object.tag);
+
   let uri = match activity_object.id {
     Some(uri) => uri,
     None => return Err(LogicErr::InvalidData),
@@ -278,6 +295,7 @@ pub async fn federate_update_article(
   post.updated_at = created_at;
 
   posts.update_post_content(&post).await?;
+  record_tags(tags, &post.post_id, &hashtags).await;
 
   Ok(FederateResult::None)
 }
@@ -287,6 +305,7 @@ pub async fn federate_ext_create_article(
   actor: &User,
   dest_actor: &Orbit,
   posts: &PostPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let post = match posts.fetch_post(post_id, &Some(actor.user_id)).await {
     Ok(post) => match post {
@@ -325,7 +344,7 @@ pub async fn federate_ext_create_article(
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
 pub async fn federate_ext_update_article(
@@ -333,6 +352,7 @@ pub async fn federate_ext_update_article(
   actor: &User,
   dest_actor: &Orbit,
   posts: &PostPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let post = match posts.fetch_post(post_id, &Some(actor.user_id)).await {
     Ok(post) => match post {
@@ -371,10 +391,15 @@ pub async fn federate_ext_update_article(
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
-pub async fn federate_ext_delete_article(post_id: &Uuid, actor: &User, dest_actor: &Orbit) -> Result<(), LogicErr> {
+pub async fn federate_ext_delete_article(
+  post_id: &Uuid,
+  actor: &User,
+  dest_actor: &Orbit,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
   // NOTE: By this point, the post is deleted in our DB, so we have to build the URI from scratch here
   let uri = format!("{}/feed/{}", SETTINGS.server.api_fqdn, post_id);
   let obj = Object::builder()
@@ -404,5 +429,100 @@ pub async fn federate_ext_delete_article(post_id: &Uuid, actor: &User, dest_acto
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+/// Relays a post made in one of our orbits to a remote member, wrapping the author's activity in an Announce from
+/// the orbit's Group actor (FEP-1b12). Deletes need the URI the post had, as it's no longer in our DB.
+pub async fn federate_ext_announce_article(
+  activity_type: ActivityType,
+  post_id: &Uuid,
+  deleted_post_uri: Option<&str>,
+  actor: &User,
+  group: &Orbit,
+  dest_actor: &User,
+  posts: &PostPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  if group.is_external {
+    return Ok(());
+  }
+
+  let obj = match activity_type {
+    ActivityType::Delete => {
+      let uri = match deleted_post_uri {
+        Some(uri) => relative_to_absolute_uri(uri),
+        None => return Err(LogicErr::MissingRecord),
+      };
+      Reference::Embedded(Box::new(
+        Object::builder()
+          .kind(Some(ObjectType::Tombstone.to_string()))
+          .id(Some(uri.clone()))
+          .url(Some(Reference::Remote(uri)))
+          .build(),
+      ))
+    }
+    _ => {
+      let post = match posts.find_optional_by_id(post_id).await {
+        Some(post) => post,
+        None => return Err(LogicErr::MissingRecord),
+      };
+
+      if post.is_external {
+        // Posts from remote members are referenced so that receivers fetch them from their origin
+        Reference::Remote(post.uri)
+      } else {
+        let post = match posts.fetch_post(post_id, &Some(actor.user_id)).await? {
+          Some(post) => post,
+          None => return Err(LogicErr::MissingRecord),
+        };
+
+        match post.to_object(&actor.fediverse_uri) {
+          Some(obj) => Reference::Embedded(Box::new(obj)),
+          None => return Err(LogicErr::MissingRecord),
+        }
+      }
+    }
+  };
+
+  let public = "https://www.w3.org/ns/activitystreams#Public".to_string();
+  let members_uri = format!("{}/orbit/{}/members", SETTINGS.server.api_fqdn, group.orbit_id);
+
+  let activity_object = Object::builder()
+    .kind(Some(activity_type.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(relative_to_absolute_uri(&actor.fediverse_uri))))
+    .to(Some(Reference::Mixed(vec![Reference::Remote(public.clone())])))
+    .cc(Some(Reference::Mixed(vec![Reference::Remote(members_uri.clone())])))
+    .audience(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, group.fediverse_uri
+    ))))
+    .activity(Some(ActivityProps::builder().object(Some(obj)).build()))
+    .build();
+
+  let response_object = Object::builder()
+    .kind(Some(ActivityType::Announce.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, group.fediverse_uri
+    ))))
+    .to(Some(Reference::Mixed(vec![Reference::Remote(public)])))
+    .cc(Some(Reference::Mixed(vec![Reference::Remote(members_uri)])))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Embedded(Box::new(activity_object))))
+        .build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &group.fediverse_uri, &group.private_key, deliveries).await
 }
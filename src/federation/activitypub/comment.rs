@@ -0,0 +1,294 @@
+use uuid::Uuid;
+
+use super::{
+  federate::FederateExtActor,
+  util::{activitypub_ref_to_id_opt, send_activitypub_object, FederateResult},
+};
+use crate::{
+  activitypub::{
+    activity::ActivityProps,
+    activity_convertible::ActivityConvertible,
+    activity_type::ActivityType,
+    document::ActivityPubDocument,
+    object::{Object, ObjectType},
+    rdf_string::RdfString,
+    reference::Reference,
+  },
+  db::{
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, follow_repository::FollowPool,
+    notification_repository::NotificationPool, orbit_membership_repository::OrbitMembershipPool,
+    orbit_moderation_repository::OrbitModerationPool, post_repository::PostPool,
+  },
+  helpers::api::relative_to_absolute_uri,
+  logic::{notification::notify, LogicErr},
+  model::{access_type::AccessType, notification::NewNotification, notification_type::NotificationType, user::User},
+  settings::SETTINGS,
+  streaming::pubsub::PubSub,
+};
+
+fn strip_local_prefix(uri: String) -> String {
+  match uri.starts_with(&SETTINGS.server.api_fqdn) {
+    true => uri.replace(&SETTINGS.server.api_fqdn, ""),
+    false => uri,
+  }
+}
+
+/// Resolves the post a Note is replying to, if it's a reply to one of the posts or comments we know about. Replies to
+/// comments are flattened onto the comment's parent post, as comments aren't threaded.
+pub async fn federate_comment_parent(
+  in_reply_to: &Option<Reference<Object>>,
+  posts: &PostPool,
+  comments: &CommentPool,
+) -> Option<Uuid> {
+  let in_reply_to = strip_local_prefix(activitypub_ref_to_id_opt(in_reply_to)?);
+
+  match posts.find_optional_by_uri(&in_reply_to).await {
+    Some(post) => Some(post.post_id),
+    None => comments
+      .fetch_comment_from_uri(&in_reply_to)
+      .await
+      .map(|comment| comment.post_id),
+  }
+}
+
+pub async fn federate_create_comment(
+  activity_object: Object,
+  post_id: Uuid,
+  actor: &User,
+  follows: &FollowPool,
+  posts: &PostPool,
+  comments: &CommentPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_membership: &OrbitMembershipPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+) -> Result<FederateResult, LogicErr> {
+  let uri = match activity_object.id {
+    Some(uri) => uri,
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  if comments.fetch_comment_from_uri(&uri).await.is_some() {
+    return Ok(FederateResult::None);
+  }
+
+  let visibility = match posts.fetch_visibility_by_id(&post_id).await {
+    Some(visibility) => visibility,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let owner_id = match posts.fetch_owner_by_id(&post_id).await {
+    Some(id) => id,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  // Remote users are held to the same rules as local users when commenting
+  if (visibility == AccessType::Private || visibility == AccessType::Shadow) && owner_id != actor.user_id {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  if visibility == AccessType::FollowersOnly && !follows.user_follows_poster(&post_id, &actor.user_id).await {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  if visibility == AccessType::MembersOnly
+    && !orbit_membership
+      .user_is_member_for_post(&post_id, &actor.user_id)
+      .await?
+  {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  if orbit_moderation
+    .user_is_banned_for_post(&post_id, &actor.user_id)
+    .await?
+  {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  if orbit_moderation.post_is_locked(&post_id).await? {
+    return Err(LogicErr::InvalidOperation("Post is locked".to_string()));
+  }
+
+  let content_html = match activity_object.content {
+    Some(content) => match content {
+      RdfString::Raw(content) => content,
+      RdfString::Props(props) => props.string,
+    },
+    None => "".to_string(),
+  };
+
+  let content_md = match activity_object.source {
+    Some(source) => {
+      if source.media_type == "text/markdown" {
+        source.content
+      } else {
+        "".to_string()
+      }
+    }
+    None => "".to_string(),
+  };
+
+  let created_at = match activity_object.published {
+    Some(date) => date,
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  let comment_id = comments
+    .create_external_comment(&actor.user_id, &post_id, &uri, &content_md, &content_html, &created_at)
+    .await?;
+
+  notify(
+    notifications,
+    pubsub,
+    NewNotification {
+      user_id: owner_id,
+      source_user_id: actor.user_id,
+      notification_type: NotificationType::Comment,
+      post_id: Some(post_id),
+      comment_id: Some(comment_id),
+      orbit_id: None,
+    },
+  )
+  .await;
+
+  Ok(FederateResult::None)
+}
+
+pub async fn federate_delete_comment(
+  target: String,
+  actor: &User,
+  comments: &CommentPool,
+) -> Result<FederateResult, LogicErr> {
+  comments.delete_comment_from_uri(&target, &actor.user_id).await?;
+
+  Ok(FederateResult::None)
+}
+
+pub async fn federate_like_comment(
+  activity_object: Object,
+  actor: &User,
+  comments: &CommentPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+) -> Result<FederateResult, LogicErr> {
+  let uri = match activity_object.id {
+    Some(uri) => strip_local_prefix(uri),
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  let comment = match comments.fetch_comment_from_uri(&uri).await {
+    Some(comment) => comment,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  comments
+    .create_comment_like(&actor.user_id, &comment.comment_id, &comment.post_id)
+    .await?;
+
+  notify(
+    notifications,
+    pubsub,
+    NewNotification {
+      user_id: comment.user_id,
+      source_user_id: actor.user_id,
+      notification_type: NotificationType::Like,
+      post_id: Some(comment.post_id),
+      comment_id: Some(comment.comment_id),
+      orbit_id: None,
+    },
+  )
+  .await;
+
+  Ok(FederateResult::None)
+}
+
+pub async fn federate_unlike_comment(
+  target: String,
+  actor: &User,
+  comments: &CommentPool,
+) -> Result<FederateResult, LogicErr> {
+  let comment = match comments.fetch_comment_from_uri(&strip_local_prefix(target)).await {
+    Some(comment) => comment,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  comments
+    .delete_comment_like(&actor.user_id, &comment.comment_id, &comment.post_id)
+    .await?;
+
+  Ok(FederateResult::None)
+}
+
+async fn federate_ext_send_comment_activity(
+  activity_type: ActivityType,
+  obj: Object,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let response_object = Object::builder()
+    .kind(Some(activity_type.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .cc(Some(Reference::Mixed(
+      dest_actor.recipient_uris().into_iter().map(Reference::Remote).collect(),
+    )))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Embedded(Box::new(obj))))
+        .build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match dest_actor.inbox_uri() {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(&response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+pub async fn federate_ext_create_comment(
+  post_id: &Uuid,
+  comment_id: &Uuid,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  comments: &CommentPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let comment = match comments.fetch_comment(post_id, comment_id, &Some(actor.user_id)).await {
+    Some(comment) => comment,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let obj = match comment.to_object(&relative_to_absolute_uri(&actor.fediverse_uri)) {
+    Some(obj) => obj,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  federate_ext_send_comment_activity(ActivityType::Create, obj, actor, dest_actor, deliveries).await
+}
+
+pub async fn federate_ext_delete_comment(
+  post_id: &Uuid,
+  comment_id: &Uuid,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  // NOTE: By this point, the comment is deleted in our DB, so we have to build the URI from scratch here
+  let uri = format!("{}/feed/{}/comments/{}", SETTINGS.server.api_fqdn, post_id, comment_id);
+  let obj = Object::builder()
+    .kind(Some(ObjectType::Tombstone.to_string()))
+    .id(Some(uri.clone()))
+    .url(Some(Reference::Remote(uri)))
+    .build();
+
+  federate_ext_send_comment_activity(ActivityType::Delete, obj, actor, dest_actor, deliveries).await
+}
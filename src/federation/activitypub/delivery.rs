@@ -0,0 +1,89 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::util::post_activitypub_json;
+use crate::{
+  db::{orbit_repository::OrbitPool, user_repository::UserPool},
+  federation::domain_policy::uri_federation_allowed,
+  logic::LogicErr,
+  model::delivery::Delivery,
+};
+
+/// Makes another attempt at sending a previously failed delivery, signed as whichever local user or orbit sent it
+pub async fn federate_redeliver(delivery: &Delivery, users: &UserPool, orbits: &OrbitPool) -> Result<(), LogicErr> {
+  if !uri_federation_allowed(&delivery.inbox_uri) {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  let private_key = match users.fetch_by_fediverse_uri(&delivery.actor_fediverse_uri).await {
+    Some(user) => user.private_key,
+    None => match orbits.fetch_by_fediverse_uri(&delivery.actor_fediverse_uri).await {
+      Some(orbit) => orbit.private_key,
+      None => return Err(LogicErr::MissingRecord),
+    },
+  };
+
+  post_activitypub_json(
+    &delivery.inbox_uri,
+    &delivery.activity,
+    &delivery.actor_fediverse_uri,
+    &private_key,
+  )
+  .await
+}
+
+fn delivery_backoff(attempts: i32) -> Duration {
+  // 1 minute after the first failure, doubling from there and capping out at a day between attempts
+  Duration::minutes(2_i64.saturating_pow((attempts.max(1) - 1) as u32).min(24 * 60))
+}
+
+pub fn next_delivery_attempt_at(attempts: i32) -> DateTime<Utc> {
+  Utc::now() + delivery_backoff(attempts)
+}
+
+/// Whether a delivery first queued at `created_at` has been retried for longer than `horizon_hours` and should be
+/// given up on
+pub fn delivery_retry_expired(created_at: &DateTime<Utc>, now: &DateTime<Utc>, horizon_hours: i64) -> bool {
+  *now - *created_at > Duration::hours(horizon_hours)
+}
+
+#[cfg(test)]
+mod tests {
+  use chrono::{Duration, Utc};
+
+  use crate::federation::activitypub::delivery::{delivery_backoff, delivery_retry_expired, next_delivery_attempt_at};
+
+  #[test]
+  fn test_delivery_backoff_doubles_up_to_a_day() {
+    assert_eq!(delivery_backoff(0), Duration::minutes(1));
+    assert_eq!(delivery_backoff(1), Duration::minutes(1));
+    assert_eq!(delivery_backoff(2), Duration::minutes(2));
+    assert_eq!(delivery_backoff(3), Duration::minutes(4));
+    assert_eq!(delivery_backoff(11), Duration::minutes(1024));
+    assert_eq!(delivery_backoff(12), Duration::days(1));
+    assert_eq!(delivery_backoff(100), Duration::days(1));
+  }
+
+  #[test]
+  fn test_next_delivery_attempt_at() {
+    let before = Utc::now();
+    let next = next_delivery_attempt_at(3);
+    let after = Utc::now();
+
+    assert!(next >= before + Duration::minutes(4));
+    assert!(next <= after + Duration::minutes(4));
+  }
+
+  #[test]
+  fn test_delivery_retry_expired_after_horizon() {
+    let now = Utc::now();
+
+    assert!(!delivery_retry_expired(&(now - Duration::hours(1)), &now, 72));
+    assert!(!delivery_retry_expired(&(now - Duration::hours(72)), &now, 72));
+    assert!(delivery_retry_expired(
+      &(now - Duration::hours(72) - Duration::minutes(1)),
+      &now,
+      72
+    ));
+    assert!(delivery_retry_expired(&(now - Duration::hours(2)), &now, 1));
+  }
+}
@@ -4,22 +4,35 @@ use uuid::Uuid;
 use super::{
   actor::{federate_orbit_group, federate_update_orbit_group, federate_update_user_actor, federate_user_actor},
   article::{
-    federate_create_article, federate_ext_create_article, federate_ext_delete_article, federate_ext_update_article,
-    federate_update_article,
+    federate_create_article, federate_ext_announce_article, federate_ext_create_article, federate_ext_delete_article,
+    federate_ext_update_article, federate_update_article,
   },
-  group::{federate_create_member, federate_remove_member},
+  comment::{
+    federate_comment_parent, federate_create_comment, federate_ext_create_comment, federate_ext_delete_comment,
+    federate_like_comment,
+  },
+  flag::{federate_create_flag, federate_ext_flag},
+  group::{
+    federate_create_member, federate_ext_group_answer_member, federate_ext_group_remove_object,
+    federate_group_announce, federate_remove_member,
+  },
+  mention::federate_post_mentions,
   note::{
-    federate_create_note, federate_ext_create_note, federate_ext_delete_note, federate_ext_update_note,
-    federate_like_note, federate_unlike_note, federate_update_note,
+    federate_announce_note, federate_create_note, federate_ext_boost_note, federate_ext_create_note,
+    federate_ext_delete_note, federate_ext_like_comment, federate_ext_like_note, federate_ext_unboost_note,
+    federate_ext_unlike_comment, federate_ext_unlike_note, federate_ext_update_note, federate_like_note,
+    federate_unlike_note, federate_update_note,
   },
   object::federate_delete_remote_object,
   person::{
-    federate_create_follow, federate_ext_create_follow, federate_ext_join_group, federate_ext_leave_group,
-    federate_ext_remove_follow, federate_remove_follow,
+    federate_answer_follow, federate_create_block, federate_create_follow, federate_ext_answer_follow,
+    federate_ext_block, federate_ext_create_follow, federate_ext_join_group, federate_ext_leave_group,
+    federate_ext_remove_follow, federate_ext_unblock, federate_remove_follow,
   },
+  undo::federate_undo,
   util::{
-    activitypub_ref_to_uri_opt, deref_activitypub_ref, deref_activitypub_ref_list, determine_activity_target,
-    determine_activity_visibility, send_activitypub_object, ActivityTarget, FederateResult,
+    activitypub_ref_to_id_opt, activitypub_ref_to_uri_opt, deref_activitypub_ref, deref_activitypub_ref_list,
+    determine_activity_target, determine_activity_visibility, send_activitypub_object, ActivityTarget, FederateResult,
   },
 };
 use crate::{
@@ -31,15 +44,21 @@ use crate::{
     reference::Reference,
   },
   db::{
-    follow_repository::FollowPool, job_repository::JobPool, like_repository::LikePool, orbit_repository::OrbitPool,
-    post_attachment_repository::PostAttachmentPool, post_repository::PostPool, user_orbit_repository::UserOrbitPool,
-    user_repository::UserPool,
+    account_restriction_repository::AccountRestrictionPool, block_repository::BlockPool,
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, follow_repository::FollowPool,
+    job_repository::JobPool, like_repository::LikePool, notification_repository::NotificationPool,
+    orbit_membership_repository::OrbitMembershipPool, orbit_moderation_repository::OrbitModerationPool,
+    orbit_repository::OrbitPool, post_attachment_repository::PostAttachmentPool,
+    post_mention_repository::PostMentionPool, post_repository::PostPool, report_repository::ReportPool,
+    tag_repository::TagPool, user_orbit_repository::UserOrbitPool, user_repository::UserPool,
   },
-  helpers::core::unwrap_or_fail,
+  federation::domain_policy::uri_federation_allowed,
+  helpers::{api::relative_to_absolute_uri, core::unwrap_or_fail},
   logic::LogicErr,
-  model::{orbit::Orbit, queue_job::OriginDataEntry, user::User},
+  model::{account_restriction::AccountRestrictionType, orbit::Orbit, queue_job::OriginDataEntry, user::User},
   net::http_sig::verify_http_signature,
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
@@ -104,39 +123,147 @@ async fn federate_get_actor_group(
   Ok(actor_orbit)
 }
 
+async fn federate_respond(
+  result: Result<FederateResult, LogicErr>,
+  activity: Object,
+  actor_user: &User,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  match result {
+    Ok(result) => {
+      let (activity_type, actor_fediverse_uri, actor_private_key) = match result {
+        FederateResult::None => return Ok(()),
+        FederateResult::Accept(actor) => (ActivityType::Accept, actor.0, actor.1),
+        FederateResult::TentativeAccept(actor) => (ActivityType::TentativeAccept, actor.0, actor.1),
+        FederateResult::Ignore(actor) => (ActivityType::Ignore, actor.0, actor.1),
+        FederateResult::Reject(actor) => (ActivityType::Reject, actor.0, actor.1),
+        FederateResult::TentativeReject(actor) => (ActivityType::TentativeReject, actor.0, actor.1),
+      };
+
+      let response_object = Object::builder()
+        .kind(Some(activity_type.to_string()))
+        .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+        .actor(Some(Reference::Remote(format!(
+          "{}{}",
+          SETTINGS.server.api_fqdn, actor_fediverse_uri
+        ))))
+        .activity(Some(
+          ActivityProps::builder()
+            .object(Some(Reference::Embedded(Box::new(activity))))
+            .build(),
+        ))
+        .build();
+
+      let doc = ActivityPubDocument::new(response_object);
+
+      let response_uri = match &actor_user.ext_apub_inbox_uri {
+        Some(uri) => uri,
+        None => return Ok(()),
+      };
+
+      send_activitypub_object(response_uri, doc, &actor_fediverse_uri, &actor_private_key, deliveries).await
+    }
+    Err(err) => Err(err),
+  }
+}
+
 pub async fn federate(
   doc: ActivityPubDocument,
   origin_data: &Option<HashMap<String, OriginDataEntry>>,
   users: &UserPool,
+  account_restrictions: &AccountRestrictionPool,
+  blocks: &BlockPool,
   follows: &FollowPool,
   posts: &PostPool,
   likes: &LikePool,
+  comments: &CommentPool,
   jobs: &JobPool,
   post_attachments: &PostAttachmentPool,
+  post_mentions: &PostMentionPool,
+  tags: &TagPool,
   orbits: &OrbitPool,
   user_orbits: &UserOrbitPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_membership: &OrbitMembershipPool,
+  reports: &ReportPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
   queue: &Queue,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let kind = match unwrap_or_fail(doc.object.kind.as_ref().map(|v| ActivityType::from_str(v))) {
     Ok(kind) => kind,
     Err(err) => return Err(err),
   };
 
+  // The inboxes turn suspended domains away, but activities queued before a policy was put in place still end up here
+  if let Some(actor_uri) = activitypub_ref_to_id_opt(&doc.object.actor) {
+    if !uri_federation_allowed(&actor_uri) {
+      return Err(LogicErr::UnauthorizedError);
+    }
+  }
+
   let actor = federate_get_actor(&doc, users, orbits, origin_data).await?;
 
   let actor_user = match actor {
     FederateActorResult::User(user) => user,
-    FederateActorResult::Group(_) => {
-      // TODO: Support activities sent from a group
+    FederateActorResult::Group(orbit) => {
+      if kind != ActivityType::Announce {
+        log::warn!(
+          "Unimplemented federation task: Activity Type {} sent from a Group",
+          kind
+        );
+        return Ok(());
+      }
+
+      let object = match &doc.object.activity {
+        Some(activity) => deref_activitypub_ref(&activity.object).await,
+        None => None,
+      };
+
+      let object = match object {
+        Some(obj) => obj,
+        None => return Err(LogicErr::InvalidData),
+      };
+
+      federate_group_announce(
+        object,
+        &orbit,
+        users,
+        posts,
+        post_mentions,
+        tags,
+        jobs,
+        post_attachments,
+        orbits,
+        notifications,
+        pubsub,
+        queue,
+      )
+      .await?;
+
       return Ok(());
     }
   };
 
+  if account_restrictions
+    .user_has_restriction(&actor_user.user_id, &AccountRestrictionType::Suspend)
+    .await?
+  {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
   let activity = match &doc.object.activity {
     Some(ac) => ac,
     None => return Err(LogicErr::InvalidData),
   };
 
+  // Flags reference a list of reported objects rather than a single one, so they're handled before dereferencing
+  if kind == ActivityType::Flag {
+    let result = federate_create_flag(&doc.object, &actor_user, users, posts, comments, reports).await;
+    return federate_respond(result, doc.object, &actor_user, deliveries).await;
+  }
+
   let object = match deref_activitypub_ref(&activity.object).await {
     Some(obj) => obj,
     None => return Err(LogicErr::InvalidData),
@@ -147,12 +274,38 @@ pub async fn federate(
   let target = activitypub_ref_to_uri_opt(&activity.target);
 
   if let Some(Ok(nested_activity_type)) = object.kind.as_ref().map(|v| ActivityType::from_str(v)) {
-    log::warn!(
-      "Unimplemented federation task: Activity Type {} on nested Activity Type: {}",
-      kind,
-      nested_activity_type
-    );
-    return Ok(());
+    let result = match kind {
+      ActivityType::Undo => {
+        federate_undo(
+          object,
+          &actor_user,
+          users,
+          blocks,
+          follows,
+          posts,
+          likes,
+          comments,
+          jobs,
+          orbits,
+          user_orbits,
+          queue,
+        )
+        .await
+      }
+      ActivityType::Accept | ActivityType::Reject if nested_activity_type == ActivityType::Follow => {
+        federate_answer_follow(kind == ActivityType::Accept, object, &actor_user, follows, users).await
+      }
+      _ => {
+        log::warn!(
+          "Unimplemented federation task: Activity Type {} on nested Activity Type: {}",
+          kind,
+          nested_activity_type
+        );
+        return Ok(());
+      }
+    };
+
+    return federate_respond(result, doc.object, &actor_user, deliveries).await;
   };
 
   let object_type = match &object.kind {
@@ -165,44 +318,94 @@ pub async fn federate(
 
   let result = match object_type {
     ObjectType::Note => match kind {
-      ActivityType::Create => {
-        let activity_visibility = match activity_visibility {
-          Some(v) => v,
-          None => return Err(LogicErr::InvalidData),
-        };
+      ActivityType::Create => match federate_comment_parent(&object.in_reply_to, posts, comments).await {
+        Some(post_id) => {
+          federate_create_comment(
+            object,
+            post_id,
+            &actor_user,
+            follows,
+            posts,
+            comments,
+            orbit_moderation,
+            orbit_membership,
+            notifications,
+            pubsub,
+          )
+          .await
+        }
+        None => {
+          let activity_visibility = match activity_visibility {
+            Some(v) => v,
+            None => return Err(LogicErr::InvalidData),
+          };
 
-        federate_create_note(
-          object,
-          &actor_user,
-          activity_visibility,
-          follows,
-          posts,
-          jobs,
-          post_attachments,
-          queue,
-        )
-        .await
-      }
+          federate_create_note(
+            object,
+            &actor_user,
+            activity_visibility,
+            users,
+            follows,
+            posts,
+            post_mentions,
+            tags,
+            jobs,
+            post_attachments,
+            notifications,
+            pubsub,
+            queue,
+          )
+          .await
+        }
+      },
       ActivityType::Update => {
         let activity_visibility = match activity_visibility {
           Some(v) => v,
           None => return Err(LogicErr::InvalidData),
         };
 
-        federate_update_note(object, &actor_user, activity_visibility, posts).await
+        federate_update_note(object, &actor_user, activity_visibility, posts, tags).await
+      }
+      ActivityType::Like => match object.in_reply_to {
+        Some(_) => federate_like_comment(object, &actor_user, comments, notifications, pubsub).await,
+        None => federate_like_note(object, &actor_user, posts, likes, notifications, pubsub).await,
+      },
+      ActivityType::Announce => {
+        federate_announce_note(object, &actor_user, users, posts, jobs, post_attachments, queue).await
       }
-      ActivityType::Like => federate_like_note(object, &actor_user, posts, likes).await,
       ActivityType::Remove => match determine_activity_target(target) {
         ActivityTarget::PostLikes(target) => federate_unlike_note(target, &actor_user, posts, likes).await,
         ActivityTarget::Unknown(target) => {
-          federate_delete_remote_object(target, &actor_user, object_type, origin_data, posts, users, likes).await
+          federate_delete_remote_object(
+            target,
+            &actor_user,
+            object_type,
+            origin_data,
+            posts,
+            users,
+            likes,
+            comments,
+            pubsub,
+          )
+          .await
         }
         _ => Err(LogicErr::InvalidData),
       },
       ActivityType::Delete => match determine_activity_target(target) {
         ActivityTarget::PostLikes(target) => federate_unlike_note(target, &actor_user, posts, likes).await,
         ActivityTarget::Unknown(target) => {
-          federate_delete_remote_object(target, &actor_user, object_type, origin_data, posts, users, likes).await
+          federate_delete_remote_object(
+            target,
+            &actor_user,
+            object_type,
+            origin_data,
+            posts,
+            users,
+            likes,
+            comments,
+            pubsub,
+          )
+          .await
         }
         _ => Err(LogicErr::InvalidData),
       },
@@ -217,7 +420,44 @@ pub async fn federate(
     },
     ObjectType::Article => match kind {
       ActivityType::Create => {
-        federate_create_article(object, &actor_user, posts, jobs, post_attachments, orbits, queue).await
+        let orbit = federate_orbit_group(&object.audience, orbits).await?;
+
+        if orbit_moderation
+          .user_is_banned(&orbit.orbit_id, &actor_user.user_id)
+          .await?
+        {
+          return Err(LogicErr::UnauthorizedError);
+        }
+
+        if !orbit.is_external
+          && !orbit_membership
+            .user_can_post(&orbit.orbit_id, &actor_user.user_id)
+            .await?
+        {
+          return Err(LogicErr::UnauthorizedError);
+        }
+
+        let tag = object.tag.clone();
+        let uri = object.id.clone().unwrap_or_default();
+
+        let result =
+          federate_create_article(object, &actor_user, posts, tags, jobs, post_attachments, orbits, queue).await;
+
+        if result.is_ok() {
+          federate_post_mentions(
+            &tag,
+            &uri,
+            &actor_user,
+            users,
+            posts,
+            post_mentions,
+            notifications,
+            pubsub,
+          )
+          .await;
+        }
+
+        result
       }
       ActivityType::Update => {
         let activity_visibility = match activity_visibility {
@@ -225,37 +465,97 @@ pub async fn federate(
           None => return Err(LogicErr::InvalidData),
         };
 
-        federate_update_article(object, &actor_user, activity_visibility, posts).await
+        federate_update_article(object, &actor_user, activity_visibility, posts, tags).await
+      }
+      ActivityType::Announce => {
+        federate_announce_note(object, &actor_user, users, posts, jobs, post_attachments, queue).await
       }
       ActivityType::Remove => match determine_activity_target(target) {
         ActivityTarget::OrbitMembers(target) => federate_remove_member(target, &actor_user, user_orbits, orbits).await,
         ActivityTarget::Unknown(target) => {
-          federate_delete_remote_object(target, &actor_user, object_type, origin_data, posts, users, likes).await
+          federate_delete_remote_object(
+            target,
+            &actor_user,
+            object_type,
+            origin_data,
+            posts,
+            users,
+            likes,
+            comments,
+            pubsub,
+          )
+          .await
         }
         _ => Err(LogicErr::InvalidData),
       },
       ActivityType::Delete => match determine_activity_target(target) {
         ActivityTarget::OrbitMembers(target) => federate_remove_member(target, &actor_user, user_orbits, orbits).await,
         ActivityTarget::Unknown(target) => {
-          federate_delete_remote_object(target, &actor_user, object_type, origin_data, posts, users, likes).await
+          federate_delete_remote_object(
+            target,
+            &actor_user,
+            object_type,
+            origin_data,
+            posts,
+            users,
+            likes,
+            comments,
+            pubsub,
+          )
+          .await
         }
         _ => Err(LogicErr::InvalidData),
       },
       _ => Err(LogicErr::InternalError("Unimplemented".to_string())),
     },
     ObjectType::Person => match kind {
-      ActivityType::Follow => federate_create_follow(object, &actor_user, follows, users).await,
+      ActivityType::Follow => {
+        federate_create_follow(
+          &doc.object,
+          object,
+          &actor_user,
+          blocks,
+          follows,
+          notifications,
+          pubsub,
+          users,
+        )
+        .await
+      }
+      ActivityType::Block => federate_create_block(object, &actor_user, blocks, follows, users).await,
       ActivityType::Remove => match determine_activity_target(target) {
         ActivityTarget::UserFollowers(target) => federate_remove_follow(target, &actor_user, follows, users).await,
         ActivityTarget::Unknown(target) => {
-          federate_delete_remote_object(target, &actor_user, object_type, origin_data, posts, users, likes).await
+          federate_delete_remote_object(
+            target,
+            &actor_user,
+            object_type,
+            origin_data,
+            posts,
+            users,
+            likes,
+            comments,
+            pubsub,
+          )
+          .await
         }
         _ => Err(LogicErr::InvalidData),
       },
       ActivityType::Delete => match determine_activity_target(target) {
         ActivityTarget::UserFollowers(target) => federate_remove_follow(target, &actor_user, follows, users).await,
         ActivityTarget::Unknown(target) => {
-          federate_delete_remote_object(target, &actor_user, object_type, origin_data, posts, users, likes).await
+          federate_delete_remote_object(
+            target,
+            &actor_user,
+            object_type,
+            origin_data,
+            posts,
+            users,
+            likes,
+            comments,
+            pubsub,
+          )
+          .await
         }
         _ => Err(LogicErr::InvalidData),
       },
@@ -285,12 +585,38 @@ pub async fn federate(
             return Ok(());
           }
         },
-        false => federate_delete_remote_object(id, &actor_user, object_type, origin_data, posts, users, likes).await,
+        false => {
+          federate_delete_remote_object(
+            id,
+            &actor_user,
+            object_type,
+            origin_data,
+            posts,
+            users,
+            likes,
+            comments,
+            pubsub,
+          )
+          .await
+        }
       },
       None => Err(LogicErr::InvalidData),
     },
     ObjectType::Group => match kind {
-      ActivityType::Follow => federate_create_member(object, &actor_user, user_orbits, orbits).await,
+      ActivityType::Follow | ActivityType::Join => {
+        federate_create_member(
+          &doc.object,
+          object,
+          &actor_user,
+          user_orbits,
+          orbits,
+          orbit_moderation,
+          orbit_membership,
+          notifications,
+          pubsub,
+        )
+        .await
+      }
       _ => {
         log::warn!(
           "Unimplemented federation task: Activity Type: {} on Object Type {}",
@@ -310,42 +636,7 @@ pub async fn federate(
     }
   };
 
-  match result {
-    Ok(result) => {
-      let (activity_type, actor_fediverse_uri, actor_private_key) = match result {
-        FederateResult::None => return Ok(()),
-        FederateResult::Accept(actor) => (ActivityType::Accept, actor.0, actor.1),
-        FederateResult::TentativeAccept(actor) => (ActivityType::TentativeAccept, actor.0, actor.1),
-        FederateResult::Ignore(actor) => (ActivityType::Ignore, actor.0, actor.1),
-        FederateResult::Reject(actor) => (ActivityType::Reject, actor.0, actor.1),
-        FederateResult::TentativeReject(actor) => (ActivityType::TentativeReject, actor.0, actor.1),
-      };
-
-      let response_object = Object::builder()
-        .kind(Some(activity_type.to_string()))
-        .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
-        .actor(Some(Reference::Remote(format!(
-          "{}{}",
-          SETTINGS.server.api_fqdn, actor_fediverse_uri
-        ))))
-        .activity(Some(
-          ActivityProps::builder()
-            .object(Some(Reference::Embedded(Box::new(doc.object))))
-            .build(),
-        ))
-        .build();
-
-      let doc = ActivityPubDocument::new(response_object);
-
-      let response_uri = match actor_user.ext_apub_inbox_uri {
-        Some(uri) => uri,
-        None => return Ok(()),
-      };
-
-      send_activitypub_object(&response_uri, doc, &actor_fediverse_uri, &actor_private_key).await
-    }
-    Err(err) => Err(err),
-  }
+  federate_respond(result, doc.object, &actor_user, deliveries).await
 }
 
 pub async fn federate_posts_collection(
@@ -353,6 +644,7 @@ pub async fn federate_posts_collection(
   users: &UserPool,
   _follows: &FollowPool,
   posts: &PostPool,
+  tags: &TagPool,
   _likes: &LikePool,
   jobs: &JobPool,
   post_attachments: &PostAttachmentPool,
@@ -421,7 +713,8 @@ pub async fn federate_posts_collection(
       // TODO: Figure out how to federate user posts that don't go to this orbit instance's users
       continue;
     } else if object_type == ObjectType::Article {
-      if let Err(err) = federate_create_article(object, &actor_user, posts, jobs, post_attachments, orbits, queue).await
+      if let Err(err) =
+        federate_create_article(object, &actor_user, posts, tags, jobs, post_attachments, orbits, queue).await
       {
         log::warn!("Failed to federate article from collection {}: {:?}", uri, err);
       }
@@ -440,8 +733,33 @@ pub enum FederateExtAction {
   DeletePost(Uuid),
   FollowProfile,
   UnfollowProfile,
+  BlockProfile,
+  UnblockProfile,
   FollowGroup(Uuid),
   UnfollowGroup(Uuid),
+  LikePost(Uuid),
+  UnlikePost(Uuid),
+  LikeComment(Uuid, Uuid),
+  UnlikeComment(Uuid, Uuid),
+  BoostPost(Uuid),
+  UnboostPost(Uuid),
+  GroupCreatePost(Uuid, Uuid),
+  GroupUpdatePost(Uuid, Uuid),
+  /// As above, along with the URI the post had, as it's no longer in our DB by the time this is federated
+  GroupDeletePost(Uuid, Uuid, String),
+  /// A moderator removed a post or comment, identified by its URI, from one of our orbits
+  GroupRemoveObject(Uuid, String),
+  /// A moderator approved a remote user's pending request to join one of our orbits, along with their Follow/Join
+  GroupAcceptMember(Uuid, String),
+  /// As above, for a request the moderator turned down
+  GroupRejectMember(Uuid, String),
+  /// A locked user approved a remote user's pending follow, along with the Follow they sent
+  AcceptFollow(String),
+  /// As above, for a follow the user turned down
+  RejectFollow(String),
+  CreateComment(Uuid, Uuid),
+  DeleteComment(Uuid, Uuid),
+  FlagReport(Uuid),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -449,6 +767,28 @@ pub enum FederateExtActor {
   None,
   Person(User),
   Group(Orbit),
+  /// A remote server's shared inbox, along with the fediverse URIs of every actor on it the activity is addressed to
+  SharedInbox(String, Vec<String>),
+}
+
+impl FederateExtActor {
+  pub fn inbox_uri(&self) -> Option<String> {
+    match self {
+      FederateExtActor::None => None,
+      FederateExtActor::Person(user) => user.ext_apub_inbox_uri.clone(),
+      FederateExtActor::Group(orbit) => orbit.ext_apub_inbox_uri.clone(),
+      FederateExtActor::SharedInbox(uri, _) => Some(uri.clone()),
+    }
+  }
+
+  pub fn recipient_uris(&self) -> Vec<String> {
+    match self {
+      FederateExtActor::None => vec![],
+      FederateExtActor::Person(user) => vec![relative_to_absolute_uri(&user.fediverse_uri)],
+      FederateExtActor::Group(orbit) => vec![relative_to_absolute_uri(&orbit.fediverse_uri)],
+      FederateExtActor::SharedInbox(_, recipients) => recipients.clone(),
+    }
+  }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -456,6 +796,37 @@ pub enum FederateExtActorRef {
   None,
   Person(Uuid),
   Group(Uuid),
+  SharedInbox(String, Vec<String>),
+}
+
+/// Collapses remote users that share an inbox into a single destination, so each server receives one copy of an
+/// activity rather than one per recipient. Users on servers without a shared inbox are still addressed individually.
+pub fn federate_ext_group_by_inbox(users: Vec<User>) -> Vec<FederateExtActorRef> {
+  let mut destinations = vec![];
+  let mut shared_inboxes: Vec<(String, Vec<String>)> = vec![];
+
+  for user in users {
+    let shared_inbox_uri = match user.ext_apub_shared_inbox_uri {
+      Some(uri) => uri,
+      None => {
+        destinations.push(FederateExtActorRef::Person(user.user_id));
+        continue;
+      }
+    };
+
+    match shared_inboxes.iter_mut().find(|(uri, _)| *uri == shared_inbox_uri) {
+      Some((_, recipients)) => recipients.push(user.fediverse_uri),
+      None => shared_inboxes.push((shared_inbox_uri, vec![user.fediverse_uri])),
+    }
+  }
+
+  destinations.extend(
+    shared_inboxes
+      .into_iter()
+      .map(|(uri, recipients)| FederateExtActorRef::SharedInbox(uri, recipients)),
+  );
+
+  destinations
 }
 
 pub async fn federate_ext(
@@ -464,26 +835,256 @@ pub async fn federate_ext(
   dest_actor: &FederateExtActor,
   posts: &PostPool,
   orbits: &OrbitPool,
+  comments: &CommentPool,
+  reports: &ReportPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   match action {
     FederateExtAction::CreatePost(post_id) => match dest_actor {
-      FederateExtActor::Person(dest_actor) => federate_ext_create_note(&post_id, actor, dest_actor, posts).await,
-      FederateExtActor::Group(dest_actor) => federate_ext_create_article(&post_id, actor, dest_actor, posts).await,
+      FederateExtActor::Group(dest_actor) => {
+        federate_ext_create_article(&post_id, actor, dest_actor, posts, deliveries).await
+      }
       FederateExtActor::None => Ok(()),
+      _ => federate_ext_create_note(&post_id, actor, dest_actor, posts, deliveries).await,
     },
     FederateExtAction::UpdatePost(post_id) => match dest_actor {
-      FederateExtActor::Person(dest_actor) => federate_ext_update_note(&post_id, actor, dest_actor, posts).await,
-      FederateExtActor::Group(dest_actor) => federate_ext_update_article(&post_id, actor, dest_actor, posts).await,
+      FederateExtActor::Group(dest_actor) => {
+        federate_ext_update_article(&post_id, actor, dest_actor, posts, deliveries).await
+      }
       FederateExtActor::None => Ok(()),
+      _ => federate_ext_update_note(&post_id, actor, dest_actor, posts, deliveries).await,
     },
     FederateExtAction::DeletePost(post_id) => match dest_actor {
-      FederateExtActor::Person(dest_actor) => federate_ext_delete_note(&post_id, actor, dest_actor).await,
-      FederateExtActor::Group(dest_actor) => federate_ext_delete_article(&post_id, actor, dest_actor).await,
+      FederateExtActor::Group(dest_actor) => federate_ext_delete_article(&post_id, actor, dest_actor, deliveries).await,
       FederateExtActor::None => Ok(()),
+      _ => federate_ext_delete_note(&post_id, actor, dest_actor, deliveries).await,
     },
-    FederateExtAction::FollowProfile => federate_ext_create_follow(actor, dest_actor).await,
-    FederateExtAction::UnfollowProfile => federate_ext_remove_follow(actor, dest_actor).await,
-    FederateExtAction::FollowGroup(group_id) => federate_ext_join_group(actor, &group_id, orbits).await,
-    FederateExtAction::UnfollowGroup(group_id) => federate_ext_leave_group(actor, &group_id, orbits).await,
+    FederateExtAction::FollowProfile => federate_ext_create_follow(actor, dest_actor, deliveries).await,
+    FederateExtAction::UnfollowProfile => federate_ext_remove_follow(actor, dest_actor, deliveries).await,
+    FederateExtAction::AcceptFollow(activity) => {
+      federate_ext_answer_follow(ActivityType::Accept, &activity, actor, dest_actor, deliveries).await
+    }
+    FederateExtAction::RejectFollow(activity) => {
+      federate_ext_answer_follow(ActivityType::Reject, &activity, actor, dest_actor, deliveries).await
+    }
+    FederateExtAction::BlockProfile => federate_ext_block(actor, dest_actor, deliveries).await,
+    FederateExtAction::UnblockProfile => federate_ext_unblock(actor, dest_actor, deliveries).await,
+    FederateExtAction::FollowGroup(group_id) => federate_ext_join_group(actor, &group_id, orbits, deliveries).await,
+    FederateExtAction::UnfollowGroup(group_id) => federate_ext_leave_group(actor, &group_id, orbits, deliveries).await,
+    FederateExtAction::LikePost(post_id) => match dest_actor {
+      FederateExtActor::Person(dest_actor) => {
+        federate_ext_like_note(&post_id, actor, dest_actor, posts, deliveries).await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::UnlikePost(post_id) => match dest_actor {
+      FederateExtActor::Person(dest_actor) => {
+        federate_ext_unlike_note(&post_id, actor, dest_actor, posts, deliveries).await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::BoostPost(post_id) => match dest_actor {
+      FederateExtActor::Person(_) | FederateExtActor::SharedInbox(_, _) => {
+        federate_ext_boost_note(&post_id, actor, dest_actor, posts, deliveries).await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::UnboostPost(post_id) => match dest_actor {
+      FederateExtActor::Person(_) | FederateExtActor::SharedInbox(_, _) => {
+        federate_ext_unboost_note(&post_id, actor, dest_actor, posts, deliveries).await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::LikeComment(post_id, comment_id) => match dest_actor {
+      FederateExtActor::Person(dest_actor) => {
+        federate_ext_like_comment(&post_id, &comment_id, actor, dest_actor, comments, deliveries).await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::UnlikeComment(post_id, comment_id) => match dest_actor {
+      FederateExtActor::Person(dest_actor) => {
+        federate_ext_unlike_comment(&post_id, &comment_id, actor, dest_actor, comments, deliveries).await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::CreateComment(post_id, comment_id) => {
+      federate_ext_create_comment(&post_id, &comment_id, actor, dest_actor, comments, deliveries).await
+    }
+    FederateExtAction::DeleteComment(post_id, comment_id) => {
+      federate_ext_delete_comment(&post_id, &comment_id, actor, dest_actor, deliveries).await
+    }
+    FederateExtAction::FlagReport(report_id) => {
+      federate_ext_flag(&report_id, actor, dest_actor, posts, comments, reports, deliveries).await
+    }
+    FederateExtAction::GroupCreatePost(orbit_id, post_id) => {
+      federate_ext_announce_group_post(
+        ActivityType::Create,
+        &orbit_id,
+        &post_id,
+        None,
+        actor,
+        dest_actor,
+        posts,
+        orbits,
+        deliveries,
+      )
+      .await
+    }
+    FederateExtAction::GroupUpdatePost(orbit_id, post_id) => {
+      federate_ext_announce_group_post(
+        ActivityType::Update,
+        &orbit_id,
+        &post_id,
+        None,
+        actor,
+        dest_actor,
+        posts,
+        orbits,
+        deliveries,
+      )
+      .await
+    }
+    FederateExtAction::GroupDeletePost(orbit_id, post_id, post_uri) => {
+      federate_ext_announce_group_post(
+        ActivityType::Delete,
+        &orbit_id,
+        &post_id,
+        Some(&post_uri),
+        actor,
+        dest_actor,
+        posts,
+        orbits,
+        deliveries,
+      )
+      .await
+    }
+    FederateExtAction::GroupRemoveObject(orbit_id, object_uri) => match dest_actor {
+      FederateExtActor::Person(dest_actor) => {
+        federate_ext_group_remove_object(&orbit_id, &object_uri, dest_actor, orbits, deliveries).await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::GroupAcceptMember(orbit_id, activity) => match dest_actor {
+      FederateExtActor::Person(dest_actor) => {
+        federate_ext_group_answer_member(
+          ActivityType::Accept,
+          &orbit_id,
+          &activity,
+          dest_actor,
+          orbits,
+          deliveries,
+        )
+        .await
+      }
+      _ => Ok(()),
+    },
+    FederateExtAction::GroupRejectMember(orbit_id, activity) => match dest_actor {
+      FederateExtActor::Person(dest_actor) => {
+        federate_ext_group_answer_member(
+          ActivityType::Reject,
+          &orbit_id,
+          &activity,
+          dest_actor,
+          orbits,
+          deliveries,
+        )
+        .await
+      }
+      _ => Ok(()),
+    },
+  }
+}
+
+async fn federate_ext_announce_group_post(
+  activity_type: ActivityType,
+  orbit_id: &Uuid,
+  post_id: &Uuid,
+  deleted_post_uri: Option<&str>,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  posts: &PostPool,
+  orbits: &OrbitPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let dest_actor = match dest_actor {
+    FederateExtActor::Person(dest_actor) => dest_actor,
+    _ => return Ok(()),
+  };
+
+  let group = match orbits.fetch_orbit(orbit_id).await? {
+    Some(orbit) => orbit,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  federate_ext_announce_article(
+    activity_type,
+    post_id,
+    deleted_post_uri,
+    actor,
+    &group,
+    dest_actor,
+    posts,
+    deliveries,
+  )
+  .await
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use crate::{
+    federation::activitypub::federate::{federate_ext_group_by_inbox, FederateExtActorRef},
+    model::{fixtures, user::User},
+  };
+
+  fn remote_user(fediverse_uri: &str, shared_inbox_uri: Option<&str>) -> User {
+    User {
+      fediverse_uri: fediverse_uri.to_string(),
+      is_external: true,
+      ext_apub_shared_inbox_uri: shared_inbox_uri.map(|uri| uri.to_string()),
+      ..fixtures::user(Uuid::new_v4())
+    }
+  }
+
+  #[test]
+  fn test_group_by_inbox_merges_shared_inboxes() {
+    let destinations = federate_ext_group_by_inbox(vec![
+      remote_user("https://a.example/users/1", Some("https://a.example/inbox")),
+      remote_user("https://b.example/users/1", Some("https://b.example/inbox")),
+      remote_user("https://a.example/users/2", Some("https://a.example/inbox")),
+    ]);
+
+    assert_eq!(destinations.len(), 2);
+    assert!(matches!(
+      &destinations[0],
+      FederateExtActorRef::SharedInbox(uri, recipients)
+        if uri == "https://a.example/inbox"
+          && *recipients == vec!["https://a.example/users/1", "https://a.example/users/2"]
+    ));
+    assert!(matches!(
+      &destinations[1],
+      FederateExtActorRef::SharedInbox(uri, recipients)
+        if uri == "https://b.example/inbox" && *recipients == vec!["https://b.example/users/1"]
+    ));
+  }
+
+  #[test]
+  fn test_group_by_inbox_falls_back_to_personal_inbox() {
+    let without_shared_inbox = remote_user("https://c.example/users/1", None);
+    let user_id = without_shared_inbox.user_id;
+
+    let destinations = federate_ext_group_by_inbox(vec![
+      without_shared_inbox,
+      remote_user("https://a.example/users/1", Some("https://a.example/inbox")),
+    ]);
+
+    assert_eq!(destinations.len(), 2);
+    assert!(matches!(destinations[0], FederateExtActorRef::Person(id) if id == user_id));
+    assert!(matches!(
+      &destinations[1],
+      FederateExtActorRef::SharedInbox(uri, recipients)
+        if uri == "https://a.example/inbox" && *recipients == vec!["https://a.example/users/1"]
+    ));
   }
 }
@@ -0,0 +1,162 @@
+use uuid::Uuid;
+
+use super::{
+  federate::FederateExtActor,
+  util::{activitypub_ref_to_id, send_activitypub_object, FederateResult},
+};
+use crate::{
+  activitypub::{
+    activity::ActivityProps, activity_type::ActivityType, document::ActivityPubDocument, object::Object,
+    rdf_string::RdfString, reference::Reference,
+  },
+  db::{
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, post_repository::PostPool,
+    report_repository::ReportPool, user_repository::UserPool,
+  },
+  helpers::api::relative_to_absolute_uri,
+  logic::LogicErr,
+  model::{report::NewReport, user::User},
+  settings::SETTINGS,
+};
+
+fn flag_object_uris(object: &Option<Reference<Object>>) -> Vec<String> {
+  match object {
+    Some(Reference::Mixed(refs)) => refs.iter().filter_map(activitypub_ref_to_id).collect(),
+    Some(obj_ref) => activitypub_ref_to_id(obj_ref).into_iter().collect(),
+    None => vec![],
+  }
+}
+
+/// Invoked when a remote server forwards a report about our users or their content. Flags list the reported actor
+/// alongside any of their posts, so these are folded into a single report.
+pub async fn federate_create_flag(
+  activity_object: &Object,
+  actor: &User,
+  users: &UserPool,
+  posts: &PostPool,
+  comments: &CommentPool,
+  reports: &ReportPool,
+) -> Result<FederateResult, LogicErr> {
+  let uris = match &activity_object.activity {
+    Some(activity) => flag_object_uris(&activity.object),
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  let mut target_user_id = None;
+  let mut post = None;
+  let mut comment = None;
+
+  for uri in uris {
+    let uri = match uri.starts_with(&SETTINGS.server.api_fqdn) {
+      true => uri.replace(&SETTINGS.server.api_fqdn, ""),
+      false => continue,
+    };
+
+    if let Some(user) = users.fetch_by_fediverse_uri(&uri).await {
+      target_user_id = target_user_id.or(Some(user.user_id));
+    } else if let Some(found) = posts.find_optional_by_uri(&uri).await {
+      post = post.or(Some(found));
+    } else if let Some(found) = comments.fetch_comment_from_uri(&uri).await {
+      comment = comment.or(Some(found));
+    }
+  }
+
+  let post = match (&post, &comment) {
+    (None, Some(comment)) => posts.find_optional_by_id(&comment.post_id).await,
+    _ => post,
+  };
+
+  let target_user_id = target_user_id
+    .or_else(|| comment.as_ref().map(|comment| comment.user_id))
+    .or_else(|| post.as_ref().map(|post| post.user_id));
+
+  if target_user_id.is_none() {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  let reason = match &activity_object.content {
+    Some(RdfString::Raw(content)) => content.to_owned(),
+    Some(RdfString::Props(props)) => props.string.to_owned(),
+    None => String::new(),
+  };
+
+  let orbit_id = post.as_ref().and_then(|post| post.orbit_id);
+
+  reports
+    .create_report(&NewReport {
+      reporter_id: Some(actor.user_id),
+      target_user_id,
+      post_id: post.as_ref().map(|post| post.post_id),
+      comment_id: comment.as_ref().map(|comment| comment.comment_id),
+      orbit_id,
+      moderator_orbit_id: orbit_id,
+      reason,
+      is_external: true,
+      forwarded: false,
+      uri: activity_object.id.clone(),
+    })
+    .await?;
+
+  Ok(FederateResult::None)
+}
+
+/// Forwards a report about remote content to the server it came from. As on Mastodon, the Flag is sent to that server's
+/// shared inbox by the instance actor, which `actor` is expected to be, so the reporter isn't revealed.
+pub async fn federate_ext_flag(
+  report_id: &Uuid,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  posts: &PostPool,
+  comments: &CommentPool,
+  reports: &ReportPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  match dest_actor {
+    FederateExtActor::Person(_) | FederateExtActor::SharedInbox(_, _) => {}
+    _ => return Err(LogicErr::MissingRecord),
+  };
+
+  let report = match reports.fetch_report(report_id).await? {
+    Some(report) => report,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let mut objects: Vec<Reference<Object>> = dest_actor.recipient_uris().into_iter().map(Reference::Remote).collect();
+
+  if let Some(post_id) = report.post_id {
+    if let Some(post) = posts.find_optional_by_id(&post_id).await {
+      objects.push(Reference::Remote(relative_to_absolute_uri(&post.uri)));
+
+      if let Some(comment_id) = report.comment_id {
+        if let Some(comment) = comments.fetch_comment(&post_id, &comment_id, &report.reporter_id).await {
+          objects.push(Reference::Remote(relative_to_absolute_uri(&comment.uri)));
+        }
+      }
+    }
+  }
+
+  let response_object = Object::builder()
+    .kind(Some(ActivityType::Flag.to_string()))
+    .id(Some(format!(
+      "{}/reports/{}",
+      SETTINGS.server.api_fqdn, report.report_id
+    )))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .content(Some(RdfString::Raw(report.reason)))
+    .activity(Some(
+      ActivityProps::builder().object(Some(Reference::Mixed(objects))).build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match dest_actor.inbox_uri() {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(&response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
@@ -1,18 +1,51 @@
+use std::str::FromStr;
+
+use uuid::Uuid;
+
 use crate::{
-  activitypub::object::Object,
-  db::{orbit_repository::OrbitPool, user_orbit_repository::UserOrbitPool},
-  logic::LogicErr,
-  model::user::User,
+  activitypub::{
+    activity::ActivityProps,
+    activity_type::ActivityType,
+    document::ActivityPubDocument,
+    object::{Object, ObjectType},
+    reference::Reference,
+  },
+  db::{
+    delivery_repository::DeliveryPool, job_repository::JobPool, notification_repository::NotificationPool,
+    orbit_membership_repository::OrbitMembershipPool, orbit_moderation_repository::OrbitModerationPool,
+    orbit_repository::OrbitPool, post_attachment_repository::PostAttachmentPool,
+    post_mention_repository::PostMentionPool, post_repository::PostPool, tag_repository::TagPool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+  },
+  helpers::api::relative_to_absolute_uri,
+  logic::{notification::notify_orbit, LogicErr},
+  model::{notification_type::NotificationType, orbit::Orbit, orbit_join_policy::OrbitJoinPolicy, user::User},
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
 };
 
-use super::util::FederateResult;
+use super::{
+  actor::federate_user_actor,
+  article::{federate_create_article, federate_update_article},
+  mention::federate_post_mentions,
+  util::{
+    activitypub_ref_to_id_opt, fetch_activitypub_object, is_same_origin, send_activitypub_object, FederateResult,
+  },
+};
 
+/// Invoked when a remote user Follows or Joins one of our orbits. Orbits that need approval answer with a
+/// TentativeAccept and keep the activity, so a moderator's eventual decision can be sent back embedding it.
 pub async fn federate_create_member(
+  activity: &Object,
   activity_object: Object,
   actor: &User,
   user_orbits: &UserOrbitPool,
   orbits: &OrbitPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_membership: &OrbitMembershipPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
 ) -> Result<FederateResult, LogicErr> {
   let uri = match activity_object.id {
     Some(uri) => match uri.starts_with(&SETTINGS.server.api_fqdn) {
@@ -31,19 +64,68 @@ pub async fn federate_create_member(
     return Err(LogicErr::MissingRecord);
   }
 
-  if !user_orbits
+  let group_actor = (
+    target_orbit.fediverse_uri.to_owned(),
+    target_orbit.private_key.to_string(),
+  );
+
+  if orbit_moderation
+    .user_is_banned(&target_orbit.orbit_id, &actor.user_id)
+    .await?
+  {
+    return Ok(FederateResult::Reject(group_actor));
+  }
+
+  if user_orbits
     .user_is_member(&actor.user_id, &target_orbit.orbit_id)
     .await?
   {
-    user_orbits
-      .create_user_orbit(&target_orbit.orbit_id, &actor.user_id)
-      .await?;
+    return Ok(FederateResult::Accept(group_actor));
   }
 
-  Ok(FederateResult::Accept((
-    target_orbit.fediverse_uri.to_owned(),
-    target_orbit.private_key.to_string(),
-  )))
+  let is_invited = orbit_membership
+    .user_is_invited(&target_orbit.orbit_id, &actor.user_id)
+    .await?;
+
+  match (target_orbit.join_policy, is_invited) {
+    (OrbitJoinPolicy::Open, _) | (_, true) => {
+      user_orbits
+        .create_user_orbit(&target_orbit.orbit_id, &actor.user_id)
+        .await?;
+      orbit_membership
+        .delete_invite(&target_orbit.orbit_id, &actor.user_id)
+        .await?;
+
+      notify_orbit(
+        notifications,
+        pubsub,
+        &target_orbit.orbit_id,
+        &actor.user_id,
+        NotificationType::OrbitJoin,
+      )
+      .await;
+
+      Ok(FederateResult::Accept(group_actor))
+    }
+    (OrbitJoinPolicy::ApprovalRequired, false) => {
+      let activity = serde_json::to_string(activity).map_err(|e| LogicErr::InternalError(e.to_string()))?;
+      orbit_membership
+        .create_join_request(&target_orbit.orbit_id, &actor.user_id, &Some(activity))
+        .await?;
+
+      notify_orbit(
+        notifications,
+        pubsub,
+        &target_orbit.orbit_id,
+        &actor.user_id,
+        NotificationType::OrbitJoinRequest,
+      )
+      .await;
+
+      Ok(FederateResult::TentativeAccept(group_actor))
+    }
+    (OrbitJoinPolicy::InviteOnly, false) => Ok(FederateResult::Reject(group_actor)),
+  }
 }
 
 pub async fn federate_remove_member(
@@ -75,3 +157,251 @@ pub async fn federate_remove_member(
     target_orbit.private_key.to_string(),
   )))
 }
+
+/// Invoked when a remote Group Announces an activity performed by one of its members (FEP-1b12). The wrapped
+/// activity is not signed by its author, so the object is re-fetched from its origin before being applied.
+pub async fn federate_group_announce(
+  activity_object: Object,
+  group: &Orbit,
+  users: &UserPool,
+  posts: &PostPool,
+  post_mentions: &PostMentionPool,
+  tags: &TagPool,
+  jobs: &JobPool,
+  post_attachments: &PostAttachmentPool,
+  orbits: &OrbitPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  queue: &Queue,
+) -> Result<FederateResult, LogicErr> {
+  if !group.is_external {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  let kind = match activity_object.kind.as_ref().map(|v| ActivityType::from_str(v)) {
+    Some(Ok(kind)) => kind,
+    _ => {
+      log::warn!("Unimplemented federation task: Group Announce of a non-activity object");
+      return Ok(FederateResult::None);
+    }
+  };
+
+  let author = federate_user_actor(&activity_object.actor, users).await?;
+
+  let target = match &activity_object.activity {
+    Some(activity) => match activitypub_ref_to_id_opt(&activity.object) {
+      Some(uri) => uri,
+      None => return Err(LogicErr::InvalidData),
+    },
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  if !is_same_origin(&target, &author.fediverse_uri) {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  match kind {
+    ActivityType::Create | ActivityType::Update => {
+      let mut object = match fetch_activitypub_object(&target).await {
+        Some(obj) => obj,
+        None => return Err(LogicErr::MissingRecord),
+      };
+
+      if object.id.as_ref() != Some(&target)
+        || activitypub_ref_to_id_opt(&object.attributed_to).as_ref() != Some(&author.fediverse_uri)
+      {
+        return Err(LogicErr::UnauthorizedError);
+      }
+
+      if object.in_reply_to.is_some() {
+        log::warn!("Unimplemented federation task: Group Announce of a reply");
+        return Ok(FederateResult::None);
+      }
+
+      match activitypub_ref_to_id_opt(&object.audience) {
+        Some(audience) if audience != group.fediverse_uri => return Err(LogicErr::UnauthorizedError),
+        Some(_) => {}
+        None => object.audience = Some(Reference::Remote(group.fediverse_uri.to_owned())),
+      };
+
+      let existing = posts.find_optional_by_uri(&target).await;
+
+      match kind {
+        ActivityType::Create => match existing {
+          // NOTE: The post may have already been picked up by crawling the group's outbox
+          Some(_) => Ok(FederateResult::None),
+          None => {
+            let tag = object.tag.clone();
+            let result =
+              federate_create_article(object, &author, posts, tags, jobs, post_attachments, orbits, queue).await;
+
+            if result.is_ok() {
+              federate_post_mentions(
+                &tag,
+                &target,
+                &author,
+                users,
+                posts,
+                post_mentions,
+                notifications,
+                pubsub,
+              )
+              .await;
+            }
+
+            result
+          }
+        },
+        _ => match existing {
+          Some(post) => {
+            if post.orbit_id != Some(group.orbit_id) {
+              return Err(LogicErr::UnauthorizedError);
+            }
+
+            // Edits never change who can see a post, so it keeps the visibility its Create gave it
+            federate_update_article(object, &author, post.visibility, posts, tags).await
+          }
+          None => federate_create_article(object, &author, posts, tags, jobs, post_attachments, orbits, queue).await,
+        },
+      }
+    }
+    ActivityType::Delete => {
+      // If the origin still serves the object, the deletion didn't come from its author
+      if let Some(obj) = fetch_activitypub_object(&target).await {
+        if ObjectType::from_str_opt(&obj.kind) != Some(ObjectType::Tombstone) {
+          return Err(LogicErr::UnauthorizedError);
+        }
+      }
+
+      let post = match posts.find_optional_by_uri(&target).await {
+        Some(post) => post,
+        None => return Ok(FederateResult::None),
+      };
+
+      if post.orbit_id != Some(group.orbit_id) {
+        return Err(LogicErr::UnauthorizedError);
+      }
+
+      posts.delete_post_from_uri(&target, &author.user_id).await?;
+
+      Ok(FederateResult::None)
+    }
+    _ => {
+      log::warn!(
+        "Unimplemented federation task: Group Announce of Activity Type: {}",
+        kind
+      );
+      Ok(FederateResult::None)
+    }
+  }
+}
+
+/// Tells a remote member that a moderator took a post or comment down from one of our orbits. The Group deletes
+/// objects that originated here, but can only remove remote members' own objects from its collection.
+pub async fn federate_ext_group_remove_object(
+  orbit_id: &Uuid,
+  object_uri: &str,
+  dest_actor: &User,
+  orbits: &OrbitPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let group = match orbits.fetch_orbit(orbit_id).await? {
+    Some(orbit) => orbit,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if group.is_external {
+    return Ok(());
+  }
+
+  let group_uri = relative_to_absolute_uri(&group.fediverse_uri);
+  let object_is_local = !object_uri.starts_with("http");
+  let object_uri = relative_to_absolute_uri(object_uri);
+
+  let activity = match object_is_local {
+    true => ActivityProps::builder()
+      .object(Some(Reference::Embedded(Box::new(
+        Object::builder()
+          .kind(Some(ObjectType::Tombstone.to_string()))
+          .id(Some(object_uri.clone()))
+          .url(Some(Reference::Remote(object_uri)))
+          .build(),
+      ))))
+      .build(),
+    false => ActivityProps::builder()
+      .object(Some(Reference::Remote(object_uri)))
+      .target(Some(Reference::Remote(format!(
+        "{}/orbit/{}/outbox",
+        SETTINGS.server.api_fqdn, group.orbit_id
+      ))))
+      .build(),
+  };
+
+  let kind = match object_is_local {
+    true => ActivityType::Delete,
+    false => ActivityType::Remove,
+  };
+
+  let public = "https://www.w3.org/ns/activitystreams#Public".to_string();
+  let members_uri = format!("{}/orbit/{}/members", SETTINGS.server.api_fqdn, group.orbit_id);
+
+  let response_object = Object::builder()
+    .kind(Some(kind.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(group_uri.clone())))
+    .to(Some(Reference::Mixed(vec![Reference::Remote(public)])))
+    .cc(Some(Reference::Mixed(vec![Reference::Remote(members_uri)])))
+    .audience(Some(Reference::Remote(group_uri)))
+    .activity(Some(activity))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &group.fediverse_uri, &group.private_key, deliveries).await
+}
+
+/// Sends a moderator's decision on a pending Follow/Join back to the remote user who asked to join
+pub async fn federate_ext_group_answer_member(
+  activity_type: ActivityType,
+  orbit_id: &Uuid,
+  activity: &str,
+  dest_actor: &User,
+  orbits: &OrbitPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let group = match orbits.fetch_orbit(orbit_id).await? {
+    Some(orbit) => orbit,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if group.is_external {
+    return Ok(());
+  }
+
+  let activity: Object = serde_json::from_str(activity).map_err(|_| LogicErr::InvalidData)?;
+
+  let response_object = Object::builder()
+    .kind(Some(activity_type.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(relative_to_absolute_uri(&group.fediverse_uri))))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Embedded(Box::new(activity))))
+        .build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &group.fediverse_uri, &group.private_key, deliveries).await
+}
@@ -0,0 +1,39 @@
+use crate::{
+  activitypub::{
+    object::{Object, ObjectType},
+    reference::Reference,
+  },
+  logic::tag::normalize_tag,
+};
+
+fn hashtag_name(obj: &Object) -> Option<String> {
+  if ObjectType::from_str_opt(&obj.kind) != Some(ObjectType::Hashtag) {
+    return None;
+  }
+
+  obj.name.as_deref().and_then(normalize_tag)
+}
+
+/// The normalized names of an incoming object's Hashtag tags
+pub fn federate_hashtags(tag: &Option<Reference<Object>>) -> Vec<String> {
+  let names: Vec<String> = match tag {
+    Some(Reference::Embedded(obj)) => hashtag_name(obj).into_iter().collect(),
+    Some(Reference::Mixed(tags)) => tags
+      .iter()
+      .filter_map(|tag| match tag {
+        Reference::Embedded(obj) => hashtag_name(obj),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  };
+
+  let mut tags: Vec<String> = vec![];
+  for name in names {
+    if !tags.contains(&name) {
+      tags.push(name);
+    }
+  }
+
+  tags
+}
@@ -0,0 +1,94 @@
+use uuid::Uuid;
+
+use super::util::activitypub_ref_to_uri_opt;
+use crate::{
+  activitypub::{
+    object::{Object, ObjectType},
+    reference::Reference,
+  },
+  db::{
+    notification_repository::NotificationPool, post_mention_repository::PostMentionPool, post_repository::PostPool,
+    user_repository::UserPool,
+  },
+  logic::mention::record_mentions,
+  model::user::User,
+  settings::SETTINGS,
+  streaming::pubsub::PubSub,
+};
+
+fn mention_href(obj: &Object) -> Option<String> {
+  if ObjectType::from_str_opt(&obj.kind) != Some(ObjectType::Mention) {
+    return None;
+  }
+
+  obj
+    .link
+    .as_ref()
+    .and_then(|link| activitypub_ref_to_uri_opt(&link.href))
+}
+
+/// The local users named in an incoming object's Mention tags
+pub async fn federate_local_mentions(tag: &Option<Reference<Object>>, users: &UserPool) -> Vec<Uuid> {
+  let hrefs: Vec<String> = match tag {
+    Some(Reference::Embedded(obj)) => mention_href(obj).into_iter().collect(),
+    Some(Reference::Mixed(tags)) => tags
+      .iter()
+      .filter_map(|tag| match tag {
+        Reference::Embedded(obj) => mention_href(obj),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  };
+
+  let mut user_ids: Vec<Uuid> = vec![];
+
+  for href in hrefs {
+    // Our actors are only ever addressed by their absolute URI, while we store them relative to the API
+    let fediverse_uri = match href.strip_prefix(&SETTINGS.server.api_fqdn) {
+      Some(uri) => uri,
+      None => continue,
+    };
+
+    if let Some(user) = users.fetch_by_fediverse_uri(fediverse_uri).await {
+      if !user.is_external && !user_ids.contains(&user.user_id) {
+        user_ids.push(user.user_id);
+      }
+    }
+  }
+
+  user_ids
+}
+
+/// Records the local users mentioned in a remote post after it has been ingested, notifying them
+pub async fn federate_post_mentions(
+  tag: &Option<Reference<Object>>,
+  post_uri: &str,
+  actor: &User,
+  users: &UserPool,
+  posts: &PostPool,
+  post_mentions: &PostMentionPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+) {
+  let user_ids = federate_local_mentions(tag, users).await;
+  if user_ids.is_empty() {
+    return;
+  }
+
+  let post = match posts.find_optional_by_uri(post_uri).await {
+    Some(post) if post.user_id == actor.user_id => post,
+    _ => return,
+  };
+
+  record_mentions(
+    post_mentions,
+    notifications,
+    pubsub,
+    &post.post_id,
+    &actor.user_id,
+    &post.visibility,
+    &user_ids,
+  )
+  .await
+}
@@ -1,9 +1,16 @@
 pub mod actor;
 mod article;
+mod comment;
+pub mod delivery;
 pub mod federate;
+mod flag;
 mod group;
+mod hashtag;
+mod mention;
 mod note;
 mod object;
 mod person;
+pub mod resolve;
+mod undo;
 mod util;
 pub use federate::*;
@@ -1,6 +1,15 @@
 use uuid::Uuid;
 
-use super::util::{activitypub_ref_to_uri_opt, deref_activitypub_ref_list, send_activitypub_object, FederateResult};
+use super::{
+  actor::federate_user_actor,
+  federate::FederateExtActor,
+  hashtag::federate_hashtags,
+  mention::federate_local_mentions,
+  util::{
+    activitypub_ref_to_uri_opt, deref_activitypub_ref_list, determine_activity_visibility, send_activitypub_object,
+    FederateResult,
+  },
+};
 use crate::{
   activitypub::{
     activity::ActivityProps,
@@ -12,20 +21,26 @@ use crate::{
     reference::Reference,
   },
   db::{
-    follow_repository::FollowPool, job_repository::JobPool, like_repository::LikePool,
-    post_attachment_repository::PostAttachmentPool, post_repository::PostPool,
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, follow_repository::FollowPool,
+    job_repository::JobPool, like_repository::LikePool, notification_repository::NotificationPool,
+    post_attachment_repository::PostAttachmentPool, post_mention_repository::PostMentionPool,
+    post_repository::PostPool, tag_repository::TagPool, user_repository::UserPool,
   },
-  helpers::api::map_db_err,
-  logic::LogicErr,
+  federation::domain_policy::uri_rejects_media,
+  helpers::api::{map_db_err, relative_to_absolute_uri},
+  logic::{mention::record_mentions, notification::notify, tag::record_tags, LogicErr},
   model::{
     access_type::AccessType,
     job::{JobStatus, NewJob},
+    notification::NewNotification,
+    notification_type::NotificationType,
     post::Post,
     post_attachment::PostAttachment,
     queue_job::{QueueJob, QueueJobType},
     user::User,
   },
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
@@ -33,19 +48,57 @@ pub async fn federate_create_note(
   activity_object: Object,
   actor: &User,
   access: AccessType,
+  users: &UserPool,
   follows: &FollowPool,
   posts: &PostPool,
+  post_mentions: &PostMentionPool,
+  tags: &TagPool,
   jobs: &JobPool,
   post_attachments: &PostAttachmentPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
   queue: &Queue,
 ) -> Result<FederateResult, LogicErr> {
   let followers = follows.fetch_user_followers(&actor.user_id).await.unwrap_or_default();
-  // Skip federating posts from users that our instance's users don't follow
-  if followers.is_empty() {
+  let mentioned_user_ids = federate_local_mentions(&activity_object.tag, users).await;
+  // Skip federating posts from users that our instance's users don't follow, unless they mention one of them
+  if followers.is_empty() && mentioned_user_ids.is_empty() {
     return Ok(FederateResult::None);
   }
 
-  let uri = match activity_object.id {
+  federate_store_note(
+    activity_object,
+    actor,
+    access,
+    &mentioned_user_ids,
+    posts,
+    post_mentions,
+    tags,
+    jobs,
+    post_attachments,
+    notifications,
+    pubsub,
+    queue,
+  )
+  .await
+}
+
+/// Stores a remote note and queues it for our users' feeds, regardless of whether anyone here follows its author
+pub async fn federate_store_note(
+  activity_object: Object,
+  actor: &User,
+  access: AccessType,
+  mentioned_user_ids: &[Uuid],
+  posts: &PostPool,
+  post_mentions: &PostMentionPool,
+  tags: &TagPool,
+  jobs: &JobPool,
+  post_attachments: &PostAttachmentPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  queue: &Queue,
+) -> Result<FederateResult, LogicErr> {
+  let uri = match activity_object.id.clone() {
     Some(uri) => uri,
     None => return Err(LogicErr::InvalidData),
   };
@@ -71,10 +124,62 @@ pub async fn federate_create_note(
     return Ok(FederateResult::None);
   };
 
+  let hashtags = federate_hashtags(&activity_object.tag);
+  let post_id = federate_ingest_note(activity_object, uri, actor, access.clone(), posts, post_attachments).await?;
+
+  record_mentions(
+    post_mentions,
+    notifications,
+    pubsub,
+    &post_id,
+    &actor.user_id,
+    &access,
+    mentioned_user_ids,
+  )
+  .await;
+
+  if !hashtags.is_empty() {
+    record_tags(tags, &post_id, &hashtags).await;
+  }
+
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(actor.user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(post_id),
+      associated_record_id: None,
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::CreatePostEvents)
+    .build();
+
+  queue.send_job(job).await?;
+
+  Ok(FederateResult::None)
+}
+
+async fn federate_ingest_note(
+  activity_object: Object,
+  uri: String,
+  actor: &User,
+  access: AccessType,
+  posts: &PostPool,
+  post_attachments: &PostAttachmentPool,
+) -> Result<Uuid, LogicErr> {
+  let reject_media = uri_rejects_media(&uri);
+
   let attachments: Vec<Object> = match deref_activitypub_ref_list(&activity_object.attachment).await {
     Some(obj) => obj
       .into_iter()
       .filter(|obj| {
+        if reject_media {
+          return false;
+        }
+
         let obj_type = match ObjectType::from_str_opt(&obj.kind) {
           Some(t) => t,
           None => return false,
@@ -188,7 +293,7 @@ pub async fn federate_create_note(
     let attachment = PostAttachment {
       attachment_id: Uuid::new_v4(),
       user_id: actor.user_id,
-      post_id,
+      post_id: Some(post_id),
       uri: Some(image_uri),
       width: image_width,
       height: image_height,
@@ -204,24 +309,7 @@ pub async fn federate_create_note(
     }
   }
 
-  let job_id = jobs
-    .create(NewJob {
-      created_by_id: Some(actor.user_id),
-      status: JobStatus::NotStarted,
-      record_id: Some(post_id),
-      associated_record_id: None,
-    })
-    .await
-    .map_err(map_db_err)?;
-
-  let job = QueueJob::builder()
-    .job_id(job_id)
-    .job_type(QueueJobType::CreatePostEvents)
-    .build();
-
-  queue.send_job(job).await?;
-
-  Ok(FederateResult::None)
+  Ok(post_id)
 }
 
 pub async fn federate_update_note(
@@ -229,7 +317,10 @@ pub async fn federate_update_note(
   actor: &User,
   access: AccessType,
   posts: &PostPool,
+  tags: &TagPool,
 ) -> Result<FederateResult, LogicErr> {
+  let hashtags = federate_hashtags(&activity_object.tag);
+
   let uri = match activity_object.id {
     Some(uri) => uri,
     None => return Err(LogicErr::InvalidData),
@@ -307,6 +398,7 @@ pub async fn federate_update_note(
   post.title = title;
 
   posts.update_post_content(&post).await?;
+  record_tags(tags, &post.post_id, &hashtags).await;
 
   Ok(FederateResult::None)
 }
@@ -314,8 +406,9 @@ pub async fn federate_update_note(
 pub async fn federate_ext_create_note(
   post_id: &Uuid,
   actor: &User,
-  dest_actor: &User,
+  dest_actor: &FederateExtActor,
   posts: &PostPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let post = match posts.fetch_post(post_id, &Some(actor.user_id)).await {
     Ok(post) => match post {
@@ -340,6 +433,9 @@ pub async fn federate_ext_create_note(
       "{}{}",
       SETTINGS.server.api_fqdn, actor.fediverse_uri
     ))))
+    .cc(Some(Reference::Mixed(
+      dest_actor.recipient_uris().into_iter().map(Reference::Remote).collect(),
+    )))
     .activity(Some(
       ActivityProps::builder()
         .object(Some(Reference::Embedded(Box::new(obj))))
@@ -349,19 +445,20 @@ pub async fn federate_ext_create_note(
 
   let doc = ActivityPubDocument::new(response_object);
 
-  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+  let response_uri = match dest_actor.inbox_uri() {
     Some(uri) => uri,
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(&response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
 pub async fn federate_ext_update_note(
   post_id: &Uuid,
   actor: &User,
-  dest_actor: &User,
+  dest_actor: &FederateExtActor,
   posts: &PostPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let post = match posts.fetch_post(post_id, &Some(actor.user_id)).await {
     Ok(post) => match post {
@@ -386,6 +483,9 @@ pub async fn federate_ext_update_note(
       "{}{}",
       SETTINGS.server.api_fqdn, actor.fediverse_uri
     ))))
+    .cc(Some(Reference::Mixed(
+      dest_actor.recipient_uris().into_iter().map(Reference::Remote).collect(),
+    )))
     .activity(Some(
       ActivityProps::builder()
         .object(Some(Reference::Embedded(Box::new(obj))))
@@ -395,15 +495,20 @@ pub async fn federate_ext_update_note(
 
   let doc = ActivityPubDocument::new(response_object);
 
-  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+  let response_uri = match dest_actor.inbox_uri() {
     Some(uri) => uri,
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(&response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
-pub async fn federate_ext_delete_note(post_id: &Uuid, actor: &User, dest_actor: &User) -> Result<(), LogicErr> {
+pub async fn federate_ext_delete_note(
+  post_id: &Uuid,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
   // NOTE: By this point, the post is deleted in our DB, so we have to build the URI from scratch here
   let uri = format!("{}/feed/{}", SETTINGS.server.api_fqdn, post_id);
   let obj = Object::builder()
@@ -419,6 +524,9 @@ pub async fn federate_ext_delete_note(post_id: &Uuid, actor: &User, dest_actor:
       "{}{}",
       SETTINGS.server.api_fqdn, actor.fediverse_uri
     ))))
+    .cc(Some(Reference::Mixed(
+      dest_actor.recipient_uris().into_iter().map(Reference::Remote).collect(),
+    )))
     .activity(Some(
       ActivityProps::builder()
         .object(Some(Reference::Embedded(Box::new(obj))))
@@ -428,12 +536,12 @@ pub async fn federate_ext_delete_note(post_id: &Uuid, actor: &User, dest_actor:
 
   let doc = ActivityPubDocument::new(response_object);
 
-  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+  let response_uri = match dest_actor.inbox_uri() {
     Some(uri) => uri,
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(&response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
 pub async fn federate_delete_note(target: String, actor: &User, posts: &PostPool) -> Result<FederateResult, LogicErr> {
@@ -447,9 +555,14 @@ pub async fn federate_like_note(
   actor: &User,
   posts: &PostPool,
   likes: &LikePool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
 ) -> Result<FederateResult, LogicErr> {
   let uri = match activitypub_ref_to_uri_opt(&activity_object.url) {
-    Some(uri) => uri,
+    Some(uri) => match uri.starts_with(&SETTINGS.server.api_fqdn) {
+      true => uri.replace(&SETTINGS.server.api_fqdn, ""),
+      false => uri,
+    },
     None => return Err(LogicErr::InvalidData),
   };
 
@@ -463,6 +576,20 @@ pub async fn federate_like_note(
 
   likes.create_like(&actor.user_id, &post.post_id).await.map(|_| ())?;
 
+  notify(
+    notifications,
+    pubsub,
+    NewNotification {
+      user_id: post.user_id,
+      source_user_id: actor.user_id,
+      notification_type: NotificationType::Like,
+      post_id: Some(post.post_id),
+      comment_id: None,
+      orbit_id: None,
+    },
+  )
+  .await;
+
   Ok(FederateResult::None)
 }
 
@@ -472,7 +599,12 @@ pub async fn federate_unlike_note(
   posts: &PostPool,
   likes: &LikePool,
 ) -> Result<FederateResult, LogicErr> {
-  let post = match posts.fetch_post_from_uri(&target, &Some(actor.user_id)).await {
+  let uri = match target.starts_with(&SETTINGS.server.api_fqdn) {
+    true => target.replace(&SETTINGS.server.api_fqdn, ""),
+    false => target,
+  };
+
+  let post = match posts.fetch_post_from_uri(&uri, &Some(actor.user_id)).await {
     Ok(post) => match post {
       Some(post) => post,
       None => return Err(LogicErr::MissingRecord),
@@ -484,3 +616,325 @@ pub async fn federate_unlike_note(
 
   Ok(FederateResult::None)
 }
+
+fn build_like_object(object_uri: &str, like_uri: &str, actor: &User) -> Object {
+  Object::builder()
+    .kind(Some(ActivityType::Like.to_string()))
+    .id(Some(like_uri.to_owned()))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Remote(object_uri.to_owned())))
+        .build(),
+    ))
+    .build()
+}
+
+async fn federate_ext_send_like(
+  object_uri: &str,
+  like_uri: &str,
+  actor: &User,
+  dest_actor: &User,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let doc = ActivityPubDocument::new(build_like_object(object_uri, like_uri, actor));
+
+  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+async fn federate_ext_send_unlike(
+  object_uri: &str,
+  like_uri: &str,
+  actor: &User,
+  dest_actor: &User,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let response_object = Object::builder()
+    .kind(Some(ActivityType::Undo.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Embedded(Box::new(build_like_object(
+          object_uri, like_uri, actor,
+        )))))
+        .build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match &dest_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+pub async fn federate_ext_like_note(
+  post_id: &Uuid,
+  actor: &User,
+  dest_actor: &User,
+  posts: &PostPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let post = match posts.find_optional_by_id(post_id).await {
+    Some(post) => post,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  // NOTE: The like ID is stable so that a later Undo can reference the same activity
+  let like_uri = format!("{}/feed/{}/likes/{}", SETTINGS.server.api_fqdn, post_id, actor.user_id);
+
+  federate_ext_send_like(
+    &relative_to_absolute_uri(&post.uri),
+    &like_uri,
+    actor,
+    dest_actor,
+    deliveries,
+  )
+  .await
+}
+
+pub async fn federate_ext_unlike_note(
+  post_id: &Uuid,
+  actor: &User,
+  dest_actor: &User,
+  posts: &PostPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let post = match posts.find_optional_by_id(post_id).await {
+    Some(post) => post,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let like_uri = format!("{}/feed/{}/likes/{}", SETTINGS.server.api_fqdn, post_id, actor.user_id);
+
+  federate_ext_send_unlike(
+    &relative_to_absolute_uri(&post.uri),
+    &like_uri,
+    actor,
+    dest_actor,
+    deliveries,
+  )
+  .await
+}
+
+pub async fn federate_ext_like_comment(
+  post_id: &Uuid,
+  comment_id: &Uuid,
+  actor: &User,
+  dest_actor: &User,
+  comments: &CommentPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let comment = match comments.fetch_comment(post_id, comment_id, &Some(actor.user_id)).await {
+    Some(comment) => comment,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let comment_uri = relative_to_absolute_uri(&comment.uri);
+  let like_uri = format!(
+    "{}/feed/{}/comments/{}/likes/{}",
+    SETTINGS.server.api_fqdn, post_id, comment_id, actor.user_id
+  );
+
+  federate_ext_send_like(&comment_uri, &like_uri, actor, dest_actor, deliveries).await
+}
+
+pub async fn federate_ext_unlike_comment(
+  post_id: &Uuid,
+  comment_id: &Uuid,
+  actor: &User,
+  dest_actor: &User,
+  comments: &CommentPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let comment = match comments.fetch_comment(post_id, comment_id, &Some(actor.user_id)).await {
+    Some(comment) => comment,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let comment_uri = relative_to_absolute_uri(&comment.uri);
+  let like_uri = format!(
+    "{}/feed/{}/comments/{}/likes/{}",
+    SETTINGS.server.api_fqdn, post_id, comment_id, actor.user_id
+  );
+
+  federate_ext_send_unlike(&comment_uri, &like_uri, actor, dest_actor, deliveries).await
+}
+
+pub async fn federate_announce_note(
+  activity_object: Object,
+  actor: &User,
+  users: &UserPool,
+  posts: &PostPool,
+  jobs: &JobPool,
+  post_attachments: &PostAttachmentPool,
+  queue: &Queue,
+) -> Result<FederateResult, LogicErr> {
+  let uri = match activity_object.id.clone() {
+    Some(uri) => match uri.starts_with(&SETTINGS.server.api_fqdn) {
+      true => uri.replace(&SETTINGS.server.api_fqdn, ""),
+      false => uri,
+    },
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  let post_id = match posts.find_optional_by_uri(&uri).await {
+    Some(post) => post.post_id,
+    None => {
+      // Only remote posts can be missing here, local posts that don't exist are simply invalid
+      if !uri.starts_with("http") {
+        return Err(LogicErr::MissingRecord);
+      }
+
+      let author = federate_user_actor(&activity_object.attributed_to, users).await?;
+
+      let access = match determine_activity_visibility(&activity_object.to, &author) {
+        Some(access) => access,
+        None => return Err(LogicErr::InvalidData),
+      };
+
+      federate_ingest_note(activity_object, uri, &author, access, posts, post_attachments).await?
+    }
+  };
+
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(actor.user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(post_id),
+      associated_record_id: None,
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::CreateBoostEvents)
+    .build();
+
+  queue.send_job(job).await?;
+
+  Ok(FederateResult::None)
+}
+
+fn build_announce_object(post_uri: &str, announce_uri: &str, actor: &User, recipients: Vec<String>) -> Object {
+  let mut cc = vec![Reference::Remote(format!(
+    "{}/user/{}/followers",
+    SETTINGS.server.api_fqdn, actor.user_id
+  ))];
+  cc.extend(recipients.into_iter().map(Reference::Remote));
+
+  Object::builder()
+    .kind(Some(ActivityType::Announce.to_string()))
+    .id(Some(announce_uri.to_owned()))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .to(Some(Reference::Mixed(vec![Reference::Remote(
+      "https://www.w3.org/ns/activitystreams#Public".to_string(),
+    )])))
+    .cc(Some(Reference::Mixed(cc)))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Remote(post_uri.to_owned())))
+        .build(),
+    ))
+    .build()
+}
+
+pub async fn federate_ext_boost_note(
+  post_id: &Uuid,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  posts: &PostPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let post = match posts.find_optional_by_id(post_id).await {
+    Some(post) => post,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  // Boosts are always public, so never leak posts that aren't already visible to the wider fediverse
+  if post.visibility != AccessType::PublicFederated && post.visibility != AccessType::Unlisted {
+    return Ok(());
+  }
+
+  // NOTE: The announce ID is stable so that a later Undo can reference the same activity
+  let announce_uri = format!("{}/feed/{}/boosts/{}", SETTINGS.server.api_fqdn, post_id, actor.user_id);
+
+  let doc = ActivityPubDocument::new(build_announce_object(
+    &relative_to_absolute_uri(&post.uri),
+    &announce_uri,
+    actor,
+    dest_actor.recipient_uris(),
+  ));
+
+  let response_uri = match dest_actor.inbox_uri() {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(&response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+pub async fn federate_ext_unboost_note(
+  post_id: &Uuid,
+  actor: &User,
+  dest_actor: &FederateExtActor,
+  posts: &PostPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let post = match posts.find_optional_by_id(post_id).await {
+    Some(post) => post,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let announce_uri = format!("{}/feed/{}/boosts/{}", SETTINGS.server.api_fqdn, post_id, actor.user_id);
+
+  let response_object = Object::builder()
+    .kind(Some(ActivityType::Undo.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .cc(Some(Reference::Mixed(
+      dest_actor.recipient_uris().into_iter().map(Reference::Remote).collect(),
+    )))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Embedded(Box::new(build_announce_object(
+          &relative_to_absolute_uri(&post.uri),
+          &announce_uri,
+          actor,
+          dest_actor.recipient_uris(),
+        )))))
+        .build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match dest_actor.inbox_uri() {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(&response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
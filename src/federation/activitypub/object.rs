@@ -2,14 +2,30 @@ use std::collections::HashMap;
 
 use crate::{
   activitypub::object::ObjectType,
-  db::{like_repository::LikePool, post_repository::PostPool, user_repository::UserPool},
+  db::{
+    comment_repository::CommentPool, like_repository::LikePool, post_repository::PostPool, user_repository::UserPool,
+  },
   logic::LogicErr,
-  model::{queue_job::OriginDataEntry, user::User},
+  model::{queue_job::OriginDataEntry, stream_message::StreamMessage, user::User},
   net::http_sig::extract_http_signature_origin,
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
 };
 
-use super::util::FederateResult;
+use super::{comment::federate_delete_comment, util::FederateResult};
+
+async fn delete_remote_post(target: &str, actor: &User, posts: &PostPool, pubsub: &PubSub) -> Result<(), LogicErr> {
+  // The post has to be looked up beforehand, as streaming clients only know it by its ID
+  let post = posts.find_optional_by_uri(target).await;
+
+  posts.delete_post_from_uri(target, &actor.user_id).await?;
+
+  if let Some(post) = post.filter(|post| post.user_id == actor.user_id) {
+    pubsub.publish(StreamMessage::Delete { post_id: post.post_id }).await;
+  }
+
+  Ok(())
+}
 
 /// Invoked when a Tombstone object is delivered to us, or we're deleting or removing any remote object.
 pub async fn federate_delete_remote_object(
@@ -20,6 +36,8 @@ pub async fn federate_delete_remote_object(
   posts: &PostPool,
   users: &UserPool,
   _likes: &LikePool,
+  comments: &CommentPool,
+  pubsub: &PubSub,
 ) -> Result<FederateResult, LogicErr> {
   if SETTINGS.app.secure {
     let origin = match extract_http_signature_origin(origin_data) {
@@ -38,7 +56,9 @@ pub async fn federate_delete_remote_object(
 
   match object_type {
     ObjectType::Note => {
-      posts.delete_post_from_uri(&target, &actor.user_id).await?;
+      // Notes can either be posts or replies to posts, so we remove whichever one we have
+      delete_remote_post(&target, actor, posts, pubsub).await?;
+      federate_delete_comment(target, actor, comments).await?;
       return Ok(FederateResult::None);
     }
     ObjectType::Profile => {
@@ -46,7 +66,7 @@ pub async fn federate_delete_remote_object(
       return Ok(FederateResult::None);
     }
     ObjectType::Article => {
-      posts.delete_post_from_uri(&target, &actor.user_id).await?;
+      delete_remote_post(&target, actor, posts, pubsub).await?;
       return Ok(FederateResult::None);
     }
     _ => {}
@@ -59,7 +79,8 @@ pub async fn federate_delete_remote_object(
   // Mastodon also sends a Delete activity for the Tombstone, which makes no sense as it's not deleting
   // a Tombstone, it's deleting an object, so we're forced to assume that any notification of Tombstone
   // means that an object is going to be deleted somewhere somehow with whatever URI it sends us.
-  posts.delete_post_from_uri(&target, &actor.user_id).await?;
+  delete_remote_post(&target, actor, posts, pubsub).await?;
+  comments.delete_comment_from_uri(&target, &actor.user_id).await?;
   users.delete_user_from_uri(&target).await?;
 
   Ok(FederateResult::None)
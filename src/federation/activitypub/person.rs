@@ -11,21 +11,32 @@ use crate::{
     reference::Reference,
     tombstone::TombstoneProps,
   },
-  db::{follow_repository::FollowPool, orbit_repository::OrbitPool, user_repository::UserPool},
-  logic::LogicErr,
-  model::user::User,
+  db::{
+    block_repository::BlockPool, delivery_repository::DeliveryPool, follow_repository::FollowPool,
+    notification_repository::NotificationPool, orbit_repository::OrbitPool, user_repository::UserPool,
+  },
+  helpers::api::relative_to_absolute_uri,
+  logic::{notification::notify, LogicErr},
+  model::{notification::NewNotification, notification_type::NotificationType, user::User},
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
 };
 
 use super::{
-  util::{send_activitypub_object, FederateResult},
+  util::{activitypub_ref_to_id_opt, send_activitypub_object, FederateResult},
   FederateExtActor,
 };
 
+/// Invoked when a remote actor follows one of our users. Follows of locked accounts are held until the user answers
+/// them, so the Follow itself is kept to be embedded in that answer.
 pub async fn federate_create_follow(
+  activity: &Object,
   activity_object: Object,
   actor: &User,
+  blocks: &BlockPool,
   follows: &FollowPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
   users: &UserPool,
 ) -> Result<FederateResult, LogicErr> {
   let uri = match activity_object.id {
@@ -45,16 +56,101 @@ pub async fn federate_create_follow(
     return Err(LogicErr::MissingRecord);
   }
 
-  if !follows.user_follows_user(&actor.user_id, &followed_user.user_id).await {
-    follows.create_follow(&actor.user_id, &followed_user.user_id).await?;
+  if blocks
+    .block_exists_between(&actor.user_id, &followed_user.user_id)
+    .await
+  {
+    return Ok(FederateResult::Reject((
+      followed_user.fediverse_uri,
+      followed_user.private_key,
+    )));
+  }
+
+  if follows.user_follows_user(&actor.user_id, &followed_user.user_id).await {
+    return Ok(FederateResult::Accept((
+      followed_user.fediverse_uri,
+      followed_user.private_key,
+    )));
+  }
+
+  let mut notification = NewNotification {
+    user_id: followed_user.user_id,
+    source_user_id: actor.user_id,
+    notification_type: NotificationType::Follow,
+    post_id: None,
+    comment_id: None,
+    orbit_id: None,
+  };
+
+  if followed_user.manually_approves_followers {
+    let activity = serde_json::to_string(activity).map_err(|_| LogicErr::InvalidData)?;
+    follows
+      .create_follow_request(&actor.user_id, &followed_user.user_id, &Some(activity))
+      .await?;
+
+    notification.notification_type = NotificationType::FollowRequest;
+    notify(notifications, pubsub, notification).await;
+
+    return Ok(FederateResult::TentativeAccept((
+      followed_user.fediverse_uri,
+      followed_user.private_key,
+    )));
   }
 
+  follows.create_follow(&actor.user_id, &followed_user.user_id).await?;
+  notify(notifications, pubsub, notification).await;
+
   Ok(FederateResult::Accept((
     followed_user.fediverse_uri,
     followed_user.private_key,
   )))
 }
 
+/// Invoked when a remote actor answers a Follow one of our users sent them. Rejects also arrive for follows that were
+/// accepted earlier, when the remote actor removes the follower.
+pub async fn federate_answer_follow(
+  accepted: bool,
+  follow: Object,
+  actor: &User,
+  follows: &FollowPool,
+  users: &UserPool,
+) -> Result<FederateResult, LogicErr> {
+  let follow_activity = match follow.activity {
+    Some(activity) => activity,
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  // Only the actor that was followed gets to answer the Follow
+  if activitypub_ref_to_id_opt(&follow_activity.object) != Some(relative_to_absolute_uri(&actor.fediverse_uri)) {
+    return Ok(FederateResult::None);
+  }
+
+  let uri = match activitypub_ref_to_id_opt(&follow.actor) {
+    Some(uri) => uri.replace(&SETTINGS.server.api_fqdn, ""),
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  let following_user = match users.fetch_by_fediverse_uri(&uri).await {
+    Some(user) => user,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if following_user.is_external {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  match accepted {
+    true => {
+      follows
+        .accept_follow_request(&following_user.user_id, &actor.user_id)
+        .await?
+    }
+    false => follows.delete_follow(&following_user.user_id, &actor.user_id).await?,
+  }
+
+  Ok(FederateResult::None)
+}
+
 pub async fn federate_remove_follow(
   target: String,
   actor: &User,
@@ -83,7 +179,68 @@ pub async fn federate_remove_follow(
   )))
 }
 
-pub async fn federate_ext_create_follow(actor: &User, following_actor: &FederateExtActor) -> Result<(), LogicErr> {
+/// Invoked when a remote actor blocks one of our users, which also severs any follows between the two
+pub async fn federate_create_block(
+  activity_object: Object,
+  actor: &User,
+  blocks: &BlockPool,
+  follows: &FollowPool,
+  users: &UserPool,
+) -> Result<FederateResult, LogicErr> {
+  let uri = match activity_object.id {
+    Some(uri) => match uri.starts_with(&SETTINGS.server.api_fqdn) {
+      true => uri.replace(&SETTINGS.server.api_fqdn, ""),
+      false => uri,
+    },
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let blocked_user = match users.fetch_by_fediverse_uri(&uri).await {
+    Some(user) => user,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if blocked_user.is_external {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  blocks.create_block(&actor.user_id, &blocked_user.user_id).await?;
+  follows.delete_follow(&actor.user_id, &blocked_user.user_id).await?;
+  follows.delete_follow(&blocked_user.user_id, &actor.user_id).await?;
+
+  Ok(FederateResult::None)
+}
+
+pub async fn federate_remove_block(
+  target: String,
+  actor: &User,
+  blocks: &BlockPool,
+  users: &UserPool,
+) -> Result<FederateResult, LogicErr> {
+  let uri = match target.starts_with(&SETTINGS.server.api_fqdn) {
+    true => target.replace(&SETTINGS.server.api_fqdn, ""),
+    false => target,
+  };
+
+  let unblocked_user = match users.fetch_by_fediverse_uri(&uri).await {
+    Some(user) => user,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if unblocked_user.is_external {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  blocks.delete_block(&actor.user_id, &unblocked_user.user_id).await?;
+
+  Ok(FederateResult::None)
+}
+
+pub async fn federate_ext_create_follow(
+  actor: &User,
+  following_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
   let following_actor = match following_actor {
     FederateExtActor::Person(actor) => actor,
     _ => return Err(LogicErr::MissingRecord),
@@ -115,10 +272,49 @@ pub async fn federate_ext_create_follow(actor: &User, following_actor: &Federate
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
-pub async fn federate_ext_remove_follow(actor: &User, unfollowing_actor: &FederateExtActor) -> Result<(), LogicErr> {
+pub async fn federate_ext_answer_follow(
+  activity_type: ActivityType,
+  activity: &str,
+  actor: &User,
+  requesting_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let requesting_actor = match requesting_actor {
+    FederateExtActor::Person(actor) => actor,
+    _ => return Err(LogicErr::MissingRecord),
+  };
+
+  let activity: Object = serde_json::from_str(activity).map_err(|_| LogicErr::InvalidData)?;
+
+  let response_object = Object::builder()
+    .kind(Some(activity_type.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(relative_to_absolute_uri(&actor.fediverse_uri))))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Embedded(Box::new(activity))))
+        .build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match &requesting_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+pub async fn federate_ext_remove_follow(
+  actor: &User,
+  unfollowing_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
   let unfollowing_actor = match unfollowing_actor {
     FederateExtActor::Person(actor) => actor,
     _ => return Err(LogicErr::MissingRecord),
@@ -161,10 +357,93 @@ pub async fn federate_ext_remove_follow(actor: &User, unfollowing_actor: &Federa
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+fn build_block_object(actor: &User, blocked_actor: &User) -> Object {
+  Object::builder()
+    .kind(Some(ActivityType::Block.to_string()))
+    .id(Some(format!(
+      "{}{}/blocks/{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri, blocked_actor.user_id
+    )))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Remote(relative_to_absolute_uri(
+          &blocked_actor.fediverse_uri,
+        ))))
+        .build(),
+    ))
+    .build()
+}
+
+pub async fn federate_ext_block(
+  actor: &User,
+  blocked_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let blocked_actor = match blocked_actor {
+    FederateExtActor::Person(actor) => actor,
+    _ => return Err(LogicErr::MissingRecord),
+  };
+
+  let doc = ActivityPubDocument::new(build_block_object(actor, blocked_actor));
+
+  let response_uri = match &blocked_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
-pub async fn federate_ext_join_group(actor: &User, joining_orbit: &Uuid, orbits: &OrbitPool) -> Result<(), LogicErr> {
+pub async fn federate_ext_unblock(
+  actor: &User,
+  unblocked_actor: &FederateExtActor,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  let unblocked_actor = match unblocked_actor {
+    FederateExtActor::Person(actor) => actor,
+    _ => return Err(LogicErr::MissingRecord),
+  };
+
+  let response_object = Object::builder()
+    .kind(Some(ActivityType::Undo.to_string()))
+    .id(Some(format!("{}/{}", SETTINGS.server.api_fqdn, Uuid::new_v4())))
+    .actor(Some(Reference::Remote(format!(
+      "{}{}",
+      SETTINGS.server.api_fqdn, actor.fediverse_uri
+    ))))
+    .activity(Some(
+      ActivityProps::builder()
+        .object(Some(Reference::Embedded(Box::new(build_block_object(
+          actor,
+          unblocked_actor,
+        )))))
+        .build(),
+    ))
+    .build();
+
+  let doc = ActivityPubDocument::new(response_object);
+
+  let response_uri = match &unblocked_actor.ext_apub_inbox_uri {
+    Some(uri) => uri,
+    None => return Ok(()),
+  };
+
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
+}
+
+pub async fn federate_ext_join_group(
+  actor: &User,
+  joining_orbit: &Uuid,
+  orbits: &OrbitPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
   let orbit = match orbits.fetch_orbit(joining_orbit).await? {
     Some(orbit) => orbit,
     None => return Err(LogicErr::MissingRecord),
@@ -191,10 +470,15 @@ pub async fn federate_ext_join_group(actor: &User, joining_orbit: &Uuid, orbits:
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
 
-pub async fn federate_ext_leave_group(actor: &User, leaving_orbit: &Uuid, orbits: &OrbitPool) -> Result<(), LogicErr> {
+pub async fn federate_ext_leave_group(
+  actor: &User,
+  leaving_orbit: &Uuid,
+  orbits: &OrbitPool,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
   let orbit = match orbits.fetch_orbit(leaving_orbit).await? {
     Some(orbit) => orbit,
     None => return Err(LogicErr::MissingRecord),
@@ -237,5 +521,5 @@ pub async fn federate_ext_leave_group(actor: &User, leaving_orbit: &Uuid, orbits
     None => return Ok(()),
   };
 
-  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key).await
+  send_activitypub_object(response_uri, doc, &actor.fediverse_uri, &actor.private_key, deliveries).await
 }
@@ -0,0 +1,173 @@
+use crate::{
+  activitypub::{
+    object::{Object, ObjectType},
+    reference::Reference,
+  },
+  db::{
+    job_repository::JobPool, notification_repository::NotificationPool, orbit_repository::OrbitPool,
+    post_attachment_repository::PostAttachmentPool, post_mention_repository::PostMentionPool,
+    post_repository::PostPool, tag_repository::TagPool, user_repository::UserPool,
+  },
+  logic::LogicErr,
+  model::{orbit::Orbit, user::User},
+  settings::SETTINGS,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
+};
+
+use super::{
+  actor::{federate_orbit_group, federate_user_actor},
+  article::federate_create_article,
+  mention::{federate_local_mentions, federate_post_mentions},
+  note::federate_store_note,
+  util::{activitypub_ref_to_id_opt, determine_activity_visibility, fetch_activitypub_object, is_same_origin},
+};
+
+/// The local copy of an object looked up by URL
+pub enum ResolvedObject {
+  /// A post, by the URI it's stored under
+  Post(String),
+  User(User),
+  Orbit(Orbit),
+}
+
+async fn find_local_object(
+  uri: &str,
+  users: &UserPool,
+  posts: &PostPool,
+  orbits: &OrbitPool,
+) -> Option<ResolvedObject> {
+  // Our own objects are stored relative to the API, while remote objects are stored by their absolute URI
+  let uri = uri.strip_prefix(&SETTINGS.server.api_fqdn).unwrap_or(uri);
+
+  if let Some(post) = posts.find_optional_by_uri(uri).await {
+    return Some(ResolvedObject::Post(post.uri));
+  }
+
+  if let Some(user) = users.fetch_by_fediverse_uri(uri).await {
+    return Some(ResolvedObject::User(user));
+  }
+
+  orbits.fetch_by_fediverse_uri(uri).await.map(ResolvedObject::Orbit)
+}
+
+/// Fetches the authoritative copy of a remote object. The URL people share is often a web page serving the object
+/// under a different ID, so anything claiming another ID is re-fetched from there rather than trusted.
+async fn fetch_remote_object(uri: &str) -> Result<Option<(String, Object)>, LogicErr> {
+  let object = match fetch_activitypub_object(uri).await {
+    Some(object) => object,
+    None => return Ok(None),
+  };
+
+  let id = match &object.id {
+    Some(id) => id.to_owned(),
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  if id == uri {
+    return Ok(Some((id, object)));
+  }
+
+  match fetch_activitypub_object(&id).await {
+    Some(object) if object.id.as_ref() == Some(&id) => Ok(Some((id, object))),
+    Some(_) => Err(LogicErr::UnauthorizedError),
+    None => Ok(None),
+  }
+}
+
+/// Looks up an object by URL for search, storing a local copy of remote posts, users and orbits we haven't seen before
+pub async fn federate_resolve_uri(
+  uri: &str,
+  users: &UserPool,
+  posts: &PostPool,
+  post_mentions: &PostMentionPool,
+  tags: &TagPool,
+  jobs: &JobPool,
+  post_attachments: &PostAttachmentPool,
+  orbits: &OrbitPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  queue: &Queue,
+) -> Result<Option<ResolvedObject>, LogicErr> {
+  if let Some(object) = find_local_object(uri, users, posts, orbits).await {
+    return Ok(Some(object));
+  }
+
+  if uri.starts_with(&SETTINGS.server.api_fqdn) || uri.starts_with(&SETTINGS.server.fqdn) {
+    return Ok(None);
+  }
+
+  let (id, object) = match fetch_remote_object(uri).await? {
+    Some(result) => result,
+    None => return Ok(None),
+  };
+
+  if id != uri {
+    if let Some(object) = find_local_object(&id, users, posts, orbits).await {
+      return Ok(Some(object));
+    }
+  }
+
+  let object_type = match ObjectType::from_str_opt(&object.kind) {
+    Some(object_type) => object_type,
+    None => return Ok(None),
+  };
+
+  match object_type {
+    ObjectType::Note | ObjectType::Article => {
+      if object.in_reply_to.is_some() {
+        log::warn!("Unimplemented federation task: resolving a reply by URL");
+        return Ok(None);
+      }
+
+      let author = federate_user_actor(&object.attributed_to, users).await?;
+
+      if activitypub_ref_to_id_opt(&object.attributed_to).as_ref() != Some(&author.fediverse_uri)
+        || !is_same_origin(&id, &author.fediverse_uri)
+      {
+        return Err(LogicErr::UnauthorizedError);
+      }
+
+      if object_type == ObjectType::Note {
+        let access = match determine_activity_visibility(&object.to, &author) {
+          Some(access) => access,
+          None => return Err(LogicErr::InvalidData),
+        };
+
+        let mentioned_user_ids = federate_local_mentions(&object.tag, users).await;
+
+        federate_store_note(
+          object,
+          &author,
+          access,
+          &mentioned_user_ids,
+          posts,
+          post_mentions,
+          tags,
+          jobs,
+          post_attachments,
+          notifications,
+          pubsub,
+          queue,
+        )
+        .await?;
+      } else {
+        let tag = object.tag.clone();
+
+        federate_create_article(object, &author, posts, tags, jobs, post_attachments, orbits, queue).await?;
+        federate_post_mentions(&tag, &id, &author, users, posts, post_mentions, notifications, pubsub).await;
+      }
+
+      Ok(Some(ResolvedObject::Post(id)))
+    }
+    ObjectType::Person | ObjectType::Service | ObjectType::Application => {
+      let user = federate_user_actor(&Some(Reference::Remote(id)), users).await?;
+      Ok(Some(ResolvedObject::User(user)))
+    }
+    ObjectType::Group => {
+      let orbit = federate_orbit_group(&Some(Reference::Remote(id)), orbits).await?;
+      Ok(Some(ResolvedObject::Orbit(orbit)))
+    }
+    _ => Ok(None),
+  }
+}
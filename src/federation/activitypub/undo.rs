@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use crate::{
+  activitypub::{activity_type::ActivityType, object::Object},
+  db::{
+    block_repository::BlockPool, comment_repository::CommentPool, follow_repository::FollowPool,
+    job_repository::JobPool, like_repository::LikePool, orbit_repository::OrbitPool, post_repository::PostPool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+  },
+  helpers::api::{map_db_err, relative_to_absolute_uri},
+  logic::LogicErr,
+  model::{
+    job::{JobStatus, NewJob},
+    queue_job::{QueueJob, QueueJobType},
+    user::User,
+  },
+  settings::SETTINGS,
+  work_queue::queue::Queue,
+};
+
+use super::{
+  comment::federate_unlike_comment,
+  group::federate_remove_member,
+  note::federate_unlike_note,
+  person::{federate_remove_block, federate_remove_follow},
+  util::{activitypub_ref_to_id_opt, FederateResult},
+};
+
+/// Invoked when an Undo activity is delivered to us, reversing the effects of the activity it wraps.
+pub async fn federate_undo(
+  activity_object: Object,
+  actor: &User,
+  users: &UserPool,
+  blocks: &BlockPool,
+  follows: &FollowPool,
+  posts: &PostPool,
+  likes: &LikePool,
+  comments: &CommentPool,
+  jobs: &JobPool,
+  orbits: &OrbitPool,
+  user_orbits: &UserOrbitPool,
+  queue: &Queue,
+) -> Result<FederateResult, LogicErr> {
+  let kind = match activity_object.kind.as_ref().map(|v| ActivityType::from_str(v)) {
+    Some(Ok(kind)) => kind,
+    _ => return Err(LogicErr::InvalidData),
+  };
+
+  // Only the actor that performed the original activity is allowed to undo it
+  match activitypub_ref_to_id_opt(&activity_object.actor) {
+    Some(uri) => {
+      if uri != relative_to_absolute_uri(&actor.fediverse_uri) {
+        return Err(LogicErr::UnauthorizedError);
+      }
+    }
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  let target = match &activity_object.activity {
+    Some(activity) => match activitypub_ref_to_id_opt(&activity.object) {
+      Some(uri) => uri,
+      None => return Err(LogicErr::InvalidData),
+    },
+    None => return Err(LogicErr::InvalidData),
+  };
+
+  match kind {
+    ActivityType::Follow => {
+      let uri = match target.starts_with(&SETTINGS.server.api_fqdn) {
+        true => target.replace(&SETTINGS.server.api_fqdn, ""),
+        false => target.clone(),
+      };
+
+      match orbits.fetch_by_fediverse_uri(&uri).await {
+        Some(_) => federate_remove_member(target, actor, user_orbits, orbits).await,
+        None => federate_remove_follow(target, actor, follows, users).await,
+      }
+    }
+    ActivityType::Join => federate_remove_member(target, actor, user_orbits, orbits).await,
+    ActivityType::Block => federate_remove_block(target, actor, blocks, users).await,
+    ActivityType::Like => {
+      let uri = match target.starts_with(&SETTINGS.server.api_fqdn) {
+        true => target.replace(&SETTINGS.server.api_fqdn, ""),
+        false => target.clone(),
+      };
+
+      match comments.fetch_comment_from_uri(&uri).await {
+        Some(_) => federate_unlike_comment(target, actor, comments).await,
+        None => federate_unlike_note(target, actor, posts, likes).await,
+      }
+    }
+    ActivityType::Announce => {
+      let uri = match target.starts_with(&SETTINGS.server.api_fqdn) {
+        true => target.replace(&SETTINGS.server.api_fqdn, ""),
+        false => target,
+      };
+
+      let post = match posts.find_optional_by_uri(&uri).await {
+        Some(post) => post,
+        None => return Err(LogicErr::MissingRecord),
+      };
+
+      let job_id = jobs
+        .create(NewJob {
+          created_by_id: Some(actor.user_id),
+          status: JobStatus::NotStarted,
+          record_id: Some(post.post_id),
+          associated_record_id: None,
+        })
+        .await
+        .map_err(map_db_err)?;
+
+      let job = QueueJob::builder()
+        .job_id(job_id)
+        .job_type(QueueJobType::DeleteBoostEvents)
+        .build();
+
+      queue.send_job(job).await?;
+
+      Ok(FederateResult::None)
+    }
+    _ => {
+      log::warn!(
+        "Unimplemented federation task: Activity Type Undo on Activity Type: {}",
+        kind
+      );
+      Ok(FederateResult::None)
+    }
+  }
+}
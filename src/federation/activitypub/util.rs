@@ -1,7 +1,10 @@
 use std::time::Duration;
 
+use super::delivery::next_delivery_attempt_at;
 use crate::{
   activitypub::{document::ActivityPubDocument, object::Object, reference::Reference},
+  db::delivery_repository::DeliveryPool,
+  federation::domain_policy::uri_federation_allowed,
   helpers::api::map_ext_err,
   logic::LogicErr,
   model::{access_type::AccessType, user::User},
@@ -12,6 +15,7 @@ use backoff::{future::retry, ExponentialBackoff};
 use base64::{engine::general_purpose::STANDARD as b64, Engine};
 use http_signing::{alg::RsaSha256, Key, PrivateKey, SigningConfig};
 use lazy_static::lazy_static;
+use serde_json::Value;
 use sha2::{Digest, Sha256};
 use tokio_stream::{self as stream, StreamExt};
 use url::Url;
@@ -75,6 +79,51 @@ pub fn activitypub_ref_to_uri_opt(obj_ref: &Option<Reference<Object>>) -> Option
   }
 }
 
+pub fn activitypub_ref_to_id(obj_ref: &Reference<Object>) -> Option<String> {
+  match obj_ref {
+    Reference::Embedded(obj) => obj.id.clone(),
+    Reference::Remote(uri) => Some(uri.to_owned()),
+    Reference::Mixed(vals) => vals.iter().find_map(activitypub_ref_to_id),
+    Reference::Map(data) => {
+      if let Some(value) = data.get("id") {
+        let uri: Result<String, serde_json::Error> = serde_json::from_value(value.to_owned());
+        match uri {
+          Ok(uri) => Some(uri),
+          Err(_) => None,
+        }
+      } else {
+        None
+      }
+    }
+  }
+}
+
+pub fn activitypub_ref_to_id_opt(obj_ref: &Option<Reference<Object>>) -> Option<String> {
+  match obj_ref {
+    Some(obj_ref) => activitypub_ref_to_id(obj_ref),
+    None => None,
+  }
+}
+
+pub fn is_same_origin(a: &str, b: &str) -> bool {
+  match (Url::parse(a), Url::parse(b)) {
+    (Ok(a), Ok(b)) => a.origin() == b.origin(),
+    _ => false,
+  }
+}
+
+pub fn activitypub_shared_inbox_uri(endpoints: &Option<Reference<Object>>) -> Option<String> {
+  match endpoints {
+    Some(Reference::Map(data)) => data.get("sharedInbox").and_then(|v| v.as_str()).map(|v| v.to_owned()),
+    Some(Reference::Embedded(obj)) => obj
+      .actors
+      .as_ref()
+      .and_then(|actors| actors.shared_inbox.as_ref())
+      .and_then(activitypub_ref_to_id),
+    _ => None,
+  }
+}
+
 pub fn determine_activity_visibility(to: &Option<Reference<Object>>, author: &User) -> Option<AccessType> {
   let objs = match to {
     Some(obj_ref) => match obj_ref {
@@ -100,6 +149,10 @@ pub fn determine_activity_visibility(to: &Option<Reference<Object>>, author: &Us
 }
 
 pub async fn fetch_activitypub_object(obj_ref: &str) -> Option<Object> {
+  if !uri_federation_allowed(obj_ref) {
+    return None;
+  }
+
   let result: Result<Option<ActivityPubDocument>, reqwest::Error> = retry(BACKOFF_POLICY.clone(), || async {
     Ok(
       HTTP_CLIENT
@@ -119,17 +172,17 @@ pub async fn fetch_activitypub_object(obj_ref: &str) -> Option<Object> {
   }
 }
 
-pub async fn send_activitypub_object(
+pub async fn post_activitypub_json(
   uri: &str,
-  doc: ActivityPubDocument,
+  body: &Value,
   actor_fediverse_uri: &str,
   actor_private_key: &str,
 ) -> Result<(), LogicErr> {
   let result: Result<reqwest::Response, LogicErr> = retry(BACKOFF_POLICY.clone(), || async {
-    let body = serde_json::to_vec(&doc).map_err(map_ext_err)?;
+    let body_bytes = serde_json::to_vec(body).map_err(map_ext_err)?;
 
     let mut body_hasher = Sha256::new();
-    body_hasher.update(body);
+    body_hasher.update(body_bytes);
     let body_hash_digest = body_hasher.finalize();
     let body_hash_digest = b64.encode(body_hash_digest);
 
@@ -146,7 +199,7 @@ pub async fn send_activitypub_object(
       .header("content-type", "application/activity+json")
       .header("digest", digest)
       .header("host", host)
-      .json(&doc)
+      .json(body)
       .build()
       .map_err(map_ext_err)?;
 
@@ -178,17 +231,70 @@ pub async fn send_activitypub_object(
   }
 }
 
+pub async fn send_activitypub_object(
+  uri: &str,
+  doc: ActivityPubDocument,
+  actor_fediverse_uri: &str,
+  actor_private_key: &str,
+  deliveries: &DeliveryPool,
+) -> Result<(), LogicErr> {
+  if !uri_federation_allowed(uri) {
+    log::info!(
+      "Not delivering activity to {}, its domain isn't allowed to federate",
+      uri
+    );
+    return Ok(());
+  }
+
+  let body = serde_json::to_value(&doc).map_err(map_ext_err)?;
+
+  match post_activitypub_json(uri, &body, actor_fediverse_uri, actor_private_key).await {
+    Ok(_) => Ok(()),
+    Err(LogicErr::InvalidData) => Err(LogicErr::InvalidData),
+    Err(err) => {
+      // The remote inbox may only be briefly unavailable, so the activity is handed off to the delivery queue to be
+      // retried by the worker rather than being dropped
+      let last_error = format!("{:?}", err);
+      log::warn!(
+        "Failed to deliver activity to {}, queueing for retry: {}",
+        uri,
+        last_error
+      );
+
+      deliveries
+        .create_delivery(
+          uri,
+          actor_fediverse_uri,
+          &body,
+          &next_delivery_attempt_at(1),
+          &last_error,
+        )
+        .await?;
+
+      Ok(())
+    }
+  }
+}
+
+fn embedded_activitypub_object(obj: &Object) -> Option<Object> {
+  // Embedded objects skip the fetch, so they have to be held to the same domain policies here
+  match &obj.id {
+    Some(id) if !uri_federation_allowed(id) => None,
+    _ => Some(obj.clone()),
+  }
+}
+
 pub async fn deref_activitypub_ref(obj_ref: &Option<Reference<Object>>) -> Option<Object> {
   match obj_ref {
     Some(a) => match a {
-      Reference::Embedded(obj) => Some((**obj).clone()),
+      Reference::Embedded(obj) => embedded_activitypub_object(obj),
       Reference::Remote(uri) => fetch_activitypub_object(uri).await,
       Reference::Mixed(values) => {
         // EDITOR'S NOTE: We could do recursion instead here, but it requires boxing which makes things much slower
         let mut stream = stream::iter(values);
         while let Some(value) = stream.next().await {
           let ret = match value {
-            Reference::Embedded(obj) => Some((**obj).clone()),
+            Reference::Embedded(obj) => embedded_activitypub_object(obj),
             Reference::Remote(uri) => fetch_activitypub_object(uri).await,
             Reference::Mixed(_) => None,
             Reference::Map(_) => None,
@@ -260,3 +366,38 @@ pub fn determine_activity_target(target: Option<String>) -> ActivityTarget {
     None => ActivityTarget::Invalid,
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::federation::activitypub::util::is_same_origin;
+
+  #[test]
+  fn test_is_same_origin() {
+    assert!(is_same_origin(
+      "https://example.social/users/a",
+      "https://example.social/notes/1"
+    ));
+    assert!(is_same_origin(
+      "https://example.social/users/a",
+      "https://EXAMPLE.social:443/b"
+    ));
+    assert!(!is_same_origin(
+      "https://example.social/users/a",
+      "https://other.social/users/a"
+    ));
+    assert!(!is_same_origin(
+      "https://example.social/users/a",
+      "https://a.example.social/users/a"
+    ));
+    assert!(!is_same_origin(
+      "https://example.social/users/a",
+      "http://example.social/users/a"
+    ));
+    assert!(!is_same_origin(
+      "https://example.social/users/a",
+      "https://example.social:8443/users/a"
+    ));
+    assert!(!is_same_origin("https://example.social/users/a", "/users/a"));
+    assert!(!is_same_origin("not a uri", "not a uri"));
+  }
+}
@@ -0,0 +1,105 @@
+use std::{
+  sync::RwLock,
+  time::{Duration, Instant},
+};
+
+use lazy_static::lazy_static;
+use url::Url;
+
+use crate::{
+  db::domain_policy_repository::DomainPolicyPool,
+  logic::LogicErr,
+  model::domain_policy::{DomainPolicy, DomainPolicyType},
+  settings::SETTINGS,
+};
+
+const DOMAIN_POLICY_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct DomainPolicyCache {
+  policies: Vec<DomainPolicy>,
+  loaded_at: Option<Instant>,
+}
+
+lazy_static! {
+  // NOTE: Policies are checked deep inside the federation code (actor and object fetches in particular) where no
+  //       repository is available, so both the API and the worker keep a process-wide copy of the table that's
+  //       reloaded on an interval by whoever has a pool to hand
+  static ref DOMAIN_POLICIES: RwLock<DomainPolicyCache> = RwLock::new(DomainPolicyCache::default());
+  static ref LOCAL_HOST: Option<String> = uri_host(&SETTINGS.server.api_root_fqdn);
+}
+
+pub async fn reload_domain_policies(domain_policies: &DomainPolicyPool) -> Result<(), LogicErr> {
+  let policies = domain_policies.fetch_domain_policies().await?;
+
+  let mut cache = DOMAIN_POLICIES
+    .write()
+    .map_err(|err| LogicErr::InternalError(err.to_string()))?;
+  cache.policies = policies;
+  cache.loaded_at = Some(Instant::now());
+
+  Ok(())
+}
+
+pub async fn sync_domain_policies(domain_policies: &DomainPolicyPool) {
+  let is_stale = match DOMAIN_POLICIES.read() {
+    Ok(cache) => match cache.loaded_at {
+      Some(loaded_at) => loaded_at.elapsed() > DOMAIN_POLICY_REFRESH_INTERVAL,
+      None => true,
+    },
+    Err(_) => true,
+  };
+
+  if !is_stale {
+    return;
+  }
+
+  if let Err(err) = reload_domain_policies(domain_policies).await {
+    log::error!("Failed to reload domain policies: {:?}", err);
+  }
+}
+
+pub fn uri_host(uri: &str) -> Option<String> {
+  let url = Url::parse(uri).ok()?;
+  url.host_str().map(|host| host.to_lowercase())
+}
+
+/// Finds the policy for a host, preferring the most specific domain when both a domain and its parent are listed
+pub fn domain_policy_for_host(host: &str) -> Option<DomainPolicyType> {
+  let host = host.to_lowercase();
+  let cache = DOMAIN_POLICIES.read().ok()?;
+
+  cache
+    .policies
+    .iter()
+    .filter(|policy| policy.matches_host(&host))
+    .max_by_key(|policy| policy.domain.len())
+    .map(|policy| policy.policy)
+}
+
+pub fn host_federation_allowed(host: &str) -> bool {
+  if LOCAL_HOST.as_deref() == Some(host.to_lowercase().as_str()) {
+    return true;
+  }
+
+  match domain_policy_for_host(host) {
+    Some(DomainPolicyType::Suspend) => false,
+    Some(_) => true,
+    None => !SETTINGS.app.federation_allowlist_mode,
+  }
+}
+
+/// Relative URIs are our own, and are always allowed
+pub fn uri_federation_allowed(uri: &str) -> bool {
+  match uri_host(uri) {
+    Some(host) => host_federation_allowed(&host),
+    None => true,
+  }
+}
+
+pub fn uri_rejects_media(uri: &str) -> bool {
+  match uri_host(uri) {
+    Some(host) => domain_policy_for_host(&host) == Some(DomainPolicyType::RejectMedia),
+    None => false,
+  }
+}
@@ -1 +1,2 @@
 pub mod activitypub;
+pub mod domain_policy;
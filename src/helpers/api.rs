@@ -1,5 +1,6 @@
 use actix_web::{HttpRequest, HttpResponse};
 use std::error::Error;
+use url::Url;
 
 use super::core::{build_api_err, build_api_not_found};
 use crate::{logic::LogicErr, settings::SETTINGS};
@@ -43,6 +44,19 @@ pub fn relative_to_absolute_uri(relative: &str) -> String {
   }
 }
 
+/// The host this instance's users are addressed at, e.g. `example.com` in `@alice@example.com`
+pub fn local_domain() -> String {
+  Url::parse(&SETTINGS.server.api_root_fqdn)
+    .ok()
+    .and_then(|url| url.host_str().map(|host| host.to_string()))
+    .unwrap_or_default()
+}
+
+/// Where a tag's timeline can be found, used as the `href` of Hashtag tags and tag links in post content
+pub fn tag_uri(name: &str) -> String {
+  format!("{}/tags/{}", SETTINGS.server.fqdn, name)
+}
+
 pub fn relative_cdn_to_absolute_cdn_uri(relative: &str) -> String {
   match relative.starts_with("http") {
     true => relative.to_string(),
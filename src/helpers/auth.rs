@@ -1,28 +1,56 @@
 use crate::{
   db::session_repository::SessionPool,
+  logic::app::scopes_allowed,
   net::jwt::{JwtContext, JwtContextProps},
 };
 
-use actix_web::{web, HttpResponse};
+use actix_web::{http::Method, web, HttpResponse};
 use uuid::Uuid;
 
-pub async fn assert_auth(jwt: &web::ReqData<JwtContext>, sessions: &SessionPool) -> Result<(), HttpResponse> {
-  let props = match (**jwt).clone() {
-    JwtContext::Valid(props) => props,
-    JwtContext::Invalid(_) => return Err(HttpResponse::Unauthorized().finish()),
-  };
+/// The OAuth scope a session needs to make a request, following Mastodon's top-level scopes. Reads need `read` and
+/// everything else needs `write`, apart from the admin API which needs the matching `admin:` scope. Token requests
+/// don't need a scope, so that sessions with narrow scopes can still be refreshed.
+pub fn required_scope(method: &Method, path: &str) -> Option<&'static str> {
+  if path.starts_with("/oauth/") || path.starts_with("/api/oauth/") {
+    return None;
+  }
+
+  let reading = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+
+  match (path.starts_with("/api/admin/"), reading) {
+    (true, true) => Some("admin:read"),
+    (true, false) => Some("admin:write"),
+    (false, true) => Some("read"),
+    (false, false) => Some("write"),
+  }
+}
 
+async fn check_session(props: &JwtContextProps, sessions: &SessionPool) -> Result<(), HttpResponse> {
   let sid = match Uuid::parse_str(&props.sid) {
     Ok(sid) => sid,
     Err(_) => return Err(HttpResponse::Unauthorized().finish()),
   };
 
-  match sessions.query_session_exists(&sid).await {
-    true => Ok(()),
-    false => Err(HttpResponse::Unauthorized().finish()),
+  let scopes = match sessions.fetch_session_scopes(&sid).await {
+    Some(scopes) => scopes,
+    None => return Err(HttpResponse::Unauthorized().finish()),
+  };
+
+  match props.required_scope {
+    Some(scope) if !scopes_allowed(scope, &scopes) => Err(HttpResponse::Forbidden().finish()),
+    _ => Ok(()),
   }
 }
 
+pub async fn assert_auth(jwt: &web::ReqData<JwtContext>, sessions: &SessionPool) -> Result<(), HttpResponse> {
+  let props = match (**jwt).clone() {
+    JwtContext::Valid(props) => props,
+    JwtContext::Invalid(_) => return Err(HttpResponse::Unauthorized().finish()),
+  };
+
+  check_session(&props, sessions).await
+}
+
 pub async fn require_auth(
   jwt: &web::ReqData<JwtContext>,
   sessions: &SessionPool,
@@ -32,15 +60,7 @@ pub async fn require_auth(
     JwtContext::Invalid(_) => return Err(HttpResponse::Unauthorized().finish()),
   };
 
-  let sid = match Uuid::parse_str(&props.sid) {
-    Ok(sid) => sid,
-    Err(_) => return Err(HttpResponse::Unauthorized().finish()),
-  };
-
-  match sessions.query_session_exists(&sid).await {
-    true => Ok(props),
-    false => Err(HttpResponse::Unauthorized().finish()),
-  }
+  check_session(&props, sessions).await.map(|_| props)
 }
 
 pub async fn query_auth(jwt: &web::ReqData<JwtContext>, sessions: &SessionPool) -> Option<JwtContextProps> {
@@ -49,13 +69,27 @@ pub async fn query_auth(jwt: &web::ReqData<JwtContext>, sessions: &SessionPool)
     JwtContext::Invalid(_) => return None,
   };
 
-  let sid = match Uuid::parse_str(&props.sid) {
-    Ok(sid) => sid,
-    Err(_) => return None,
-  };
+  check_session(&props, sessions).await.ok().map(|_| props)
+}
+
+#[cfg(test)]
+mod tests {
+  use actix_web::http::Method;
+
+  use crate::helpers::auth::required_scope;
 
-  match sessions.query_session_exists(&sid).await {
-    true => Some(props),
-    false => None,
+  #[test]
+  fn test_required_scope() {
+    assert_eq!(required_scope(&Method::GET, "/api/feed"), Some("read"));
+    assert_eq!(required_scope(&Method::GET, "/api/v1/timelines/home"), Some("read"));
+    assert_eq!(required_scope(&Method::POST, "/api/v1/statuses"), Some("write"));
+    assert_eq!(required_scope(&Method::DELETE, "/api/v1/statuses/1"), Some("write"));
+    assert_eq!(required_scope(&Method::GET, "/api/admin/reports"), Some("admin:read"));
+    assert_eq!(
+      required_scope(&Method::PUT, "/api/admin/domain-policies/a.example"),
+      Some("admin:write")
+    );
+    assert_eq!(required_scope(&Method::POST, "/oauth/token"), None);
+    assert_eq!(required_scope(&Method::POST, "/api/oauth/token"), None);
   }
 }
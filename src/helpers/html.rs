@@ -1,4 +1,6 @@
 use actix_web::HttpResponse;
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::Serialize;
 
 use crate::{logic::LogicErr, model::app::App, net::templates::HANDLEBARS, settings::SETTINGS};
@@ -14,6 +16,26 @@ struct OAuthAuthorizeErrData<'a> {
   pub orbit_name: &'a str,
 }
 
+lazy_static! {
+  static ref UNLINKABLE_HTML_RE: Regex = Regex::new(r"(?s)<a\b.*?</a>|<code\b.*?</code>|<pre\b.*?</pre>").unwrap();
+}
+
+/// Applies `f` to the parts of rendered HTML that may be turned into links, leaving existing links and code untouched
+pub fn map_linkable_html(content_html: &str, f: impl Fn(&str) -> String) -> String {
+  let mut mapped = String::with_capacity(content_html.len());
+  let mut last = 0;
+
+  for unlinkable in UNLINKABLE_HTML_RE.find_iter(content_html) {
+    mapped.push_str(&f(&content_html[last..unlinkable.start()]));
+    mapped.push_str(unlinkable.as_str());
+    last = unlinkable.end();
+  }
+
+  mapped.push_str(&f(&content_html[last..]));
+
+  mapped
+}
+
 pub fn build_orbit_name() -> String {
   SETTINGS.server.fqdn.replace("https://", "").replace("http://", "")
 }
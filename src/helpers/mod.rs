@@ -3,4 +3,5 @@ pub mod auth;
 pub mod core;
 pub mod html;
 pub mod math;
+pub mod pagination;
 pub mod types;
@@ -0,0 +1,206 @@
+use std::future::Future;
+
+use actix_web::{HttpRequest, HttpResponse};
+use serde::Serialize;
+use url::form_urlencoded;
+use uuid::Uuid;
+
+use crate::{
+  logic::LogicErr,
+  model::{
+    page_cursor::PageCursor,
+    response::{CursorResponse, ListResponse},
+  },
+  settings::SETTINGS,
+};
+
+use super::math::div_up;
+
+/// The page number asked for, if any. Numbered pages are kept for older clients and are only used when no cursor is
+/// given, as skipping through a list by offset shifts whenever something new is added to it.
+pub fn numbered_page(page: &Option<i64>, cursor: &PageCursor) -> Option<i64> {
+  match cursor.is_empty() {
+    true => *page,
+    false => None,
+  }
+}
+
+/// Counts a list only if the count is going to be used, since counting long lists costs as much as fetching them
+pub async fn optional_count(
+  wanted: bool,
+  count: impl Future<Output = Result<i64, LogicErr>>,
+) -> Result<Option<i64>, LogicErr> {
+  match wanted {
+    true => count.await.map(Some),
+    false => Ok(None),
+  }
+}
+
+fn cursor_link(req: &HttpRequest, param: &str, id: &Uuid) -> String {
+  let mut query = form_urlencoded::Serializer::new(String::new());
+  for (key, value) in form_urlencoded::parse(req.query_string().as_bytes()) {
+    if !matches!(key.as_ref(), "page" | "max_id" | "since_id" | "min_id") {
+      query.append_pair(&key, &value);
+    }
+  }
+  query.append_pair(param, &id.to_string());
+
+  format!("{}{}?{}", SETTINGS.server.api_root_fqdn, req.path(), query.finish())
+}
+
+fn cursor_links(
+  req: &HttpRequest,
+  first_id: Option<Uuid>,
+  last_id: Option<Uuid>,
+  oldest_first: bool,
+) -> Option<String> {
+  let (next, prev) = match oldest_first {
+    true => (last_id.map(|id| ("min_id", id)), first_id.map(|id| ("max_id", id))),
+    false => (last_id.map(|id| ("max_id", id)), first_id.map(|id| ("min_id", id))),
+  };
+
+  let links: Vec<String> = [(next, "next"), (prev, "prev")]
+    .into_iter()
+    .filter_map(|(link, rel)| link.map(|(param, id)| format!("<{}>; rel=\"{}\"", cursor_link(req, param, &id), rel)))
+    .collect();
+
+  match links.is_empty() {
+    true => None,
+    false => Some(links.join(", ")),
+  }
+}
+
+/// Responds with a page of a list walked by cursor, linking the pages either side of it in the `Link` header the
+/// same way Mastodon does. `oldest_first` lists page forward with `min_id` rather than `max_id`.
+pub fn build_cursor_response<T: Serialize>(
+  req: &HttpRequest,
+  data: Vec<T>,
+  id: impl Fn(&T) -> Uuid,
+  oldest_first: bool,
+  total_items: Option<i64>,
+) -> HttpResponse {
+  let first_id = data.first().map(&id);
+  let last_id = data.last().map(&id);
+
+  let mut res = HttpResponse::Ok();
+  if let Some(links) = cursor_links(req, first_id, last_id, oldest_first) {
+    res.insert_header(("Link", links));
+  }
+
+  res.json(CursorResponse {
+    data,
+    next_max_id: if oldest_first { None } else { last_id },
+    prev_since_id: if oldest_first { None } else { first_id },
+    next_min_id: if oldest_first { last_id } else { None },
+    total_items,
+  })
+}
+
+/// Responds with a bare list as the Mastodon API does, leaving the `Link` header as the only way to find other pages
+pub fn build_link_response<T: Serialize>(
+  req: &HttpRequest,
+  data: Vec<T>,
+  id: impl Fn(&T) -> Uuid,
+  oldest_first: bool,
+) -> HttpResponse {
+  let first_id = data.first().map(&id);
+  let last_id = data.last().map(&id);
+
+  let mut res = HttpResponse::Ok();
+  if let Some(links) = cursor_links(req, first_id, last_id, oldest_first) {
+    res.insert_header(("Link", links));
+  }
+
+  res.json(data)
+}
+
+/// Responds with a numbered page if one was asked for, or a page walked by cursor otherwise
+pub fn build_page_response<T: Serialize>(
+  req: &HttpRequest,
+  page: Option<i64>,
+  page_size: i64,
+  data: Vec<T>,
+  id: impl Fn(&T) -> Uuid,
+  oldest_first: bool,
+  total_items: Option<i64>,
+) -> HttpResponse {
+  match page {
+    Some(page) => {
+      let total_items = total_items.unwrap_or_default();
+
+      HttpResponse::Ok().json(ListResponse {
+        data,
+        page,
+        total_items,
+        total_pages: div_up(total_items, page_size) + 1,
+      })
+    }
+    None => build_cursor_response(req, data, id, oldest_first, total_items),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use actix_web::{body::to_bytes, test::TestRequest};
+  use serde_json::Value;
+  use uuid::Uuid;
+
+  use crate::{
+    helpers::pagination::{build_cursor_response, cursor_links},
+    settings::SETTINGS,
+  };
+
+  #[test]
+  fn test_cursor_links_replace_paging_params() {
+    let first_id = Uuid::new_v4();
+    let last_id = Uuid::new_v4();
+    let root = &SETTINGS.server.api_root_fqdn;
+
+    let req = TestRequest::default()
+      .uri(&format!("/api/feed?page=2&count=true&max_id={}", Uuid::new_v4()))
+      .to_http_request();
+
+    assert_eq!(
+      cursor_links(&req, Some(first_id), Some(last_id), false),
+      Some(format!(
+        "<{}/api/feed?count=true&max_id={}>; rel=\"next\", <{}/api/feed?count=true&min_id={}>; rel=\"prev\"",
+        root, last_id, root, first_id
+      ))
+    );
+    assert_eq!(
+      cursor_links(&req, Some(first_id), Some(last_id), true),
+      Some(format!(
+        "<{}/api/feed?count=true&min_id={}>; rel=\"next\", <{}/api/feed?count=true&max_id={}>; rel=\"prev\"",
+        root, last_id, root, first_id
+      ))
+    );
+    assert_eq!(cursor_links(&req, None, None, false), None);
+  }
+
+  #[actix_web::test]
+  async fn test_build_cursor_response() {
+    let ids = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let req = TestRequest::default().uri("/api/feed").to_http_request();
+
+    let res = build_cursor_response(&req, ids.clone(), |id| *id, false, Some(10));
+    assert!(res.headers().contains_key("Link"));
+
+    let body: Value = serde_json::from_slice(&to_bytes(res.into_body()).await.unwrap()).unwrap();
+    assert_eq!(body["data"].as_array().map(|data| data.len()), Some(3));
+    assert_eq!(body["next_max_id"], Value::String(ids[2].to_string()));
+    assert_eq!(body["prev_since_id"], Value::String(ids[0].to_string()));
+    assert_eq!(body.get("next_min_id"), None);
+    assert_eq!(body["total_items"], Value::from(10));
+
+    let res = build_cursor_response(&req, ids.clone(), |id| *id, true, None);
+
+    let body: Value = serde_json::from_slice(&to_bytes(res.into_body()).await.unwrap()).unwrap();
+    assert_eq!(body["next_max_id"], Value::Null);
+    assert_eq!(body["prev_since_id"], Value::Null);
+    assert_eq!(body["next_min_id"], Value::String(ids[2].to_string()));
+    assert_eq!(body.get("total_items"), None);
+
+    let res = build_cursor_response(&req, Vec::<Uuid>::new(), |id| *id, false, None);
+    assert!(!res.headers().contains_key("Link"));
+  }
+}
@@ -1,5 +1,9 @@
-use crate::{db::job_repository::JobPool, logic::LogicErr};
+use crate::{
+  db::{delivery_repository::DeliveryPool, job_repository::JobPool},
+  logic::LogicErr,
+};
 
-pub async fn clean_jobs(jobs: &JobPool) -> Result<(), LogicErr> {
+pub async fn clean_jobs(jobs: &JobPool, deliveries: &DeliveryPool) -> Result<(), LogicErr> {
+  deliveries.purge_delivered_deliveries().await?;
   jobs.purge_completed_jobs().await
 }
@@ -1,10 +1,16 @@
 use uuid::Uuid;
 
 use crate::{
-  db::{event_repository::EventPool, job_repository::JobPool, post_repository::PostPool},
+  db::{
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, event_repository::EventPool,
+    job_repository::JobPool, orbit_repository::OrbitPool, post_repository::PostPool, report_repository::ReportPool,
+    user_repository::UserPool,
+  },
+  federation::activitypub::{federate_ext, FederateExtAction, FederateExtActor},
   helpers::api::map_ext_err,
-  logic::LogicErr,
+  logic::{streaming::feed_event_messages, LogicErr},
   model::{event::NewEvent, event_type::EventType},
+  streaming::pubsub::PubSub,
 };
 
 pub async fn create_boost_event(
@@ -12,6 +18,12 @@ pub async fn create_boost_event(
   jobs: &JobPool,
   posts: &PostPool,
   events: &EventPool,
+  users: &UserPool,
+  orbits: &OrbitPool,
+  comments: &CommentPool,
+  reports: &ReportPool,
+  deliveries: &DeliveryPool,
+  pubsub: &PubSub,
 ) -> Result<(), LogicErr> {
   let job = match jobs.fetch_optional_by_id(&job_id).await {
     Some(job) => job,
@@ -38,6 +50,35 @@ pub async fn create_boost_event(
     None => return Err(LogicErr::InternalError("Visibility not found for post".to_string())),
   };
 
+  let dest_user = match users.fetch_by_id(&target_user_id).await {
+    Ok(user) => user,
+    Err(err) => return Err(err),
+  };
+
+  if dest_user.is_external {
+    let user = match users.fetch_by_id(&user_id).await {
+      Ok(user) => user,
+      Err(err) => return Err(err),
+    };
+
+    // Boosts made by remote users are federated by their own instance
+    if user.is_external {
+      return Ok(());
+    }
+
+    return federate_ext(
+      FederateExtAction::BoostPost(post_id),
+      &user,
+      &FederateExtActor::Person(dest_user),
+      posts,
+      orbits,
+      comments,
+      reports,
+      deliveries,
+    )
+    .await;
+  }
+
   let own_event = NewEvent {
     source_user_id: user_id,
     target_user_id: Some(target_user_id),
@@ -48,5 +89,190 @@ pub async fn create_boost_event(
     event_type: EventType::Boost,
   };
 
-  events.create_event(own_event).await.map_err(map_ext_err)
+  events.create_event(own_event).await.map_err(map_ext_err)?;
+
+  let post = posts.fetch_by_id(&post_id).await?;
+
+  for message in feed_event_messages(&target_user_id, &user_id, &post, EventType::Boost) {
+    pubsub.publish(message).await;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use chrono::Utc;
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      comment_repository::{CommentPool, MockCommentRepo},
+      delivery_repository::{DeliveryPool, MockDeliveryRepo},
+      event_repository::{EventPool, MockEventRepo},
+      job_repository::{JobPool, MockJobRepo},
+      orbit_repository::{MockOrbitRepo, OrbitPool},
+      post_repository::{MockPostRepo, PostPool},
+      report_repository::{MockReportRepo, ReportPool},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    job::create_boost_event::create_boost_event,
+    model::{
+      access_type::AccessType,
+      fixtures,
+      job::{Job, JobStatus},
+      post::Post,
+      user::User,
+    },
+    streaming::pubsub::{MockPubSubBackend, PubSub},
+  };
+
+  fn build_jobs(job_id: Uuid, post_id: Uuid, user_id: Uuid, target_user_id: Uuid) -> JobPool {
+    let mut job_repo = MockJobRepo::new();
+    job_repo
+      .expect_fetch_optional_by_id()
+      .with(eq(job_id))
+      .times(1)
+      .return_const(Some(Job {
+        job_id,
+        record_id: Some(post_id),
+        associated_record_id: Some(target_user_id),
+        created_by_id: Some(user_id),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        status: JobStatus::InProgress,
+        failed_count: 0,
+      }));
+
+    Arc::new(job_repo)
+  }
+
+  fn remote_user(user_id: Uuid) -> User {
+    User {
+      fediverse_uri: "https://example.social/users/a".to_string(),
+      is_external: true,
+      ext_apub_inbox_uri: Some("https://example.social/users/a/inbox".to_string()),
+      ..fixtures::user(user_id)
+    }
+  }
+
+  #[async_std::test]
+  async fn test_create_boost_event_leaves_remote_boosts_to_their_instance() {
+    let job_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    let target_user_id = Uuid::new_v4();
+
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_visibility_by_id()
+      .with(eq(post_id))
+      .times(1)
+      .return_const(Some(AccessType::PublicFederated));
+
+    let mut user_repo = MockUserRepo::new();
+    user_repo
+      .expect_fetch_by_id()
+      .with(eq(target_user_id))
+      .times(1)
+      .return_const(Ok(remote_user(target_user_id)));
+    user_repo
+      .expect_fetch_by_id()
+      .with(eq(user_id))
+      .times(1)
+      .return_const(Ok(remote_user(user_id)));
+
+    let jobs = build_jobs(job_id, post_id, user_id, target_user_id);
+    let posts: PostPool = Arc::new(post_repo);
+    let events: EventPool = Arc::new(MockEventRepo::new());
+    let users: UserPool = Arc::new(user_repo);
+    let orbits: OrbitPool = Arc::new(MockOrbitRepo::new());
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let reports: ReportPool = Arc::new(MockReportRepo::new());
+    let deliveries: DeliveryPool = Arc::new(MockDeliveryRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+
+    assert_eq!(
+      create_boost_event(
+        job_id,
+        &jobs,
+        &posts,
+        &events,
+        &users,
+        &orbits,
+        &comments,
+        &reports,
+        &deliveries,
+        &pubsub
+      )
+      .await,
+      Ok(())
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_boost_event_only_announces_public_posts_to_remote_followers() {
+    let job_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    let target_user_id = Uuid::new_v4();
+
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_visibility_by_id()
+      .with(eq(post_id))
+      .times(1)
+      .return_const(Some(AccessType::FollowersOnly));
+    post_repo
+      .expect_find_optional_by_id()
+      .with(eq(post_id))
+      .times(1)
+      .return_const(Some(Post {
+        visibility: AccessType::FollowersOnly,
+        ..fixtures::post(post_id, user_id, None)
+      }));
+
+    let mut user_repo = MockUserRepo::new();
+    user_repo
+      .expect_fetch_by_id()
+      .with(eq(target_user_id))
+      .times(1)
+      .return_const(Ok(remote_user(target_user_id)));
+    user_repo
+      .expect_fetch_by_id()
+      .with(eq(user_id))
+      .times(1)
+      .return_const(Ok(fixtures::user(user_id)));
+
+    // Neither an event nor a delivery is created for the remote follower
+    let jobs = build_jobs(job_id, post_id, user_id, target_user_id);
+    let posts: PostPool = Arc::new(post_repo);
+    let events: EventPool = Arc::new(MockEventRepo::new());
+    let users: UserPool = Arc::new(user_repo);
+    let orbits: OrbitPool = Arc::new(MockOrbitRepo::new());
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let reports: ReportPool = Arc::new(MockReportRepo::new());
+    let deliveries: DeliveryPool = Arc::new(MockDeliveryRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+
+    assert_eq!(
+      create_boost_event(
+        job_id,
+        &jobs,
+        &posts,
+        &events,
+        &users,
+        &orbits,
+        &comments,
+        &reports,
+        &deliveries,
+        &pubsub
+      )
+      .await,
+      Ok(())
+    );
+  }
 }
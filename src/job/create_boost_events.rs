@@ -1,19 +1,31 @@
 use uuid::Uuid;
 
+use super::federate_activitypub_ext::queue_federate_ext_to_followers;
 use crate::{
-  db::{follow_repository::FollowPool, job_repository::JobPool},
+  db::{
+    follow_repository::FollowPool, job_repository::JobPool, notification_repository::NotificationPool,
+    post_repository::PostPool, user_repository::UserPool,
+  },
+  federation::activitypub::FederateExtAction,
   helpers::api::map_db_err,
-  logic::LogicErr,
+  logic::{notification::notify, LogicErr},
   model::{
     job::{JobStatus, NewJob},
+    notification::NewNotification,
+    notification_type::NotificationType,
     queue_job::{QueueJob, QueueJobType},
   },
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
 pub async fn create_boost_events(
   jobs: &JobPool,
   follows: &FollowPool,
+  users: &UserPool,
+  posts: &PostPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
   job_id: Uuid,
   queue: &Queue,
 ) -> Result<(), LogicErr> {
@@ -32,7 +44,20 @@ pub async fn create_boost_events(
     None => return Err(LogicErr::InternalError("User ID not found for job".to_string())),
   };
 
-  let followers = follows.fetch_user_followers(&user_id).await.unwrap_or_default();
+  let external_followers: Vec<Uuid> = users
+    .fetch_external_followers(&user_id)
+    .await?
+    .into_iter()
+    .map(|follower| follower.user_id)
+    .collect();
+
+  // Remote followers are federated to in bulk rather than one at a time
+  let followers = follows
+    .fetch_user_followers(&user_id)
+    .await
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|follower| !external_followers.contains(&follower.user_id));
 
   for follower in followers {
     let job_id = jobs
@@ -53,5 +78,34 @@ pub async fn create_boost_events(
     queue.send_job(job).await?;
   }
 
-  Ok(())
+  if let Some(owner_id) = posts.fetch_owner_by_id(&post_id).await {
+    notify(
+      notifications,
+      pubsub,
+      NewNotification {
+        user_id: owner_id,
+        source_user_id: user_id,
+        notification_type: NotificationType::Boost,
+        post_id: Some(post_id),
+        comment_id: None,
+        orbit_id: None,
+      },
+    )
+    .await;
+  }
+
+  // Boosts made by remote users are federated by their own instance
+  if users.user_is_external(&user_id).await {
+    return Ok(());
+  }
+
+  queue_federate_ext_to_followers(
+    FederateExtAction::BoostPost(post_id),
+    &user_id,
+    &post_id,
+    users,
+    jobs,
+    queue,
+  )
+  .await
 }
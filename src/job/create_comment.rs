@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    follow_repository::FollowPool, job_repository::JobPool, notification_repository::NotificationPool,
+    orbit_repository::OrbitPool, post_repository::PostPool, user_orbit_repository::UserOrbitPool,
+    user_repository::UserPool,
+  },
+  federation::activitypub::{federate_ext_group_by_inbox, FederateExtAction, FederateExtActorRef},
+  helpers::api::map_db_err,
+  logic::{notification::notify, LogicErr},
+  model::{
+    access_type::AccessType,
+    job::{JobStatus, NewJob},
+    notification::NewNotification,
+    notification_type::NotificationType,
+    queue_job::{QueueJob, QueueJobType},
+  },
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
+};
+
+/// Determines every remote actor that should hear about a comment: the post's author, the post's orbit (or its remote
+/// members if the orbit is ours) and the commenter's remote followers. Remote users sharing an inbox are collapsed into
+/// a single destination.
+pub async fn fetch_comment_recipients(
+  post_id: &Uuid,
+  user_id: &Uuid,
+  posts: &PostPool,
+  follows: &FollowPool,
+  orbits: &OrbitPool,
+  user_orbits: &UserOrbitPool,
+  users: &UserPool,
+) -> Result<Vec<FederateExtActorRef>, LogicErr> {
+  let post = posts.fetch_by_id(post_id).await?;
+
+  // Comments are only federated when the post they're on is visible to the wider fediverse
+  if post.visibility != AccessType::PublicFederated
+    && post.visibility != AccessType::Unlisted
+    && post.visibility != AccessType::FollowersOnly
+  {
+    return Ok(vec![]);
+  }
+
+  let mut recipients = vec![];
+  let mut people = HashSet::<Uuid>::new();
+
+  if post.user_id != *user_id && users.user_is_external(&post.user_id).await {
+    people.insert(post.user_id);
+  }
+
+  if let Some(orbit_id) = post.orbit_id {
+    if let Some(orbit) = orbits.fetch_orbit(&orbit_id).await? {
+      match orbit.is_external {
+        true => recipients.push(FederateExtActorRef::Group(orbit_id)),
+        false => people.extend(user_orbits.fetch_orbit_external_user_ids(&orbit_id).await?),
+      }
+    }
+  }
+
+  for follower in follows.fetch_user_followers(user_id).await.unwrap_or_default() {
+    if users.user_is_external(&follower.user_id).await {
+      people.insert(follower.user_id);
+    }
+  }
+
+  people.remove(user_id);
+
+  let mut people_users = vec![];
+  for person in people {
+    people_users.push(users.fetch_by_id(&person).await?);
+  }
+
+  recipients.extend(federate_ext_group_by_inbox(people_users));
+
+  Ok(recipients)
+}
+
+pub async fn create_comment(
+  job_id: Uuid,
+  jobs: &JobPool,
+  posts: &PostPool,
+  follows: &FollowPool,
+  orbits: &OrbitPool,
+  user_orbits: &UserOrbitPool,
+  users: &UserPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  queue: &Queue,
+) -> Result<(), LogicErr> {
+  let job = match jobs.fetch_optional_by_id(&job_id).await {
+    Some(job) => job,
+    None => return Err(LogicErr::InternalError("Job not found".to_string())),
+  };
+
+  let user_id = match job.created_by_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("User not found".to_string())),
+  };
+
+  let comment_id = match job.record_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("Comment ID not found for job".to_string())),
+  };
+
+  let post_id = match job.associated_record_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("Post ID not found for job".to_string())),
+  };
+
+  if let Some(owner_id) = posts.fetch_owner_by_id(&post_id).await {
+    notify(
+      notifications,
+      pubsub,
+      NewNotification {
+        user_id: owner_id,
+        source_user_id: user_id,
+        notification_type: NotificationType::Comment,
+        post_id: Some(post_id),
+        comment_id: Some(comment_id),
+        orbit_id: None,
+      },
+    )
+    .await;
+  }
+
+  let recipients = fetch_comment_recipients(&post_id, &user_id, posts, follows, orbits, user_orbits, users).await?;
+
+  for recipient in recipients {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(comment_id),
+        associated_record_id: Some(post_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::CreateComment(post_id, comment_id))
+      .activitypub_federate_ext_dest_actor(recipient)
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(())
+}
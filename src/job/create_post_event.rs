@@ -2,13 +2,15 @@ use uuid::Uuid;
 
 use crate::{
   db::{
-    event_repository::EventPool, job_repository::JobPool, orbit_repository::OrbitPool, post_repository::PostPool,
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, event_repository::EventPool,
+    job_repository::JobPool, orbit_repository::OrbitPool, post_repository::PostPool, report_repository::ReportPool,
     user_repository::UserPool,
   },
   federation::activitypub::{federate_ext, FederateExtAction, FederateExtActor},
   helpers::api::map_ext_err,
-  logic::LogicErr,
+  logic::{streaming::feed_event_messages, LogicErr},
   model::{event::NewEvent, event_type::EventType},
+  streaming::pubsub::PubSub,
 };
 
 pub async fn create_post_event(
@@ -18,6 +20,10 @@ pub async fn create_post_event(
   users: &UserPool,
   orbits: &OrbitPool,
   job_id: Uuid,
+  comments: &CommentPool,
+  reports: &ReportPool,
+  deliveries: &DeliveryPool,
+  pubsub: &PubSub,
 ) -> Result<(), LogicErr> {
   let job = match jobs.fetch_optional_by_id(&job_id).await {
     Some(job) => job,
@@ -50,6 +56,11 @@ pub async fn create_post_event(
   };
 
   if dest_user.fediverse_uri.starts_with("http") {
+    // Remote members of an orbit receive the post from the orbit's Group actor instead
+    if posts.fetch_by_id(&post_id).await?.orbit_id.is_some() {
+      return Ok(());
+    }
+
     let user = match users.fetch_by_id(&user_id).await {
       Ok(user) => user,
       Err(err) => return Err(err),
@@ -61,6 +72,9 @@ pub async fn create_post_event(
       &FederateExtActor::Person(dest_user),
       posts,
       orbits,
+      comments,
+      reports,
+      deliveries,
     )
     .await;
   }
@@ -75,5 +89,13 @@ pub async fn create_post_event(
     event_type: EventType::Post,
   };
 
-  events.create_event(own_event).await.map_err(map_ext_err)
+  events.create_event(own_event).await.map_err(map_ext_err)?;
+
+  let post = posts.fetch_by_id(&post_id).await?;
+
+  for message in feed_event_messages(&target_user_id, &user_id, &post, EventType::Post) {
+    pubsub.publish(message).await;
+  }
+
+  Ok(())
 }
@@ -1,33 +1,43 @@
 use log::warn;
 use uuid::Uuid;
 
+use super::federate_activitypub_ext::{queue_federate_ext_to_followers, queue_federate_ext_to_mentions};
 use crate::{
   db::{
-    event_repository::EventPool, follow_repository::FollowPool, job_repository::JobPool, orbit_repository::OrbitPool,
-    post_repository::PostPool, user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, event_repository::EventPool,
+    follow_repository::FollowPool, job_repository::JobPool, orbit_repository::OrbitPool,
+    post_mention_repository::PostMentionPool, post_repository::PostPool, report_repository::ReportPool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
   },
-  federation::activitypub::{federate_ext, FederateExtAction, FederateExtActor},
+  federation::activitypub::{federate_ext, FederateExtAction, FederateExtActor, FederateExtActorRef},
   helpers::api::{map_db_err, map_ext_err},
-  logic::LogicErr,
+  logic::{streaming::post_messages, LogicErr},
   model::{
+    access_type::AccessType,
     event::NewEvent,
     event_type::EventType,
     job::{JobStatus, NewJob},
     queue_job::{QueueJob, QueueJobType},
   },
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
 pub async fn create_post_events(
   jobs: &JobPool,
   posts: &PostPool,
+  post_mentions: &PostMentionPool,
   events: &EventPool,
   follows: &FollowPool,
   user_orbits: &UserOrbitPool,
   orbits: &OrbitPool,
   users: &UserPool,
   job_id: Uuid,
+  pubsub: &PubSub,
   queue: &Queue,
+  comments: &CommentPool,
+  reports: &ReportPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let job = match jobs.fetch_optional_by_id(&job_id).await {
     Some(job) => job,
@@ -62,18 +72,59 @@ pub async fn create_post_events(
     }
   }
 
+  for message in post_messages(&post) {
+    pubsub.publish(message).await;
+  }
+
+  // Shadow posts only ever reach their author's own feed
+  if post.visibility == AccessType::Shadow {
+    return Ok(());
+  }
+
+  // Remote users the post is already being delivered to, who don't need a separate copy for being mentioned in it
+  let mut reached_user_ids: Vec<Uuid> = vec![];
+
   if let Some(orbit_id) = post.orbit_id {
     match orbits.fetch_orbit(&orbit_id).await? {
       Some(orbit) => {
         let user = users.fetch_by_id(&user_id).await?;
 
-        if orbit.is_external && !post.is_external {
+        if !orbit.is_external {
+          let members = user_orbits.fetch_orbit_external_user_ids(&orbit_id).await?;
+          reached_user_ids.extend(members.iter().copied());
+
+          // The orbit's Group actor relays the post to remote members, except to the remote author it came from
+          for member in members.into_iter().filter(|member| *member != user_id) {
+            let job_id = jobs
+              .create(NewJob {
+                created_by_id: Some(user_id),
+                status: JobStatus::NotStarted,
+                record_id: Some(post_id),
+                associated_record_id: Some(member),
+              })
+              .await
+              .map_err(map_db_err)?;
+
+            let job = QueueJob::builder()
+              .job_id(job_id)
+              .job_type(QueueJobType::FederateActivityPubExt)
+              .context(vec![user_id.to_string()])
+              .activitypub_federate_ext_action(FederateExtAction::GroupCreatePost(orbit_id, post_id))
+              .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(member))
+              .build();
+
+            queue.send_job(job).await?;
+          }
+        } else if !post.is_external {
           federate_ext(
             FederateExtAction::CreatePost(post_id),
             &user,
             &FederateExtActor::Group(orbit),
             posts,
             orbits,
+            comments,
+            reports,
+            deliveries,
           )
           .await?;
         }
@@ -108,7 +159,21 @@ pub async fn create_post_events(
       queue.send_job(job).await?;
     }
   } else {
-    let followers = follows.fetch_user_followers(&user_id).await.unwrap_or_default();
+    let external_followers: Vec<Uuid> = users
+      .fetch_external_followers(&user_id)
+      .await?
+      .into_iter()
+      .map(|follower| follower.user_id)
+      .collect();
+    reached_user_ids.extend(external_followers.iter().copied());
+
+    // Remote followers are federated to in bulk rather than one at a time
+    let followers = follows
+      .fetch_user_followers(&user_id)
+      .await
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|follower| !external_followers.contains(&follower.user_id));
 
     for follower in followers {
       let job_id = jobs
@@ -128,6 +193,31 @@ pub async fn create_post_events(
 
       queue.send_job(job).await?;
     }
+
+    if !post.is_external {
+      queue_federate_ext_to_followers(
+        FederateExtAction::CreatePost(post_id),
+        &user_id,
+        &post_id,
+        users,
+        jobs,
+        queue,
+      )
+      .await?;
+    }
+  }
+
+  if !post.is_external {
+    queue_federate_ext_to_mentions(
+      FederateExtAction::CreatePost(post_id),
+      &user_id,
+      &post_id,
+      &reached_user_ids,
+      post_mentions,
+      jobs,
+      queue,
+    )
+    .await?;
   }
 
   Ok(())
@@ -1,13 +1,22 @@
 use uuid::Uuid;
 
+use super::federate_activitypub_ext::queue_federate_ext_to_followers;
 use crate::{
-  db::{event_repository::EventPool, job_repository::JobPool},
+  db::{event_repository::EventPool, job_repository::JobPool, user_repository::UserPool},
+  federation::activitypub::FederateExtAction,
   helpers::api::map_db_err,
   logic::LogicErr,
   model::event_type::EventType,
+  work_queue::queue::Queue,
 };
 
-pub async fn delete_boost_events(job_id: Uuid, jobs: &JobPool, events: &EventPool) -> Result<(), LogicErr> {
+pub async fn delete_boost_events(
+  job_id: Uuid,
+  jobs: &JobPool,
+  events: &EventPool,
+  users: &UserPool,
+  queue: &Queue,
+) -> Result<(), LogicErr> {
   let job = match jobs.fetch_optional_by_id(&job_id).await {
     Some(job) => job,
     None => return Err(LogicErr::InternalError("Job not found".to_string())),
@@ -26,5 +35,20 @@ pub async fn delete_boost_events(job_id: Uuid, jobs: &JobPool, events: &EventPoo
   events
     .delete_post_events(&post_id, &user_id, EventType::Boost)
     .await
-    .map_err(map_db_err)
+    .map_err(map_db_err)?;
+
+  // Boosts made by remote users are federated by their own instance
+  if users.user_is_external(&user_id).await {
+    return Ok(());
+  }
+
+  queue_federate_ext_to_followers(
+    FederateExtAction::UnboostPost(post_id),
+    &user_id,
+    &post_id,
+    users,
+    jobs,
+    queue,
+  )
+  .await
 }
@@ -0,0 +1,75 @@
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    follow_repository::FollowPool, job_repository::JobPool, orbit_repository::OrbitPool, post_repository::PostPool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+  },
+  federation::activitypub::FederateExtAction,
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::{
+    job::{JobStatus, NewJob},
+    queue_job::{QueueJob, QueueJobType},
+  },
+  work_queue::queue::Queue,
+};
+
+use super::create_comment::fetch_comment_recipients;
+
+pub async fn delete_comment(
+  job_id: Uuid,
+  jobs: &JobPool,
+  posts: &PostPool,
+  follows: &FollowPool,
+  orbits: &OrbitPool,
+  user_orbits: &UserOrbitPool,
+  users: &UserPool,
+  queue: &Queue,
+) -> Result<(), LogicErr> {
+  let job = match jobs.fetch_optional_by_id(&job_id).await {
+    Some(job) => job,
+    None => return Err(LogicErr::InternalError("Job not found".to_string())),
+  };
+
+  let user_id = match job.created_by_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("User not found".to_string())),
+  };
+
+  let comment_id = match job.record_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("Comment ID not found for job".to_string())),
+  };
+
+  let post_id = match job.associated_record_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("Post ID not found for job".to_string())),
+  };
+
+  let recipients = fetch_comment_recipients(&post_id, &user_id, posts, follows, orbits, user_orbits, users).await?;
+
+  for recipient in recipients {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(comment_id),
+        associated_record_id: Some(post_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::DeleteComment(post_id, comment_id))
+      .activitypub_federate_ext_dest_actor(recipient)
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(())
+}
@@ -1,8 +1,10 @@
 use uuid::Uuid;
 
+use super::federate_activitypub_ext::queue_federate_ext_to_followers;
 use crate::{
   db::{
-    follow_repository::FollowPool, job_repository::JobPool, orbit_repository::OrbitPool, post_repository::PostPool,
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, job_repository::JobPool,
+    orbit_repository::OrbitPool, post_repository::PostPool, report_repository::ReportPool,
     user_orbit_repository::UserOrbitPool, user_repository::UserPool,
   },
   federation::activitypub::{federate_ext, FederateExtAction, FederateExtActor, FederateExtActorRef},
@@ -11,19 +13,25 @@ use crate::{
   model::{
     job::{JobStatus, NewJob},
     queue_job::{QueueJob, QueueJobType},
+    stream_message::StreamMessage,
   },
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
 pub async fn delete_post(
   job_id: Uuid,
+  context: &Option<Vec<String>>,
   jobs: &JobPool,
   orbits: &OrbitPool,
   user_orbits: &UserOrbitPool,
   users: &UserPool,
   posts: &PostPool,
-  follows: &FollowPool,
+  pubsub: &PubSub,
   queue: &Queue,
+  comments: &CommentPool,
+  reports: &ReportPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let job = match jobs.fetch_optional_by_id(&job_id).await {
     Some(job) => job,
@@ -40,6 +48,14 @@ pub async fn delete_post(
     None => return Err(LogicErr::InternalError("Post ID not found for job".to_string())),
   };
 
+  // NOTE: Jobs queued before the post's URI was passed along can only have been for local posts
+  let post_uri = match context.as_ref().and_then(|context| context.first()) {
+    Some(uri) => uri.to_owned(),
+    None => format!("/feed/{}", post_id),
+  };
+
+  pubsub.publish(StreamMessage::Delete { post_id }).await;
+
   if let Some(orbit_id) = job.associated_record_id {
     match orbits.fetch_orbit(&orbit_id).await? {
       Some(orbit) => {
@@ -52,6 +68,9 @@ pub async fn delete_post(
             &FederateExtActor::Group(orbit),
             posts,
             orbits,
+            comments,
+            reports,
+            deliveries,
           )
           .await?;
           return Ok(());
@@ -83,36 +102,22 @@ pub async fn delete_post(
         .job_id(job_id)
         .job_type(QueueJobType::FederateActivityPubExt)
         .context(vec![user_id.to_string()])
-        .activitypub_federate_ext_action(FederateExtAction::DeletePost(post_id))
+        .activitypub_federate_ext_action(FederateExtAction::GroupDeletePost(orbit_id, post_id, post_uri.clone()))
         .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(user))
         .build();
 
       queue.send_job(job).await?;
     }
   } else {
-    let followers = follows.fetch_user_followers(&user_id).await.unwrap_or_default();
-
-    for follower in followers {
-      let job_id = jobs
-        .create(NewJob {
-          created_by_id: Some(user_id),
-          status: JobStatus::NotStarted,
-          record_id: Some(post_id),
-          associated_record_id: Some(follower.user_id),
-        })
-        .await
-        .map_err(map_db_err)?;
-
-      let job = QueueJob::builder()
-        .job_id(job_id)
-        .job_type(QueueJobType::FederateActivityPubExt)
-        .context(vec![user_id.to_string()])
-        .activitypub_federate_ext_action(FederateExtAction::DeletePost(post_id))
-        .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(follower.user_id))
-        .build();
-
-      queue.send_job(job).await?;
-    }
+    queue_federate_ext_to_followers(
+      FederateExtAction::DeletePost(post_id),
+      &user_id,
+      &post_id,
+      users,
+      jobs,
+      queue,
+    )
+    .await?;
   }
 
   Ok(())
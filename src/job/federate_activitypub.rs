@@ -8,6 +8,7 @@ use crate::{
   federation::activitypub::federate,
   logic::LogicErr,
   model::queue_job::OriginDataEntry,
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
@@ -15,6 +16,7 @@ pub async fn federate_activitypub(
   data: &Option<Value>,
   origin_data: &Option<HashMap<String, OriginDataEntry>>,
   repositories: &Repositories,
+  pubsub: &PubSub,
   queue: &Queue,
 ) -> Result<(), LogicErr> {
   debug!(
@@ -39,14 +41,25 @@ pub async fn federate_activitypub(
     doc,
     origin_data,
     &repositories.users,
+    &repositories.account_restrictions,
+    &repositories.blocks,
     &repositories.follows,
     &repositories.posts,
     &repositories.likes,
+    &repositories.comments,
     &repositories.jobs,
     &repositories.post_attachments,
+    &repositories.post_mentions,
+    &repositories.tags,
     &repositories.orbits,
     &repositories.user_orbits,
+    &repositories.orbit_moderation,
+    &repositories.orbit_membership,
+    &repositories.reports,
+    &repositories.notifications,
+    pubsub,
     queue,
+    &repositories.deliveries,
   )
   .await
 }
@@ -3,10 +3,20 @@ use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::{
-  db::repositories::Repositories,
-  federation::activitypub::{federate_ext, FederateExtAction, FederateExtActor, FederateExtActorRef},
-  helpers::api::map_ext_err,
+  db::{
+    job_repository::JobPool, post_mention_repository::PostMentionPool, repositories::Repositories,
+    user_repository::UserPool,
+  },
+  federation::activitypub::{
+    federate_ext, federate_ext_group_by_inbox, FederateExtAction, FederateExtActor, FederateExtActorRef,
+  },
+  helpers::api::{map_db_err, map_ext_err},
   logic::LogicErr,
+  model::{
+    job::{JobStatus, NewJob},
+    queue_job::{QueueJob, QueueJobType},
+  },
+  work_queue::queue::Queue,
 };
 
 pub async fn federate_activitypub(
@@ -39,6 +49,7 @@ pub async fn federate_activitypub(
       Some(orbit) => FederateExtActor::Group(orbit),
       None => return Err(LogicErr::MissingRecord),
     },
+    FederateExtActorRef::SharedInbox(uri, recipients) => FederateExtActor::SharedInbox(uri.clone(), recipients.clone()),
   };
 
   federate_ext(
@@ -47,6 +58,91 @@ pub async fn federate_activitypub(
     &dest_actor,
     &repositories.posts,
     &repositories.orbits,
+    &repositories.comments,
+    &repositories.reports,
+    &repositories.deliveries,
   )
   .await
 }
+
+/// Queues an activity for delivery to all of a user's remote followers, sending one copy to each shared inbox
+pub async fn queue_federate_ext_to_followers(
+  action: FederateExtAction,
+  user_id: &Uuid,
+  record_id: &Uuid,
+  users: &UserPool,
+  jobs: &JobPool,
+  queue: &Queue,
+) -> Result<(), LogicErr> {
+  let followers = users.fetch_external_followers(user_id).await?;
+
+  for dest_actor in federate_ext_group_by_inbox(followers) {
+    let associated_record_id = match &dest_actor {
+      FederateExtActorRef::Person(id) => Some(*id),
+      _ => None,
+    };
+
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(*user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(*record_id),
+        associated_record_id,
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(action.clone())
+      .activitypub_federate_ext_dest_actor(dest_actor)
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(())
+}
+
+/// Queues an activity for delivery to the remote users a post mentions, skipping those it already reaches some other way
+pub async fn queue_federate_ext_to_mentions(
+  action: FederateExtAction,
+  user_id: &Uuid,
+  post_id: &Uuid,
+  reached_user_ids: &[Uuid],
+  post_mentions: &PostMentionPool,
+  jobs: &JobPool,
+  queue: &Queue,
+) -> Result<(), LogicErr> {
+  let mentions = post_mentions
+    .fetch_post_mentions(post_id)
+    .await?
+    .into_iter()
+    .filter(|mention| mention.is_external && !reached_user_ids.contains(&mention.user_id));
+
+  for mention in mentions {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(*user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(*post_id),
+        associated_record_id: Some(mention.user_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(action.clone())
+      .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(mention.user_id))
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(())
+}
@@ -3,8 +3,8 @@ use uuid::Uuid;
 use crate::{
   db::{
     follow_repository::FollowPool, job_repository::JobPool, like_repository::LikePool, orbit_repository::OrbitPool,
-    post_attachment_repository::PostAttachmentPool, post_repository::PostPool, user_orbit_repository::UserOrbitPool,
-    user_repository::UserPool,
+    post_attachment_repository::PostAttachmentPool, post_repository::PostPool, tag_repository::TagPool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
   },
   federation::activitypub::federate_posts_collection,
   logic::LogicErr,
@@ -16,6 +16,7 @@ pub async fn fetch_external_orbit_posts(
   users: &UserPool,
   follows: &FollowPool,
   posts: &PostPool,
+  tags: &TagPool,
   likes: &LikePool,
   jobs: &JobPool,
   post_attachments: &PostAttachmentPool,
@@ -48,6 +49,7 @@ pub async fn fetch_external_orbit_posts(
     users,
     follows,
     posts,
+    tags,
     likes,
     jobs,
     post_attachments,
@@ -1,8 +1,10 @@
 use crate::{
   cdn::cdn_store::Cdn,
   db::repositories::Repositories,
+  federation::domain_policy::sync_domain_policies,
   logic::LogicErr,
   model::queue_job::{QueueJob, QueueJobType},
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
@@ -10,9 +12,11 @@ mod clean_jobs;
 mod convert_new_post_images;
 mod create_boost_event;
 mod create_boost_events;
+mod create_comment;
 mod create_post_event;
 mod create_post_events;
 mod delete_boost_events;
+mod delete_comment;
 mod delete_post;
 mod federate_activitypub;
 mod federate_activitypub_ext;
@@ -21,14 +25,19 @@ mod refresh_external_orbit;
 mod refresh_external_orbits;
 mod refresh_external_profile;
 mod refresh_external_profiles;
+mod remove_orbit_object;
+mod retry_deliveries;
 mod update_post;
 
 pub async fn delegate_job(
   queue_job: &QueueJob,
   repositories: &Repositories,
   cdn: &Cdn,
+  pubsub: &PubSub,
   queue: &Queue,
 ) -> Result<(), LogicErr> {
+  sync_domain_policies(&repositories.domain_policies).await;
+
   match queue_job.job_type {
     QueueJobType::ConvertNewPostImages => {
       convert_new_post_images::convert_new_post_images(
@@ -43,13 +52,18 @@ pub async fn delegate_job(
       create_post_events::create_post_events(
         &repositories.jobs,
         &repositories.posts,
+        &repositories.post_mentions,
         &repositories.events,
         &repositories.follows,
         &repositories.user_orbits,
         &repositories.orbits,
         &repositories.users,
         queue_job.job_id,
+        pubsub,
         queue,
+        &repositories.comments,
+        &repositories.reports,
+        &repositories.deliveries,
       )
       .await
     }
@@ -61,11 +75,25 @@ pub async fn delegate_job(
         &repositories.users,
         &repositories.orbits,
         queue_job.job_id,
+        &repositories.comments,
+        &repositories.reports,
+        &repositories.deliveries,
+        pubsub,
       )
       .await
     }
     QueueJobType::CreateBoostEvents => {
-      create_boost_events::create_boost_events(&repositories.jobs, &repositories.follows, queue_job.job_id, queue).await
+      create_boost_events::create_boost_events(
+        &repositories.jobs,
+        &repositories.follows,
+        &repositories.users,
+        &repositories.posts,
+        &repositories.notifications,
+        pubsub,
+        queue_job.job_id,
+        queue,
+      )
+      .await
     }
     QueueJobType::CreateBoostEvent => {
       create_boost_event::create_boost_event(
@@ -73,22 +101,39 @@ pub async fn delegate_job(
         &repositories.jobs,
         &repositories.posts,
         &repositories.events,
+        &repositories.users,
+        &repositories.orbits,
+        &repositories.comments,
+        &repositories.reports,
+        &repositories.deliveries,
+        pubsub,
       )
       .await
     }
     QueueJobType::DeleteBoostEvents => {
-      delete_boost_events::delete_boost_events(queue_job.job_id, &repositories.jobs, &repositories.events).await
+      delete_boost_events::delete_boost_events(
+        queue_job.job_id,
+        &repositories.jobs,
+        &repositories.events,
+        &repositories.users,
+        queue,
+      )
+      .await
     }
     QueueJobType::DeletePost => {
       delete_post::delete_post(
         queue_job.job_id,
+        &queue_job.context,
         &repositories.jobs,
         &repositories.orbits,
         &repositories.user_orbits,
         &repositories.users,
         &repositories.posts,
-        &repositories.follows,
+        pubsub,
         queue,
+        &repositories.comments,
+        &repositories.reports,
+        &repositories.deliveries,
       )
       .await
     }
@@ -100,13 +145,56 @@ pub async fn delegate_job(
         &repositories.user_orbits,
         &repositories.users,
         &repositories.posts,
+        &repositories.post_mentions,
+        queue,
+        &repositories.comments,
+        &repositories.reports,
+        &repositories.deliveries,
+      )
+      .await
+    }
+    QueueJobType::CreateComment => {
+      create_comment::create_comment(
+        queue_job.job_id,
+        &repositories.jobs,
+        &repositories.posts,
+        &repositories.follows,
+        &repositories.orbits,
+        &repositories.user_orbits,
+        &repositories.users,
+        &repositories.notifications,
+        pubsub,
+        queue,
+      )
+      .await
+    }
+    QueueJobType::DeleteComment => {
+      delete_comment::delete_comment(
+        queue_job.job_id,
+        &repositories.jobs,
+        &repositories.posts,
         &repositories.follows,
+        &repositories.orbits,
+        &repositories.user_orbits,
+        &repositories.users,
+        queue,
+      )
+      .await
+    }
+    QueueJobType::RemoveOrbitObject => {
+      remove_orbit_object::remove_orbit_object(
+        queue_job.job_id,
+        &queue_job.context,
+        &repositories.jobs,
+        &repositories.orbits,
+        &repositories.user_orbits,
         queue,
       )
       .await
     }
     QueueJobType::FederateActivityPub => {
-      federate_activitypub::federate_activitypub(&queue_job.data, &queue_job.origin_data, repositories, queue).await
+      federate_activitypub::federate_activitypub(&queue_job.data, &queue_job.origin_data, repositories, pubsub, queue)
+        .await
     }
     QueueJobType::FederateActivityPubExt => {
       federate_activitypub_ext::federate_activitypub(
@@ -123,6 +211,7 @@ pub async fn delegate_job(
         &repositories.users,
         &repositories.follows,
         &repositories.posts,
+        &repositories.tags,
         &repositories.likes,
         &repositories.jobs,
         &repositories.post_attachments,
@@ -132,7 +221,10 @@ pub async fn delegate_job(
       )
       .await
     }
-    QueueJobType::CleanJobs => clean_jobs::clean_jobs(&repositories.jobs).await,
+    QueueJobType::CleanJobs => clean_jobs::clean_jobs(&repositories.jobs, &repositories.deliveries).await,
+    QueueJobType::RetryDeliveries => {
+      retry_deliveries::retry_deliveries(&repositories.deliveries, &repositories.users, &repositories.orbits).await
+    }
     QueueJobType::RefreshExternalOrbits => {
       refresh_external_orbits::refresh_external_orbits(&repositories.orbits, &repositories.jobs, queue).await
     }
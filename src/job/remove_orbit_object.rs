@@ -0,0 +1,73 @@
+use uuid::Uuid;
+
+use crate::{
+  db::{job_repository::JobPool, orbit_repository::OrbitPool, user_orbit_repository::UserOrbitPool},
+  federation::activitypub::{FederateExtAction, FederateExtActorRef},
+  helpers::api::map_db_err,
+  logic::LogicErr,
+  model::{
+    job::{JobStatus, NewJob},
+    queue_job::{QueueJob, QueueJobType},
+  },
+  work_queue::queue::Queue,
+};
+
+pub async fn remove_orbit_object(
+  job_id: Uuid,
+  context: &Option<Vec<String>>,
+  jobs: &JobPool,
+  orbits: &OrbitPool,
+  user_orbits: &UserOrbitPool,
+  queue: &Queue,
+) -> Result<(), LogicErr> {
+  let job = match jobs.fetch_optional_by_id(&job_id).await {
+    Some(job) => job,
+    None => return Err(LogicErr::InternalError("Job not found".to_string())),
+  };
+
+  let user_id = match job.created_by_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("User not found".to_string())),
+  };
+
+  let orbit_id = match job.record_id {
+    Some(id) => id,
+    None => return Err(LogicErr::InternalError("Orbit ID not found for job".to_string())),
+  };
+
+  let object_uri = match context.as_ref().and_then(|context| context.first()) {
+    Some(uri) => uri.to_owned(),
+    None => return Err(LogicErr::InternalError("Object URI not found for job".to_string())),
+  };
+
+  match orbits.fetch_orbit(&orbit_id).await? {
+    Some(orbit) if !orbit.is_external => {}
+    _ => return Ok(()),
+  };
+
+  let members = user_orbits.fetch_orbit_external_user_ids(&orbit_id).await?;
+
+  for member in members {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(orbit_id),
+        associated_record_id: Some(member),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::GroupRemoveObject(orbit_id, object_uri.clone()))
+      .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(member))
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(())
+}
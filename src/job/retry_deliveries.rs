@@ -0,0 +1,56 @@
+use chrono::Utc;
+
+use crate::{
+  db::{delivery_repository::DeliveryPool, orbit_repository::OrbitPool, user_repository::UserPool},
+  federation::activitypub::delivery::{delivery_retry_expired, federate_redeliver, next_delivery_attempt_at},
+  logic::LogicErr,
+  settings::SETTINGS,
+};
+
+const RETRY_BATCH_SIZE: i64 = 100;
+
+pub async fn retry_deliveries(deliveries: &DeliveryPool, users: &UserPool, orbits: &OrbitPool) -> Result<(), LogicErr> {
+  let due_deliveries = deliveries.claim_due_deliveries(RETRY_BATCH_SIZE).await?;
+
+  for delivery in due_deliveries {
+    let err = match federate_redeliver(&delivery, users, orbits).await {
+      Ok(_) => {
+        deliveries.mark_delivered(&delivery.delivery_id).await?;
+        continue;
+      }
+      Err(err) => err,
+    };
+
+    // Nobody is left to sign for the activity if the sending user or orbit has since been deleted, and there's no
+    // point in holding onto activities for a domain that has since been suspended
+    let expired = err == LogicErr::MissingRecord
+      || err == LogicErr::UnauthorizedError
+      || delivery_retry_expired(
+        &delivery.created_at,
+        &Utc::now(),
+        SETTINGS.queue.delivery_retry_horizon_hours,
+      );
+    let last_error = format!("{:?}", err);
+
+    if expired {
+      log::warn!(
+        "Giving up on delivery {} to {} after {} attempts: {}",
+        delivery.delivery_id,
+        delivery.inbox_uri,
+        delivery.attempts + 1,
+        last_error
+      );
+      deliveries.mark_dead(&delivery.delivery_id, &last_error).await?;
+    } else {
+      deliveries
+        .reschedule_delivery(
+          &delivery.delivery_id,
+          &next_delivery_attempt_at(delivery.attempts + 1),
+          &last_error,
+        )
+        .await?;
+    }
+  }
+
+  Ok(())
+}
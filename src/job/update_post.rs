@@ -1,14 +1,17 @@
 use uuid::Uuid;
 
+use super::federate_activitypub_ext::{queue_federate_ext_to_followers, queue_federate_ext_to_mentions};
 use crate::{
   db::{
-    follow_repository::FollowPool, job_repository::JobPool, orbit_repository::OrbitPool, post_repository::PostPool,
-    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+    comment_repository::CommentPool, delivery_repository::DeliveryPool, job_repository::JobPool,
+    orbit_repository::OrbitPool, post_mention_repository::PostMentionPool, post_repository::PostPool,
+    report_repository::ReportPool, user_orbit_repository::UserOrbitPool, user_repository::UserPool,
   },
   federation::activitypub::{federate_ext, FederateExtAction, FederateExtActor, FederateExtActorRef},
   helpers::api::map_db_err,
   logic::LogicErr,
   model::{
+    access_type::AccessType,
     job::{JobStatus, NewJob},
     queue_job::{QueueJob, QueueJobType},
   },
@@ -22,8 +25,11 @@ pub async fn update_post(
   user_orbits: &UserOrbitPool,
   users: &UserPool,
   posts: &PostPool,
-  follows: &FollowPool,
+  post_mentions: &PostMentionPool,
   queue: &Queue,
+  comments: &CommentPool,
+  reports: &ReportPool,
+  deliveries: &DeliveryPool,
 ) -> Result<(), LogicErr> {
   let job = match jobs.fetch_optional_by_id(&job_id).await {
     Some(job) => job,
@@ -40,6 +46,13 @@ pub async fn update_post(
     None => return Err(LogicErr::InternalError("Post ID not found for job".to_string())),
   };
 
+  // Shadow posts were never federated, so there's nothing to update remotely
+  if posts.fetch_visibility_by_id(&post_id).await == Some(AccessType::Shadow) {
+    return Ok(());
+  }
+
+  let mut reached_user_ids: Vec<Uuid> = vec![];
+
   if let Some(orbit_id) = job.associated_record_id {
     match orbits.fetch_orbit(&orbit_id).await? {
       Some(orbit) => {
@@ -52,9 +65,22 @@ pub async fn update_post(
             &FederateExtActor::Group(orbit),
             posts,
             orbits,
+            comments,
+            reports,
+            deliveries,
           )
           .await?;
-          return Ok(());
+
+          return queue_federate_ext_to_mentions(
+            FederateExtAction::UpdatePost(post_id),
+            &user_id,
+            &post_id,
+            &reached_user_ids,
+            post_mentions,
+            jobs,
+            queue,
+          )
+          .await;
         }
       }
       _ => {
@@ -67,6 +93,7 @@ pub async fn update_post(
     };
 
     let users = user_orbits.fetch_orbit_external_user_ids(&orbit_id).await?;
+    reached_user_ids.extend(users.iter().copied());
 
     for user in users {
       let job_id = jobs
@@ -83,37 +110,40 @@ pub async fn update_post(
         .job_id(job_id)
         .job_type(QueueJobType::FederateActivityPubExt)
         .context(vec![user_id.to_string()])
-        .activitypub_federate_ext_action(FederateExtAction::UpdatePost(post_id))
+        .activitypub_federate_ext_action(FederateExtAction::GroupUpdatePost(orbit_id, post_id))
         .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(user))
         .build();
 
       queue.send_job(job).await?;
     }
   } else {
-    let followers = follows.fetch_user_followers(&user_id).await.unwrap_or_default();
+    reached_user_ids.extend(
+      users
+        .fetch_external_followers(&user_id)
+        .await?
+        .into_iter()
+        .map(|follower| follower.user_id),
+    );
 
-    for follower in followers {
-      let job_id = jobs
-        .create(NewJob {
-          created_by_id: Some(user_id),
-          status: JobStatus::NotStarted,
-          record_id: Some(post_id),
-          associated_record_id: Some(follower.user_id),
-        })
-        .await
-        .map_err(map_db_err)?;
-
-      let job = QueueJob::builder()
-        .job_id(job_id)
-        .job_type(QueueJobType::FederateActivityPubExt)
-        .context(vec![user_id.to_string()])
-        .activitypub_federate_ext_action(FederateExtAction::UpdatePost(post_id))
-        .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(follower.user_id))
-        .build();
-
-      queue.send_job(job).await?;
-    }
+    queue_federate_ext_to_followers(
+      FederateExtAction::UpdatePost(post_id),
+      &user_id,
+      &post_id,
+      users,
+      jobs,
+      queue,
+    )
+    .await?;
   }
 
-  Ok(())
+  queue_federate_ext_to_mentions(
+    FederateExtAction::UpdatePost(post_id),
+    &user_id,
+    &post_id,
+    &reached_user_ids,
+    post_mentions,
+    jobs,
+    queue,
+  )
+  .await
 }
@@ -0,0 +1,259 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+  db::{account_restriction_repository::AccountRestrictionPool, user_repository::UserPool},
+  model::account_restriction::{AccountRestriction, AccountRestrictionType},
+};
+
+use super::{user::assert_admin, LogicErr};
+
+pub async fn get_account_restrictions(
+  users: &UserPool,
+  account_restrictions: &AccountRestrictionPool,
+  user_id: &Uuid,
+) -> Result<Vec<AccountRestriction>, LogicErr> {
+  assert_admin(users, user_id).await?;
+
+  account_restrictions.fetch_restrictions(&None).await
+}
+
+pub async fn restrict_account(
+  users: &UserPool,
+  account_restrictions: &AccountRestrictionPool,
+  handle: &str,
+  restriction: &AccountRestrictionType,
+  reason: &str,
+  expires_at: &Option<DateTime<Utc>>,
+  user_id: &Uuid,
+) -> Result<Vec<AccountRestriction>, LogicErr> {
+  assert_admin(users, user_id).await?;
+
+  let reason = reason.trim();
+
+  if reason.is_empty() {
+    return Err(LogicErr::InvalidOperation("A reason is required".to_string()));
+  }
+
+  if let Some(expires_at) = expires_at {
+    if *expires_at <= Utc::now() {
+      return Err(LogicErr::InvalidOperation("Expiry must be in the future".to_string()));
+    }
+  }
+
+  let target = match users.fetch_by_handle(handle).await? {
+    Some(user) => user,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if &target.user_id == user_id {
+    return Err(LogicErr::InvalidOperation("Cannot restrict yourself".to_string()));
+  }
+
+  if users.user_is_admin(&target.user_id).await {
+    return Err(LogicErr::InvalidOperation("Cannot restrict an admin".to_string()));
+  }
+
+  account_restrictions
+    .upsert_restriction(&target.user_id, restriction, reason, expires_at, user_id)
+    .await?;
+
+  account_restrictions.fetch_restrictions(&Some(target.user_id)).await
+}
+
+pub async fn lift_account_restriction(
+  users: &UserPool,
+  account_restrictions: &AccountRestrictionPool,
+  handle: &str,
+  restriction: &AccountRestrictionType,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_admin(users, user_id).await?;
+
+  let target = match users.fetch_by_handle(handle).await? {
+    Some(user) => user,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  account_restrictions
+    .delete_restriction(&target.user_id, restriction)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use chrono::{Duration, Utc};
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      account_restriction_repository::{AccountRestrictionPool, MockAccountRestrictionRepo},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    logic::{
+      account_restriction::{get_account_restrictions, lift_account_restriction, restrict_account},
+      LogicErr,
+    },
+    model::{account_restriction::AccountRestrictionType, fixtures},
+  };
+
+  #[async_std::test]
+  async fn test_get_account_restrictions_rejects_non_admins() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| false);
+
+    let users: UserPool = Arc::new(user_repo);
+    let account_restrictions: AccountRestrictionPool = Arc::new(MockAccountRestrictionRepo::new());
+
+    assert_eq!(
+      get_account_restrictions(&users, &account_restrictions, &user_id)
+        .await
+        .err(),
+      Some(LogicErr::UnauthorizedError)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_restrict_account_requires_reason() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| true);
+
+    let users: UserPool = Arc::new(user_repo);
+    let account_restrictions: AccountRestrictionPool = Arc::new(MockAccountRestrictionRepo::new());
+
+    assert_eq!(
+      restrict_account(
+        &users,
+        &account_restrictions,
+        "target",
+        &AccountRestrictionType::Suspend,
+        " ",
+        &None,
+        &user_id,
+      )
+      .await
+      .err(),
+      Some(LogicErr::InvalidOperation("A reason is required".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_restrict_account_upserts_restriction() {
+    let user_id = Uuid::new_v4();
+    let target_id = Uuid::new_v4();
+    let expires_at = Some(Utc::now() + Duration::days(7));
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| true);
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("target"))
+      .returning(move |_| Ok(Some(fixtures::user(target_id))));
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(target_id))
+      .returning(|_| false);
+
+    let mut account_restriction_repo = MockAccountRestrictionRepo::new();
+
+    account_restriction_repo
+      .expect_upsert_restriction()
+      .times(1)
+      .with(
+        eq(target_id),
+        eq(AccountRestrictionType::Silence),
+        eq("Spam"),
+        eq(expires_at),
+        eq(user_id),
+      )
+      .returning(|_, _, _, _, _| Ok(()));
+
+    account_restriction_repo
+      .expect_fetch_restrictions()
+      .times(1)
+      .with(eq(Some(target_id)))
+      .returning(|_| Ok(vec![]));
+
+    let users: UserPool = Arc::new(user_repo);
+    let account_restrictions: AccountRestrictionPool = Arc::new(account_restriction_repo);
+
+    assert!(restrict_account(
+      &users,
+      &account_restrictions,
+      "target",
+      &AccountRestrictionType::Silence,
+      "Spam",
+      &expires_at,
+      &user_id,
+    )
+    .await
+    .is_ok());
+  }
+
+  #[async_std::test]
+  async fn test_lift_account_restriction_deletes_restriction() {
+    let user_id = Uuid::new_v4();
+    let target_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| true);
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("target"))
+      .returning(move |_| Ok(Some(fixtures::user(target_id))));
+
+    let mut account_restriction_repo = MockAccountRestrictionRepo::new();
+
+    account_restriction_repo
+      .expect_delete_restriction()
+      .times(1)
+      .with(eq(target_id), eq(AccountRestrictionType::Suspend))
+      .returning(|_, _| Ok(()));
+
+    let users: UserPool = Arc::new(user_repo);
+    let account_restrictions: AccountRestrictionPool = Arc::new(account_restriction_repo);
+
+    assert!(lift_account_restriction(
+      &users,
+      &account_restrictions,
+      "target",
+      &AccountRestrictionType::Suspend,
+      &user_id,
+    )
+    .await
+    .is_ok());
+  }
+}
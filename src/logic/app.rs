@@ -1,21 +1,90 @@
+use chrono::Utc;
 use rand::distributions::{Alphanumeric, DistString};
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 use super::LogicErr;
-use crate::{db::app_repository::AppPool, model::app::App};
+use crate::{
+  db::{app_repository::AppPool, authorization_code_repository::AuthorizationCodePool},
+  model::{app::App, authorization_code::AuthorizationCode},
+};
+
+/// Scopes granted to apps that don't ask for any, as Orbit's own apps have always had full access
+pub const FULL_SCOPES: &str = "read write follow push admin:read admin:write";
+
+/// How long an authorization code can be exchanged for a session before it expires
+const AUTHORIZATION_CODE_LIFETIME_SECS: i64 = 30;
+
+const TOP_LEVEL_SCOPES: [&str; 7] = [
+  "read",
+  "write",
+  "follow",
+  "push",
+  "profile",
+  "admin:read",
+  "admin:write",
+];
 
 #[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
 pub struct NewApp {
-  name: String,
-  description: String,
-  owner_name: String,
-  owner_uri: String,
-  redirect_uri: String,
+  pub name: String,
+  pub description: String,
+  pub owner_name: String,
+  pub owner_uri: String,
+  pub redirect_uri: String,
+  #[serde(default)]
+  pub scopes: Option<String>,
+}
+
+fn scope_is_known(scope: &str) -> bool {
+  TOP_LEVEL_SCOPES.contains(&scope)
+    || scope
+      .rsplit_once(':')
+      .map(|(parent, _)| TOP_LEVEL_SCOPES.contains(&parent))
+      .unwrap_or(false)
+}
+
+/// Checks a space separated list of OAuth scopes, as Mastodon defines them, returning it with duplicates removed
+pub fn normalize_scopes(scopes: &str) -> Result<String, LogicErr> {
+  let mut normalized: Vec<&str> = vec![];
+
+  for scope in scopes
+    .split(|c: char| c.is_whitespace() || c == '+')
+    .filter(|s| !s.is_empty())
+  {
+    if !scope_is_known(scope) {
+      return Err(LogicErr::InvalidOperation(format!("Unknown scope: {}", scope)));
+    }
+
+    if !normalized.contains(&scope) {
+      normalized.push(scope);
+    }
+  }
+
+  match normalized.is_empty() {
+    true => Err(LogicErr::InvalidOperation("No scopes were given".to_string())),
+    false => Ok(normalized.join(" ")),
+  }
+}
+
+/// Whether an app registered for `registered` may be granted every scope in `requested`. Narrower scopes such as
+/// `read:statuses` are covered by the scope they narrow down.
+pub fn scopes_allowed(requested: &str, registered: &str) -> bool {
+  let registered: Vec<&str> = registered.split_whitespace().collect();
+
+  requested.split_whitespace().all(|scope| {
+    registered.contains(&scope)
+      || scope
+        .rsplit_once(':')
+        .map(|(parent, _)| registered.contains(&parent))
+        .unwrap_or(false)
+  })
 }
 
 pub async fn create_app(apps: &AppPool, new_app: &NewApp) -> Result<App, LogicErr> {
+  let scopes = normalize_scopes(new_app.scopes.as_deref().unwrap_or(FULL_SCOPES))?;
+
   let mut client_id_hasher = Sha256::new();
   let client_id_data = Alphanumeric.sample_string(&mut rand::thread_rng(), 16);
   client_id_hasher.update(client_id_data.as_bytes());
@@ -36,25 +105,121 @@ pub async fn create_app(apps: &AppPool, new_app: &NewApp) -> Result<App, LogicEr
     redirect_uri: new_app.redirect_uri.clone(),
     client_id,
     client_secret,
+    scopes,
   };
 
   apps.create(&app).await.map(|_| Ok(app))?
 }
 
+/// Issues a single-use authorization code for `user_id`, which only `app` can redeem with the same `redirect_uri`
+pub async fn issue_authorization_code(
+  codes: &AuthorizationCodePool,
+  user_id: &Uuid,
+  app: &App,
+  redirect_uri: &str,
+  scopes: &str,
+) -> Result<String, LogicErr> {
+  let mut code_hasher = Sha256::new();
+  let code_data = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+  code_hasher.update(code_data.as_bytes());
+
+  let now = Utc::now();
+
+  let code = AuthorizationCode {
+    code: hex::encode(code_hasher.finalize()),
+    user_id: *user_id,
+    app_id: app.app_id,
+    redirect_uri: redirect_uri.to_string(),
+    scopes: scopes.to_string(),
+    created_at: now,
+    expires_at: now + chrono::Duration::seconds(AUTHORIZATION_CODE_LIFETIME_SECS),
+  };
+
+  codes.create(&code).await.map(|_| code.code)
+}
+
+/// Redeems an authorization code, which is used up whether or not it was presented by the app it was issued to
+pub async fn redeem_authorization_code(
+  codes: &AuthorizationCodePool,
+  code: &str,
+  app: &App,
+  redirect_uri: &str,
+) -> Result<AuthorizationCode, LogicErr> {
+  let code = match codes.take(code).await? {
+    Some(code) => code,
+    None => return Err(LogicErr::UnauthorizedError),
+  };
+
+  if code.app_id != app.app_id || code.redirect_uri != redirect_uri || code.expires_at <= Utc::now() {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  Ok(code)
+}
+
 #[cfg(test)]
 mod tests {
   use std::sync::Arc;
 
+  use chrono::{Duration, Utc};
   use mockall::predicate::*;
+  use uuid::Uuid;
 
   use crate::{
-    db::app_repository::{AppPool, MockAppRepo},
+    db::{
+      app_repository::{AppPool, MockAppRepo},
+      authorization_code_repository::{AuthorizationCodePool, MockAuthorizationCodeRepo},
+    },
     logic::{
-      app::{create_app, NewApp},
+      app::{create_app, normalize_scopes, redeem_authorization_code, scopes_allowed, NewApp},
       LogicErr,
     },
+    model::{app::App, authorization_code::AuthorizationCode},
   };
 
+  #[test]
+  fn test_normalize_scopes() {
+    assert_eq!(normalize_scopes("read write read"), Ok("read write".to_string()));
+    assert_eq!(
+      normalize_scopes("read+write:statuses admin:read:accounts"),
+      Ok("read write:statuses admin:read:accounts".to_string())
+    );
+    assert!(matches!(
+      normalize_scopes("read everything"),
+      Err(LogicErr::InvalidOperation(_))
+    ));
+    assert!(matches!(normalize_scopes(" "), Err(LogicErr::InvalidOperation(_))));
+  }
+
+  #[test]
+  fn test_scopes_allowed() {
+    assert!(scopes_allowed("read write:statuses", "read write follow"));
+    assert!(!scopes_allowed("read push", "read write follow"));
+    assert!(!scopes_allowed("write", "write:statuses"));
+  }
+
+  #[async_std::test]
+  async fn test_create_app_rejects_unknown_scopes() {
+    let new_app = NewApp {
+      name: "a".to_string(),
+      description: "a".to_string(),
+      owner_name: "a".to_string(),
+      owner_uri: "a".to_string(),
+      redirect_uri: "a".to_string(),
+      scopes: Some("read everything".to_string()),
+    };
+
+    let mut app_repo = MockAppRepo::new();
+    app_repo.expect_create().times(0);
+
+    let apps: AppPool = Arc::new(app_repo);
+
+    assert!(matches!(
+      create_app(&apps, &new_app).await,
+      Err(LogicErr::InvalidOperation(_))
+    ));
+  }
+
   #[async_std::test]
   async fn test_create_app_rejects_db_err_passthrough() {
     let new_app = NewApp {
@@ -63,6 +228,7 @@ mod tests {
       owner_name: "a".to_string(),
       owner_uri: "a".to_string(),
       redirect_uri: "a".to_string(),
+      scopes: None,
     };
 
     let mut app_repo = MockAppRepo::new();
@@ -89,6 +255,7 @@ mod tests {
       owner_name: "a".to_string(),
       owner_uri: "a".to_string(),
       redirect_uri: "a".to_string(),
+      scopes: None,
     };
 
     let mut app_repo = MockAppRepo::new();
@@ -99,4 +266,114 @@ mod tests {
 
     assert!(create_app(&apps, &new_app).await.is_ok());
   }
+
+  #[async_std::test]
+  async fn test_redeem_authorization_code() {
+    let app = App {
+      app_id: Uuid::new_v4(),
+      name: "a".to_string(),
+      description: "a".to_string(),
+      owner_name: "a".to_string(),
+      owner_uri: "a".to_string(),
+      redirect_uri: "https://a.example/callback urn:ietf:wg:oauth:2.0:oob".to_string(),
+      client_id: "a".to_string(),
+      client_secret: "a".to_string(),
+      scopes: "read write".to_string(),
+    };
+
+    let code = AuthorizationCode {
+      code: "code".to_string(),
+      user_id: Uuid::new_v4(),
+      app_id: app.app_id,
+      redirect_uri: "https://a.example/callback".to_string(),
+      scopes: "read".to_string(),
+      created_at: Utc::now(),
+      expires_at: Utc::now() + Duration::seconds(30),
+    };
+
+    let mut code_repo = MockAuthorizationCodeRepo::new();
+    code_repo
+      .expect_take()
+      .times(1)
+      .with(eq("code"))
+      .return_const(Ok(Some(code.clone())));
+
+    let codes: AuthorizationCodePool = Arc::new(code_repo);
+
+    assert_eq!(
+      redeem_authorization_code(&codes, "code", &app, "https://a.example/callback").await,
+      Ok(code)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_redeem_authorization_code_rejects_mismatches() {
+    let app = App {
+      app_id: Uuid::new_v4(),
+      name: "a".to_string(),
+      description: "a".to_string(),
+      owner_name: "a".to_string(),
+      owner_uri: "a".to_string(),
+      redirect_uri: "https://a.example/callback urn:ietf:wg:oauth:2.0:oob".to_string(),
+      client_id: "a".to_string(),
+      client_secret: "a".to_string(),
+      scopes: "read write".to_string(),
+    };
+
+    let issued_to_other_app = AuthorizationCode {
+      code: "other".to_string(),
+      user_id: Uuid::new_v4(),
+      app_id: Uuid::new_v4(),
+      redirect_uri: "https://a.example/callback".to_string(),
+      scopes: "read".to_string(),
+      created_at: Utc::now(),
+      expires_at: Utc::now() + Duration::seconds(30),
+    };
+
+    let issued_for_oob = AuthorizationCode {
+      code: "oob".to_string(),
+      app_id: app.app_id,
+      redirect_uri: "urn:ietf:wg:oauth:2.0:oob".to_string(),
+      ..issued_to_other_app.clone()
+    };
+
+    let expired = AuthorizationCode {
+      code: "expired".to_string(),
+      app_id: app.app_id,
+      created_at: Utc::now() - Duration::seconds(60),
+      expires_at: Utc::now() - Duration::seconds(30),
+      ..issued_to_other_app.clone()
+    };
+
+    let mut code_repo = MockAuthorizationCodeRepo::new();
+    code_repo
+      .expect_take()
+      .times(1)
+      .with(eq("other"))
+      .return_const(Ok(Some(issued_to_other_app)));
+    code_repo
+      .expect_take()
+      .times(1)
+      .with(eq("oob"))
+      .return_const(Ok(Some(issued_for_oob)));
+    code_repo
+      .expect_take()
+      .times(1)
+      .with(eq("expired"))
+      .return_const(Ok(Some(expired)));
+    code_repo
+      .expect_take()
+      .times(1)
+      .with(eq("redeemed"))
+      .return_const(Ok(None));
+
+    let codes: AuthorizationCodePool = Arc::new(code_repo);
+
+    for code in ["other", "oob", "expired", "redeemed"] {
+      assert_eq!(
+        redeem_authorization_code(&codes, code, &app, "https://a.example/callback").await,
+        Err(LogicErr::UnauthorizedError)
+      );
+    }
+  }
 }
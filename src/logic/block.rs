@@ -0,0 +1,258 @@
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    block_repository::BlockPool, follow_repository::FollowPool, job_repository::JobPool, user_repository::UserPool,
+  },
+  federation::activitypub::{FederateExtAction, FederateExtActorRef},
+  helpers::api::map_db_err,
+  model::{
+    job::{JobStatus, NewJob},
+    queue_job::{QueueJob, QueueJobType},
+  },
+  work_queue::queue::Queue,
+};
+
+use super::LogicErr;
+
+async fn queue_block_federation(
+  jobs: &JobPool,
+  queue: &Queue,
+  action: FederateExtAction,
+  blocked_user_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(*user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(*blocked_user_id),
+      associated_record_id: None,
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::FederateActivityPubExt)
+    .context(vec![user_id.to_string()])
+    .activitypub_federate_ext_action(action)
+    .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(*blocked_user_id))
+    .build();
+
+  queue.send_job(job).await
+}
+
+pub async fn create_block(
+  users: &UserPool,
+  blocks: &BlockPool,
+  follows: &FollowPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  blocked_user_handle: &str,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let blocked_user = match users.fetch_by_handle(blocked_user_handle).await? {
+    Some(user) => user,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let blocked_user_id = blocked_user.user_id;
+
+  if blocked_user_id == *user_id {
+    return Err(LogicErr::InvalidOperation("Cannot block yourself".to_string()));
+  }
+
+  blocks.create_block(user_id, &blocked_user_id).await?;
+
+  // A block severs the relationship in both directions
+  follows.delete_follow(user_id, &blocked_user_id).await?;
+  follows.delete_follow(&blocked_user_id, user_id).await?;
+
+  if blocked_user.is_external {
+    queue_block_federation(jobs, queue, FederateExtAction::BlockProfile, &blocked_user_id, user_id).await?;
+  }
+
+  Ok(())
+}
+
+pub async fn delete_block(
+  users: &UserPool,
+  blocks: &BlockPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  blocked_user_handle: &str,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let blocked_user = match users.fetch_by_handle(blocked_user_handle).await? {
+    Some(user) => user,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let blocked_user_id = blocked_user.user_id;
+
+  blocks.delete_block(user_id, &blocked_user_id).await?;
+
+  if blocked_user.is_external {
+    queue_block_federation(
+      jobs,
+      queue,
+      FederateExtAction::UnblockProfile,
+      &blocked_user_id,
+      user_id,
+    )
+    .await?;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      block_repository::{BlockPool, MockBlockRepo},
+      follow_repository::{FollowPool, MockFollowRepo},
+      job_repository::{JobPool, MockJobRepo},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    logic::{
+      block::{create_block, delete_block},
+      LogicErr,
+    },
+    model::fixtures,
+    work_queue::queue::{MockQueueBackend, Queue},
+  };
+
+  #[async_std::test]
+  async fn test_create_block_rejects_for_missing_user() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("user_handle"))
+      .return_const(Ok(None));
+
+    let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(MockBlockRepo::new());
+    let follows: FollowPool = Arc::new(MockFollowRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      create_block(&users, &blocks, &follows, &jobs, &queue, "user_handle", &user_id).await,
+      Err(LogicErr::MissingRecord)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_block_rejects_blocking_self() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("user_handle"))
+      .return_const(Ok(Some(fixtures::user(user_id))));
+
+    let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(MockBlockRepo::new());
+    let follows: FollowPool = Arc::new(MockFollowRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      create_block(&users, &blocks, &follows, &jobs, &queue, "user_handle", &user_id).await,
+      Err(LogicErr::InvalidOperation("Cannot block yourself".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_block_removes_follows_in_both_directions() {
+    let user_id = Uuid::new_v4();
+    let blocked_user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("user_handle"))
+      .return_const(Ok(Some(fixtures::user(blocked_user_id))));
+
+    let mut block_repo = MockBlockRepo::new();
+
+    block_repo
+      .expect_create_block()
+      .times(1)
+      .with(eq(user_id), eq(blocked_user_id))
+      .returning(|_, _| Ok(Uuid::new_v4()));
+
+    let mut follow_repo = MockFollowRepo::new();
+
+    follow_repo
+      .expect_delete_follow()
+      .times(1)
+      .with(eq(user_id), eq(blocked_user_id))
+      .returning(|_, _| Ok(()));
+
+    follow_repo
+      .expect_delete_follow()
+      .times(1)
+      .with(eq(blocked_user_id), eq(user_id))
+      .returning(|_, _| Ok(()));
+
+    let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(block_repo);
+    let follows: FollowPool = Arc::new(follow_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      create_block(&users, &blocks, &follows, &jobs, &queue, "user_handle", &user_id).await,
+      Ok(())
+    );
+  }
+
+  #[async_std::test]
+  async fn test_delete_block_db_err_passthrough() {
+    let user_id = Uuid::new_v4();
+    let blocked_user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("user_handle"))
+      .return_const(Ok(Some(fixtures::user(blocked_user_id))));
+
+    let mut block_repo = MockBlockRepo::new();
+
+    block_repo
+      .expect_delete_block()
+      .times(1)
+      .with(eq(user_id), eq(blocked_user_id))
+      .returning(|_, _| Err(LogicErr::DbError("Boop".to_string())));
+
+    let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(block_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      delete_block(&users, &blocks, &jobs, &queue, "user_handle", &user_id).await,
+      Err(LogicErr::DbError("Boop".to_string()))
+    );
+  }
+}
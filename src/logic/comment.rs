@@ -2,21 +2,41 @@ use uuid::Uuid;
 
 use crate::{
   activitypub::{
-    activity_convertible::ActivityConvertible, document::ActivityPubDocument,
-    helpers::create_activitypub_ordered_collection_page,
+    activity_convertible::ActivityConvertible,
+    document::ActivityPubDocument,
+    helpers::{create_activitypub_ordered_collection_page, cursor_page_links, numbered_page_links},
+  },
+  db::{
+    comment_repository::CommentPool, follow_repository::FollowPool, job_repository::JobPool,
+    notification_repository::NotificationPool, orbit_membership_repository::OrbitMembershipPool,
+    orbit_moderation_repository::OrbitModerationPool, post_repository::PostPool, user_repository::UserPool,
+  },
+  federation::activitypub::{FederateExtAction, FederateExtActorRef},
+  helpers::{api::map_db_err, pagination::numbered_page},
+  model::{
+    access_type::AccessType,
+    comment_pub::CommentPub,
+    job::{JobStatus, NewJob},
+    notification::NewNotification,
+    notification_type::NotificationType,
+    page_cursor::PageCursor,
+    queue_job::{QueueJob, QueueJobType},
   },
-  db::{comment_repository::CommentPool, follow_repository::FollowPool, post_repository::PostPool},
-  helpers::math::div_up,
-  model::{access_type::AccessType, comment_pub::CommentPub, response::ListResponse},
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
 };
 
-use super::LogicErr;
+use super::{notification::notify, LogicErr};
 
 pub async fn create_comment(
   posts: &PostPool,
   follows: &FollowPool,
   comments: &CommentPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_membership: &OrbitMembershipPool,
+  jobs: &JobPool,
+  queue: &Queue,
   post_id: &Uuid,
   user_id: &Uuid,
   content_md: &str,
@@ -42,25 +62,69 @@ pub async fn create_comment(
     return Err(LogicErr::MissingRecord);
   }
 
+  if visibility == AccessType::MembersOnly && !orbit_membership.user_is_member_for_post(post_id, user_id).await? {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  if orbit_moderation.user_is_banned_for_post(post_id, user_id).await? {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  if orbit_moderation.post_is_locked(post_id).await? {
+    return Err(LogicErr::InvalidOperation("Post is locked".to_string()));
+  }
+
   let content_html = markdown::to_html(content_md);
 
   let comment_id = comments
     .create_comment(user_id, post_id, content_md, &content_html)
     .await?;
 
-  match comments
+  let comment = match comments
     .fetch_comment(post_id, &comment_id, &Some(user_id.to_owned()))
     .await
   {
-    Some(comment) => Ok(comment),
-    None => Err(LogicErr::MissingRecord),
-  }
+    Some(comment) => comment,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  queue_comment_job(jobs, queue, QueueJobType::CreateComment, post_id, &comment_id, user_id).await?;
+
+  Ok(comment)
+}
+
+async fn queue_comment_job(
+  jobs: &JobPool,
+  queue: &Queue,
+  job_type: QueueJobType,
+  post_id: &Uuid,
+  comment_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(*user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(*comment_id),
+      associated_record_id: Some(*post_id),
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder().job_id(job_id).job_type(job_type).build();
+
+  queue.send_job(job).await
 }
 
 pub async fn create_comment_like(
   posts: &PostPool,
   follows: &FollowPool,
   comments: &CommentPool,
+  users: &UserPool,
+  jobs: &JobPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  queue: &Queue,
   post_id: &Uuid,
   comment_id: &Uuid,
   user_id: &Uuid,
@@ -86,57 +150,123 @@ pub async fn create_comment_like(
     return Err(LogicErr::MissingRecord);
   }
 
-  comments.create_comment_like(user_id, comment_id, post_id).await
+  comments.create_comment_like(user_id, comment_id, post_id).await?;
+
+  let comment = match comments.fetch_comment(post_id, comment_id, &Some(*user_id)).await {
+    Some(comment) => comment,
+    None => return Ok(()),
+  };
+
+  if users.user_is_external(&comment.user_id).await {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(*user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(*comment_id),
+        associated_record_id: Some(comment.user_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::LikeComment(*post_id, *comment_id))
+      .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(comment.user_id))
+      .build();
+
+    queue.send_job(job).await?;
+  } else {
+    notify(
+      notifications,
+      pubsub,
+      NewNotification {
+        user_id: comment.user_id,
+        source_user_id: *user_id,
+        notification_type: NotificationType::Like,
+        post_id: Some(*post_id),
+        comment_id: Some(*comment_id),
+        orbit_id: None,
+      },
+    )
+    .await;
+  }
+
+  Ok(())
 }
 
 pub async fn delete_comment(
   comments: &CommentPool,
+  jobs: &JobPool,
+  queue: &Queue,
   post_id: &Uuid,
   comment_id: &Uuid,
   user_id: &Uuid,
 ) -> Result<(), LogicErr> {
-  comments.delete_comment(user_id, post_id, comment_id).await
+  comments.delete_comment(user_id, post_id, comment_id).await?;
+
+  queue_comment_job(jobs, queue, QueueJobType::DeleteComment, post_id, comment_id, user_id).await
 }
 
 pub async fn delete_comment_like(
   comments: &CommentPool,
+  users: &UserPool,
+  jobs: &JobPool,
+  queue: &Queue,
   post_id: &Uuid,
   comment_id: &Uuid,
   user_id: &Uuid,
 ) -> Result<(), LogicErr> {
-  comments.delete_comment_like(user_id, comment_id, post_id).await
+  comments.delete_comment_like(user_id, comment_id, post_id).await?;
+
+  let comment = match comments.fetch_comment(post_id, comment_id, &Some(*user_id)).await {
+    Some(comment) => comment,
+    None => return Ok(()),
+  };
+
+  if users.user_is_external(&comment.user_id).await {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(*user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(*comment_id),
+        associated_record_id: Some(comment.user_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::UnlikeComment(*post_id, *comment_id))
+      .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(comment.user_id))
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(())
 }
 
 pub async fn get_comments(
   comments: &CommentPool,
   post_id: &Uuid,
   own_user_id: &Option<Uuid>,
-  page: &Option<i64>,
-  page_size: &Option<i64>,
-) -> Result<ListResponse<CommentPub>, LogicErr> {
-  let page = page.unwrap_or(0);
-  let page_size = page_size.unwrap_or(20);
-  let comments_count = match comments.fetch_comments_count(post_id, own_user_id).await {
-    Ok(count) => count,
-    Err(err) => return Err(err),
-  };
-
-  if comments_count == 0 {
-    return Err(LogicErr::MissingRecord);
-  }
+  cursor: &PageCursor,
+  limit: i64,
+  skip: i64,
+) -> Result<Vec<CommentPub>, LogicErr> {
+  comments.fetch_comments(post_id, own_user_id, cursor, limit, skip).await
+}
 
-  match comments
-    .fetch_comments(post_id, own_user_id, page_size, page * page_size)
-    .await
-  {
-    Ok(comments) => Ok(ListResponse {
-      data: comments,
-      page,
-      total_items: comments_count,
-      total_pages: div_up(comments_count, page_size) + 1,
-    }),
-    Err(err) => Err(err),
-  }
+pub async fn get_comments_count(
+  comments: &CommentPool,
+  post_id: &Uuid,
+  own_user_id: &Option<Uuid>,
+) -> Result<i64, LogicErr> {
+  comments.fetch_comments_count(post_id, own_user_id).await
 }
 
 pub async fn activitypub_get_comments(
@@ -144,6 +274,7 @@ pub async fn activitypub_get_comments(
   comments: &CommentPool,
   post_id: &Uuid,
   own_user_id: &Option<Uuid>,
+  cursor: &PageCursor,
   page: &Option<i64>,
   page_size: &Option<i64>,
 ) -> Result<ActivityPubDocument, LogicErr> {
@@ -152,27 +283,29 @@ pub async fn activitypub_get_comments(
     None => return Err(LogicErr::MissingRecord),
   };
 
-  let page = page.unwrap_or(0);
+  let page = numbered_page(page, cursor);
   let page_size = page_size.unwrap_or(20);
-  let comments_count = match comments.fetch_comments_count(post_id, own_user_id).await {
-    Ok(count) => count,
-    Err(err) => return Err(err),
+  let base_uri = format!("{}/feed/{}/comments", SETTINGS.server.api_fqdn, post_id);
+
+  let comments_page = comments
+    .fetch_comments(post_id, own_user_id, cursor, page_size, page.unwrap_or(0) * page_size)
+    .await?;
+
+  let (prev, next) = match page {
+    Some(page) => {
+      let comments_count = comments.fetch_comments_count(post_id, own_user_id).await?;
+      numbered_page_links(&base_uri, page, page_size, comments_count)
+    }
+    None => cursor_page_links(&base_uri, cursor, page_size, &comments_page, |c| c.comment_id, true),
   };
 
-  match comments
-    .fetch_comments(post_id, own_user_id, page_size, page * page_size)
-    .await
-  {
-    Ok(comments) => Ok(create_activitypub_ordered_collection_page(
-      &format!("{}/feed/{}/comments", SETTINGS.server.api_fqdn, post_id),
-      page.try_into().unwrap_or_default(),
-      page_size.try_into().unwrap_or_default(),
-      comments_count.try_into().unwrap_or_default(),
-      comments,
-      Some(format!("{}/user/{}", SETTINGS.server.api_fqdn, author_id)),
-    )),
-    Err(err) => Err(err),
-  }
+  Ok(create_activitypub_ordered_collection_page(
+    &base_uri,
+    prev,
+    next,
+    comments_page,
+    Some(format!("{}/user/{}", SETTINGS.server.api_fqdn, author_id)),
+  ))
 }
 
 pub async fn get_comment(
@@ -225,13 +358,20 @@ mod tests {
     db::{
       comment_repository::{CommentPool, MockCommentRepo},
       follow_repository::{FollowPool, MockFollowRepo},
+      job_repository::{JobPool, MockJobRepo},
+      notification_repository::{MockNotificationRepo, NotificationPool},
+      orbit_membership_repository::{MockOrbitMembershipRepo, OrbitMembershipPool},
+      orbit_moderation_repository::{MockOrbitModerationRepo, OrbitModerationPool},
       post_repository::{MockPostRepo, PostPool},
+      user_repository::{MockUserRepo, UserPool},
     },
     logic::{
       comment::{create_comment, create_comment_like, delete_comment, delete_comment_like},
       LogicErr,
     },
     model::{access_type::AccessType, comment_pub::CommentPub},
+    streaming::pubsub::{MockPubSubBackend, PubSub},
+    work_queue::queue::{MockQueueBackend, Queue},
   };
 
   #[async_std::test]
@@ -250,9 +390,25 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment(&posts, &follows, &comments, &post_id, &user_id, "test").await,
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -279,9 +435,25 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment(&posts, &follows, &comments, &post_id, &user_id, "test").await,
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -308,9 +480,25 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment(&posts, &follows, &comments, &post_id, &user_id, "test").await,
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
       Err(LogicErr::UnauthorizedError)
     );
   }
@@ -337,9 +525,25 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment(&posts, &follows, &comments, &post_id, &user_id, "test").await,
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
       Err(LogicErr::UnauthorizedError)
     );
   }
@@ -374,13 +578,88 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(follow_repo);
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment(&posts, &follows, &comments, &post_id, &user_id, "test").await,
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
 
+  #[async_std::test]
+  async fn test_create_comment_rejects_for_locked_post() {
+    let post_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+
+    let mut post_repo = MockPostRepo::new();
+
+    post_repo
+      .expect_fetch_visibility_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(|_| Some(AccessType::PublicFederated));
+
+    post_repo
+      .expect_fetch_owner_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(|_| Some(Uuid::new_v4()));
+
+    let mut orbit_moderation_repo = MockOrbitModerationRepo::new();
+
+    orbit_moderation_repo
+      .expect_user_is_banned_for_post()
+      .times(1)
+      .with(eq(post_id), eq(user_id))
+      .return_const(Ok(false));
+
+    orbit_moderation_repo
+      .expect_post_is_locked()
+      .times(1)
+      .with(eq(post_id))
+      .return_const(Ok(true));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let follows: FollowPool = Arc::new(MockFollowRepo::new());
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(orbit_moderation_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
+      Err(LogicErr::InvalidOperation("Post is locked".to_string()))
+    );
+  }
+
   #[async_std::test]
   async fn test_create_comment_rejects_for_db_err() {
     let post_id = Uuid::new_v4();
@@ -400,6 +679,20 @@ mod tests {
       .with(eq(post_id))
       .returning(|_| Some(Uuid::new_v4()));
 
+    let mut orbit_moderation_repo = MockOrbitModerationRepo::new();
+
+    orbit_moderation_repo
+      .expect_user_is_banned_for_post()
+      .times(1)
+      .with(eq(post_id), eq(user_id))
+      .return_const(Ok(false));
+
+    orbit_moderation_repo
+      .expect_post_is_locked()
+      .times(1)
+      .with(eq(post_id))
+      .return_const(Ok(false));
+
     let mut comment_repo = MockCommentRepo::new();
 
     comment_repo
@@ -411,9 +704,25 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(comment_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(orbit_moderation_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment(&posts, &follows, &comments, &post_id, &user_id, "test").await,
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -438,6 +747,10 @@ mod tests {
       likes: 0,
       liked: Some(true),
       visibility: AccessType::PublicFederated,
+      uri: format!("/feed/{}/comments/{}", post_id, exp_comment_id),
+      is_external: false,
+      post_uri: format!("/feed/{}", post_id),
+      user_fediverse_uri: format!("/user/{}", user_id),
     });
 
     let mut post_repo = MockPostRepo::new();
@@ -454,6 +767,20 @@ mod tests {
       .with(eq(post_id))
       .returning(|_| Some(Uuid::new_v4()));
 
+    let mut orbit_moderation_repo = MockOrbitModerationRepo::new();
+
+    orbit_moderation_repo
+      .expect_user_is_banned_for_post()
+      .times(1)
+      .with(eq(post_id), eq(user_id))
+      .return_const(Ok(false));
+
+    orbit_moderation_repo
+      .expect_post_is_locked()
+      .times(1)
+      .with(eq(post_id))
+      .return_const(Ok(false));
+
     let mut comment_repo = MockCommentRepo::new();
 
     comment_repo
@@ -471,10 +798,88 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(comment_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(orbit_moderation_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let mut job_repo = MockJobRepo::new();
+    job_repo
+      .expect_create()
+      .with(always())
+      .times(1)
+      .return_const(Ok(Uuid::new_v4()));
+
+    let mut queue_be = MockQueueBackend::new();
+    queue_be.expect_send_job().with(always()).times(1).return_const(Ok(()));
+
+    let jobs: JobPool = Arc::new(job_repo);
+    let queue = Queue::new_inner(Box::new(queue_be));
+
+    assert!(create_comment(
+      &posts,
+      &follows,
+      &comments,
+      &orbit_moderation,
+      &orbit_membership,
+      &jobs,
+      &queue,
+      &post_id,
+      &user_id,
+      "test"
+    )
+    .await
+    .is_ok());
+  }
 
-    assert!(create_comment(&posts, &follows, &comments, &post_id, &user_id, "test")
-      .await
-      .is_ok());
+  #[async_std::test]
+  async fn test_create_comment_rejects_members_only_post_for_non_member() {
+    let post_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    let owner_id = Uuid::new_v4();
+
+    let mut post_repo = MockPostRepo::new();
+    let mut orbit_membership_repo = MockOrbitMembershipRepo::new();
+
+    post_repo
+      .expect_fetch_visibility_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(|_| Some(AccessType::MembersOnly));
+
+    post_repo
+      .expect_fetch_owner_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(move |_| Some(owner_id));
+
+    orbit_membership_repo
+      .expect_user_is_member_for_post()
+      .times(1)
+      .with(eq(post_id), eq(user_id))
+      .return_const(Ok(false));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let follows: FollowPool = Arc::new(MockFollowRepo::new());
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(orbit_membership_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      create_comment(
+        &posts,
+        &follows,
+        &comments,
+        &orbit_moderation,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &post_id,
+        &user_id,
+        "test"
+      )
+      .await,
+      Err(LogicErr::MissingRecord)
+    );
   }
 
   #[async_std::test]
@@ -494,9 +899,27 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment_like(&posts, &follows, &comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -524,9 +947,27 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment_like(&posts, &follows, &comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -554,9 +995,27 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment_like(&posts, &follows, &comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::UnauthorizedError)
     );
   }
@@ -584,9 +1043,27 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment_like(&posts, &follows, &comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::UnauthorizedError)
     );
   }
@@ -622,9 +1099,27 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(follow_repo);
     let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment_like(&posts, &follows, &comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -660,9 +1155,27 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(comment_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment_like(&posts, &follows, &comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -695,12 +1208,36 @@ mod tests {
       .with(eq(user_id), eq(comment_id), eq(post_id))
       .returning(|_, _, _| Ok(()));
 
+    comment_repo
+      .expect_fetch_comment()
+      .times(1)
+      .with(eq(post_id), eq(comment_id), eq(Some(user_id)))
+      .return_const(None);
+
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(comment_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_comment_like(&posts, &follows, &comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Ok(())
     );
   }
@@ -720,9 +1257,11 @@ mod tests {
       .returning(|_, _, _| Err(LogicErr::DbError("Boop".to_string())));
 
     let comments: CommentPool = Arc::new(comment_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      delete_comment(&comments, &post_id, &comment_id, &user_id).await,
+      delete_comment(&comments, &jobs, &queue, &post_id, &comment_id, &user_id).await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -742,8 +1281,23 @@ mod tests {
       .returning(|_, _, _| Ok(()));
 
     let comments: CommentPool = Arc::new(comment_repo);
+    let mut job_repo = MockJobRepo::new();
+    job_repo
+      .expect_create()
+      .with(always())
+      .times(1)
+      .return_const(Ok(Uuid::new_v4()));
 
-    assert_eq!(delete_comment(&comments, &post_id, &comment_id, &user_id).await, Ok(()));
+    let mut queue_be = MockQueueBackend::new();
+    queue_be.expect_send_job().with(always()).times(1).return_const(Ok(()));
+
+    let jobs: JobPool = Arc::new(job_repo);
+    let queue = Queue::new_inner(Box::new(queue_be));
+
+    assert_eq!(
+      delete_comment(&comments, &jobs, &queue, &post_id, &comment_id, &user_id).await,
+      Ok(())
+    );
   }
 
   #[async_std::test]
@@ -761,9 +1315,12 @@ mod tests {
       .returning(|_, _, _| Err(LogicErr::DbError("Boop".to_string())));
 
     let comments: CommentPool = Arc::new(comment_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      delete_comment_like(&comments, &post_id, &comment_id, &user_id).await,
+      delete_comment_like(&comments, &users, &jobs, &queue, &post_id, &comment_id, &user_id).await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -782,10 +1339,121 @@ mod tests {
       .with(eq(user_id), eq(comment_id), eq(post_id))
       .returning(|_, _, _| Ok(()));
 
+    comment_repo
+      .expect_fetch_comment()
+      .times(1)
+      .with(eq(post_id), eq(comment_id), eq(Some(user_id)))
+      .return_const(None);
+
+    let comments: CommentPool = Arc::new(comment_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      delete_comment_like(&comments, &users, &jobs, &queue, &post_id, &comment_id, &user_id).await,
+      Ok(())
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_comment_like_federates_for_external_author() {
+    let post_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    let author_id = Uuid::new_v4();
+    let comment_id = Uuid::new_v4();
+    let job_id = Uuid::new_v4();
+    let comment = Some(CommentPub {
+      comment_id,
+      user_id: author_id,
+      post_id,
+      content_md: "test".to_string(),
+      content_html: "<p>test</p>".to_string(),
+      created_at: Utc::now(),
+      updated_at: Utc::now(),
+      user_handle: "a".to_string(),
+      user_fediverse_id: "a".to_string(),
+      user_avatar_url: Some("a".to_string()),
+      likes: 1,
+      liked: Some(true),
+      visibility: AccessType::PublicFederated,
+      uri: "https://example.com/comments/1".to_string(),
+      is_external: true,
+      post_uri: format!("/feed/{}", post_id),
+      user_fediverse_uri: "https://example.com/users/a".to_string(),
+    });
+
+    let mut post_repo = MockPostRepo::new();
+
+    post_repo
+      .expect_fetch_visibility_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(|_| Some(AccessType::PublicFederated));
+
+    post_repo
+      .expect_fetch_owner_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(|_| Some(Uuid::new_v4()));
+
+    let mut comment_repo = MockCommentRepo::new();
+
+    comment_repo
+      .expect_create_comment_like()
+      .times(1)
+      .with(eq(user_id), eq(comment_id), eq(post_id))
+      .returning(|_, _, _| Ok(()));
+
+    comment_repo
+      .expect_fetch_comment()
+      .times(1)
+      .with(eq(post_id), eq(comment_id), eq(Some(user_id)))
+      .return_const(comment);
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_external()
+      .times(1)
+      .with(eq(author_id))
+      .returning(|_| true);
+
+    let mut job_repo = MockJobRepo::new();
+
+    job_repo
+      .expect_create()
+      .with(always())
+      .times(1)
+      .return_const(Ok(job_id));
+
+    let mut queue_be = MockQueueBackend::new();
+    queue_be.expect_send_job().with(always()).times(1).return_const(Ok(()));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let comments: CommentPool = Arc::new(comment_repo);
+    let users: UserPool = Arc::new(user_repo);
+    let jobs: JobPool = Arc::new(job_repo);
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(queue_be));
 
     assert_eq!(
-      delete_comment_like(&comments, &post_id, &comment_id, &user_id).await,
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &comment_id,
+        &user_id
+      )
+      .await,
       Ok(())
     );
   }
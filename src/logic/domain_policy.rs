@@ -0,0 +1,228 @@
+use uuid::Uuid;
+
+use crate::{
+  db::{domain_policy_repository::DomainPolicyPool, user_repository::UserPool},
+  federation::domain_policy::reload_domain_policies,
+  model::domain_policy::{DomainPolicy, DomainPolicyType},
+};
+
+use super::{user::assert_admin, LogicErr};
+
+fn normalize_domain(domain: &str) -> Option<String> {
+  let domain = domain.trim().trim_end_matches('.').to_lowercase();
+
+  if domain.is_empty()
+    || domain.len() > 253
+    || domain.starts_with('.')
+    || domain.starts_with('-')
+    || !domain
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+  {
+    return None;
+  }
+
+  Some(domain)
+}
+
+pub async fn get_domain_policies(
+  users: &UserPool,
+  domain_policies: &DomainPolicyPool,
+  user_id: &Uuid,
+) -> Result<Vec<DomainPolicy>, LogicErr> {
+  assert_admin(users, user_id).await?;
+
+  domain_policies.fetch_domain_policies().await
+}
+
+pub async fn upsert_domain_policy(
+  users: &UserPool,
+  domain_policies: &DomainPolicyPool,
+  user_id: &Uuid,
+  domain: &str,
+  policy: &DomainPolicyType,
+  reason: &Option<String>,
+) -> Result<DomainPolicy, LogicErr> {
+  assert_admin(users, user_id).await?;
+
+  let domain = match normalize_domain(domain) {
+    Some(domain) => domain,
+    None => return Err(LogicErr::InvalidOperation("Invalid domain".to_string())),
+  };
+
+  let domain_policy = domain_policies
+    .upsert_domain_policy(&domain, policy, reason, user_id)
+    .await?;
+
+  reload_domain_policies(domain_policies).await?;
+
+  Ok(domain_policy)
+}
+
+pub async fn delete_domain_policy(
+  users: &UserPool,
+  domain_policies: &DomainPolicyPool,
+  user_id: &Uuid,
+  domain: &str,
+) -> Result<(), LogicErr> {
+  assert_admin(users, user_id).await?;
+
+  let domain = match normalize_domain(domain) {
+    Some(domain) => domain,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  domain_policies.delete_domain_policy(&domain).await?;
+
+  reload_domain_policies(domain_policies).await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use chrono::Utc;
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      domain_policy_repository::{DomainPolicyPool, MockDomainPolicyRepo},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    logic::{
+      domain_policy::{delete_domain_policy, get_domain_policies, upsert_domain_policy},
+      LogicErr,
+    },
+    model::domain_policy::{DomainPolicy, DomainPolicyType},
+  };
+
+  #[async_std::test]
+  async fn test_get_domain_policies_rejects_non_admins() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| false);
+
+    let users: UserPool = Arc::new(user_repo);
+    let domain_policies: DomainPolicyPool = Arc::new(MockDomainPolicyRepo::new());
+
+    assert_eq!(
+      get_domain_policies(&users, &domain_policies, &user_id).await.err(),
+      Some(LogicErr::UnauthorizedError)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_upsert_domain_policy_rejects_invalid_domains() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| true);
+
+    let users: UserPool = Arc::new(user_repo);
+    let domain_policies: DomainPolicyPool = Arc::new(MockDomainPolicyRepo::new());
+
+    assert_eq!(
+      upsert_domain_policy(
+        &users,
+        &domain_policies,
+        &user_id,
+        "https://example.social/",
+        &DomainPolicyType::Suspend,
+        &None,
+      )
+      .await
+      .err(),
+      Some(LogicErr::InvalidOperation("Invalid domain".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_upsert_domain_policy_normalizes_domain() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| true);
+
+    let mut domain_policy_repo = MockDomainPolicyRepo::new();
+
+    domain_policy_repo
+      .expect_upsert_domain_policy()
+      .times(1)
+      .with(
+        eq("example.social"),
+        eq(DomainPolicyType::Silence),
+        eq(Some("Spam".to_string())),
+        eq(user_id),
+      )
+      .returning(|domain, policy, reason, user_id| {
+        Ok(DomainPolicy {
+          domain_policy_id: Uuid::new_v4(),
+          created_at: Utc::now(),
+          updated_at: Utc::now(),
+          created_by_id: Some(*user_id),
+          domain: domain.to_string(),
+          policy: *policy,
+          reason: reason.clone(),
+        })
+      });
+
+    domain_policy_repo
+      .expect_fetch_domain_policies()
+      .times(1)
+      .returning(|| Ok(vec![]));
+
+    let users: UserPool = Arc::new(user_repo);
+    let domain_policies: DomainPolicyPool = Arc::new(domain_policy_repo);
+
+    let result = upsert_domain_policy(
+      &users,
+      &domain_policies,
+      &user_id,
+      " Example.Social. ",
+      &DomainPolicyType::Silence,
+      &Some("Spam".to_string()),
+    )
+    .await;
+
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().domain, "example.social");
+  }
+
+  #[async_std::test]
+  async fn test_delete_domain_policy_rejects_non_admins() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| false);
+
+    let users: UserPool = Arc::new(user_repo);
+    let domain_policies: DomainPolicyPool = Arc::new(MockDomainPolicyRepo::new());
+
+    assert_eq!(
+      delete_domain_policy(&users, &domain_policies, &user_id, "example.social").await,
+      Err(LogicErr::UnauthorizedError)
+    );
+  }
+}
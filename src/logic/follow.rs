@@ -1,26 +1,44 @@
 use uuid::Uuid;
 
 use crate::{
-  db::{follow_repository::FollowPool, job_repository::JobPool, user_repository::UserPool},
+  db::{
+    block_repository::BlockPool, follow_repository::FollowPool, job_repository::JobPool,
+    notification_repository::NotificationPool, user_repository::UserPool,
+  },
   federation::activitypub::{FederateExtAction, FederateExtActorRef},
   helpers::api::map_db_err,
   model::{
+    follow::FollowRequest,
     job::{JobStatus, NewJob},
+    notification::NewNotification,
+    notification_type::NotificationType,
     queue_job::{QueueJob, QueueJobType},
   },
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
-use super::LogicErr;
+use super::{notification::notify, LogicErr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowResult {
+  Followed,
+  Requested,
+}
 
+/// Follows another user. Locked accounts only gain the follower once they accept the request, which for remote users
+/// arrives as an Accept of the Follow we send them.
 pub async fn create_follow(
   users: &UserPool,
+  blocks: &BlockPool,
   follows: &FollowPool,
   jobs: &JobPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
   queue: &Queue,
   following_user_handle: &str,
   user_id: &Uuid,
-) -> Result<(), LogicErr> {
+) -> Result<FollowResult, LogicErr> {
   let following_user = match users.fetch_by_handle(following_user_handle).await? {
     Some(user_id) => user_id,
     None => return Err(LogicErr::MissingRecord),
@@ -28,6 +46,10 @@ pub async fn create_follow(
 
   let following_user_id = following_user.user_id;
 
+  if blocks.block_exists_between(user_id, &following_user_id).await {
+    return Err(LogicErr::InvalidOperation("Cannot follow a blocked user".to_string()));
+  }
+
   if following_user.is_external {
     let job_id = jobs
       .create(NewJob {
@@ -50,8 +72,40 @@ pub async fn create_follow(
     queue.send_job(job).await?;
   }
 
-  follows.create_follow(user_id, &following_user_id).await?;
-  Ok(())
+  let result = if following_user.manually_approves_followers {
+    follows
+      .create_follow_request(user_id, &following_user_id, &None)
+      .await?;
+
+    FollowResult::Requested
+  } else {
+    follows.create_follow(user_id, &following_user_id).await?;
+
+    FollowResult::Followed
+  };
+
+  if !following_user.is_external {
+    let notification_type = match result {
+      FollowResult::Followed => NotificationType::Follow,
+      FollowResult::Requested => NotificationType::FollowRequest,
+    };
+
+    notify(
+      notifications,
+      pubsub,
+      NewNotification {
+        user_id: following_user_id,
+        source_user_id: *user_id,
+        notification_type,
+        post_id: None,
+        comment_id: None,
+        orbit_id: None,
+      },
+    )
+    .await;
+  }
+
+  Ok(result)
 }
 
 pub async fn delete_follow(
@@ -94,6 +148,106 @@ pub async fn delete_follow(
   follows.delete_follow(user_id, &following_user_id).await
 }
 
+pub async fn get_follow_requests(
+  follows: &FollowPool,
+  limit: i64,
+  skip: i64,
+  user_id: &Uuid,
+) -> Result<Vec<FollowRequest>, LogicErr> {
+  follows.fetch_follow_requests(user_id, limit, skip).await
+}
+
+pub async fn get_follow_requests_count(follows: &FollowPool, user_id: &Uuid) -> Result<i64, LogicErr> {
+  follows.count_follow_requests(user_id).await
+}
+
+/// Remote users are sent the answer as an Accept or Reject of the Follow they sent
+async fn queue_follow_answer(
+  jobs: &JobPool,
+  queue: &Queue,
+  action: FederateExtAction,
+  requester_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(*user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(*requester_id),
+      associated_record_id: None,
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::FederateActivityPubExt)
+    .context(vec![user_id.to_string()])
+    .activitypub_federate_ext_action(action)
+    .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(*requester_id))
+    .build();
+
+  queue.send_job(job).await
+}
+
+pub async fn accept_follow_request(
+  follows: &FollowPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  requester_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let request = match follows.fetch_follow_request(requester_id, user_id).await? {
+    Some(request) => request,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  follows.accept_follow_request(requester_id, user_id).await?;
+
+  match request.activity {
+    Some(activity) => {
+      queue_follow_answer(
+        jobs,
+        queue,
+        FederateExtAction::AcceptFollow(activity),
+        requester_id,
+        user_id,
+      )
+      .await
+    }
+    None => Ok(()),
+  }
+}
+
+pub async fn reject_follow_request(
+  follows: &FollowPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  requester_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let request = match follows.fetch_follow_request(requester_id, user_id).await? {
+    Some(request) => request,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  follows.delete_follow(requester_id, user_id).await?;
+
+  match request.activity {
+    Some(activity) => {
+      queue_follow_answer(
+        jobs,
+        queue,
+        FederateExtAction::RejectFollow(activity),
+        requester_id,
+        user_id,
+      )
+      .await
+    }
+    None => Ok(()),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::sync::Arc;
@@ -104,15 +258,18 @@ mod tests {
 
   use crate::{
     db::{
+      block_repository::{BlockPool, MockBlockRepo},
       follow_repository::{FollowPool, MockFollowRepo},
       job_repository::{JobPool, MockJobRepo},
+      notification_repository::{MockNotificationRepo, NotificationPool},
       user_repository::{MockUserRepo, UserPool},
     },
     logic::{
-      follow::{create_follow, delete_follow},
+      follow::{accept_follow_request, create_follow, delete_follow, reject_follow_request, FollowResult},
       LogicErr,
     },
-    model::user::User,
+    model::{fixtures, notification::NewNotification, notification_type::NotificationType, user::User},
+    streaming::pubsub::{MockPubSubBackend, PubSub},
     work_queue::queue::{MockQueueBackend, Queue},
   };
 
@@ -130,12 +287,26 @@ mod tests {
       .return_const(Ok(None));
 
     let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(MockBlockRepo::new());
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
     let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_follow(&users, &follows, &jobs, &queue, &following_user_handle, &user_id).await,
+      create_follow(
+        &users,
+        &blocks,
+        &follows,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &following_user_handle,
+        &user_id
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -174,6 +345,8 @@ mod tests {
       ext_apub_following_uri: None,
       ext_apub_inbox_uri: None,
       ext_apub_outbox_uri: None,
+      ext_apub_shared_inbox_uri: None,
+      manually_approves_followers: false,
       created_at: Utc::now(),
       updated_at: Utc::now(),
     };
@@ -186,6 +359,14 @@ mod tests {
       .with(eq("user_handle"))
       .return_const(Ok(Some(following_user)));
 
+    let mut block_repo = MockBlockRepo::new();
+
+    block_repo
+      .expect_block_exists_between()
+      .times(1)
+      .with(eq(user_id), eq(following_user_id_eq))
+      .return_const(false);
+
     let mut follow_repo = MockFollowRepo::new();
 
     follow_repo
@@ -195,12 +376,26 @@ mod tests {
       .returning(|_, _| Err(LogicErr::DbError("Boop".to_string())));
 
     let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(block_repo);
     let follows: FollowPool = Arc::new(follow_repo);
     let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
     let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_follow(&users, &follows, &jobs, &queue, &following_user_handle, &user_id).await,
+      create_follow(
+        &users,
+        &blocks,
+        &follows,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &following_user_handle,
+        &user_id
+      )
+      .await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -240,6 +435,8 @@ mod tests {
       ext_apub_following_uri: None,
       ext_apub_inbox_uri: None,
       ext_apub_outbox_uri: None,
+      ext_apub_shared_inbox_uri: None,
+      manually_approves_followers: false,
       created_at: Utc::now(),
       updated_at: Utc::now(),
     };
@@ -252,6 +449,14 @@ mod tests {
       .with(eq("user_handle"))
       .return_const(Ok(Some(following_user)));
 
+    let mut block_repo = MockBlockRepo::new();
+
+    block_repo
+      .expect_block_exists_between()
+      .times(1)
+      .with(eq(user_id), eq(following_user_id_eq))
+      .return_const(false);
+
     let mut follow_repo = MockFollowRepo::new();
 
     follow_repo
@@ -260,14 +465,148 @@ mod tests {
       .with(eq(user_id), eq(following_user_id_eq))
       .returning(move |_, _| Ok(follow_id));
 
+    let mut notification_repo = MockNotificationRepo::new();
+
+    notification_repo
+      .expect_create_notification()
+      .times(1)
+      .with(eq(NewNotification {
+        user_id: following_user_id,
+        source_user_id: user_id,
+        notification_type: NotificationType::Follow,
+        post_id: None,
+        comment_id: None,
+        orbit_id: None,
+      }))
+      .return_const(Ok(None));
+
     let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(block_repo);
     let follows: FollowPool = Arc::new(follow_repo);
     let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
     let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_follow(&users, &follows, &jobs, &queue, &following_user_handle, &user_id).await,
-      Ok(())
+      create_follow(
+        &users,
+        &blocks,
+        &follows,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &following_user_handle,
+        &user_id
+      )
+      .await,
+      Ok(FollowResult::Followed)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_follow_requests_for_locked_user() {
+    let user_id = Uuid::new_v4();
+    let following_user_id = Uuid::new_v4();
+    let following_user_id_eq = following_user_id;
+    let following_user_handle = "user_handle".to_string();
+
+    let following_user = User {
+      user_id: following_user_id,
+      fediverse_id: "user@127.0.0.1:8000".to_string(),
+      handle: "a".to_string(),
+      fediverse_uri: "d".to_string(),
+      avatar_url: None,
+      email: Some("b".to_string()),
+      password_hash: Some("c".to_string()),
+      is_external: false,
+      url_1: None,
+      url_2: None,
+      url_3: None,
+      url_4: None,
+      url_5: None,
+      url_1_title: None,
+      url_2_title: None,
+      url_3_title: None,
+      url_4_title: None,
+      url_5_title: None,
+      intro_md: None,
+      intro_html: None,
+      private_key: "d".to_string(),
+      public_key: "e".to_string(),
+      ext_apub_followers_uri: None,
+      ext_apub_following_uri: None,
+      ext_apub_inbox_uri: None,
+      ext_apub_outbox_uri: None,
+      ext_apub_shared_inbox_uri: None,
+      manually_approves_followers: true,
+      created_at: Utc::now(),
+      updated_at: Utc::now(),
+    };
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("user_handle"))
+      .return_const(Ok(Some(following_user)));
+
+    let mut block_repo = MockBlockRepo::new();
+
+    block_repo
+      .expect_block_exists_between()
+      .times(1)
+      .with(eq(user_id), eq(following_user_id_eq))
+      .return_const(false);
+
+    let mut follow_repo = MockFollowRepo::new();
+
+    follow_repo.expect_create_follow().times(0);
+    follow_repo
+      .expect_create_follow_request()
+      .times(1)
+      .with(eq(user_id), eq(following_user_id_eq), eq(None))
+      .returning(|_, _, _| Ok(()));
+
+    let mut notification_repo = MockNotificationRepo::new();
+
+    notification_repo
+      .expect_create_notification()
+      .times(1)
+      .with(eq(NewNotification {
+        user_id: following_user_id,
+        source_user_id: user_id,
+        notification_type: NotificationType::FollowRequest,
+        post_id: None,
+        comment_id: None,
+        orbit_id: None,
+      }))
+      .return_const(Ok(None));
+
+    let users: UserPool = Arc::new(user_repo);
+    let blocks: BlockPool = Arc::new(block_repo);
+    let follows: FollowPool = Arc::new(follow_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      create_follow(
+        &users,
+        &blocks,
+        &follows,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &following_user_handle,
+        &user_id
+      )
+      .await,
+      Ok(FollowResult::Requested)
     );
   }
 
@@ -329,6 +668,8 @@ mod tests {
       ext_apub_following_uri: None,
       ext_apub_inbox_uri: None,
       ext_apub_outbox_uri: None,
+      ext_apub_shared_inbox_uri: None,
+      manually_approves_followers: false,
       created_at: Utc::now(),
       updated_at: Utc::now(),
     };
@@ -394,6 +735,8 @@ mod tests {
       ext_apub_following_uri: None,
       ext_apub_inbox_uri: None,
       ext_apub_outbox_uri: None,
+      ext_apub_shared_inbox_uri: None,
+      manually_approves_followers: false,
       created_at: Utc::now(),
       updated_at: Utc::now(),
     };
@@ -424,4 +767,90 @@ mod tests {
       Ok(())
     );
   }
+
+  #[async_std::test]
+  async fn test_accept_follow_request_rejects_for_missing_request() {
+    let user_id = Uuid::new_v4();
+    let requester_id = Uuid::new_v4();
+
+    let mut follow_repo = MockFollowRepo::new();
+
+    follow_repo
+      .expect_fetch_follow_request()
+      .times(1)
+      .with(eq(requester_id), eq(user_id))
+      .returning(|_, _| Ok(None));
+    follow_repo.expect_accept_follow_request().times(0);
+
+    let follows: FollowPool = Arc::new(follow_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      accept_follow_request(&follows, &jobs, &queue, &requester_id, &user_id).await,
+      Err(LogicErr::MissingRecord)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_accept_follow_request_answers_remote_requester() {
+    let user_id = Uuid::new_v4();
+    let requester_id = Uuid::new_v4();
+    let request = fixtures::follow_request(requester_id, user_id, Some("{}".to_string()));
+
+    let mut follow_repo = MockFollowRepo::new();
+    let mut job_repo = MockJobRepo::new();
+    let mut queue_be = MockQueueBackend::new();
+
+    follow_repo
+      .expect_fetch_follow_request()
+      .times(1)
+      .with(eq(requester_id), eq(user_id))
+      .return_const(Ok(Some(request)));
+    follow_repo
+      .expect_accept_follow_request()
+      .times(1)
+      .with(eq(requester_id), eq(user_id))
+      .returning(|_, _| Ok(()));
+    job_repo.expect_create().times(1).return_const(Ok(Uuid::new_v4()));
+    queue_be.expect_send_job().times(1).return_const(Ok(()));
+
+    let follows: FollowPool = Arc::new(follow_repo);
+    let jobs: JobPool = Arc::new(job_repo);
+    let queue = Queue::new_inner(Box::new(queue_be));
+
+    assert_eq!(
+      accept_follow_request(&follows, &jobs, &queue, &requester_id, &user_id).await,
+      Ok(())
+    );
+  }
+
+  #[async_std::test]
+  async fn test_reject_follow_request_removes_local_request() {
+    let user_id = Uuid::new_v4();
+    let requester_id = Uuid::new_v4();
+    let request = fixtures::follow_request(requester_id, user_id, None);
+
+    let mut follow_repo = MockFollowRepo::new();
+
+    follow_repo
+      .expect_fetch_follow_request()
+      .times(1)
+      .with(eq(requester_id), eq(user_id))
+      .return_const(Ok(Some(request)));
+    follow_repo
+      .expect_delete_follow()
+      .times(1)
+      .with(eq(requester_id), eq(user_id))
+      .returning(|_, _| Ok(()));
+
+    let follows: FollowPool = Arc::new(follow_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      reject_follow_request(&follows, &jobs, &queue, &requester_id, &user_id).await,
+      Ok(())
+    );
+  }
 }
@@ -1,16 +1,34 @@
 use uuid::Uuid;
 
 use crate::{
-  db::{follow_repository::FollowPool, like_repository::LikePool, post_repository::PostPool},
-  model::access_type::AccessType,
+  db::{
+    follow_repository::FollowPool, job_repository::JobPool, like_repository::LikePool,
+    notification_repository::NotificationPool, post_repository::PostPool, user_repository::UserPool,
+  },
+  federation::activitypub::{FederateExtAction, FederateExtActorRef},
+  helpers::api::map_db_err,
+  model::{
+    access_type::AccessType,
+    job::{JobStatus, NewJob},
+    notification::NewNotification,
+    notification_type::NotificationType,
+    queue_job::{QueueJob, QueueJobType},
+  },
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
 };
 
-use super::LogicErr;
+use super::{notification::notify, LogicErr};
 
 pub async fn create_like(
   posts: &PostPool,
   follows: &FollowPool,
   likes: &LikePool,
+  users: &UserPool,
+  jobs: &JobPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  queue: &Queue,
   post_id: &Uuid,
   user_id: &Uuid,
 ) -> Result<Uuid, LogicErr> {
@@ -35,11 +53,86 @@ pub async fn create_like(
     return Err(LogicErr::MissingRecord);
   }
 
-  likes.create_like(user_id, post_id).await
+  let like_id = likes.create_like(user_id, post_id).await?;
+
+  if users.user_is_external(&owner_id).await {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(*user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(*post_id),
+        associated_record_id: Some(owner_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::LikePost(*post_id))
+      .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(owner_id))
+      .build();
+
+    queue.send_job(job).await?;
+  } else {
+    notify(
+      notifications,
+      pubsub,
+      NewNotification {
+        user_id: owner_id,
+        source_user_id: *user_id,
+        notification_type: NotificationType::Like,
+        post_id: Some(*post_id),
+        comment_id: None,
+        orbit_id: None,
+      },
+    )
+    .await;
+  }
+
+  Ok(like_id)
 }
 
-pub async fn delete_like(likes: &LikePool, post_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr> {
-  likes.delete_like(user_id, post_id).await
+pub async fn delete_like(
+  posts: &PostPool,
+  likes: &LikePool,
+  users: &UserPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  post_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  likes.delete_like(user_id, post_id).await?;
+
+  let owner_id = match posts.fetch_owner_by_id(post_id).await {
+    Some(id) => id,
+    None => return Ok(()),
+  };
+
+  if users.user_is_external(&owner_id).await {
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(*user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(*post_id),
+        associated_record_id: Some(owner_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::UnlikePost(*post_id))
+      .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(owner_id))
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(())
 }
 
 #[cfg(test)]
@@ -52,14 +145,19 @@ mod tests {
   use crate::{
     db::{
       follow_repository::{FollowPool, MockFollowRepo},
+      job_repository::{JobPool, MockJobRepo},
       like_repository::{LikePool, MockLikeRepo},
+      notification_repository::{MockNotificationRepo, NotificationPool},
       post_repository::{MockPostRepo, PostPool},
+      user_repository::{MockUserRepo, UserPool},
     },
     logic::{
       like::{create_like, delete_like},
       LogicErr,
     },
-    model::access_type::AccessType,
+    model::{access_type::AccessType, notification::NewNotification, notification_type::NotificationType},
+    streaming::pubsub::{MockPubSubBackend, PubSub},
+    work_queue::queue::{MockQueueBackend, Queue},
   };
 
   #[async_std::test]
@@ -78,9 +176,26 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let likes: LikePool = Arc::new(MockLikeRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_like(&posts, &follows, &likes, &post_id, &user_id).await,
+      create_like(
+        &posts,
+        &follows,
+        &likes,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -107,9 +222,26 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let likes: LikePool = Arc::new(MockLikeRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_like(&posts, &follows, &likes, &post_id, &user_id).await,
+      create_like(
+        &posts,
+        &follows,
+        &likes,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -137,9 +269,26 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(MockFollowRepo::new());
     let likes: LikePool = Arc::new(MockLikeRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_like(&posts, &follows, &likes, &post_id, &user_id).await,
+      create_like(
+        &posts,
+        &follows,
+        &likes,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::UnauthorizedError)
     );
   }
@@ -174,9 +323,26 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(follow_repo);
     let likes: LikePool = Arc::new(MockLikeRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_like(&posts, &follows, &likes, &post_id, &user_id).await,
+      create_like(
+        &posts,
+        &follows,
+        &likes,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::MissingRecord)
     );
   }
@@ -218,9 +384,26 @@ mod tests {
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(follow_repo);
     let likes: LikePool = Arc::new(like_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_like(&posts, &follows, &likes, &post_id, &user_id).await,
+      create_like(
+        &posts,
+        &follows,
+        &likes,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &user_id
+      )
+      .await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -261,12 +444,50 @@ mod tests {
       .with(eq(user_id), eq(post_id))
       .returning(move |_, _| Ok(like_id));
 
+    let mut user_repo = MockUserRepo::new();
+    user_repo
+      .expect_user_is_external()
+      .times(1)
+      .with(eq(owner_id))
+      .returning(|_| false);
+
+    let mut notification_repo = MockNotificationRepo::new();
+    notification_repo
+      .expect_create_notification()
+      .times(1)
+      .with(eq(NewNotification {
+        user_id: owner_id,
+        source_user_id: user_id,
+        notification_type: NotificationType::Like,
+        post_id: Some(post_id),
+        comment_id: None,
+        orbit_id: None,
+      }))
+      .return_const(Ok(None));
+
     let posts: PostPool = Arc::new(post_repo);
     let follows: FollowPool = Arc::new(follow_repo);
     let likes: LikePool = Arc::new(like_repo);
+    let users: UserPool = Arc::new(user_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      create_like(&posts, &follows, &likes, &post_id, &user_id).await,
+      create_like(
+        &posts,
+        &follows,
+        &likes,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post_id,
+        &user_id
+      )
+      .await,
       Ok(like_id_eq)
     );
   }
@@ -283,10 +504,14 @@ mod tests {
       .with(eq(user_id), eq(post_id))
       .returning(move |_, _| Err(LogicErr::DbError("Boop".to_string())));
 
+    let posts: PostPool = Arc::new(MockPostRepo::new());
     let likes: LikePool = Arc::new(like_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
     assert_eq!(
-      delete_like(&likes, &post_id, &user_id).await,
+      delete_like(&posts, &likes, &users, &jobs, &queue, &post_id, &user_id).await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -294,6 +519,7 @@ mod tests {
   #[async_std::test]
   async fn test_delete_like_succeeds() {
     let user_id = Uuid::new_v4();
+    let owner_id = Uuid::new_v4();
     let post_id = Uuid::new_v4();
 
     let mut like_repo = MockLikeRepo::new();
@@ -303,8 +529,79 @@ mod tests {
       .with(eq(user_id), eq(post_id))
       .returning(move |_, _| Ok(()));
 
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_owner_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(move |_| Some(owner_id));
+
+    let mut user_repo = MockUserRepo::new();
+    user_repo
+      .expect_user_is_external()
+      .times(1)
+      .with(eq(owner_id))
+      .returning(|_| false);
+
+    let posts: PostPool = Arc::new(post_repo);
     let likes: LikePool = Arc::new(like_repo);
+    let users: UserPool = Arc::new(user_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
 
-    assert_eq!(delete_like(&likes, &post_id, &user_id).await, Ok(()));
+    assert_eq!(
+      delete_like(&posts, &likes, &users, &jobs, &queue, &post_id, &user_id).await,
+      Ok(())
+    );
+  }
+
+  #[async_std::test]
+  async fn test_delete_like_federates_for_external_owner() {
+    let user_id = Uuid::new_v4();
+    let owner_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let job_id = Uuid::new_v4();
+
+    let mut like_repo = MockLikeRepo::new();
+    like_repo
+      .expect_delete_like()
+      .times(1)
+      .with(eq(user_id), eq(post_id))
+      .returning(move |_, _| Ok(()));
+
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_owner_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(move |_| Some(owner_id));
+
+    let mut user_repo = MockUserRepo::new();
+    user_repo
+      .expect_user_is_external()
+      .times(1)
+      .with(eq(owner_id))
+      .returning(|_| true);
+
+    let mut job_repo = MockJobRepo::new();
+    job_repo
+      .expect_create()
+      .with(always())
+      .times(1)
+      .return_const(Ok(job_id));
+
+    let mut queue_be = MockQueueBackend::new();
+    queue_be.expect_send_job().with(always()).times(1).return_const(Ok(()));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let likes: LikePool = Arc::new(like_repo);
+    let users: UserPool = Arc::new(user_repo);
+    let jobs: JobPool = Arc::new(job_repo);
+    let queue = Queue::new_inner(Box::new(queue_be));
+
+    assert_eq!(
+      delete_like(&posts, &likes, &users, &jobs, &queue, &post_id, &user_id).await,
+      Ok(())
+    );
   }
 }
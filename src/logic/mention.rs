@@ -0,0 +1,302 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    notification_repository::NotificationPool, post_mention_repository::PostMentionPool, user_repository::UserPool,
+  },
+  federation::activitypub::actor::federate_user_actor_from_webfinger,
+  helpers::{
+    api::{local_domain, relative_to_absolute_uri},
+    html::map_linkable_html,
+  },
+  model::{access_type::AccessType, notification::NewNotification, notification_type::NotificationType, user::User},
+  settings::SETTINGS,
+  streaming::pubsub::PubSub,
+};
+
+use super::{notification::notify, LogicErr};
+
+lazy_static! {
+  /// `@handle` or `@handle@domain`, as long as it isn't part of an email address or URL
+  static ref MENTION_RE: Regex =
+    Regex::new(r"(^|[^\w@/])@([A-Za-z0-9_]+(?:@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)+)?)").unwrap();
+}
+
+/// A mention written in a post, along with the user it refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedMention {
+  /// The mention as written, without the leading `@`
+  pub acct: String,
+  pub user: User,
+}
+
+/// Finds everyone mentioned in a post's markdown, in order of first appearance and without the leading `@`
+pub fn parse_mentions(content_md: &str) -> Vec<String> {
+  let mut accts: Vec<String> = vec![];
+
+  for captures in MENTION_RE.captures_iter(content_md) {
+    let acct = captures[2].to_string();
+    if !accts.contains(&acct) {
+      accts.push(acct);
+    }
+  }
+
+  accts
+}
+
+async fn resolve_mention(users: &UserPool, acct: &str) -> Result<Option<User>, LogicErr> {
+  match acct.split_once('@') {
+    None => {
+      users
+        .fetch_by_fediverse_id(&format!("@{}@{}", acct, SETTINGS.server.fqdn))
+        .await
+    }
+    Some((handle, domain)) if domain.eq_ignore_ascii_case(&local_domain()) => {
+      users
+        .fetch_by_fediverse_id(&format!("@{}@{}", handle, SETTINGS.server.fqdn))
+        .await
+    }
+    Some((_, domain)) => match users.fetch_by_fediverse_id(&format!("@{}", acct)).await? {
+      Some(user) => Ok(Some(user)),
+      None => federate_user_actor_from_webfinger(domain, &format!("acct:{}", acct), users).await,
+    },
+  }
+}
+
+/// Looks up the users mentioned in a post's markdown, federating remote users we haven't seen before. Mentions that
+/// can't be resolved are left as plain text rather than failing the post.
+pub async fn resolve_mentions(users: &UserPool, content_md: &str) -> Vec<ResolvedMention> {
+  let mut mentions = vec![];
+
+  for acct in parse_mentions(content_md) {
+    match resolve_mention(users, &acct).await {
+      Ok(Some(user)) => mentions.push(ResolvedMention { acct, user }),
+      Ok(None) => {}
+      Err(err) => log::warn!("Failed to resolve mention of @{}: {}", acct, err),
+    }
+  }
+
+  mentions
+}
+
+fn link_mentions(html: &str, mentions: &[ResolvedMention]) -> String {
+  MENTION_RE
+    .replace_all(html, |captures: &Captures| {
+      let mention = match mentions.iter().find(|m| m.acct == captures[2]) {
+        Some(mention) => mention,
+        None => return captures[0].to_string(),
+      };
+
+      let handle = mention.acct.split('@').next().unwrap_or_default();
+
+      format!(
+        r#"{}<span class="h-card"><a href="{}" class="u-url mention">@<span>{}</span></a></span>"#,
+        &captures[1],
+        relative_to_absolute_uri(&mention.user.fediverse_uri),
+        handle
+      )
+    })
+    .into_owned()
+}
+
+/// Turns resolved mentions in rendered post content into profile links, leaving existing links and code untouched
+pub fn render_mentions(content_html: &str, mentions: &[ResolvedMention]) -> String {
+  if mentions.is_empty() {
+    return content_html.to_string();
+  }
+
+  map_linkable_html(content_html, |html| link_mentions(html, mentions))
+}
+
+/// Records who a post mentions and notifies the local users among them who weren't mentioned in it before. Shadow
+/// posts are recorded without notifying anyone. Like notifying, this happens after the post already exists, so errors
+/// are only logged.
+pub async fn record_mentions(
+  post_mentions: &PostMentionPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  post_id: &Uuid,
+  source_user_id: &Uuid,
+  visibility: &AccessType,
+  user_ids: &[Uuid],
+) {
+  if user_ids.is_empty() {
+    return;
+  }
+
+  let created = match post_mentions.create_post_mentions(post_id, user_ids).await {
+    Ok(created) => created,
+    Err(err) => {
+      log::warn!("Failed to record mentions for post {}: {}", post_id, err);
+      return;
+    }
+  };
+
+  if *visibility == AccessType::Shadow {
+    return;
+  }
+
+  for user_id in created {
+    notify(
+      notifications,
+      pubsub,
+      NewNotification {
+        user_id,
+        source_user_id: *source_user_id,
+        notification_type: NotificationType::Mention,
+        post_id: Some(*post_id),
+        comment_id: None,
+        orbit_id: None,
+      },
+    )
+    .await;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      notification_repository::{MockNotificationRepo, NotificationPool},
+      post_mention_repository::{MockPostMentionRepo, PostMentionPool},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    logic::mention::{parse_mentions, record_mentions, render_mentions, resolve_mentions, ResolvedMention},
+    model::{access_type::AccessType, fixtures, user::User},
+    settings::SETTINGS,
+    streaming::pubsub::{MockPubSubBackend, PubSub},
+  };
+
+  fn build_user(handle: &str, fediverse_id: &str, fediverse_uri: &str, is_external: bool) -> User {
+    User {
+      handle: handle.to_string(),
+      fediverse_id: fediverse_id.to_string(),
+      fediverse_uri: fediverse_uri.to_string(),
+      is_external,
+      ..fixtures::user(Uuid::new_v4())
+    }
+  }
+
+  #[test]
+  fn test_parse_mentions_ignores_emails_and_urls() {
+    assert_eq!(
+      parse_mentions(
+        "hi @alice and @bob@example.social! cc (@alice), mail me at carol@example.com or see https://example.com/@dave"
+      ),
+      vec!["alice".to_string(), "bob@example.social".to_string()]
+    );
+    assert_eq!(
+      parse_mentions("@erin@example.social."),
+      vec!["erin@example.social".to_string()]
+    );
+  }
+
+  #[test]
+  fn test_render_mentions_links_resolved_mentions_only() {
+    let mentions = vec![ResolvedMention {
+      acct: "bob@example.social".to_string(),
+      user: build_user("bob", "@bob@example.social", "https://example.social/users/bob", true),
+    }];
+
+    assert_eq!(
+      render_mentions(
+        "<p>@bob@example.social and @nobody</p><p><code>@bob@example.social</code></p>",
+        &mentions
+      ),
+      concat!(
+        r#"<p><span class="h-card"><a href="https://example.social/users/bob" class="u-url mention">"#,
+        r#"@<span>bob</span></a></span> and @nobody</p><p><code>@bob@example.social</code></p>"#
+      )
+    );
+  }
+
+  #[async_std::test]
+  async fn test_resolve_mentions_skips_unknown_users() {
+    let alice = build_user(
+      "alice",
+      &format!("@alice@{}", SETTINGS.server.fqdn),
+      "/user/alice",
+      false,
+    );
+    let bob = build_user("bob", "@bob@example.social", "https://example.social/users/bob", true);
+
+    let mut user_repo = MockUserRepo::new();
+    user_repo
+      .expect_fetch_by_fediverse_id()
+      .with(eq(format!("@alice@{}", SETTINGS.server.fqdn)))
+      .times(1)
+      .return_const(Ok(Some(alice.clone())));
+    user_repo
+      .expect_fetch_by_fediverse_id()
+      .with(eq("@bob@example.social"))
+      .times(1)
+      .return_const(Ok(Some(bob.clone())));
+    user_repo
+      .expect_fetch_by_fediverse_id()
+      .with(eq(format!("@nobody@{}", SETTINGS.server.fqdn)))
+      .times(1)
+      .return_const(Ok(None));
+
+    let users: UserPool = Arc::new(user_repo);
+
+    let mentions = resolve_mentions(&users, "@alice @nobody @bob@example.social").await;
+
+    assert_eq!(
+      mentions,
+      vec![
+        ResolvedMention {
+          acct: "alice".to_string(),
+          user: alice
+        },
+        ResolvedMention {
+          acct: "bob@example.social".to_string(),
+          user: bob
+        },
+      ]
+    );
+  }
+
+  #[async_std::test]
+  async fn test_record_mentions_only_notifies_new_mentions() {
+    let post_id = Uuid::new_v4();
+    let source_user_id = Uuid::new_v4();
+    let already_mentioned = Uuid::new_v4();
+    let newly_mentioned = Uuid::new_v4();
+
+    let mut post_mention_repo = MockPostMentionRepo::new();
+    post_mention_repo
+      .expect_create_post_mentions()
+      .with(eq(post_id), eq(vec![already_mentioned, newly_mentioned]))
+      .times(1)
+      .return_const(Ok(vec![newly_mentioned]));
+
+    let mut notification_repo = MockNotificationRepo::new();
+    notification_repo
+      .expect_create_notification()
+      .withf(move |n| n.user_id == newly_mentioned && n.post_id == Some(post_id))
+      .times(1)
+      .return_const(Ok(None));
+
+    let post_mentions: PostMentionPool = Arc::new(post_mention_repo);
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+
+    record_mentions(
+      &post_mentions,
+      &notifications,
+      &pubsub,
+      &post_id,
+      &source_user_id,
+      &AccessType::PublicFederated,
+      &[already_mentioned, newly_mentioned],
+    )
+    .await;
+  }
+}
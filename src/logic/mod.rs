@@ -2,12 +2,24 @@ use std::error::Error;
 
 use strum::Display;
 
+pub mod account_restriction;
 pub mod app;
+pub mod block;
 pub mod comment;
+pub mod domain_policy;
 pub mod follow;
 pub mod job;
 pub mod like;
+pub mod mention;
+pub mod mute;
+pub mod notification;
+pub mod orbit_membership;
+pub mod orbit_moderation;
 pub mod post;
+pub mod report;
+pub mod search;
+pub mod streaming;
+pub mod tag;
 pub mod user;
 
 #[derive(Debug, PartialEq, Eq, Clone, Display)]
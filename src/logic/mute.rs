@@ -0,0 +1,274 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+  db::{mute_repository::MutePool, orbit_repository::OrbitPool, user_repository::UserPool},
+  model::mute::{KeywordFilter, OrbitMute, UserMute},
+};
+
+use super::LogicErr;
+
+const MAX_KEYWORD_FILTER_LENGTH: usize = 256;
+
+fn assert_expiry_in_future(expires_at: &Option<DateTime<Utc>>) -> Result<(), LogicErr> {
+  match expires_at {
+    Some(expires_at) if *expires_at <= Utc::now() => {
+      Err(LogicErr::InvalidOperation("Expiry must be in the future".to_string()))
+    }
+    _ => Ok(()),
+  }
+}
+
+pub async fn get_user_mutes(mutes: &MutePool, user_id: &Uuid) -> Result<Vec<UserMute>, LogicErr> {
+  mutes.fetch_user_mutes(user_id).await
+}
+
+pub async fn mute_user(
+  users: &UserPool,
+  mutes: &MutePool,
+  muted_user_handle: &str,
+  user_id: &Uuid,
+  boosts_only: bool,
+  expires_at: &Option<DateTime<Utc>>,
+) -> Result<(), LogicErr> {
+  let muted_user_id = match users.fetch_by_handle(muted_user_handle).await? {
+    Some(user) => user.user_id,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if muted_user_id == *user_id {
+    return Err(LogicErr::InvalidOperation("Cannot mute yourself".to_string()));
+  }
+
+  assert_expiry_in_future(expires_at)?;
+
+  mutes
+    .create_user_mute(user_id, &muted_user_id, boosts_only, expires_at)
+    .await?;
+
+  Ok(())
+}
+
+pub async fn unmute_user(
+  users: &UserPool,
+  mutes: &MutePool,
+  muted_user_handle: &str,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let muted_user_id = match users.fetch_by_handle(muted_user_handle).await? {
+    Some(user) => user.user_id,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  mutes.delete_user_mute(user_id, &muted_user_id).await
+}
+
+pub async fn get_orbit_mutes(mutes: &MutePool, user_id: &Uuid) -> Result<Vec<OrbitMute>, LogicErr> {
+  mutes.fetch_orbit_mutes(user_id).await
+}
+
+pub async fn mute_orbit(orbits: &OrbitPool, mutes: &MutePool, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr> {
+  if orbits.fetch_orbit(orbit_id).await?.is_none() {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  mutes.create_orbit_mute(user_id, orbit_id).await?;
+
+  Ok(())
+}
+
+pub async fn unmute_orbit(mutes: &MutePool, orbit_id: &Uuid, user_id: &Uuid) -> Result<(), LogicErr> {
+  mutes.delete_orbit_mute(user_id, orbit_id).await
+}
+
+pub async fn get_keyword_filters(mutes: &MutePool, user_id: &Uuid) -> Result<Vec<KeywordFilter>, LogicErr> {
+  mutes.fetch_keyword_filters(user_id).await
+}
+
+pub async fn create_keyword_filter(
+  mutes: &MutePool,
+  user_id: &Uuid,
+  phrase: &str,
+  is_regex: bool,
+  expires_at: &Option<DateTime<Utc>>,
+) -> Result<KeywordFilter, LogicErr> {
+  let phrase = phrase.trim();
+
+  if phrase.is_empty() || phrase.chars().count() > MAX_KEYWORD_FILTER_LENGTH {
+    return Err(LogicErr::InvalidOperation("Invalid phrase".to_string()));
+  }
+
+  if is_regex && !mutes.keyword_filter_pattern_valid(phrase).await {
+    return Err(LogicErr::InvalidOperation("Invalid regular expression".to_string()));
+  }
+
+  assert_expiry_in_future(expires_at)?;
+
+  mutes.create_keyword_filter(user_id, phrase, is_regex, expires_at).await
+}
+
+pub async fn delete_keyword_filter(mutes: &MutePool, user_id: &Uuid, keyword_filter_id: &Uuid) -> Result<(), LogicErr> {
+  mutes.delete_keyword_filter(user_id, keyword_filter_id).await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use chrono::{Duration, Utc};
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      mute_repository::{MockMuteRepo, MutePool},
+      orbit_repository::{MockOrbitRepo, OrbitPool},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    logic::{
+      mute::{create_keyword_filter, mute_orbit, mute_user},
+      LogicErr,
+    },
+    model::{fixtures, mute::KeywordFilter},
+  };
+
+  #[async_std::test]
+  async fn test_mute_user_rejects_muting_self() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("user_handle"))
+      .return_const(Ok(Some(fixtures::user(user_id))));
+
+    let users: UserPool = Arc::new(user_repo);
+    let mutes: MutePool = Arc::new(MockMuteRepo::new());
+
+    assert_eq!(
+      mute_user(&users, &mutes, "user_handle", &user_id, false, &None).await,
+      Err(LogicErr::InvalidOperation("Cannot mute yourself".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_mute_user_passes_boosts_only() {
+    let user_id = Uuid::new_v4();
+    let muted_user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_fetch_by_handle()
+      .times(1)
+      .with(eq("user_handle"))
+      .return_const(Ok(Some(fixtures::user(muted_user_id))));
+
+    let mut mute_repo = MockMuteRepo::new();
+
+    mute_repo
+      .expect_create_user_mute()
+      .times(1)
+      .with(eq(user_id), eq(muted_user_id), eq(true), eq(None))
+      .returning(|_, _, _, _| Ok(Uuid::new_v4()));
+
+    let users: UserPool = Arc::new(user_repo);
+    let mutes: MutePool = Arc::new(mute_repo);
+
+    assert_eq!(
+      mute_user(&users, &mutes, "user_handle", &user_id, true, &None).await,
+      Ok(())
+    );
+  }
+
+  #[async_std::test]
+  async fn test_mute_orbit_rejects_for_missing_orbit() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+
+    let mut orbit_repo = MockOrbitRepo::new();
+
+    orbit_repo
+      .expect_fetch_orbit()
+      .times(1)
+      .with(eq(orbit_id))
+      .returning(|_| Ok(None));
+
+    let orbits: OrbitPool = Arc::new(orbit_repo);
+    let mutes: MutePool = Arc::new(MockMuteRepo::new());
+
+    assert_eq!(
+      mute_orbit(&orbits, &mutes, &orbit_id, &user_id).await,
+      Err(LogicErr::MissingRecord)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_keyword_filter_rejects_invalid_regex() {
+    let user_id = Uuid::new_v4();
+
+    let mut mute_repo = MockMuteRepo::new();
+
+    mute_repo
+      .expect_keyword_filter_pattern_valid()
+      .times(1)
+      .with(eq("(unclosed"))
+      .return_const(false);
+
+    let mutes: MutePool = Arc::new(mute_repo);
+
+    assert_eq!(
+      create_keyword_filter(&mutes, &user_id, "(unclosed", true, &None).await,
+      Err(LogicErr::InvalidOperation("Invalid regular expression".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_keyword_filter_rejects_past_expiry() {
+    let user_id = Uuid::new_v4();
+    let mutes: MutePool = Arc::new(MockMuteRepo::new());
+
+    assert_eq!(
+      create_keyword_filter(
+        &mutes,
+        &user_id,
+        "spoilers",
+        false,
+        &Some(Utc::now() - Duration::hours(1))
+      )
+      .await,
+      Err(LogicErr::InvalidOperation("Expiry must be in the future".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_keyword_filter_trims_phrase() {
+    let user_id = Uuid::new_v4();
+
+    let mut mute_repo = MockMuteRepo::new();
+
+    mute_repo
+      .expect_create_keyword_filter()
+      .times(1)
+      .with(eq(user_id), eq("spoilers"), eq(false), eq(None))
+      .returning(|user_id, phrase, is_regex, expires_at| {
+        Ok(KeywordFilter {
+          keyword_filter_id: Uuid::new_v4(),
+          user_id: *user_id,
+          phrase: phrase.to_string(),
+          is_regex,
+          expires_at: *expires_at,
+          created_at: Utc::now(),
+        })
+      });
+
+    let mutes: MutePool = Arc::new(mute_repo);
+
+    let result = create_keyword_filter(&mutes, &user_id, "  spoilers ", false, &None).await;
+
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().phrase, "spoilers");
+  }
+}
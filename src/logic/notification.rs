@@ -0,0 +1,289 @@
+use uuid::Uuid;
+
+use crate::{
+  db::notification_repository::NotificationPool,
+  model::{
+    notification::{NewNotification, Notification, NotificationFilter, NotificationGroup},
+    notification_type::NotificationType,
+    stream_message::StreamMessage,
+  },
+  streaming::pubsub::PubSub,
+};
+
+use super::LogicErr;
+
+pub const DEFAULT_NOTIFICATIONS_LIMIT: i64 = 20;
+pub const MAX_NOTIFICATIONS_LIMIT: i64 = 40;
+
+/// Records a notification for an interaction that has already happened. Failing to notify shouldn't undo or fail the
+/// interaction itself, so errors are only logged.
+pub async fn notify(notifications: &NotificationPool, pubsub: &PubSub, notification: NewNotification) {
+  let user_id = notification.user_id;
+
+  match notifications.create_notification(notification).await {
+    Ok(Some(notification_id)) => {
+      pubsub
+        .publish(StreamMessage::Notification {
+          user_id,
+          notification_id,
+        })
+        .await
+    }
+    Ok(None) => {}
+    Err(err) => log::warn!("Failed to create notification: {}", err),
+  }
+}
+
+pub async fn notify_orbit(
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  orbit_id: &Uuid,
+  source_user_id: &Uuid,
+  notification_type: NotificationType,
+) {
+  match notifications
+    .create_orbit_notification(orbit_id, source_user_id, notification_type)
+    .await
+  {
+    Ok(created) => {
+      for (user_id, notification_id) in created {
+        pubsub
+          .publish(StreamMessage::Notification {
+            user_id,
+            notification_id,
+          })
+          .await;
+      }
+    }
+    Err(err) => log::warn!("Failed to create orbit notification: {}", err),
+  }
+}
+
+pub async fn get_notifications(
+  notifications: &NotificationPool,
+  user_id: &Uuid,
+  filter: &NotificationFilter,
+  limit: Option<i64>,
+) -> Result<Vec<Notification>, LogicErr> {
+  let limit = limit
+    .unwrap_or(DEFAULT_NOTIFICATIONS_LIMIT)
+    .clamp(1, MAX_NOTIFICATIONS_LIMIT);
+
+  notifications.fetch_notifications(user_id, filter, limit).await
+}
+
+pub async fn get_unread_notifications_count(notifications: &NotificationPool, user_id: &Uuid) -> Result<i64, LogicErr> {
+  notifications.count_unread_notifications(user_id).await
+}
+
+/// Collapses notifications sharing a group key, keeping the groups in the order of their most recent notification
+pub fn group_notifications(notifications: Vec<Notification>) -> Vec<NotificationGroup> {
+  let mut groups: Vec<NotificationGroup> = vec![];
+
+  for notification in notifications {
+    let group_key = notification.group_key();
+
+    match groups.iter_mut().find(|g| g.group_key == group_key) {
+      Some(group) => {
+        group.unread = group.unread || notification.read_at.is_none();
+        if notification.created_at > group.latest_created_at {
+          group.latest_created_at = notification.created_at;
+        }
+        group.notifications.push(notification);
+      }
+      None => groups.push(NotificationGroup {
+        group_key,
+        notification_type: notification.notification_type,
+        unread: notification.read_at.is_none(),
+        latest_created_at: notification.created_at,
+        notifications: vec![notification],
+      }),
+    }
+  }
+
+  groups
+}
+
+pub async fn mark_notification_read(
+  notifications: &NotificationPool,
+  user_id: &Uuid,
+  notification_id: &Uuid,
+) -> Result<(), LogicErr> {
+  notifications.mark_notification_read(user_id, notification_id).await
+}
+
+pub async fn mark_notifications_read(
+  notifications: &NotificationPool,
+  user_id: &Uuid,
+  max_id: &Option<Uuid>,
+) -> Result<(), LogicErr> {
+  notifications.mark_notifications_read(user_id, max_id).await
+}
+
+pub async fn dismiss_notification(
+  notifications: &NotificationPool,
+  user_id: &Uuid,
+  notification_id: &Uuid,
+) -> Result<(), LogicErr> {
+  notifications.delete_notification(user_id, notification_id).await
+}
+
+pub async fn clear_notifications(notifications: &NotificationPool, user_id: &Uuid) -> Result<(), LogicErr> {
+  notifications.delete_notifications(user_id).await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use chrono::{Duration, Utc};
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::notification_repository::{MockNotificationRepo, NotificationPool},
+    logic::{
+      notification::{get_notifications, group_notifications, notify, MAX_NOTIFICATIONS_LIMIT},
+      LogicErr,
+    },
+    model::{
+      notification::{NewNotification, Notification, NotificationFilter},
+      notification_type::NotificationType,
+      stream_message::StreamMessage,
+    },
+    streaming::pubsub::{MockPubSubBackend, PubSub},
+  };
+
+  fn notification(notification_type: NotificationType, post_id: Option<Uuid>, minutes_ago: i64) -> Notification {
+    Notification {
+      notification_id: Uuid::new_v4(),
+      user_id: Uuid::new_v4(),
+      source_user_id: Uuid::new_v4(),
+      source_user_handle: "test".to_string(),
+      source_user_fediverse_id: "@test@example.com".to_string(),
+      source_user_avatar_url: None,
+      notification_type,
+      post_id,
+      comment_id: None,
+      orbit_id: None,
+      read_at: Some(Utc::now()),
+      created_at: Utc::now() - Duration::minutes(minutes_ago),
+    }
+  }
+
+  #[test]
+  fn test_group_notifications_collapses_likes_on_the_same_post() {
+    let post_id = Some(Uuid::new_v4());
+    let mut unread_like = notification(NotificationType::Like, post_id, 1);
+    unread_like.read_at = None;
+
+    let groups = group_notifications(vec![
+      unread_like,
+      notification(NotificationType::Comment, post_id, 2),
+      notification(NotificationType::Like, post_id, 3),
+      notification(NotificationType::Like, Some(Uuid::new_v4()), 4),
+    ]);
+
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[0].notification_type, NotificationType::Like);
+    assert_eq!(groups[0].notifications.len(), 2);
+    assert!(groups[0].unread);
+    assert_eq!(groups[1].notification_type, NotificationType::Comment);
+    assert_eq!(groups[2].notifications.len(), 1);
+    assert!(!groups[2].unread);
+  }
+
+  #[test]
+  fn test_group_notifications_keeps_comments_separate() {
+    let post_id = Some(Uuid::new_v4());
+
+    let groups = group_notifications(vec![
+      notification(NotificationType::Comment, post_id, 1),
+      notification(NotificationType::Comment, post_id, 2),
+    ]);
+
+    assert_eq!(groups.len(), 2);
+  }
+
+  #[async_std::test]
+  async fn test_get_notifications_clamps_limit() {
+    let user_id = Uuid::new_v4();
+    let filter = NotificationFilter::default();
+
+    let mut notification_repo = MockNotificationRepo::new();
+    notification_repo
+      .expect_fetch_notifications()
+      .with(eq(user_id), eq(filter.clone()), eq(MAX_NOTIFICATIONS_LIMIT))
+      .times(1)
+      .return_const(Ok(vec![]));
+
+    let notifications: NotificationPool = Arc::new(notification_repo);
+
+    let result = get_notifications(&notifications, &user_id, &filter, Some(1000)).await;
+
+    assert!(result.is_ok());
+  }
+
+  #[async_std::test]
+  async fn test_notify_swallows_errors() {
+    let new_notification = NewNotification {
+      user_id: Uuid::new_v4(),
+      source_user_id: Uuid::new_v4(),
+      notification_type: NotificationType::Follow,
+      post_id: None,
+      comment_id: None,
+      orbit_id: None,
+    };
+
+    let mut notification_repo = MockNotificationRepo::new();
+    notification_repo
+      .expect_create_notification()
+      .with(eq(new_notification.clone()))
+      .times(1)
+      .return_const(Err(LogicErr::DbError("Failed".to_string())));
+
+    let mut pubsub_backend = MockPubSubBackend::new();
+    pubsub_backend.expect_publish().times(0);
+
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(pubsub_backend));
+
+    notify(&notifications, &pubsub, new_notification).await;
+  }
+
+  #[async_std::test]
+  async fn test_notify_publishes_new_notifications() {
+    let user_id = Uuid::new_v4();
+    let notification_id = Uuid::new_v4();
+    let new_notification = NewNotification {
+      user_id,
+      source_user_id: Uuid::new_v4(),
+      notification_type: NotificationType::Like,
+      post_id: Some(Uuid::new_v4()),
+      comment_id: None,
+      orbit_id: None,
+    };
+
+    let mut notification_repo = MockNotificationRepo::new();
+    notification_repo
+      .expect_create_notification()
+      .with(eq(new_notification.clone()))
+      .times(1)
+      .return_const(Ok(Some(notification_id)));
+
+    let mut pubsub_backend = MockPubSubBackend::new();
+    pubsub_backend
+      .expect_publish()
+      .with(eq(StreamMessage::Notification {
+        user_id,
+        notification_id,
+      }))
+      .times(1)
+      .return_const(Ok(()));
+
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(pubsub_backend));
+
+    notify(&notifications, &pubsub, new_notification).await;
+  }
+}
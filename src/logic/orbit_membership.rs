@@ -0,0 +1,513 @@
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    job_repository::JobPool, notification_repository::NotificationPool,
+    orbit_membership_repository::OrbitMembershipPool, orbit_moderator_repository::OrbitModeratorPool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+  },
+  federation::activitypub::{FederateExtAction, FederateExtActorRef},
+  helpers::api::map_db_err,
+  model::{
+    job::{JobStatus, NewJob},
+    notification_type::NotificationType,
+    orbit::Orbit,
+    orbit_join_policy::OrbitJoinPolicy,
+    orbit_membership::{OrbitInvite, OrbitJoinRequest},
+    queue_job::{QueueJob, QueueJobType},
+  },
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
+};
+
+use super::{notification::notify_orbit, orbit_moderation::assert_orbit_moderator, LogicErr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinOrbitResult {
+  Joined,
+  Requested,
+}
+
+/// Lets a user into one of our orbits according to its join policy. An invite gets the user straight in whatever the
+/// policy, and is used up in doing so.
+pub async fn join_orbit(
+  user_orbits: &UserOrbitPool,
+  orbit_membership: &OrbitMembershipPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
+  orbit: &Orbit,
+  user_id: &Uuid,
+) -> Result<JoinOrbitResult, LogicErr> {
+  if user_orbits.user_is_member(user_id, &orbit.orbit_id).await? {
+    return Ok(JoinOrbitResult::Joined);
+  }
+
+  let is_invited = orbit_membership.user_is_invited(&orbit.orbit_id, user_id).await?;
+
+  match (orbit.join_policy, is_invited) {
+    (OrbitJoinPolicy::Open, _) | (_, true) => {
+      user_orbits.create_user_orbit(&orbit.orbit_id, user_id).await?;
+      orbit_membership.delete_invite(&orbit.orbit_id, user_id).await?;
+
+      notify_orbit(
+        notifications,
+        pubsub,
+        &orbit.orbit_id,
+        user_id,
+        NotificationType::OrbitJoin,
+      )
+      .await;
+
+      Ok(JoinOrbitResult::Joined)
+    }
+    (OrbitJoinPolicy::ApprovalRequired, false) => {
+      orbit_membership
+        .create_join_request(&orbit.orbit_id, user_id, &None)
+        .await?;
+
+      notify_orbit(
+        notifications,
+        pubsub,
+        &orbit.orbit_id,
+        user_id,
+        NotificationType::OrbitJoinRequest,
+      )
+      .await;
+
+      Ok(JoinOrbitResult::Requested)
+    }
+    (OrbitJoinPolicy::InviteOnly, false) => Err(LogicErr::UnauthorizedError),
+  }
+}
+
+pub async fn get_join_requests(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_membership: &OrbitMembershipPool,
+  orbit_id: &Uuid,
+  limit: i64,
+  skip: i64,
+  user_id: &Uuid,
+) -> Result<Vec<OrbitJoinRequest>, LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  orbit_membership.fetch_join_requests(orbit_id, limit, skip).await
+}
+
+pub async fn get_join_requests_count(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_membership: &OrbitMembershipPool,
+  orbit_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<i64, LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  orbit_membership.count_join_requests(orbit_id).await
+}
+
+/// Remote users are sent the moderator's decision as an Accept or Reject of the Follow/Join they asked to join with
+async fn queue_join_answer(
+  jobs: &JobPool,
+  queue: &Queue,
+  action: FederateExtAction,
+  target_user_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(*user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(*target_user_id),
+      associated_record_id: None,
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::FederateActivityPubExt)
+    .context(vec![user_id.to_string()])
+    .activitypub_federate_ext_action(action)
+    .activitypub_federate_ext_dest_actor(FederateExtActorRef::Person(*target_user_id))
+    .build();
+
+  queue.send_job(job).await
+}
+
+pub async fn approve_join_request(
+  user_orbits: &UserOrbitPool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_membership: &OrbitMembershipPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  orbit_id: &Uuid,
+  target_user_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  let request = match orbit_membership.fetch_join_request(orbit_id, target_user_id).await? {
+    Some(request) => request,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if !user_orbits.user_is_member(target_user_id, orbit_id).await? {
+    user_orbits.create_user_orbit(orbit_id, target_user_id).await?;
+  }
+  orbit_membership.delete_join_request(orbit_id, target_user_id).await?;
+
+  match request.activity {
+    Some(activity) => {
+      queue_join_answer(
+        jobs,
+        queue,
+        FederateExtAction::GroupAcceptMember(*orbit_id, activity),
+        target_user_id,
+        user_id,
+      )
+      .await
+    }
+    None => Ok(()),
+  }
+}
+
+pub async fn reject_join_request(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_membership: &OrbitMembershipPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  orbit_id: &Uuid,
+  target_user_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  let request = match orbit_membership.fetch_join_request(orbit_id, target_user_id).await? {
+    Some(request) => request,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  orbit_membership.delete_join_request(orbit_id, target_user_id).await?;
+
+  match request.activity {
+    Some(activity) => {
+      queue_join_answer(
+        jobs,
+        queue,
+        FederateExtAction::GroupRejectMember(*orbit_id, activity),
+        target_user_id,
+        user_id,
+      )
+      .await
+    }
+    None => Ok(()),
+  }
+}
+
+pub async fn get_invites(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_membership: &OrbitMembershipPool,
+  orbit_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<Vec<OrbitInvite>, LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  orbit_membership.fetch_invites(orbit_id).await
+}
+
+pub async fn invite_user(
+  users: &UserPool,
+  user_orbits: &UserOrbitPool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_membership: &OrbitMembershipPool,
+  orbit_id: &Uuid,
+  target_user_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  if user_orbits.user_is_member(target_user_id, orbit_id).await? {
+    return Err(LogicErr::InvalidOperation("User is already a member".to_string()));
+  }
+
+  users.fetch_by_id(target_user_id).await?;
+
+  orbit_membership.create_invite(orbit_id, target_user_id, user_id).await
+}
+
+pub async fn revoke_invite(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_membership: &OrbitMembershipPool,
+  orbit_id: &Uuid,
+  target_user_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  orbit_membership.delete_invite(orbit_id, target_user_id).await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      job_repository::{JobPool, MockJobRepo},
+      notification_repository::{MockNotificationRepo, NotificationPool},
+      orbit_membership_repository::{MockOrbitMembershipRepo, OrbitMembershipPool},
+      orbit_moderator_repository::{MockOrbitModeratorRepo, OrbitModeratorPool},
+      user_orbit_repository::{MockUserOrbitRepo, UserOrbitPool},
+    },
+    logic::{
+      orbit_membership::{approve_join_request, join_orbit, reject_join_request, JoinOrbitResult},
+      LogicErr,
+    },
+    model::{fixtures, notification_type::NotificationType, orbit_join_policy::OrbitJoinPolicy},
+    streaming::pubsub::{MockPubSubBackend, PubSub},
+    work_queue::queue::{MockQueueBackend, Queue},
+  };
+
+  #[async_std::test]
+  async fn test_join_orbit_requests_approval() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let orbit = fixtures::orbit(orbit_id, OrbitJoinPolicy::ApprovalRequired);
+
+    let mut user_orbit_repo = MockUserOrbitRepo::new();
+    let mut orbit_membership_repo = MockOrbitMembershipRepo::new();
+
+    user_orbit_repo
+      .expect_user_is_member()
+      .times(1)
+      .with(eq(user_id), eq(orbit_id))
+      .return_const(Ok(false));
+
+    user_orbit_repo.expect_create_user_orbit().never();
+
+    orbit_membership_repo
+      .expect_user_is_invited()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .return_const(Ok(false));
+
+    orbit_membership_repo
+      .expect_create_join_request()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id), eq(None))
+      .return_const(Ok(()));
+
+    let mut notification_repo = MockNotificationRepo::new();
+    notification_repo
+      .expect_create_orbit_notification()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id), eq(NotificationType::OrbitJoinRequest))
+      .return_const(Ok(vec![]));
+
+    let user_orbits: UserOrbitPool = Arc::new(user_orbit_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(orbit_membership_repo);
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+
+    assert_eq!(
+      join_orbit(
+        &user_orbits,
+        &orbit_membership,
+        &notifications,
+        &pubsub,
+        &orbit,
+        &user_id
+      )
+      .await,
+      Ok(JoinOrbitResult::Requested)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_join_orbit_rejects_uninvited_users() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let orbit = fixtures::orbit(orbit_id, OrbitJoinPolicy::InviteOnly);
+
+    let mut user_orbit_repo = MockUserOrbitRepo::new();
+    let mut orbit_membership_repo = MockOrbitMembershipRepo::new();
+
+    user_orbit_repo.expect_user_is_member().times(1).return_const(Ok(false));
+    orbit_membership_repo
+      .expect_user_is_invited()
+      .times(1)
+      .return_const(Ok(false));
+
+    let user_orbits: UserOrbitPool = Arc::new(user_orbit_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(orbit_membership_repo);
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+
+    assert_eq!(
+      join_orbit(
+        &user_orbits,
+        &orbit_membership,
+        &notifications,
+        &pubsub,
+        &orbit,
+        &user_id
+      )
+      .await,
+      Err(LogicErr::UnauthorizedError)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_join_orbit_uses_up_invite() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let orbit = fixtures::orbit(orbit_id, OrbitJoinPolicy::InviteOnly);
+
+    let mut user_orbit_repo = MockUserOrbitRepo::new();
+    let mut orbit_membership_repo = MockOrbitMembershipRepo::new();
+
+    user_orbit_repo.expect_user_is_member().times(1).return_const(Ok(false));
+    user_orbit_repo
+      .expect_create_user_orbit()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .return_const(Ok(Uuid::new_v4()));
+
+    orbit_membership_repo
+      .expect_user_is_invited()
+      .times(1)
+      .return_const(Ok(true));
+    orbit_membership_repo
+      .expect_delete_invite()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .return_const(Ok(()));
+
+    let mut notification_repo = MockNotificationRepo::new();
+    notification_repo
+      .expect_create_orbit_notification()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id), eq(NotificationType::OrbitJoin))
+      .return_const(Ok(vec![]));
+
+    let user_orbits: UserOrbitPool = Arc::new(user_orbit_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(orbit_membership_repo);
+    let notifications: NotificationPool = Arc::new(notification_repo);
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+
+    assert_eq!(
+      join_orbit(
+        &user_orbits,
+        &orbit_membership,
+        &notifications,
+        &pubsub,
+        &orbit,
+        &user_id
+      )
+      .await,
+      Ok(JoinOrbitResult::Joined)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_approve_join_request_answers_remote_users() {
+    let user_id = Uuid::new_v4();
+    let target_user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+
+    let mut user_orbit_repo = MockUserOrbitRepo::new();
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+    let mut orbit_membership_repo = MockOrbitMembershipRepo::new();
+    let mut job_repo = MockJobRepo::new();
+    let mut queue_be = MockQueueBackend::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(true));
+
+    orbit_membership_repo
+      .expect_fetch_join_request()
+      .times(1)
+      .with(eq(orbit_id), eq(target_user_id))
+      .returning(move |_, _| {
+        Ok(Some(fixtures::orbit_join_request(
+          orbit_id,
+          target_user_id,
+          Some("{\"type\":\"Follow\"}".to_string()),
+        )))
+      });
+
+    orbit_membership_repo
+      .expect_delete_join_request()
+      .times(1)
+      .with(eq(orbit_id), eq(target_user_id))
+      .return_const(Ok(()));
+
+    user_orbit_repo.expect_user_is_member().times(1).return_const(Ok(false));
+    user_orbit_repo
+      .expect_create_user_orbit()
+      .times(1)
+      .with(eq(orbit_id), eq(target_user_id))
+      .return_const(Ok(Uuid::new_v4()));
+
+    job_repo.expect_create().times(1).return_const(Ok(Uuid::new_v4()));
+    queue_be.expect_send_job().times(1).return_const(Ok(()));
+
+    let user_orbits: UserOrbitPool = Arc::new(user_orbit_repo);
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(orbit_membership_repo);
+    let jobs: JobPool = Arc::new(job_repo);
+    let queue = Queue::new_inner(Box::new(queue_be));
+
+    assert!(approve_join_request(
+      &user_orbits,
+      &orbit_moderators,
+      &orbit_membership,
+      &jobs,
+      &queue,
+      &orbit_id,
+      &target_user_id,
+      &user_id,
+    )
+    .await
+    .is_ok());
+  }
+
+  #[async_std::test]
+  async fn test_reject_join_request_rejects_non_moderators() {
+    let user_id = Uuid::new_v4();
+    let target_user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(false));
+
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      reject_join_request(
+        &orbit_moderators,
+        &orbit_membership,
+        &jobs,
+        &queue,
+        &orbit_id,
+        &target_user_id,
+        &user_id,
+      )
+      .await,
+      Err(LogicErr::UnauthorizedError)
+    );
+  }
+}
@@ -0,0 +1,599 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+  activitypub::object::ObjectType,
+  db::{
+    comment_repository::CommentPool, job_repository::JobPool, orbit_moderation_repository::OrbitModerationPool,
+    orbit_moderator_repository::OrbitModeratorPool, post_repository::PostPool, tombstone_repository::TombstonePool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+  },
+  helpers::api::map_db_err,
+  model::{
+    job::{JobStatus, NewJob},
+    orbit_moderation::{ModerationAction, NewOrbitModlogEntry, OrbitBan, OrbitModlogEntry},
+    post::Post,
+    queue_job::{QueueJob, QueueJobType},
+  },
+  work_queue::queue::Queue,
+};
+
+use super::LogicErr;
+
+pub async fn assert_orbit_moderator(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  match orbit_moderators.user_is_moderator(orbit_id, user_id).await? {
+    true => Ok(()),
+    false => Err(LogicErr::UnauthorizedError),
+  }
+}
+
+async fn fetch_orbit_post(posts: &PostPool, orbit_id: &Uuid, post_id: &Uuid) -> Result<Post, LogicErr> {
+  match posts.find_optional_by_id(post_id).await {
+    Some(post) if post.orbit_id.as_ref() == Some(orbit_id) => Ok(post),
+    _ => Err(LogicErr::MissingRecord),
+  }
+}
+
+/// Removed objects are federated as coming from the orbit's Group actor rather than from their author, and the
+/// removal is sent to every remote member of the orbit
+async fn queue_orbit_removal(
+  jobs: &JobPool,
+  queue: &Queue,
+  orbit_id: &Uuid,
+  object_uri: &str,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(*user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(*orbit_id),
+      associated_record_id: None,
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::RemoveOrbitObject)
+    .context(vec![object_uri.to_string()])
+    .build();
+
+  queue.send_job(job).await
+}
+
+pub async fn remove_post(
+  posts: &PostPool,
+  tombstones: &TombstonePool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  orbit_id: &Uuid,
+  post_id: &Uuid,
+  reason: &Option<String>,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  let post = fetch_orbit_post(posts, orbit_id, post_id).await?;
+
+  posts.delete_post(post_id, &post.user_id).await?;
+  tombstones
+    .create_tombstone(&post.uri, &ObjectType::Article.to_string())
+    .await?;
+
+  orbit_moderation
+    .create_modlog_entry(&NewOrbitModlogEntry {
+      orbit_id: *orbit_id,
+      moderator_id: *user_id,
+      action: ModerationAction::RemovePost,
+      target_user_id: Some(post.user_id),
+      post_id: Some(*post_id),
+      comment_id: None,
+      reason: reason.clone(),
+    })
+    .await?;
+
+  queue_orbit_removal(jobs, queue, orbit_id, &post.uri, user_id).await
+}
+
+pub async fn remove_comment(
+  posts: &PostPool,
+  comments: &CommentPool,
+  tombstones: &TombstonePool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  orbit_id: &Uuid,
+  post_id: &Uuid,
+  comment_id: &Uuid,
+  reason: &Option<String>,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  fetch_orbit_post(posts, orbit_id, post_id).await?;
+
+  let comment = match comments.fetch_comment(post_id, comment_id, &None).await {
+    Some(comment) => comment,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  comments.delete_comment(&comment.user_id, post_id, comment_id).await?;
+  tombstones
+    .create_tombstone(&comment.uri, &ObjectType::Note.to_string())
+    .await?;
+
+  orbit_moderation
+    .create_modlog_entry(&NewOrbitModlogEntry {
+      orbit_id: *orbit_id,
+      moderator_id: *user_id,
+      action: ModerationAction::RemoveComment,
+      target_user_id: Some(comment.user_id),
+      post_id: Some(*post_id),
+      comment_id: Some(*comment_id),
+      reason: reason.clone(),
+    })
+    .await?;
+
+  queue_orbit_removal(jobs, queue, orbit_id, &comment.uri, user_id).await
+}
+
+pub async fn get_orbit_bans(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<Vec<OrbitBan>, LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  orbit_moderation.fetch_bans(orbit_id).await
+}
+
+/// Bans a user from an orbit, which also removes them from its members. Bans without an expiry are permanent.
+pub async fn ban_user(
+  users: &UserPool,
+  user_orbits: &UserOrbitPool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_id: &Uuid,
+  target_user_id: &Uuid,
+  reason: &Option<String>,
+  expires_at: &Option<DateTime<Utc>>,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  if target_user_id == user_id {
+    return Err(LogicErr::InvalidOperation("You can't ban yourself".to_string()));
+  }
+
+  if let Some(expires_at) = expires_at {
+    if expires_at <= &Utc::now() {
+      return Err(LogicErr::InvalidOperation("Expiry must be in the future".to_string()));
+    }
+  }
+
+  if orbit_moderators.user_is_moderator(orbit_id, target_user_id).await? {
+    return Err(LogicErr::InvalidOperation("Moderators can't be banned".to_string()));
+  }
+
+  users.fetch_by_id(target_user_id).await?;
+
+  orbit_moderation
+    .upsert_ban(orbit_id, target_user_id, reason, expires_at, user_id)
+    .await?;
+  user_orbits.delete_user_orbit(orbit_id, target_user_id).await?;
+
+  orbit_moderation
+    .create_modlog_entry(&NewOrbitModlogEntry {
+      orbit_id: *orbit_id,
+      moderator_id: *user_id,
+      action: ModerationAction::BanUser,
+      target_user_id: Some(*target_user_id),
+      post_id: None,
+      comment_id: None,
+      reason: reason.clone(),
+    })
+    .await
+}
+
+pub async fn unban_user(
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_id: &Uuid,
+  target_user_id: &Uuid,
+  reason: &Option<String>,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  orbit_moderation.delete_ban(orbit_id, target_user_id).await?;
+
+  orbit_moderation
+    .create_modlog_entry(&NewOrbitModlogEntry {
+      orbit_id: *orbit_id,
+      moderator_id: *user_id,
+      action: ModerationAction::UnbanUser,
+      target_user_id: Some(*target_user_id),
+      post_id: None,
+      comment_id: None,
+      reason: reason.clone(),
+    })
+    .await
+}
+
+pub async fn lock_post(
+  posts: &PostPool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_id: &Uuid,
+  post_id: &Uuid,
+  is_locked: bool,
+  reason: &Option<String>,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  let post = fetch_orbit_post(posts, orbit_id, post_id).await?;
+
+  orbit_moderation.update_post_locked(post_id, is_locked).await?;
+
+  orbit_moderation
+    .create_modlog_entry(&NewOrbitModlogEntry {
+      orbit_id: *orbit_id,
+      moderator_id: *user_id,
+      action: match is_locked {
+        true => ModerationAction::LockPost,
+        false => ModerationAction::UnlockPost,
+      },
+      target_user_id: Some(post.user_id),
+      post_id: Some(*post_id),
+      comment_id: None,
+      reason: reason.clone(),
+    })
+    .await
+}
+
+/// Pinned posts are shown ahead of the rest of the orbit's feed, most recently pinned first
+pub async fn pin_post(
+  posts: &PostPool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_id: &Uuid,
+  post_id: &Uuid,
+  is_pinned: bool,
+  reason: &Option<String>,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  assert_orbit_moderator(orbit_moderators, orbit_id, user_id).await?;
+
+  let post = fetch_orbit_post(posts, orbit_id, post_id).await?;
+
+  orbit_moderation.update_post_pinned(post_id, is_pinned).await?;
+
+  orbit_moderation
+    .create_modlog_entry(&NewOrbitModlogEntry {
+      orbit_id: *orbit_id,
+      moderator_id: *user_id,
+      action: match is_pinned {
+        true => ModerationAction::PinPost,
+        false => ModerationAction::UnpinPost,
+      },
+      target_user_id: Some(post.user_id),
+      post_id: Some(*post_id),
+      comment_id: None,
+      reason: reason.clone(),
+    })
+    .await
+}
+
+pub async fn get_modlog(
+  orbit_moderation: &OrbitModerationPool,
+  orbit_id: &Uuid,
+  limit: i64,
+  skip: i64,
+) -> Result<Vec<OrbitModlogEntry>, LogicErr> {
+  orbit_moderation.fetch_modlog(orbit_id, limit, skip).await
+}
+
+pub async fn get_modlog_count(orbit_moderation: &OrbitModerationPool, orbit_id: &Uuid) -> Result<i64, LogicErr> {
+  orbit_moderation.fetch_modlog_count(orbit_id).await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use chrono::{Duration, Utc};
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      comment_repository::{CommentPool, MockCommentRepo},
+      job_repository::{JobPool, MockJobRepo},
+      orbit_moderation_repository::{MockOrbitModerationRepo, OrbitModerationPool},
+      orbit_moderator_repository::{MockOrbitModeratorRepo, OrbitModeratorPool},
+      post_repository::{MockPostRepo, PostPool},
+      tombstone_repository::{MockTombstoneRepo, TombstonePool},
+      user_orbit_repository::{MockUserOrbitRepo, UserOrbitPool},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    logic::{
+      orbit_moderation::{ban_user, lock_post, remove_comment, remove_post},
+      LogicErr,
+    },
+    model::{fixtures, orbit_moderation::ModerationAction},
+    work_queue::queue::{MockQueueBackend, Queue},
+  };
+
+  #[async_std::test]
+  async fn test_remove_post_rejects_non_moderators() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(false));
+
+    let posts: PostPool = Arc::new(MockPostRepo::new());
+    let tombstones: TombstonePool = Arc::new(MockTombstoneRepo::new());
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      remove_post(
+        &posts,
+        &tombstones,
+        &orbit_moderators,
+        &orbit_moderation,
+        &jobs,
+        &queue,
+        &orbit_id,
+        &post_id,
+        &None,
+        &user_id,
+      )
+      .await
+      .err(),
+      Some(LogicErr::UnauthorizedError)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_remove_post_deletes_tombstones_and_logs() {
+    let user_id = Uuid::new_v4();
+    let poster_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let job_id = Uuid::new_v4();
+
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+    let mut post_repo = MockPostRepo::new();
+    let mut tombstone_repo = MockTombstoneRepo::new();
+    let mut orbit_moderation_repo = MockOrbitModerationRepo::new();
+    let mut job_repo = MockJobRepo::new();
+    let mut queue_be = MockQueueBackend::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(true));
+
+    post_repo
+      .expect_find_optional_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(move |_| Some(fixtures::post(post_id, poster_id, Some(orbit_id))));
+
+    post_repo
+      .expect_delete_post()
+      .times(1)
+      .with(eq(post_id), eq(poster_id))
+      .return_const(Ok(()));
+
+    tombstone_repo
+      .expect_create_tombstone()
+      .times(1)
+      .with(eq(format!("/feed/{}", post_id)), always())
+      .return_const(Ok(Uuid::new_v4()));
+
+    orbit_moderation_repo
+      .expect_create_modlog_entry()
+      .times(1)
+      .withf(move |entry| {
+        entry.action == ModerationAction::RemovePost
+          && entry.target_user_id == Some(poster_id)
+          && entry.post_id == Some(post_id)
+          && entry.reason == Some("Spam".to_string())
+      })
+      .return_const(Ok(()));
+
+    job_repo.expect_create().times(1).return_const(Ok(job_id));
+    queue_be.expect_send_job().times(1).return_const(Ok(()));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let tombstones: TombstonePool = Arc::new(tombstone_repo);
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(orbit_moderation_repo);
+    let jobs: JobPool = Arc::new(job_repo);
+    let queue = Queue::new_inner(Box::new(queue_be));
+
+    assert!(remove_post(
+      &posts,
+      &tombstones,
+      &orbit_moderators,
+      &orbit_moderation,
+      &jobs,
+      &queue,
+      &orbit_id,
+      &post_id,
+      &Some("Spam".to_string()),
+      &user_id,
+    )
+    .await
+    .is_ok());
+  }
+
+  #[async_std::test]
+  async fn test_remove_comment_rejects_posts_outside_orbit() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let comment_id = Uuid::new_v4();
+
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+    let mut post_repo = MockPostRepo::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(true));
+
+    post_repo
+      .expect_find_optional_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(move |_| Some(fixtures::post(post_id, Uuid::new_v4(), Some(Uuid::new_v4()))));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let tombstones: TombstonePool = Arc::new(MockTombstoneRepo::new());
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      remove_comment(
+        &posts,
+        &comments,
+        &tombstones,
+        &orbit_moderators,
+        &orbit_moderation,
+        &jobs,
+        &queue,
+        &orbit_id,
+        &post_id,
+        &comment_id,
+        &None,
+        &user_id,
+      )
+      .await
+      .err(),
+      Some(LogicErr::MissingRecord)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_ban_user_rejects_banning_moderators() {
+    let user_id = Uuid::new_v4();
+    let target_user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(true));
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(target_user_id))
+      .returning(|_, _| Ok(true));
+
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let user_orbits: UserOrbitPool = Arc::new(MockUserOrbitRepo::new());
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+
+    assert_eq!(
+      ban_user(
+        &users,
+        &user_orbits,
+        &orbit_moderators,
+        &orbit_moderation,
+        &orbit_id,
+        &target_user_id,
+        &None,
+        &Some(Utc::now() + Duration::days(1)),
+        &user_id,
+      )
+      .await
+      .err(),
+      Some(LogicErr::InvalidOperation("Moderators can't be banned".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_lock_post_updates_and_logs() {
+    let user_id = Uuid::new_v4();
+    let poster_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+    let mut post_repo = MockPostRepo::new();
+    let mut orbit_moderation_repo = MockOrbitModerationRepo::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(true));
+
+    post_repo
+      .expect_find_optional_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(move |_| Some(fixtures::post(post_id, poster_id, Some(orbit_id))));
+
+    orbit_moderation_repo
+      .expect_update_post_locked()
+      .times(1)
+      .with(eq(post_id), eq(true))
+      .return_const(Ok(()));
+
+    orbit_moderation_repo
+      .expect_create_modlog_entry()
+      .times(1)
+      .withf(move |entry| entry.action == ModerationAction::LockPost && entry.post_id == Some(post_id))
+      .return_const(Ok(()));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(orbit_moderation_repo);
+
+    assert!(lock_post(
+      &posts,
+      &orbit_moderators,
+      &orbit_moderation,
+      &orbit_id,
+      &post_id,
+      true,
+      &None,
+      &user_id,
+    )
+    .await
+    .is_ok());
+  }
+}
@@ -5,22 +5,32 @@ use serde::{Deserialize, Serialize};
 use std::{pin::Pin, str::FromStr};
 use uuid::Uuid;
 
-use super::LogicErr;
+use super::{
+  mention::{record_mentions, render_mentions, resolve_mentions},
+  tag::{parse_tags, record_tags, render_tags},
+  LogicErr,
+};
 use crate::{
   activitypub::object::ObjectType,
   cdn::cdn_store::Cdn,
   db::{
-    job_repository::JobPool, post_attachment_repository::PostAttachmentPool, post_repository::PostPool,
-    tombstone_repository::TombstonePool,
+    account_restriction_repository::AccountRestrictionPool, follow_repository::FollowPool, job_repository::JobPool,
+    notification_repository::NotificationPool, orbit_membership_repository::OrbitMembershipPool,
+    orbit_moderation_repository::OrbitModerationPool, post_attachment_repository::PostAttachmentPool,
+    post_mention_repository::PostMentionPool, post_repository::PostPool, tag_repository::TagPool,
+    tombstone_repository::TombstonePool, user_repository::UserPool,
   },
   helpers::api::{map_db_err, map_ext_err},
   model::{
     access_type::AccessType,
+    account_restriction::AccountRestrictionType,
     job::{JobStatus, NewJob},
+    page_cursor::PageCursor,
     post_attachment::PostAttachment,
     post_event::PostEvent,
     queue_job::{QueueJob, QueueJobType},
   },
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
@@ -41,25 +51,27 @@ pub struct NewPostResponse {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CreatePostResult {
   WaitingForImages(Uuid),
-  JobQueued(Uuid),
+  JobQueued { job_id: Uuid, post_id: Uuid },
 }
 
 pub async fn get_user_posts(
   user_id: &Uuid,
+  cursor: &PageCursor,
   limit: i64,
   skip: i64,
   posts: &PostPool,
 ) -> Result<Vec<PostEvent>, LogicErr> {
-  posts.fetch_user_own_feed(user_id, limit, skip).await
+  posts.fetch_user_own_feed(user_id, cursor, limit, skip).await
 }
 
 pub async fn get_user_friends_posts(
   user_id: &Uuid,
+  cursor: &PageCursor,
   limit: i64,
   skip: i64,
   posts: &PostPool,
 ) -> Result<Vec<PostEvent>, LogicErr> {
-  posts.fetch_user_friends_feed(user_id, limit, skip).await
+  posts.fetch_user_friends_feed(user_id, cursor, limit, skip).await
 }
 
 pub async fn get_post(post_id: &Uuid, user_id: &Option<Uuid>, posts: &PostPool) -> Result<Option<PostEvent>, LogicErr> {
@@ -74,34 +86,99 @@ pub async fn get_user_friends_posts_count(user_id: &Uuid, posts: &PostPool) -> R
   posts.count_user_friends_feed(user_id).await
 }
 
-pub async fn get_global_posts(limit: i64, skip: i64, posts: &PostPool) -> Result<Vec<PostEvent>, LogicErr> {
-  posts.fetch_global_federated_feed(limit, skip).await
+pub async fn get_global_posts(
+  own_user_id: &Option<Uuid>,
+  cursor: &PageCursor,
+  limit: i64,
+  skip: i64,
+  posts: &PostPool,
+) -> Result<Vec<PostEvent>, LogicErr> {
+  posts
+    .fetch_global_federated_feed(own_user_id, cursor, limit, skip)
+    .await
 }
 
-pub async fn get_global_posts_count(posts: &PostPool) -> Result<i64, LogicErr> {
-  posts.count_global_federated_feed().await
+pub async fn get_global_posts_count(own_user_id: &Option<Uuid>, posts: &PostPool) -> Result<i64, LogicErr> {
+  posts.count_global_federated_feed(own_user_id).await
 }
 
 pub async fn create_post(
   posts: &PostPool,
+  users: &UserPool,
+  post_mentions: &PostMentionPool,
+  tags: &TagPool,
+  account_restrictions: &AccountRestrictionPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_membership: &OrbitMembershipPool,
+  notifications: &NotificationPool,
+  pubsub: &PubSub,
   jobs: &JobPool,
   queue: &Queue,
   req: &NewPostRequest,
   user_id: &Uuid,
 ) -> Result<CreatePostResult, LogicErr> {
-  let content_html = markdown::to_html(&req.content_md);
+  match &req.orbit_id {
+    Some(orbit_id) => {
+      if orbit_moderation.user_is_banned(orbit_id, user_id).await? {
+        return Err(LogicErr::UnauthorizedError);
+      }
+
+      if !orbit_membership.user_can_post(orbit_id, user_id).await? {
+        return Err(LogicErr::UnauthorizedError);
+      }
+    }
+    None => {
+      if req.visibility == AccessType::MembersOnly {
+        return Err(LogicErr::InvalidOperation(
+          "Members-only posts must be made in an orbit".to_string(),
+        ));
+      }
+    }
+  }
+
+  let mentions = resolve_mentions(users, &req.content_md).await;
+  let post_tags = parse_tags(&req.content_md);
+  let content_html = render_tags(
+    &render_mentions(&markdown::to_html(&req.content_md), &mentions),
+    &post_tags,
+  );
+
+  // Shadow restricted users keep posting as normal, but nobody other than themselves gets to see it
+  let visibility = match account_restrictions
+    .user_has_restriction(user_id, &AccountRestrictionType::Shadow)
+    .await?
+  {
+    true => AccessType::Shadow,
+    false => req.visibility.clone(),
+  };
 
   let post_id = posts
     .create_post(
       user_id,
       &req.content_md,
       &content_html,
-      &req.visibility,
+      &visibility,
       &req.orbit_id,
       &req.title,
     )
     .await?;
 
+  let mentioned_user_ids: Vec<Uuid> = mentions.iter().map(|m| m.user.user_id).collect();
+  record_mentions(
+    post_mentions,
+    notifications,
+    pubsub,
+    &post_id,
+    user_id,
+    &visibility,
+    &mentioned_user_ids,
+  )
+  .await;
+
+  if !post_tags.is_empty() {
+    record_tags(tags, &post_id, &post_tags).await;
+  }
+
   if req.attachment_count > 0 {
     return Ok(CreatePostResult::WaitingForImages(post_id));
   }
@@ -122,18 +199,20 @@ pub async fn create_post(
     .build();
 
   match queue.send_job(job).await {
-    Ok(_) => Ok(CreatePostResult::JobQueued(job_id)),
+    Ok(_) => Ok(CreatePostResult::JobQueued { job_id, post_id }),
     Err(err) => Err(err),
   }
 }
 
-async fn upload_post_file(
+/// Uploads an image for a post, or for a post that's yet to be made when `post_id` is missing. Only PNG and JPEG
+/// images are accepted.
+pub async fn upload_attachment(
   post_attachments: &PostAttachmentPool,
-  post_id: &Uuid,
+  post_id: &Option<Uuid>,
   user_id: &Uuid,
   cdn: &Cdn,
   upload: &Tempfile,
-) -> Result<(), LogicErr> {
+) -> Result<PostAttachment, LogicErr> {
   let file_name = match upload.file_name.to_owned() {
     Some(name) => name,
     None => return Err(LogicErr::InvalidData),
@@ -172,8 +251,52 @@ async fn upload_post_file(
     created_at: Utc::now(),
   };
 
-  post_attachments.create_attachment_from(attachment).await?;
-  Ok(())
+  post_attachments.create_attachment_from(attachment.clone()).await?;
+  Ok(attachment)
+}
+
+/// Processes a post's new images and then publishes it, returning the ID of the job that publishes it
+async fn queue_post_image_jobs(
+  jobs: &JobPool,
+  queue: &Queue,
+  post_id: &Uuid,
+  user_id: &Uuid,
+) -> Result<Uuid, LogicErr> {
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(*user_id),
+      status: JobStatus::NotStarted,
+      record_id: Some(*post_id),
+      associated_record_id: None,
+    })
+    .await?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::ConvertNewPostImages)
+    .build();
+
+  queue.send_job(job).await?;
+
+  let job_id = jobs
+    .create(NewJob {
+      created_by_id: Some(user_id.to_owned()),
+      status: JobStatus::NotStarted,
+      record_id: Some(post_id.to_owned()),
+      associated_record_id: None,
+    })
+    .await
+    .map_err(map_db_err)?;
+
+  let job = QueueJob::builder()
+    .job_id(job_id)
+    .job_type(QueueJobType::CreatePostEvents)
+    .build();
+
+  match queue.send_job(job).await {
+    Ok(_) => Ok(job_id),
+    Err(err) => Err(err),
+  }
 }
 
 pub async fn upload_post_files(
@@ -193,13 +316,14 @@ pub async fn upload_post_files(
   // This type is complex, yes, but also unavoidable due to the types we have to work with here
   #[allow(clippy::type_complexity)]
   let mut futures: Vec<
-    Pin<Box<dyn futures_util::Future<Output = std::result::Result<(), LogicErr>> + std::marker::Send>>,
+    Pin<Box<dyn futures_util::Future<Output = std::result::Result<PostAttachment, LogicErr>> + std::marker::Send>>,
   > = vec![];
 
+  let attach_to = Some(*post_id);
   for upload in uploads {
-    futures.push(Box::pin(upload_post_file(
+    futures.push(Box::pin(upload_attachment(
       post_attachments,
-      post_id,
+      &attach_to,
       user_id,
       cdn,
       upload,
@@ -221,6 +345,92 @@ pub async fn upload_post_files(
     return Err(LogicErr::InternalError("Failed to process all attachments".to_owned()));
   }
 
+  queue_post_image_jobs(jobs, queue, post_id, user_id).await
+}
+
+/// Checks every image uploaded ahead of time for a new post belongs to the user and isn't attached to another post, so
+/// the post isn't created only to fail attaching them
+pub async fn validate_post_media(
+  post_attachments: &PostAttachmentPool,
+  user_id: &Uuid,
+  attachment_ids: &[Uuid],
+) -> Result<(), LogicErr> {
+  if post_attachments.count_unattached(user_id, attachment_ids).await? != attachment_ids.len() as i64 {
+    return Err(LogicErr::InvalidOperation(
+      "Some of the attachments could not be found".to_string(),
+    ));
+  }
+
+  Ok(())
+}
+
+/// Attaches images uploaded ahead of time to a post that's waiting for them, then publishes it
+pub async fn attach_post_media(
+  posts: &PostPool,
+  jobs: &JobPool,
+  post_attachments: &PostAttachmentPool,
+  queue: &Queue,
+  post_id: &Uuid,
+  user_id: &Uuid,
+  attachment_ids: &[Uuid],
+) -> Result<Uuid, LogicErr> {
+  if !posts.user_owns_post(user_id, post_id).await {
+    return Err(LogicErr::UnauthorizedError);
+  }
+
+  if post_attachments
+    .attach_to_post(user_id, post_id, attachment_ids)
+    .await?
+    != attachment_ids.len() as u64
+  {
+    return Err(LogicErr::InvalidOperation(
+      "Some of the attachments could not be found".to_string(),
+    ));
+  }
+
+  queue_post_image_jobs(jobs, queue, post_id, user_id).await
+}
+
+/// Whether a user, or a signed out visitor when `user_id` is missing, is allowed to see a post
+pub async fn user_can_view_post(
+  follows: &FollowPool,
+  orbit_membership: &OrbitMembershipPool,
+  post: &PostEvent,
+  user_id: &Option<Uuid>,
+) -> bool {
+  if post.visibility == AccessType::PublicFederated
+    || post.visibility == AccessType::PublicLocal
+    || post.visibility == AccessType::Unlisted
+  {
+    return true;
+  }
+
+  let user_id = match user_id {
+    Some(user_id) => user_id,
+    None => return false,
+  };
+
+  if &post.user_id == user_id {
+    return true;
+  }
+
+  match post.visibility {
+    AccessType::FollowersOnly => follows.user_follows_poster(&post.post_id, user_id).await,
+    AccessType::MembersOnly => orbit_membership
+      .user_is_member_for_post(&post.post_id, user_id)
+      .await
+      .unwrap_or(false),
+    _ => false,
+  }
+}
+
+async fn queue_boost_job(
+  jobs: &JobPool,
+  queue: &Queue,
+  post_id: &Uuid,
+  user_id: &Uuid,
+  job_type: QueueJobType,
+) -> Result<Uuid, LogicErr> {
   let job_id = jobs
     .create(NewJob {
       created_by_id: Some(*user_id),
@@ -230,32 +440,18 @@ pub async fn upload_post_files(
     })
     .await?;
 
-  let job = QueueJob::builder()
-    .job_id(job_id)
-    .job_type(QueueJobType::ConvertNewPostImages)
-    .build();
+  let job = QueueJob::builder().job_id(job_id).job_type(job_type).build();
 
   queue.send_job(job).await?;
+  Ok(job_id)
+}
 
-  let job_id = jobs
-    .create(NewJob {
-      created_by_id: Some(user_id.to_owned()),
-      status: JobStatus::NotStarted,
-      record_id: Some(post_id.to_owned()),
-      associated_record_id: None,
-    })
-    .await
-    .map_err(map_db_err)?;
-
-  let job = QueueJob::builder()
-    .job_id(job_id)
-    .job_type(QueueJobType::CreatePostEvents)
-    .build();
+pub async fn boost_post(jobs: &JobPool, queue: &Queue, post_id: &Uuid, user_id: &Uuid) -> Result<Uuid, LogicErr> {
+  queue_boost_job(jobs, queue, post_id, user_id, QueueJobType::CreateBoostEvents).await
+}
 
-  match queue.send_job(job).await {
-    Ok(_) => Ok(job_id),
-    Err(err) => Err(err),
-  }
+pub async fn unboost_post(jobs: &JobPool, queue: &Queue, post_id: &Uuid, user_id: &Uuid) -> Result<Uuid, LogicErr> {
+  queue_boost_job(jobs, queue, post_id, user_id, QueueJobType::DeleteBoostEvents).await
 }
 
 pub async fn delete_post(
@@ -292,9 +488,11 @@ pub async fn delete_post(
     .await
     .map_err(map_db_err)?;
 
+  // The post's URI is passed along, as the job runs after the post is gone from our DB
   let job = QueueJob::builder()
     .job_id(job_id)
     .job_type(QueueJobType::DeletePost)
+    .context(vec![post.uri])
     .build();
 
   queue.send_job(job).await
@@ -313,18 +511,30 @@ mod tests {
   use crate::{
     cdn::cdn_store::{Cdn, MockCdnStore},
     db::{
+      account_restriction_repository::{AccountRestrictionPool, MockAccountRestrictionRepo},
+      follow_repository::{FollowPool, MockFollowRepo},
       job_repository::{JobPool, MockJobRepo},
+      notification_repository::{MockNotificationRepo, NotificationPool},
+      orbit_membership_repository::{MockOrbitMembershipRepo, OrbitMembershipPool},
+      orbit_moderation_repository::{MockOrbitModerationRepo, OrbitModerationPool},
       post_attachment_repository::{MockPostAttachmentRepo, PostAttachmentPool},
+      post_mention_repository::{MockPostMentionRepo, PostMentionPool},
       post_repository::{MockPostRepo, PostPool},
+      tag_repository::{MockTagRepo, TagPool},
+      user_repository::{MockUserRepo, UserPool},
     },
     logic::{
       post::{
-        create_post, get_global_posts, get_global_posts_count, get_post, get_user_posts, get_user_posts_count,
-        upload_post_files, NewPostRequest,
+        attach_post_media, create_post, get_global_posts, get_global_posts_count, get_post, get_user_posts,
+        get_user_posts_count, upload_post_files, user_can_view_post, validate_post_media, NewPostRequest,
       },
       LogicErr,
     },
-    model::{access_type::AccessType, event_type::EventType, post_event::PostEvent},
+    model::{
+      access_type::AccessType, account_restriction::AccountRestrictionType, event_type::EventType,
+      page_cursor::PageCursor, post_event::PostEvent,
+    },
+    streaming::pubsub::{MockPubSubBackend, PubSub},
     work_queue::queue::{MockQueueBackend, Queue},
   };
 
@@ -336,13 +546,13 @@ mod tests {
     post_repo
       .expect_fetch_user_own_feed()
       .times(1)
-      .with(eq(user_id), eq(1), eq(2))
-      .returning(|_, _, _| Err(LogicErr::DbError("Boop".to_string())));
+      .with(eq(user_id), eq(PageCursor::default()), eq(1), eq(2))
+      .returning(|_, _, _, _| Err(LogicErr::DbError("Boop".to_string())));
 
     let posts: PostPool = Arc::new(post_repo);
 
     assert_eq!(
-      get_user_posts(&user_id, 1, 2, &posts).await,
+      get_user_posts(&user_id, &PageCursor::default(), 1, 2, &posts).await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -355,12 +565,15 @@ mod tests {
     post_repo
       .expect_fetch_user_own_feed()
       .times(1)
-      .with(eq(user_id), eq(1), eq(2))
-      .returning(|_, _, _| Ok(vec![]));
+      .with(eq(user_id), eq(PageCursor::default()), eq(1), eq(2))
+      .returning(|_, _, _, _| Ok(vec![]));
 
     let posts: PostPool = Arc::new(post_repo);
 
-    assert_eq!(get_user_posts(&user_id, 1, 2, &posts).await, Ok(vec![]));
+    assert_eq!(
+      get_user_posts(&user_id, &PageCursor::default(), 1, 2, &posts).await,
+      Ok(vec![])
+    );
   }
 
   #[async_std::test]
@@ -396,6 +609,9 @@ mod tests {
       content_md: "a".to_string(),
       content_html: "a".to_string(),
       visibility: AccessType::PublicFederated,
+      sensitive: false,
+      is_locked: false,
+      pinned_at: None,
       created_at: Utc::now(),
       updated_at: Utc::now(),
       user_id: user_id.unwrap(),
@@ -411,6 +627,8 @@ mod tests {
       liked: Some(false),
       comments: 1,
       attachments: vec![],
+      mentions: vec![],
+      tags: vec![],
       orbit_id: None,
       orbit_name: None,
       orbit_uri: None,
@@ -472,13 +690,13 @@ mod tests {
     post_repo
       .expect_fetch_global_federated_feed()
       .times(1)
-      .with(eq(1), eq(2))
+      .with(eq(None), eq(PageCursor::default()), eq(1), eq(2))
       .return_const(Err(LogicErr::DbError("Boop".to_string())));
 
     let posts: PostPool = Arc::new(post_repo);
 
     assert_eq!(
-      get_global_posts(1, 2, &posts).await,
+      get_global_posts(&None, &PageCursor::default(), 1, 2, &posts).await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -489,12 +707,15 @@ mod tests {
     post_repo
       .expect_fetch_global_federated_feed()
       .times(1)
-      .with(eq(1), eq(2))
+      .with(eq(None), eq(PageCursor::default()), eq(1), eq(2))
       .return_const(Ok(vec![]));
 
     let posts: PostPool = Arc::new(post_repo);
 
-    assert_eq!(get_global_posts(1, 2, &posts).await, Ok(vec![]));
+    assert_eq!(
+      get_global_posts(&None, &PageCursor::default(), 1, 2, &posts).await,
+      Ok(vec![])
+    );
   }
 
   #[async_std::test]
@@ -503,12 +724,13 @@ mod tests {
     post_repo
       .expect_count_global_federated_feed()
       .times(1)
+      .with(eq(None))
       .return_const(Err(LogicErr::DbError("Boop".to_string())));
 
     let posts: PostPool = Arc::new(post_repo);
 
     assert_eq!(
-      get_global_posts_count(&posts).await,
+      get_global_posts_count(&None, &posts).await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -519,11 +741,12 @@ mod tests {
     post_repo
       .expect_count_global_federated_feed()
       .times(1)
+      .with(eq(None))
       .return_const(Ok(123));
 
     let posts: PostPool = Arc::new(post_repo);
 
-    assert_eq!(get_global_posts_count(&posts).await, Ok(123));
+    assert_eq!(get_global_posts_count(&None, &posts).await, Ok(123));
   }
 
   #[async_std::test]
@@ -541,8 +764,16 @@ mod tests {
     let visibility_eq = new_post.visibility.clone();
 
     let mut post_repo = MockPostRepo::new();
+    let mut account_restriction_repo = MockAccountRestrictionRepo::new();
     let jobs: JobPool = Arc::new(MockJobRepo::new());
     let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    account_restriction_repo
+      .expect_user_has_restriction()
+      .with(eq(user_id), eq(AccountRestrictionType::Shadow))
+      .times(1)
+      .return_const(Ok(false));
+
     post_repo
       .expect_create_post()
       .with(
@@ -557,9 +788,32 @@ mod tests {
       .return_const(Err(LogicErr::DbError("Boop".to_string())));
 
     let posts: PostPool = Arc::new(post_repo);
+    let account_restrictions: AccountRestrictionPool = Arc::new(account_restriction_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let post_mentions: PostMentionPool = Arc::new(MockPostMentionRepo::new());
+    let tags: TagPool = Arc::new(MockTagRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
 
     assert_eq!(
-      create_post(&posts, &jobs, &queue, &new_post, &user_id).await,
+      create_post(
+        &posts,
+        &users,
+        &post_mentions,
+        &tags,
+        &account_restrictions,
+        &orbit_moderation,
+        &orbit_membership,
+        &notifications,
+        &pubsub,
+        &jobs,
+        &queue,
+        &new_post,
+        &user_id
+      )
+      .await,
       Err(LogicErr::DbError("Boop".to_string()))
     );
   }
@@ -581,9 +835,16 @@ mod tests {
     let visibility_eq = new_post.visibility.clone();
 
     let mut post_repo = MockPostRepo::new();
+    let mut account_restriction_repo = MockAccountRestrictionRepo::new();
     let mut job_repo = MockJobRepo::new();
     let mut queue_be = MockQueueBackend::new();
 
+    account_restriction_repo
+      .expect_user_has_restriction()
+      .with(eq(user_id), eq(AccountRestrictionType::Shadow))
+      .times(1)
+      .return_const(Ok(false));
+
     post_repo
       .expect_create_post()
       .with(
@@ -606,10 +867,217 @@ mod tests {
     queue_be.expect_send_job().with(always()).times(1).return_const(Ok(()));
 
     let posts: PostPool = Arc::new(post_repo);
+    let account_restrictions: AccountRestrictionPool = Arc::new(account_restriction_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let post_mentions: PostMentionPool = Arc::new(MockPostMentionRepo::new());
+    let tags: TagPool = Arc::new(MockTagRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
     let jobs: JobPool = Arc::new(job_repo);
     let queue = Queue::new_inner(Box::new(queue_be));
 
-    assert!(create_post(&posts, &jobs, &queue, &new_post, &user_id).await.is_ok(),);
+    assert!(create_post(
+      &posts,
+      &users,
+      &post_mentions,
+      &tags,
+      &account_restrictions,
+      &orbit_moderation,
+      &orbit_membership,
+      &notifications,
+      &pubsub,
+      &jobs,
+      &queue,
+      &new_post,
+      &user_id
+    )
+    .await
+    .is_ok(),);
+  }
+
+  #[async_std::test]
+  async fn create_post_forces_shadow_visibility_for_shadow_restricted_users() {
+    let user_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let job_id = Uuid::new_v4();
+
+    let new_post = NewPostRequest {
+      content_md: "hello".to_string(),
+      visibility: AccessType::PublicFederated,
+      orbit_id: None,
+      attachment_count: 0,
+      title: None,
+    };
+
+    let mut post_repo = MockPostRepo::new();
+    let mut account_restriction_repo = MockAccountRestrictionRepo::new();
+    let mut job_repo = MockJobRepo::new();
+    let mut queue_be = MockQueueBackend::new();
+
+    account_restriction_repo
+      .expect_user_has_restriction()
+      .with(eq(user_id), eq(AccountRestrictionType::Shadow))
+      .times(1)
+      .return_const(Ok(true));
+
+    post_repo
+      .expect_create_post()
+      .with(
+        eq(user_id),
+        always(),
+        always(),
+        eq(AccessType::Shadow),
+        eq(None),
+        eq(None),
+      )
+      .times(1)
+      .return_const(Ok(post_id));
+
+    job_repo
+      .expect_create()
+      .with(always())
+      .times(1)
+      .return_const(Ok(job_id));
+
+    queue_be.expect_send_job().with(always()).times(1).return_const(Ok(()));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let account_restrictions: AccountRestrictionPool = Arc::new(account_restriction_repo);
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let post_mentions: PostMentionPool = Arc::new(MockPostMentionRepo::new());
+    let tags: TagPool = Arc::new(MockTagRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let jobs: JobPool = Arc::new(job_repo);
+    let queue = Queue::new_inner(Box::new(queue_be));
+
+    assert!(create_post(
+      &posts,
+      &users,
+      &post_mentions,
+      &tags,
+      &account_restrictions,
+      &orbit_moderation,
+      &orbit_membership,
+      &notifications,
+      &pubsub,
+      &jobs,
+      &queue,
+      &new_post,
+      &user_id
+    )
+    .await
+    .is_ok());
+  }
+
+  #[async_std::test]
+  async fn create_post_rejects_members_only_posts_outside_orbits() {
+    let user_id = Uuid::new_v4();
+
+    let new_post = NewPostRequest {
+      content_md: "hello".to_string(),
+      visibility: AccessType::MembersOnly,
+      orbit_id: None,
+      attachment_count: 0,
+      title: None,
+    };
+
+    let posts: PostPool = Arc::new(MockPostRepo::new());
+    let account_restrictions: AccountRestrictionPool = Arc::new(MockAccountRestrictionRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(MockOrbitModerationRepo::new());
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let post_mentions: PostMentionPool = Arc::new(MockPostMentionRepo::new());
+    let tags: TagPool = Arc::new(MockTagRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert!(matches!(
+      create_post(
+        &posts,
+        &users,
+        &post_mentions,
+        &tags,
+        &account_restrictions,
+        &orbit_moderation,
+        &orbit_membership,
+        &notifications,
+        &pubsub,
+        &jobs,
+        &queue,
+        &new_post,
+        &user_id
+      )
+      .await,
+      Err(LogicErr::InvalidOperation(_))
+    ));
+  }
+
+  #[async_std::test]
+  async fn create_post_rejects_non_members_of_closed_orbits() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+
+    let new_post = NewPostRequest {
+      content_md: "hello".to_string(),
+      visibility: AccessType::MembersOnly,
+      orbit_id: Some(orbit_id),
+      attachment_count: 0,
+      title: None,
+    };
+
+    let mut orbit_moderation_repo = MockOrbitModerationRepo::new();
+    let mut orbit_membership_repo = MockOrbitMembershipRepo::new();
+
+    orbit_moderation_repo
+      .expect_user_is_banned()
+      .with(eq(orbit_id), eq(user_id))
+      .times(1)
+      .return_const(Ok(false));
+
+    orbit_membership_repo
+      .expect_user_can_post()
+      .with(eq(orbit_id), eq(user_id))
+      .times(1)
+      .return_const(Ok(false));
+
+    let posts: PostPool = Arc::new(MockPostRepo::new());
+    let account_restrictions: AccountRestrictionPool = Arc::new(MockAccountRestrictionRepo::new());
+    let orbit_moderation: OrbitModerationPool = Arc::new(orbit_moderation_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(orbit_membership_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let post_mentions: PostMentionPool = Arc::new(MockPostMentionRepo::new());
+    let tags: TagPool = Arc::new(MockTagRepo::new());
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+    let pubsub = PubSub::new_inner(Box::new(MockPubSubBackend::new()));
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert_eq!(
+      create_post(
+        &posts,
+        &users,
+        &post_mentions,
+        &tags,
+        &account_restrictions,
+        &orbit_moderation,
+        &orbit_membership,
+        &notifications,
+        &pubsub,
+        &jobs,
+        &queue,
+        &new_post,
+        &user_id
+      )
+      .await,
+      Err(LogicErr::UnauthorizedError)
+    );
   }
 
   #[async_std::test]
@@ -697,4 +1165,131 @@ mod tests {
       Err(LogicErr::InternalError("Failed to process all attachments".to_string()))
     );
   }
+
+  #[async_std::test]
+  async fn attach_post_media_fails_unless_everything_attached() {
+    let user_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let attachment_ids = vec![Uuid::new_v4(), Uuid::new_v4()];
+
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_user_owns_post()
+      .with(eq(user_id), eq(post_id))
+      .times(1)
+      .return_const(true);
+
+    let mut post_attachment_repo = MockPostAttachmentRepo::new();
+    post_attachment_repo
+      .expect_attach_to_post()
+      .with(eq(user_id), eq(post_id), eq(attachment_ids.clone()))
+      .times(1)
+      .return_const(Ok(1));
+
+    let mut job_repo = MockJobRepo::new();
+    job_repo.expect_create().times(0);
+
+    let posts: PostPool = Arc::new(post_repo);
+    let jobs: JobPool = Arc::new(job_repo);
+    let post_attachments: PostAttachmentPool = Arc::new(post_attachment_repo);
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    assert!(matches!(
+      attach_post_media(
+        &posts,
+        &jobs,
+        &post_attachments,
+        &queue,
+        &post_id,
+        &user_id,
+        &attachment_ids
+      )
+      .await,
+      Err(LogicErr::InvalidOperation(_))
+    ));
+  }
+
+  #[async_std::test]
+  async fn validate_post_media_rejects_missing_attachments() {
+    let user_id = Uuid::new_v4();
+    let attachment_ids = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+
+    let mut post_attachment_repo = MockPostAttachmentRepo::new();
+    post_attachment_repo
+      .expect_count_unattached()
+      .with(eq(user_id), eq(attachment_ids.clone()))
+      .times(1)
+      .return_const(Ok(1));
+
+    let post_attachments: PostAttachmentPool = Arc::new(post_attachment_repo);
+
+    assert!(matches!(
+      validate_post_media(&post_attachments, &user_id, &attachment_ids).await,
+      Err(LogicErr::InvalidOperation(_))
+    ));
+  }
+
+  #[async_std::test]
+  async fn user_can_view_followers_only_post() {
+    let poster_id = Uuid::new_v4();
+    let follower_id = Uuid::new_v4();
+    let stranger_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+
+    let post = PostEvent {
+      event_type: EventType::Post,
+      post_id,
+      uri: "a".to_string(),
+      title: None,
+      content_md: "a".to_string(),
+      content_html: "a".to_string(),
+      visibility: AccessType::FollowersOnly,
+      sensitive: false,
+      is_locked: false,
+      pinned_at: None,
+      created_at: Utc::now(),
+      updated_at: Utc::now(),
+      user_id: poster_id,
+      user_handle: "a".to_string(),
+      user_fediverse_id: "a".to_string(),
+      user_fediverse_uri: "a".to_string(),
+      user_avatar_url: None,
+      event_user_handle: "a".to_string(),
+      event_user_fediverse_id: "a".to_string(),
+      event_user_fediverse_uri: "a".to_string(),
+      event_user_avatar_url: None,
+      likes: 0,
+      liked: None,
+      comments: 0,
+      attachments: vec![],
+      mentions: vec![],
+      tags: vec![],
+      orbit_id: None,
+      orbit_name: None,
+      orbit_uri: None,
+      orbit_fediverse_uri: None,
+      orbit_avatar_uri: None,
+      orbit_shortcode: None,
+    };
+
+    let mut follow_repo = MockFollowRepo::new();
+    follow_repo
+      .expect_user_follows_poster()
+      .with(eq(post_id), eq(follower_id))
+      .times(1)
+      .return_const(true);
+    follow_repo
+      .expect_user_follows_poster()
+      .with(eq(post_id), eq(stranger_id))
+      .times(1)
+      .return_const(false);
+
+    let follows: FollowPool = Arc::new(follow_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+
+    assert!(user_can_view_post(&follows, &orbit_membership, &post, &Some(poster_id)).await);
+    assert!(user_can_view_post(&follows, &orbit_membership, &post, &Some(follower_id)).await);
+    assert!(!user_can_view_post(&follows, &orbit_membership, &post, &Some(stranger_id)).await);
+    assert!(!user_can_view_post(&follows, &orbit_membership, &post, &None).await);
+  }
 }
@@ -0,0 +1,394 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    comment_repository::CommentPool, job_repository::JobPool, orbit_moderator_repository::OrbitModeratorPool,
+    orbit_repository::OrbitPool, post_repository::PostPool, report_repository::ReportPool, user_repository::UserPool,
+  },
+  federation::activitypub::{FederateExtAction, FederateExtActorRef},
+  helpers::api::map_db_err,
+  model::{
+    job::{JobStatus, NewJob},
+    queue_job::{QueueJob, QueueJobType},
+    report::{NewReport, Report, ReportStatus},
+  },
+  work_queue::queue::Queue,
+};
+
+use super::{
+  user::{assert_admin, get_instance_actor},
+  LogicErr,
+};
+
+#[derive(Deserialize)]
+pub struct NewReportRequest {
+  pub user_handle: Option<String>,
+  pub post_id: Option<Uuid>,
+  pub comment_id: Option<Uuid>,
+  pub orbit_id: Option<Uuid>,
+  pub reason: String,
+  /// Whether a copy of the report should be sent to the reported user's server, if they're remote
+  #[serde(default)]
+  pub forward: bool,
+}
+
+/// Admins can act on any queue, orbit moderators only on their orbit's
+async fn assert_can_moderate(
+  users: &UserPool,
+  orbit_moderators: &OrbitModeratorPool,
+  moderator_orbit_id: &Option<Uuid>,
+  user_id: &Uuid,
+) -> Result<(), LogicErr> {
+  if let Some(orbit_id) = moderator_orbit_id {
+    if orbit_moderators.user_is_moderator(orbit_id, user_id).await? {
+      return Ok(());
+    }
+  }
+
+  assert_admin(users, user_id).await
+}
+
+pub async fn create_report(
+  users: &UserPool,
+  posts: &PostPool,
+  comments: &CommentPool,
+  orbits: &OrbitPool,
+  reports: &ReportPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  req: &NewReportRequest,
+  user_id: &Uuid,
+) -> Result<Report, LogicErr> {
+  let reason = req.reason.trim();
+
+  if reason.is_empty() {
+    return Err(LogicErr::InvalidOperation("A reason is required".to_string()));
+  }
+
+  let mut report = NewReport {
+    reporter_id: Some(*user_id),
+    target_user_id: None,
+    post_id: None,
+    comment_id: None,
+    orbit_id: None,
+    moderator_orbit_id: None,
+    reason: reason.to_string(),
+    is_external: false,
+    forwarded: false,
+    uri: None,
+  };
+
+  if let Some(post_id) = req.post_id {
+    let post = match posts.find_optional_by_id(&post_id).await {
+      Some(post) => post,
+      None => return Err(LogicErr::MissingRecord),
+    };
+
+    report.post_id = Some(post.post_id);
+    report.target_user_id = Some(post.user_id);
+    report.orbit_id = post.orbit_id;
+    report.moderator_orbit_id = post.orbit_id;
+
+    if let Some(comment_id) = req.comment_id {
+      let comment = match comments.fetch_comment(&post_id, &comment_id, &Some(*user_id)).await {
+        Some(comment) => comment,
+        None => return Err(LogicErr::MissingRecord),
+      };
+
+      report.comment_id = Some(comment.comment_id);
+      report.target_user_id = Some(comment.user_id);
+    }
+  } else if let Some(handle) = &req.user_handle {
+    match users.fetch_by_handle(handle).await? {
+      Some(user) => report.target_user_id = Some(user.user_id),
+      None => return Err(LogicErr::MissingRecord),
+    };
+  } else if let Some(orbit_id) = req.orbit_id {
+    // Reports about an orbit itself go to the admins rather than the orbit's own moderators
+    match orbits.fetch_orbit(&orbit_id).await? {
+      Some(orbit) => report.orbit_id = Some(orbit.orbit_id),
+      None => return Err(LogicErr::MissingRecord),
+    };
+  } else {
+    return Err(LogicErr::InvalidOperation("Nothing to report".to_string()));
+  }
+
+  if report.target_user_id == Some(*user_id) {
+    return Err(LogicErr::InvalidOperation("Cannot report yourself".to_string()));
+  }
+
+  let forward_to = match (req.forward, report.target_user_id) {
+    (true, Some(target_user_id)) => Some(users.fetch_by_id(&target_user_id).await?).filter(|user| user.is_external),
+    _ => None,
+  };
+
+  report.forwarded = forward_to.is_some();
+
+  let report = reports.create_report(&report).await?;
+
+  if let Some(target) = forward_to {
+    // Forwarded reports are sent by the instance actor, so the reported user's server never learns who filed them
+    let instance_actor = get_instance_actor(users).await?;
+
+    let job_id = jobs
+      .create(NewJob {
+        created_by_id: Some(*user_id),
+        status: JobStatus::NotStarted,
+        record_id: Some(report.report_id),
+        associated_record_id: Some(target.user_id),
+      })
+      .await
+      .map_err(map_db_err)?;
+
+    let dest_actor = match target.ext_apub_shared_inbox_uri {
+      Some(uri) => FederateExtActorRef::SharedInbox(uri, vec![target.fediverse_uri]),
+      None => FederateExtActorRef::Person(target.user_id),
+    };
+
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FederateActivityPubExt)
+      .context(vec![instance_actor.user_id.to_string()])
+      .activitypub_federate_ext_action(FederateExtAction::FlagReport(report.report_id))
+      .activitypub_federate_ext_dest_actor(dest_actor)
+      .build();
+
+    queue.send_job(job).await?;
+  }
+
+  Ok(report)
+}
+
+pub async fn get_reports(
+  users: &UserPool,
+  orbit_moderators: &OrbitModeratorPool,
+  reports: &ReportPool,
+  moderator_orbit_id: &Option<Uuid>,
+  status: &Option<ReportStatus>,
+  limit: i64,
+  skip: i64,
+  user_id: &Uuid,
+) -> Result<Vec<Report>, LogicErr> {
+  assert_can_moderate(users, orbit_moderators, moderator_orbit_id, user_id).await?;
+
+  reports.fetch_reports(moderator_orbit_id, status, limit, skip).await
+}
+
+pub async fn get_reports_count(
+  users: &UserPool,
+  orbit_moderators: &OrbitModeratorPool,
+  reports: &ReportPool,
+  moderator_orbit_id: &Option<Uuid>,
+  status: &Option<ReportStatus>,
+  user_id: &Uuid,
+) -> Result<i64, LogicErr> {
+  assert_can_moderate(users, orbit_moderators, moderator_orbit_id, user_id).await?;
+
+  reports.count_reports(moderator_orbit_id, status).await
+}
+
+pub async fn update_report_status(
+  users: &UserPool,
+  orbit_moderators: &OrbitModeratorPool,
+  reports: &ReportPool,
+  report_id: &Uuid,
+  status: &ReportStatus,
+  resolution_note: &Option<String>,
+  user_id: &Uuid,
+) -> Result<Report, LogicErr> {
+  let report = match reports.fetch_report(report_id).await? {
+    Some(report) => report,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  assert_can_moderate(users, orbit_moderators, &report.moderator_orbit_id, user_id).await?;
+
+  reports
+    .update_report_status(report_id, status, user_id, resolution_note)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      comment_repository::{CommentPool, MockCommentRepo},
+      job_repository::{JobPool, MockJobRepo},
+      orbit_moderator_repository::{MockOrbitModeratorRepo, OrbitModeratorPool},
+      orbit_repository::{MockOrbitRepo, OrbitPool},
+      post_repository::{MockPostRepo, PostPool},
+      report_repository::{MockReportRepo, ReportPool},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    logic::{
+      report::{create_report, get_reports, update_report_status, NewReportRequest},
+      LogicErr,
+    },
+    model::{fixtures, report::ReportStatus},
+    work_queue::queue::{MockQueueBackend, Queue},
+  };
+
+  #[async_std::test]
+  async fn test_create_report_requires_reason() {
+    let user_id = Uuid::new_v4();
+
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let posts: PostPool = Arc::new(MockPostRepo::new());
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbits: OrbitPool = Arc::new(MockOrbitRepo::new());
+    let reports: ReportPool = Arc::new(MockReportRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    let req = NewReportRequest {
+      user_handle: Some("user_handle".to_string()),
+      post_id: None,
+      comment_id: None,
+      orbit_id: None,
+      reason: "  ".to_string(),
+      forward: false,
+    };
+
+    assert_eq!(
+      create_report(&users, &posts, &comments, &orbits, &reports, &jobs, &queue, &req, &user_id)
+        .await
+        .err(),
+      Some(LogicErr::InvalidOperation("A reason is required".to_string()))
+    );
+  }
+
+  #[async_std::test]
+  async fn test_create_report_routes_orbit_posts_to_orbit_moderators() {
+    let user_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let poster_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+
+    let mut post_repo = MockPostRepo::new();
+
+    post_repo
+      .expect_find_optional_by_id()
+      .times(1)
+      .with(eq(post_id))
+      .returning(move |_| Some(fixtures::post(post_id, poster_id, Some(orbit_id))));
+
+    let mut report_repo = MockReportRepo::new();
+
+    report_repo
+      .expect_create_report()
+      .times(1)
+      .withf(move |report| {
+        report.moderator_orbit_id == Some(orbit_id)
+          && report.target_user_id == Some(poster_id)
+          && report.post_id == Some(post_id)
+          && !report.forwarded
+      })
+      .returning(move |_| Ok(fixtures::report(Some(orbit_id))));
+
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let posts: PostPool = Arc::new(post_repo);
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let orbits: OrbitPool = Arc::new(MockOrbitRepo::new());
+    let reports: ReportPool = Arc::new(report_repo);
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    let req = NewReportRequest {
+      user_handle: None,
+      post_id: Some(post_id),
+      comment_id: None,
+      orbit_id: None,
+      reason: "Spam".to_string(),
+      forward: false,
+    };
+
+    assert!(
+      create_report(&users, &posts, &comments, &orbits, &reports, &jobs, &queue, &req, &user_id)
+        .await
+        .is_ok()
+    );
+  }
+
+  #[async_std::test]
+  async fn test_get_reports_rejects_non_admins_for_admin_queue() {
+    let user_id = Uuid::new_v4();
+
+    let mut user_repo = MockUserRepo::new();
+
+    user_repo
+      .expect_user_is_admin()
+      .times(1)
+      .with(eq(user_id))
+      .returning(|_| false);
+
+    let users: UserPool = Arc::new(user_repo);
+    let orbit_moderators: OrbitModeratorPool = Arc::new(MockOrbitModeratorRepo::new());
+    let reports: ReportPool = Arc::new(MockReportRepo::new());
+
+    assert_eq!(
+      get_reports(&users, &orbit_moderators, &reports, &None, &None, 20, 0, &user_id)
+        .await
+        .err(),
+      Some(LogicErr::UnauthorizedError)
+    );
+  }
+
+  #[async_std::test]
+  async fn test_update_report_status_allows_orbit_moderators() {
+    let user_id = Uuid::new_v4();
+    let orbit_id = Uuid::new_v4();
+    let report = fixtures::report(Some(orbit_id));
+    let report_id = report.report_id;
+
+    let mut orbit_moderator_repo = MockOrbitModeratorRepo::new();
+
+    orbit_moderator_repo
+      .expect_user_is_moderator()
+      .times(1)
+      .with(eq(orbit_id), eq(user_id))
+      .returning(|_, _| Ok(true));
+
+    let mut report_repo = MockReportRepo::new();
+
+    report_repo
+      .expect_fetch_report()
+      .times(1)
+      .with(eq(report_id))
+      .return_const(Ok(Some(report.clone())));
+
+    report_repo
+      .expect_update_report_status()
+      .times(1)
+      .with(eq(report_id), eq(ReportStatus::Resolved), eq(user_id), eq(None))
+      .returning(move |_, status, resolved_by_id, _| {
+        let mut report = report.clone();
+        report.status = *status;
+        report.resolved_by_id = Some(*resolved_by_id);
+        Ok(report)
+      });
+
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let orbit_moderators: OrbitModeratorPool = Arc::new(orbit_moderator_repo);
+    let reports: ReportPool = Arc::new(report_repo);
+
+    let result = update_report_status(
+      &users,
+      &orbit_moderators,
+      &reports,
+      &report_id,
+      &ReportStatus::Resolved,
+      &None,
+      &user_id,
+    )
+    .await;
+
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().status, ReportStatus::Resolved);
+  }
+}
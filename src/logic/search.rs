@@ -0,0 +1,361 @@
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    comment_repository::CommentPool, follow_repository::FollowPool, job_repository::JobPool,
+    orbit_membership_repository::OrbitMembershipPool, orbit_repository::OrbitPool, post_repository::PostPool,
+    search_repository::SearchPool, user_repository::UserPool,
+  },
+  federation::activitypub::resolve::ResolvedObject,
+  model::{
+    job::{JobStatus, NewJob},
+    orbit::Orbit,
+    orbit_pub::OrbitPub,
+    queue_job::{QueueJob, QueueJobType},
+    search_hit::SearchHit,
+    search_result::SearchResult,
+    search_result_type::SearchResultType,
+    user_account_pub::UserAccountPub,
+  },
+  work_queue::queue::Queue,
+};
+
+use super::{post::user_can_view_post, LogicErr};
+
+/// Loads what a search hit points to. Records that disappeared between ranking and loading them are skipped.
+async fn load_search_hit(
+  posts: &PostPool,
+  comments: &CommentPool,
+  users: &UserPool,
+  orbits: &OrbitPool,
+  own_user_id: &Option<Uuid>,
+  hit: &SearchHit,
+) -> Result<Option<SearchResult>, LogicErr> {
+  match hit.result_type {
+    SearchResultType::Post => Ok(posts.fetch_post(&hit.id, own_user_id).await?.map(SearchResult::Post)),
+    SearchResultType::Comment => match hit.post_id {
+      Some(post_id) => Ok(
+        comments
+          .fetch_comment(&post_id, &hit.id, own_user_id)
+          .await
+          .map(SearchResult::Comment),
+      ),
+      None => Ok(None),
+    },
+    SearchResultType::User => Ok(
+      users
+        .fetch_by_id(&hit.id)
+        .await
+        .ok()
+        .map(|user| SearchResult::User(UserAccountPub::from(user))),
+    ),
+    SearchResultType::Orbit => Ok(
+      orbits
+        .fetch_orbit(&hit.id)
+        .await?
+        .map(|orbit| SearchResult::Orbit(OrbitPub::from(orbit))),
+    ),
+  }
+}
+
+/// Runs a full-text search, returning the best ranked results first
+pub async fn search(
+  searches: &SearchPool,
+  posts: &PostPool,
+  comments: &CommentPool,
+  users: &UserPool,
+  orbits: &OrbitPool,
+  term: &str,
+  own_user_id: &Option<Uuid>,
+  result_type: &Option<SearchResultType>,
+  limit: i64,
+  skip: i64,
+) -> Result<Vec<SearchResult>, LogicErr> {
+  let term = term.trim();
+  if term.is_empty() {
+    return Ok(vec![]);
+  }
+
+  let hits = searches.search(term, own_user_id, result_type, limit, skip).await?;
+
+  let mut results = vec![];
+  for hit in hits {
+    if let Some(result) = load_search_hit(posts, comments, users, orbits, own_user_id, &hit).await? {
+      results.push(result);
+    }
+  }
+
+  Ok(results)
+}
+
+pub async fn search_count(
+  searches: &SearchPool,
+  term: &str,
+  own_user_id: &Option<Uuid>,
+  result_type: &Option<SearchResultType>,
+) -> Result<i64, LogicErr> {
+  let term = term.trim();
+  if term.is_empty() {
+    return Ok(0);
+  }
+
+  searches.count_search(term, own_user_id, result_type).await
+}
+
+pub async fn queue_fetch_external_orbit_posts(jobs: &JobPool, queue: &Queue, orbit: &Orbit) {
+  if let Ok(job_id) = jobs
+    .create(NewJob {
+      created_by_id: None,
+      status: JobStatus::NotStarted,
+      record_id: Some(orbit.orbit_id),
+      associated_record_id: None,
+    })
+    .await
+  {
+    let job = QueueJob::builder()
+      .job_id(job_id)
+      .job_type(QueueJobType::FetchExternalOrbitPosts)
+      .build();
+
+    match queue.send_job(job).await {
+      Ok(_) => {}
+      Err(err) => {
+        log::warn!("Failed to queue job to fetch external orbit posts: {:?}", err);
+      }
+    };
+  }
+}
+
+/// Turns an object resolved by URI into a search result for `own_user_id`. Posts they aren't allowed to see are
+/// left out, as local posts are matched by URI whatever their visibility.
+pub async fn load_resolved_object(
+  resolved: Option<ResolvedObject>,
+  posts: &PostPool,
+  follows: &FollowPool,
+  orbit_membership: &OrbitMembershipPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  own_user_id: &Uuid,
+) -> Result<Option<SearchResult>, LogicErr> {
+  match resolved {
+    Some(ResolvedObject::Post(uri)) => {
+      let own_user_id = Some(*own_user_id);
+
+      match posts.fetch_post_from_uri(&uri, &own_user_id).await? {
+        Some(post) if user_can_view_post(follows, orbit_membership, &post, &own_user_id).await => {
+          Ok(Some(SearchResult::Post(post)))
+        }
+        _ => Ok(None),
+      }
+    }
+    Some(ResolvedObject::User(user)) => Ok(Some(SearchResult::User(UserAccountPub::from(user)))),
+    Some(ResolvedObject::Orbit(orbit)) => {
+      if orbit.is_external {
+        queue_fetch_external_orbit_posts(jobs, queue, &orbit).await;
+      }
+
+      Ok(Some(SearchResult::Orbit(OrbitPub::from(orbit))))
+    }
+    None => Ok(None),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      comment_repository::{CommentPool, MockCommentRepo},
+      follow_repository::{FollowPool, MockFollowRepo},
+      job_repository::{JobPool, MockJobRepo},
+      orbit_membership_repository::{MockOrbitMembershipRepo, OrbitMembershipPool},
+      orbit_repository::{MockOrbitRepo, OrbitPool},
+      post_repository::{MockPostRepo, PostPool},
+      search_repository::{MockSearchRepo, SearchPool},
+      user_repository::{MockUserRepo, UserPool},
+    },
+    federation::activitypub::resolve::ResolvedObject,
+    logic::{
+      search::{load_resolved_object, search},
+      LogicErr,
+    },
+    model::{
+      access_type::AccessType, fixtures, search_hit::SearchHit, search_result::SearchResult,
+      search_result_type::SearchResultType,
+    },
+    work_queue::queue::{MockQueueBackend, Queue},
+  };
+
+  #[async_std::test]
+  async fn test_search_skips_blank_terms() {
+    let mut search_repo = MockSearchRepo::new();
+    search_repo.expect_search().times(0);
+
+    let searches: SearchPool = Arc::new(search_repo);
+    let posts: PostPool = Arc::new(MockPostRepo::new());
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let orbits: OrbitPool = Arc::new(MockOrbitRepo::new());
+
+    let results = search(
+      &searches, &posts, &comments, &users, &orbits, "   ", &None, &None, 20, 0,
+    )
+    .await;
+
+    assert!(matches!(results, Ok(results) if results.is_empty()));
+  }
+
+  #[async_std::test]
+  async fn test_search_skips_hits_that_no_longer_exist() {
+    let user_id = Some(Uuid::new_v4());
+    let post_id = Uuid::new_v4();
+    let comment_id = Uuid::new_v4();
+
+    let hits = vec![
+      SearchHit {
+        result_type: SearchResultType::Post,
+        id: post_id,
+        post_id: None,
+        rank: 0.5,
+      },
+      SearchHit {
+        result_type: SearchResultType::Comment,
+        id: comment_id,
+        post_id: Some(post_id),
+        rank: 0.2,
+      },
+    ];
+
+    let mut search_repo = MockSearchRepo::new();
+    search_repo
+      .expect_search()
+      .with(eq("orbit"), eq(user_id), eq(None), eq(20), eq(0))
+      .times(1)
+      .return_const(Ok(hits));
+
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_post()
+      .with(eq(post_id), eq(user_id))
+      .times(1)
+      .return_const(Ok(None));
+
+    let mut comment_repo = MockCommentRepo::new();
+    comment_repo
+      .expect_fetch_comment()
+      .with(eq(post_id), eq(comment_id), eq(user_id))
+      .times(1)
+      .return_const(None);
+
+    let searches: SearchPool = Arc::new(search_repo);
+    let posts: PostPool = Arc::new(post_repo);
+    let comments: CommentPool = Arc::new(comment_repo);
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let orbits: OrbitPool = Arc::new(MockOrbitRepo::new());
+
+    let results = search(
+      &searches, &posts, &comments, &users, &orbits, " orbit ", &user_id, &None, 20, 0,
+    )
+    .await;
+
+    assert!(matches!(results, Ok(results) if results.is_empty()));
+  }
+
+  #[async_std::test]
+  async fn test_search_db_err_passthrough() {
+    let mut search_repo = MockSearchRepo::new();
+    search_repo
+      .expect_search()
+      .times(1)
+      .return_const(Err(LogicErr::DbError("Failed".to_string())));
+
+    let searches: SearchPool = Arc::new(search_repo);
+    let posts: PostPool = Arc::new(MockPostRepo::new());
+    let comments: CommentPool = Arc::new(MockCommentRepo::new());
+    let users: UserPool = Arc::new(MockUserRepo::new());
+    let orbits: OrbitPool = Arc::new(MockOrbitRepo::new());
+
+    let results = search(
+      &searches,
+      &posts,
+      &comments,
+      &users,
+      &orbits,
+      "orbit",
+      &None,
+      &Some(SearchResultType::Post),
+      20,
+      0,
+    )
+    .await;
+
+    assert!(matches!(results, Err(LogicErr::DbError(_))));
+  }
+
+  #[async_std::test]
+  async fn test_load_resolved_object_hides_posts_the_user_cannot_see() {
+    let stranger_id = Uuid::new_v4();
+    let follower_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let post = fixtures::post_event(post_id, Uuid::new_v4(), AccessType::FollowersOnly);
+    let uri = post.uri.clone();
+
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_post_from_uri()
+      .with(eq(uri.clone()), eq(Some(stranger_id)))
+      .times(1)
+      .return_const(Ok(Some(post.clone())));
+    post_repo
+      .expect_fetch_post_from_uri()
+      .with(eq(uri.clone()), eq(Some(follower_id)))
+      .times(1)
+      .return_const(Ok(Some(post.clone())));
+
+    let mut follow_repo = MockFollowRepo::new();
+    follow_repo
+      .expect_user_follows_poster()
+      .with(eq(post_id), eq(stranger_id))
+      .times(1)
+      .return_const(false);
+    follow_repo
+      .expect_user_follows_poster()
+      .with(eq(post_id), eq(follower_id))
+      .times(1)
+      .return_const(true);
+
+    let posts: PostPool = Arc::new(post_repo);
+    let follows: FollowPool = Arc::new(follow_repo);
+    let orbit_membership: OrbitMembershipPool = Arc::new(MockOrbitMembershipRepo::new());
+    let jobs: JobPool = Arc::new(MockJobRepo::new());
+    let queue = Queue::new_inner(Box::new(MockQueueBackend::new()));
+
+    let result = load_resolved_object(
+      Some(ResolvedObject::Post(uri.clone())),
+      &posts,
+      &follows,
+      &orbit_membership,
+      &jobs,
+      &queue,
+      &stranger_id,
+    )
+    .await;
+    assert!(matches!(result, Ok(None)));
+
+    let result = load_resolved_object(
+      Some(ResolvedObject::Post(uri)),
+      &posts,
+      &follows,
+      &orbit_membership,
+      &jobs,
+      &queue,
+      &follower_id,
+    )
+    .await;
+    assert!(matches!(result, Ok(Some(SearchResult::Post(resolved))) if resolved == post));
+  }
+}
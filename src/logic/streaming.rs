@@ -0,0 +1,361 @@
+use uuid::Uuid;
+
+use crate::{
+  db::{notification_repository::NotificationPool, post_repository::PostPool},
+  model::{
+    access_type::AccessType, event_type::EventType, notification::Notification, post::Post, post_event::PostEvent,
+    stream_message::StreamMessage, stream_type::StreamType,
+  },
+};
+
+use super::LogicErr;
+
+/// What a streaming client is connected to. Home, friends and notification streams belong to a user, while orbit and
+/// federated streams can be followed by anyone who can read them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamSubscription {
+  pub stream: StreamType,
+  pub user_id: Option<Uuid>,
+  pub orbit_id: Option<Uuid>,
+}
+
+/// A stream message resolved into what the subscriber is actually sent
+pub enum StreamEvent {
+  Update(PostEvent),
+  Delete(Uuid),
+  Notification(Notification),
+}
+
+impl StreamSubscription {
+  /// Whether a message was addressed to this subscription, before checking the subscriber is allowed to see it
+  pub fn wants(&self, message: &StreamMessage) -> bool {
+    match message {
+      StreamMessage::Update { stream, target_id, .. } => {
+        *stream == self.stream
+          && match stream {
+            StreamType::Home | StreamType::Friends => self.user_id.is_some() && *target_id == self.user_id,
+            StreamType::Orbit => self.orbit_id.is_some() && *target_id == self.orbit_id,
+            StreamType::Federated => true,
+            StreamType::Notifications => false,
+          }
+      }
+      StreamMessage::Delete { .. } => self.stream != StreamType::Notifications,
+      StreamMessage::Notification { user_id, .. } => {
+        self.stream == StreamType::Notifications && self.user_id == Some(*user_id)
+      }
+    }
+  }
+}
+
+/// The messages for a new post, mirroring the author's own event in `create_post_events` and the orbit feed
+pub fn post_messages(post: &Post) -> Vec<StreamMessage> {
+  let update = |stream: StreamType, target_id: Option<Uuid>| StreamMessage::Update {
+    stream,
+    target_id,
+    post_id: post.post_id,
+    source_user_id: post.user_id,
+    event_type: EventType::Post,
+  };
+
+  let mut messages = vec![];
+
+  if !post.is_external {
+    messages.push(update(StreamType::Home, Some(post.user_id)));
+
+    if post.visibility == AccessType::PublicFederated || post.visibility == AccessType::PublicLocal {
+      messages.push(update(StreamType::Federated, None));
+    }
+  }
+
+  if let Some(orbit_id) = post.orbit_id {
+    if post.visibility == AccessType::PublicFederated
+      || post.visibility == AccessType::PublicLocal
+      || post.visibility == AccessType::MembersOnly
+    {
+      messages.push(update(StreamType::Orbit, Some(orbit_id)));
+    }
+  }
+
+  messages
+}
+
+/// The messages for an event written to another user's feed, mirroring the visibility rules of their own and friends
+/// feeds
+pub fn feed_event_messages(
+  target_user_id: &Uuid,
+  source_user_id: &Uuid,
+  post: &Post,
+  event_type: EventType,
+) -> Vec<StreamMessage> {
+  let update = |stream: StreamType| StreamMessage::Update {
+    stream,
+    target_id: Some(*target_user_id),
+    post_id: post.post_id,
+    source_user_id: *source_user_id,
+    event_type: event_type.clone(),
+  };
+
+  let mut messages = vec![];
+
+  match post.visibility {
+    AccessType::PublicFederated | AccessType::PublicLocal | AccessType::FollowersOnly => {
+      messages.push(update(StreamType::Home));
+
+      if post.orbit_id.is_none() && source_user_id != target_user_id {
+        messages.push(update(StreamType::Friends));
+      }
+    }
+    AccessType::MembersOnly => messages.push(update(StreamType::Home)),
+    _ => {}
+  }
+
+  messages
+}
+
+/// Looks up what a message refers to from the subscriber's perspective. Nothing is returned when the message isn't for
+/// this subscription, or when the subscriber's blocks, mutes or filters hide it.
+pub async fn resolve_stream_message(
+  posts: &PostPool,
+  notifications: &NotificationPool,
+  subscription: &StreamSubscription,
+  message: &StreamMessage,
+) -> Result<Option<StreamEvent>, LogicErr> {
+  if !subscription.wants(message) {
+    return Ok(None);
+  }
+
+  match message {
+    StreamMessage::Update {
+      post_id,
+      source_user_id,
+      event_type,
+      ..
+    } => Ok(
+      posts
+        .fetch_stream_post(
+          post_id,
+          source_user_id,
+          event_type,
+          &subscription.user_id,
+          &subscription.stream,
+        )
+        .await?
+        .map(StreamEvent::Update),
+    ),
+    StreamMessage::Delete { post_id } => Ok(Some(StreamEvent::Delete(*post_id))),
+    StreamMessage::Notification {
+      user_id,
+      notification_id,
+    } => Ok(
+      notifications
+        .fetch_notification(user_id, notification_id)
+        .await?
+        .map(StreamEvent::Notification),
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use mockall::predicate::*;
+  use uuid::Uuid;
+
+  use crate::{
+    db::{
+      notification_repository::{MockNotificationRepo, NotificationPool},
+      post_repository::{MockPostRepo, PostPool},
+    },
+    logic::streaming::{feed_event_messages, post_messages, resolve_stream_message, StreamEvent, StreamSubscription},
+    model::{
+      access_type::AccessType, event_type::EventType, fixtures, post::Post, stream_message::StreamMessage,
+      stream_type::StreamType,
+    },
+  };
+
+  fn post(visibility: AccessType, orbit_id: Option<Uuid>) -> Post {
+    Post {
+      visibility,
+      ..fixtures::post(Uuid::new_v4(), Uuid::new_v4(), orbit_id)
+    }
+  }
+
+  fn streams(messages: &[StreamMessage]) -> Vec<StreamType> {
+    messages
+      .iter()
+      .filter_map(|m| match m {
+        StreamMessage::Update { stream, .. } => Some(*stream),
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_subscription_only_wants_its_own_user_streams() {
+    let user_id = Uuid::new_v4();
+    let subscription = StreamSubscription {
+      stream: StreamType::Home,
+      user_id: Some(user_id),
+      orbit_id: None,
+    };
+
+    let update = |stream: StreamType, target_id: Uuid| StreamMessage::Update {
+      stream,
+      target_id: Some(target_id),
+      post_id: Uuid::new_v4(),
+      source_user_id: Uuid::new_v4(),
+      event_type: EventType::Post,
+    };
+
+    assert!(subscription.wants(&update(StreamType::Home, user_id)));
+    assert!(!subscription.wants(&update(StreamType::Home, Uuid::new_v4())));
+    assert!(!subscription.wants(&update(StreamType::Friends, user_id)));
+    assert!(subscription.wants(&StreamMessage::Delete {
+      post_id: Uuid::new_v4()
+    }));
+    assert!(!subscription.wants(&StreamMessage::Notification {
+      user_id,
+      notification_id: Uuid::new_v4()
+    }));
+  }
+
+  #[test]
+  fn test_post_messages_leave_private_posts_out_of_public_streams() {
+    let orbit_id = Some(Uuid::new_v4());
+
+    assert_eq!(
+      streams(&post_messages(&post(AccessType::PublicFederated, orbit_id))),
+      vec![StreamType::Home, StreamType::Federated, StreamType::Orbit]
+    );
+    assert_eq!(
+      streams(&post_messages(&post(AccessType::MembersOnly, orbit_id))),
+      vec![StreamType::Home, StreamType::Orbit]
+    );
+    assert_eq!(
+      streams(&post_messages(&post(AccessType::Shadow, None))),
+      vec![StreamType::Home]
+    );
+  }
+
+  #[test]
+  fn test_feed_event_messages_keep_orbit_posts_out_of_friends_stream() {
+    let target_user_id = Uuid::new_v4();
+    let source_user_id = Uuid::new_v4();
+
+    assert_eq!(
+      streams(&feed_event_messages(
+        &target_user_id,
+        &source_user_id,
+        &post(AccessType::FollowersOnly, None),
+        EventType::Post
+      )),
+      vec![StreamType::Home, StreamType::Friends]
+    );
+    assert_eq!(
+      streams(&feed_event_messages(
+        &target_user_id,
+        &source_user_id,
+        &post(AccessType::PublicFederated, Some(Uuid::new_v4())),
+        EventType::Boost
+      )),
+      vec![StreamType::Home]
+    );
+    assert!(feed_event_messages(
+      &target_user_id,
+      &source_user_id,
+      &post(AccessType::Unlisted, None),
+      EventType::Post
+    )
+    .is_empty());
+  }
+
+  #[async_std::test]
+  async fn test_resolve_stream_message_drops_hidden_posts() {
+    let user_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let source_user_id = Uuid::new_v4();
+
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_stream_post()
+      .with(
+        eq(post_id),
+        eq(source_user_id),
+        eq(EventType::Boost),
+        eq(Some(user_id)),
+        eq(StreamType::Friends),
+      )
+      .times(1)
+      .returning(|_, _, _, _, _| Ok(None));
+
+    let posts: PostPool = Arc::new(post_repo);
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+
+    let subscription = StreamSubscription {
+      stream: StreamType::Friends,
+      user_id: Some(user_id),
+      orbit_id: None,
+    };
+
+    let message = StreamMessage::Update {
+      stream: StreamType::Friends,
+      target_id: Some(user_id),
+      post_id,
+      source_user_id,
+      event_type: EventType::Boost,
+    };
+
+    let result = resolve_stream_message(&posts, &notifications, &subscription, &message).await;
+
+    assert!(matches!(result, Ok(None)));
+  }
+
+  #[async_std::test]
+  async fn test_resolve_stream_message_keeps_silenced_authors_on_home_stream() {
+    let user_id = Uuid::new_v4();
+    let post_id = Uuid::new_v4();
+    let silenced_user_id = Uuid::new_v4();
+
+    // The repo only hides silenced authors when asked for the federated stream
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_stream_post()
+      .with(
+        eq(post_id),
+        eq(silenced_user_id),
+        eq(EventType::Post),
+        eq(Some(user_id)),
+        eq(StreamType::Home),
+      )
+      .times(1)
+      .returning(move |_, _, _, _, _| {
+        Ok(Some(fixtures::post_event(
+          post_id,
+          silenced_user_id,
+          AccessType::PublicFederated,
+        )))
+      });
+
+    let posts: PostPool = Arc::new(post_repo);
+    let notifications: NotificationPool = Arc::new(MockNotificationRepo::new());
+
+    let subscription = StreamSubscription {
+      stream: StreamType::Home,
+      user_id: Some(user_id),
+      orbit_id: None,
+    };
+
+    let message = StreamMessage::Update {
+      stream: StreamType::Home,
+      target_id: Some(user_id),
+      post_id,
+      source_user_id: silenced_user_id,
+      event_type: EventType::Post,
+    };
+
+    let result = resolve_stream_message(&posts, &notifications, &subscription, &message).await;
+
+    assert!(matches!(result, Ok(Some(StreamEvent::Update(post))) if post.post_id == post_id));
+  }
+}
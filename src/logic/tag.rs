@@ -0,0 +1,166 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use uuid::Uuid;
+
+use crate::{
+  db::{post_repository::PostPool, tag_repository::TagPool},
+  helpers::{api::tag_uri, html::map_linkable_html},
+  model::{page_cursor::PageCursor, post_event::PostEvent},
+};
+
+use super::LogicErr;
+
+const MAX_TAG_LENGTH: usize = 128;
+
+lazy_static! {
+  /// `#tag`, as long as it isn't part of a word, URL fragment or HTML entity and isn't only digits
+  static ref TAG_RE: Regex = Regex::new(r"(^|[^\w&/#])#(\w*[^\W\d]\w*)").unwrap();
+  static ref TAG_NAME_RE: Regex = Regex::new(r"^\w*[^\W\d]\w*$").unwrap();
+}
+
+/// Turns a tag as written, with or without its leading `#`, into the name it's stored under
+pub fn normalize_tag(name: &str) -> Option<String> {
+  let name = name.strip_prefix('#').unwrap_or(name).to_lowercase();
+
+  if name.chars().count() > MAX_TAG_LENGTH || !TAG_NAME_RE.is_match(&name) {
+    return None;
+  }
+
+  Some(name)
+}
+
+/// Finds the tags in a post's markdown, normalized and in order of first appearance
+pub fn parse_tags(content_md: &str) -> Vec<String> {
+  let mut tags: Vec<String> = vec![];
+
+  for captures in TAG_RE.captures_iter(content_md) {
+    if let Some(tag) = normalize_tag(&captures[2]) {
+      if !tags.contains(&tag) {
+        tags.push(tag);
+      }
+    }
+  }
+
+  tags
+}
+
+fn link_tags(html: &str, tags: &[String]) -> String {
+  TAG_RE
+    .replace_all(html, |captures: &Captures| match normalize_tag(&captures[2]) {
+      Some(tag) if tags.contains(&tag) => format!(
+        r#"{}<a href="{}" class="mention hashtag" rel="tag">#<span>{}</span></a>"#,
+        &captures[1],
+        tag_uri(&tag),
+        &captures[2]
+      ),
+      _ => captures[0].to_string(),
+    })
+    .into_owned()
+}
+
+/// Turns a post's tags in its rendered content into links to their tag feeds, leaving existing links and code untouched
+pub fn render_tags(content_html: &str, tags: &[String]) -> String {
+  if tags.is_empty() {
+    return content_html.to_string();
+  }
+
+  map_linkable_html(content_html, |html| link_tags(html, tags))
+}
+
+/// Replaces the tags stored for a post. Like mentions, this happens after the post already exists, so errors are only
+/// logged.
+pub async fn record_tags(tags: &TagPool, post_id: &Uuid, names: &[String]) {
+  if let Err(err) = tags.set_post_tags(post_id, names).await {
+    log::warn!("Failed to record tags for post {}: {}", post_id, err);
+  }
+}
+
+pub async fn get_tag_posts(
+  tag: &str,
+  own_user_id: &Option<Uuid>,
+  cursor: &PageCursor,
+  limit: i64,
+  skip: i64,
+  posts: &PostPool,
+) -> Result<Vec<PostEvent>, LogicErr> {
+  match normalize_tag(tag) {
+    Some(tag) => posts.fetch_tag_feed(&tag, own_user_id, cursor, limit, skip).await,
+    None => Err(LogicErr::InvalidOperation(format!("Invalid tag: {}", tag))),
+  }
+}
+
+pub async fn get_tag_posts_count(tag: &str, own_user_id: &Option<Uuid>, posts: &PostPool) -> Result<i64, LogicErr> {
+  match normalize_tag(tag) {
+    Some(tag) => posts.count_tag_feed(&tag, own_user_id).await,
+    None => Err(LogicErr::InvalidOperation(format!("Invalid tag: {}", tag))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use mockall::predicate::*;
+
+  use crate::{
+    db::post_repository::{MockPostRepo, PostPool},
+    helpers::api::tag_uri,
+    logic::{
+      tag::{get_tag_posts, normalize_tag, parse_tags, render_tags},
+      LogicErr,
+    },
+    model::page_cursor::PageCursor,
+  };
+
+  #[test]
+  fn test_parse_tags_normalizes_and_dedupes() {
+    assert_eq!(
+      parse_tags("#Rust and #rust, #2023 #open_source. See https://example.com/#anchor or issue#12 &#39;"),
+      vec!["rust".to_string(), "open_source".to_string()]
+    );
+    assert_eq!(parse_tags("# Heading\n\n(#Café)"), vec!["café".to_string()]);
+  }
+
+  #[test]
+  fn test_normalize_tag_rejects_invalid_names() {
+    assert_eq!(normalize_tag("#Orbit"), Some("orbit".to_string()));
+    assert_eq!(normalize_tag("1234"), None);
+    assert_eq!(normalize_tag("two words"), None);
+    assert_eq!(normalize_tag(&"a".repeat(129)), None);
+  }
+
+  #[test]
+  fn test_render_tags_links_known_tags_only() {
+    assert_eq!(
+      render_tags(
+        r#"<p>#Rust and #other</p><p><a href="https://example.com/#rust">#rust</a></p>"#,
+        &["rust".to_string()]
+      ),
+      format!(
+        r#"<p><a href="{}" class="mention hashtag" rel="tag">#<span>Rust</span></a> and #other</p><p><a href="https://example.com/#rust">#rust</a></p>"#,
+        tag_uri("rust")
+      )
+    );
+  }
+
+  #[async_std::test]
+  async fn test_get_tag_posts_normalizes_tag() {
+    let mut post_repo = MockPostRepo::new();
+    post_repo
+      .expect_fetch_tag_feed()
+      .with(eq("rust"), eq(None), eq(PageCursor::default()), eq(20), eq(0))
+      .times(1)
+      .return_const(Ok(vec![]));
+
+    let posts: PostPool = Arc::new(post_repo);
+
+    assert_eq!(
+      get_tag_posts("#Rust", &None, &PageCursor::default(), 20, 0, &posts).await,
+      Ok(vec![])
+    );
+    assert!(matches!(
+      get_tag_posts("123", &None, &PageCursor::default(), 20, 0, &posts).await,
+      Err(LogicErr::InvalidOperation(_))
+    ));
+  }
+}
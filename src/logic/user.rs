@@ -8,10 +8,14 @@ use rsa::{
 };
 use uuid::Uuid;
 
-use crate::{db::user_repository::UserPool, model::user::User, net::jwt::JwtFactory, settings::SETTINGS};
+use crate::{db::user_repository::UserPool, model::user::User, settings::SETTINGS};
 
 use super::LogicErr;
 
+/// The handle of the local actor that federates on behalf of the server itself rather than any one user, e.g. when
+/// forwarding reports. It's reserved, so nobody can register it.
+pub const INSTANCE_ACTOR_HANDLE: &str = "instance.actor";
+
 pub async fn get_user_by_id(id: &Uuid, users: &UserPool) -> Result<User, LogicErr> {
   users.fetch_by_id(id).await
 }
@@ -20,11 +24,18 @@ pub async fn get_user_by_handle(handle: &str, users: &UserPool) -> Result<Option
   users.fetch_by_handle(handle).await
 }
 
+pub async fn assert_admin(users: &UserPool, user_id: &Uuid) -> Result<(), LogicErr> {
+  match users.user_is_admin(user_id).await {
+    true => Ok(()),
+    false => Err(LogicErr::UnauthorizedError),
+  }
+}
+
 pub async fn get_user_by_webfinger(webfinger: &str, users: &UserPool) -> Result<Option<User>, LogicErr> {
   users.fetch_by_fediverse_id(&webfinger.replace("acct:", "@")).await
 }
 
-pub async fn authorize_user(username: &str, password: &str, users: &UserPool) -> Result<String, LogicErr> {
+pub async fn authorize_user(username: &str, password: &str, users: &UserPool) -> Result<(), LogicErr> {
   let current_hash = match users.fetch_password_hash(username).await? {
     Some(hash) => hash,
     None => return Err(LogicErr::UnauthorizedError),
@@ -42,7 +53,59 @@ pub async fn authorize_user(username: &str, password: &str, users: &UserPool) ->
     return Err(LogicErr::UnauthorizedError);
   }
 
-  JwtFactory::generate_jwt_short_lived(username)
+  Ok(())
+}
+
+fn generate_key_pair() -> Result<(String, String), LogicErr> {
+  let mut rng = rand::thread_rng();
+  let bits = 2048;
+  let priv_key = match RsaPrivateKey::new(&mut rng, bits) {
+    Ok(key) => key,
+    Err(err) => return Err(LogicErr::InternalError(err.to_string())),
+  };
+  let pub_key = RsaPublicKey::from(&priv_key);
+
+  let priv_key = match priv_key.to_pkcs1_pem(LineEnding::LF) {
+    Ok(key) => key.to_string(),
+    Err(err) => return Err(LogicErr::InternalError(err.to_string())),
+  };
+
+  let pub_key = match pub_key.to_public_key_pem(LineEnding::LF) {
+    Ok(key) => key.to_string(),
+    Err(err) => return Err(LogicErr::InternalError(err.to_string())),
+  };
+
+  Ok((priv_key, pub_key))
+}
+
+/// Fetches the instance actor, creating it the first time it's needed. It has no password, so it can't be signed into.
+pub async fn get_instance_actor(users: &UserPool) -> Result<User, LogicErr> {
+  if let Some(user) = users.fetch_by_handle(INSTANCE_ACTOR_HANDLE).await? {
+    return match user.is_external || matches!(user.password_hash.as_deref(), Some(hash) if !hash.is_empty()) {
+      true => Err(LogicErr::InternalError(
+        "The instance actor's handle belongs to another user".to_string(),
+      )),
+      false => Ok(user),
+    };
+  }
+
+  let (priv_key, pub_key) = generate_key_pair()?;
+  let fediverse_id = format!("@{}@{}", INSTANCE_ACTOR_HANDLE, SETTINGS.server.fqdn);
+
+  let user_id = users
+    .create(
+      INSTANCE_ACTOR_HANDLE,
+      &fediverse_id,
+      &None,
+      &None,
+      "",
+      false,
+      &priv_key,
+      &pub_key,
+    )
+    .await?;
+
+  users.fetch_by_id(&user_id).await
 }
 
 pub async fn register_user(
@@ -50,7 +113,11 @@ pub async fn register_user(
   password: &str,
   email: &Option<String>,
   users: &UserPool,
-) -> Result<String, LogicErr> {
+) -> Result<(), LogicErr> {
+  if username == INSTANCE_ACTOR_HANDLE {
+    return Err(LogicErr::InvalidOperation("This username is not available".to_string()));
+  }
+
   let salt = SaltString::generate(&mut OsRng);
   let argon2 = Argon2::default();
 
@@ -70,23 +137,7 @@ pub async fn register_user(
       .to_string(),
   );
 
-  let mut rng = rand::thread_rng();
-  let bits = 2048;
-  let priv_key = match RsaPrivateKey::new(&mut rng, bits) {
-    Ok(key) => key,
-    Err(err) => return Err(LogicErr::InternalError(err.to_string())),
-  };
-  let pub_key = RsaPublicKey::from(&priv_key);
-
-  let priv_key = match priv_key.to_pkcs1_pem(LineEnding::LF) {
-    Ok(key) => key.to_string(),
-    Err(err) => return Err(LogicErr::InternalError(err.to_string())),
-  };
-
-  let pub_key = match pub_key.to_public_key_pem(LineEnding::LF) {
-    Ok(key) => key.to_string(),
-    Err(err) => return Err(LogicErr::InternalError(err.to_string())),
-  };
+  let (priv_key, pub_key) = generate_key_pair()?;
 
   match users
     .create(
@@ -105,7 +156,7 @@ pub async fn register_user(
     Err(err) => return Err(LogicErr::DbError(err.to_string())),
   };
 
-  JwtFactory::generate_jwt_short_lived(username)
+  Ok(())
 }
 
 #[cfg(test)]
@@ -117,7 +168,7 @@ mod tests {
   use crate::{
     db::user_repository::{MockUserRepo, UserPool},
     logic::{
-      user::{authorize_user, get_user_by_handle, get_user_by_webfinger},
+      user::{authorize_user, get_user_by_handle, get_user_by_webfinger, register_user, INSTANCE_ACTOR_HANDLE},
       LogicErr,
     },
   };
@@ -249,4 +300,17 @@ mod tests {
 
     assert!(authorize_user("handle", "test", &users).await.is_ok());
   }
+
+  #[async_std::test]
+  async fn test_register_user_rejects_instance_actor_handle() {
+    let mut user_repo = MockUserRepo::new();
+    user_repo.expect_create().times(0);
+
+    let users: UserPool = Arc::new(user_repo);
+
+    assert_eq!(
+      register_user(INSTANCE_ACTOR_HANDLE, "test", &None, &users).await,
+      Err(LogicErr::InvalidOperation("This username is not available".to_string()))
+    );
+  }
 }
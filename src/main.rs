@@ -11,11 +11,13 @@ mod federation;
 mod helpers;
 mod job;
 mod logic;
+mod mastodon;
 mod model;
 mod net;
 mod rabbitmq;
 mod routes;
 mod settings;
+mod streaming;
 mod work_queue;
 
 use actix_cors::Cors;
@@ -38,25 +40,71 @@ use routes::activitypub::{
   api_activitypub_federate_orbit_inbox, api_activitypub_federate_shared_inbox, api_activitypub_federate_user_inbox,
   api_activitypub_get_comment, api_activitypub_get_comments, api_activitypub_get_federated_orbit_posts,
   api_activitypub_get_federated_user_liked_posts, api_activitypub_get_federated_user_posts, api_activitypub_get_orbit,
-  api_activitypub_get_orbit_members, api_activitypub_get_post, api_activitypub_get_user_followers,
-  api_activitypub_get_user_following, api_activitypub_get_user_profile,
+  api_activitypub_get_orbit_members, api_activitypub_get_orbit_outbox, api_activitypub_get_post,
+  api_activitypub_get_user_followers, api_activitypub_get_user_following, api_activitypub_get_user_outbox,
+  api_activitypub_get_user_profile,
+};
+use routes::admin::{
+  api_delete_domain_policy, api_get_account_restrictions, api_get_domain_policies, api_lift_account_restriction,
+  api_restrict_account, api_upsert_domain_policy,
 };
 use routes::apps::api_create_app;
+use routes::block::{api_create_block, api_delete_block};
 use routes::comment::{
   api_create_comment, api_create_comment_like, api_delete_comment, api_delete_comment_like, api_get_comment,
   api_get_comments,
 };
-use routes::follow::{api_create_follow, api_delete_follow};
+use routes::follow::{
+  api_accept_follow_request, api_create_follow, api_delete_follow, api_get_follow_requests, api_reject_follow_request,
+};
 use routes::host_meta::api_get_host_meta;
 use routes::job::api_job_query_status;
 use routes::like::{api_create_like, api_delete_like};
+use routes::mastodon::accounts::{
+  api_mastodon_follow_account, api_mastodon_get_account, api_mastodon_get_account_followers,
+  api_mastodon_get_account_following, api_mastodon_get_account_statuses, api_mastodon_get_relationships,
+  api_mastodon_lookup_account, api_mastodon_unfollow_account, api_mastodon_verify_credentials,
+};
+use routes::mastodon::apps::api_mastodon_create_app;
+use routes::mastodon::instance::{api_mastodon_get_instance_v1, api_mastodon_get_instance_v2};
+use routes::mastodon::lists::{api_mastodon_get_list, api_mastodon_get_lists};
+use routes::mastodon::media::api_mastodon_upload_media;
+use routes::mastodon::notifications::{
+  api_mastodon_clear_notifications, api_mastodon_dismiss_notification, api_mastodon_get_notification,
+  api_mastodon_get_notifications,
+};
+use routes::mastodon::search::api_mastodon_search;
+use routes::mastodon::statuses::{
+  api_mastodon_create_status, api_mastodon_delete_status, api_mastodon_favourite_status, api_mastodon_get_status,
+  api_mastodon_get_status_context, api_mastodon_reblog_status, api_mastodon_unfavourite_status,
+  api_mastodon_unreblog_status,
+};
+use routes::mastodon::timelines::{
+  api_mastodon_home_timeline, api_mastodon_list_timeline, api_mastodon_public_timeline, api_mastodon_tag_timeline,
+};
+use routes::mute::{
+  api_create_keyword_filter, api_delete_keyword_filter, api_get_keyword_filters, api_get_orbit_mutes,
+  api_get_user_mutes, api_mute_orbit, api_mute_user, api_unmute_orbit, api_unmute_user,
+};
 use routes::nodeinfo::{api_get_nodeinfo, api_get_nodeinfo_2_1};
+use routes::notification::{
+  api_clear_notifications, api_dismiss_notification, api_get_notifications, api_get_unread_notifications_count,
+  api_mark_notification_read, api_mark_notifications_read,
+};
 use routes::oauth::{api_oauth_authorize, api_oauth_authorize_post, api_oauth_token};
 use routes::orbit::{
   api_create_orbit, api_create_orbit_moderator, api_delete_orbit, api_delete_orbit_moderator, api_get_orbit,
   api_get_orbit_moderators, api_get_orbit_named, api_get_orbits, api_get_popular_orbits, api_get_user_orbits,
   api_join_orbit, api_leave_orbit, api_update_orbit, api_update_orbit_assets, api_update_orbit_moderator,
 };
+use routes::orbit_membership::{
+  api_approve_orbit_join_request, api_create_orbit_invite, api_delete_orbit_invite, api_get_orbit_invites,
+  api_get_orbit_join_requests, api_reject_orbit_join_request,
+};
+use routes::orbit_moderation::{
+  api_ban_orbit_user, api_get_orbit_bans, api_get_orbit_modlog, api_lock_orbit_post, api_pin_orbit_post,
+  api_remove_orbit_comment, api_remove_orbit_post, api_unban_orbit_user, api_unlock_orbit_post, api_unpin_orbit_post,
+};
 use routes::post::{
   api_boost_post, api_create_post, api_delete_post, api_get_global_feed, api_get_orbit_feed, api_get_orbit_feed_by_id,
   api_get_post, api_get_user_friends_feed, api_get_user_liked_posts, api_get_user_own_feed, api_get_user_post,
@@ -68,14 +116,18 @@ use routes::redirect::{
   api_redirect_to_orbit_members, api_redirect_to_post, api_redirect_to_post_comment, api_redirect_to_post_comments,
   api_redirect_to_user, api_redirect_to_user_followers, api_redirect_to_user_following,
 };
+use routes::report::{api_create_report, api_get_admin_reports, api_get_orbit_reports, api_update_report};
 use routes::search::api_search;
 use routes::status::api_get_server_status;
+use routes::streaming::{api_stream, api_stream_orbit};
+use routes::tag::api_get_tag_feed;
 use routes::user::{
   api_get_profile, api_get_user_followers, api_get_user_following, api_get_user_profile, api_get_user_stats,
   api_update_profile, api_update_profile_assets,
 };
 use routes::webfinger::api_webfinger_query_resource;
 use settings::SETTINGS;
+use streaming::{hub::StreamHub, pubsub::PubSub};
 use work_queue::queue::Queue;
 
 #[actix_web::main]
@@ -112,22 +164,43 @@ async fn main() -> std::io::Result<()> {
     pool
   };
 
+  let account_restriction_pool = Repository::new_account_restriction_pool(&pool);
   let app_pool = Repository::new_app_pool(&pool);
+  let authorization_code_pool = Repository::new_authorization_code_pool(&pool);
+  let block_pool = Repository::new_block_pool(&pool);
   let comment_pool = Repository::new_comment_pool(&pool);
+  let domain_policy_pool = Repository::new_domain_policy_pool(&pool);
   let event_pool = Repository::new_event_pool(&pool);
   let follow_pool = Repository::new_follow_pool(&pool);
   let job_pool = Repository::new_job_pool(&pool);
   let like_pool = Repository::new_like_pool(&pool);
+  let mute_pool = Repository::new_mute_pool(&pool);
+  let notification_pool = Repository::new_notification_pool(&pool);
   let post_pool = Repository::new_post_pool(&pool);
   let post_attachment_pool = Repository::new_post_attachment_pool(&pool);
+  let post_mention_pool = Repository::new_post_mention_pool(&pool);
+  let tag_pool = Repository::new_tag_pool(&pool);
+  let report_pool = Repository::new_report_pool(&pool);
+  let search_pool = Repository::new_search_pool(&pool);
   let session_pool = Repository::new_session_pool(&pool);
   let user_pool = Repository::new_user_pool(&pool);
   let user_stats_pool = Repository::new_user_stats_pool(&pool);
   let orbits = Repository::new_orbit_pool(&pool);
+  let orbit_membership = Repository::new_orbit_membership_pool(&pool);
+  let orbit_moderation = Repository::new_orbit_moderation_pool(&pool);
   let orbit_moderators = Repository::new_orbit_moderator_pool(&pool);
   let user_orbits = Repository::new_user_orbit_pool(&pool);
   let tombstones = Repository::new_tombstone_pool(&pool);
 
+  let pubsub = web::Data::new(PubSub::new(&pool));
+  let stream_hub = StreamHub::new();
+
+  {
+    let pubsub = pubsub.clone();
+    let stream_hub = stream_hub.clone();
+    actix_web::rt::spawn(async move { stream_hub.run(&pubsub).await });
+  }
+
   HttpServer::new(move || {
     let cors = Cors::default()
       .allowed_origin_fn(|_, _| true)
@@ -141,23 +214,37 @@ async fn main() -> std::io::Result<()> {
       .wrap(cors)
       .wrap(JwtSession::default())
       .app_data(web::Data::new(pool.clone()))
+      .app_data(web::Data::new(account_restriction_pool.clone()))
       .app_data(web::Data::new(app_pool.clone()))
+      .app_data(web::Data::new(authorization_code_pool.clone()))
+      .app_data(web::Data::new(block_pool.clone()))
       .app_data(web::Data::new(comment_pool.clone()))
+      .app_data(web::Data::new(domain_policy_pool.clone()))
       .app_data(web::Data::new(event_pool.clone()))
       .app_data(web::Data::new(follow_pool.clone()))
       .app_data(web::Data::new(job_pool.clone()))
       .app_data(web::Data::new(like_pool.clone()))
+      .app_data(web::Data::new(mute_pool.clone()))
+      .app_data(web::Data::new(notification_pool.clone()))
       .app_data(web::Data::new(post_pool.clone()))
       .app_data(web::Data::new(post_attachment_pool.clone()))
+      .app_data(web::Data::new(post_mention_pool.clone()))
+      .app_data(web::Data::new(tag_pool.clone()))
+      .app_data(web::Data::new(report_pool.clone()))
+      .app_data(web::Data::new(search_pool.clone()))
       .app_data(web::Data::new(session_pool.clone()))
       .app_data(web::Data::new(user_pool.clone()))
       .app_data(web::Data::new(user_stats_pool.clone()))
       .app_data(web::Data::new(orbits.clone()))
+      .app_data(web::Data::new(orbit_membership.clone()))
+      .app_data(web::Data::new(orbit_moderation.clone()))
       .app_data(web::Data::new(orbit_moderators.clone()))
       .app_data(web::Data::new(user_orbits.clone()))
       .app_data(web::Data::new(tombstones.clone()))
       .app_data(web::Data::new(Cdn::new()))
       .app_data(web::Data::new(Queue::new()))
+      .app_data(pubsub.clone())
+      .app_data(web::Data::new(stream_hub.clone()))
       .service(
         web::resource("/api/user/{user_id}")
           .name("get_user_by_id")
@@ -178,6 +265,16 @@ async fn main() -> std::io::Result<()> {
           )
           .route(web::get().guard(HTML_GUARD).to(api_redirect_to_federated_user_posts)),
       )
+      .service(
+        web::resource("/api/user/{user_id}/outbox")
+          .name("get_user_id_outbox")
+          .route(
+            web::get()
+              .guard(ACTIVITYPUB_ACCEPT_GUARD)
+              .to(api_activitypub_get_user_outbox),
+          )
+          .route(web::get().guard(HTML_GUARD).to(api_redirect_to_federated_user_posts)),
+      )
       .service(
         web::resource("/api/user/{user_id}/likes")
           .name("get_user_id_public_likes_feed")
@@ -233,6 +330,18 @@ async fn main() -> std::io::Result<()> {
           .route(web::post().to(api_create_follow))
           .route(web::delete().to(api_delete_follow)),
       )
+      .service(
+        web::resource("/api/users/{user_handle}/blocks")
+          .name("user_blocks")
+          .route(web::post().to(api_create_block))
+          .route(web::delete().to(api_delete_block)),
+      )
+      .service(
+        web::resource("/api/users/{user_handle}/mutes")
+          .name("user_mutes")
+          .route(web::post().to(api_mute_user))
+          .route(web::delete().to(api_unmute_user)),
+      )
       .service(
         web::resource("/api/users/{user_handle}/followers")
           .name("user_followers")
@@ -264,6 +373,17 @@ async fn main() -> std::io::Result<()> {
           .name("oauth_token")
           .route(web::post().to(api_oauth_token)),
       )
+      .service(
+        web::resource("/oauth/authorize")
+          .name("mastodon_oauth_authorize")
+          .route(web::get().to(api_oauth_authorize))
+          .route(web::post().to(api_oauth_authorize_post)),
+      )
+      .service(
+        web::resource("/oauth/token")
+          .name("mastodon_oauth_token")
+          .route(web::post().to(api_oauth_token)),
+      )
       .service(
         web::resource("/api/feed")
           .name("feed")
@@ -285,6 +405,11 @@ async fn main() -> std::io::Result<()> {
           .name("orbit_feed")
           .route(web::get().to(api_get_orbit_feed)),
       )
+      .service(
+        web::resource("/api/tags/{tag}/feed")
+          .name("tag_feed")
+          .route(web::get().to(api_get_tag_feed)),
+      )
       .service(
         web::resource("/api/feed/{post_id}")
           .name("post")
@@ -353,6 +478,53 @@ async fn main() -> std::io::Result<()> {
           .name("profile_assets")
           .route(web::post().to(api_update_profile_assets)),
       )
+      .service(
+        web::resource("/api/profile/follow-requests")
+          .name("profile_follow_requests")
+          .route(web::get().to(api_get_follow_requests)),
+      )
+      .service(
+        web::resource("/api/profile/follow-requests/{user_id}")
+          .name("profile_follow_request")
+          .route(web::put().to(api_accept_follow_request))
+          .route(web::delete().to(api_reject_follow_request)),
+      )
+      .service(
+        web::resource("/api/notifications")
+          .name("notifications")
+          .route(web::get().to(api_get_notifications))
+          .route(web::delete().to(api_clear_notifications)),
+      )
+      .service(
+        web::resource("/api/notifications/unread-count")
+          .name("notifications_unread_count")
+          .route(web::get().to(api_get_unread_notifications_count)),
+      )
+      .service(
+        web::resource("/api/notifications/read")
+          .name("notifications_read")
+          .route(web::post().to(api_mark_notifications_read)),
+      )
+      .service(
+        web::resource("/api/notifications/{notification_id}")
+          .name("notification")
+          .route(web::delete().to(api_dismiss_notification)),
+      )
+      .service(
+        web::resource("/api/notifications/{notification_id}/read")
+          .name("notification_read")
+          .route(web::post().to(api_mark_notification_read)),
+      )
+      .service(
+        web::resource("/api/streaming/orbit/{orbit_id}")
+          .name("streaming_orbit")
+          .route(web::get().to(api_stream_orbit)),
+      )
+      .service(
+        web::resource("/api/streaming/{stream}")
+          .name("streaming")
+          .route(web::get().to(api_stream)),
+      )
       .service(
         web::resource("/api/job/{job_id}")
           .name("jobs")
@@ -414,6 +586,16 @@ async fn main() -> std::io::Result<()> {
           .route(web::get().guard(HTML_GUARD).to(api_redirect_to_orbit))
           .route(web::get().to(api_get_orbit_feed_by_id)),
       )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/outbox")
+          .name("orbit_id_outbox")
+          .route(
+            web::get()
+              .guard(ACTIVITYPUB_ACCEPT_GUARD)
+              .to(api_activitypub_get_orbit_outbox),
+          )
+          .route(web::get().guard(HTML_GUARD).to(api_redirect_to_orbit)),
+      )
       .service(
         web::resource("/api/orbit/{orbit_id}/join")
           .name("orbit_join")
@@ -432,6 +614,139 @@ async fn main() -> std::io::Result<()> {
           .route(web::patch().to(api_update_orbit_moderator))
           .route(web::delete().to(api_delete_orbit_moderator)),
       )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/mutes")
+          .name("orbit_mutes")
+          .route(web::post().to(api_mute_orbit))
+          .route(web::delete().to(api_unmute_orbit)),
+      )
+      .service(
+        web::resource("/api/mutes/users")
+          .name("muted_users")
+          .route(web::get().to(api_get_user_mutes)),
+      )
+      .service(
+        web::resource("/api/mutes/orbits")
+          .name("muted_orbits")
+          .route(web::get().to(api_get_orbit_mutes)),
+      )
+      .service(
+        web::resource("/api/filters")
+          .name("keyword_filters")
+          .route(web::get().to(api_get_keyword_filters))
+          .route(web::post().to(api_create_keyword_filter)),
+      )
+      .service(
+        web::resource("/api/filters/{keyword_filter_id}")
+          .name("keyword_filter")
+          .route(web::delete().to(api_delete_keyword_filter)),
+      )
+      .service(
+        web::resource("/api/admin/domain-policies")
+          .name("admin_domain_policies")
+          .route(web::get().to(api_get_domain_policies))
+          .route(web::post().to(api_upsert_domain_policy)),
+      )
+      .service(
+        web::resource("/api/admin/domain-policies/{domain}")
+          .name("admin_domain_policy")
+          .route(web::delete().to(api_delete_domain_policy)),
+      )
+      .service(
+        web::resource("/api/admin/restrictions")
+          .name("admin_account_restrictions")
+          .route(web::get().to(api_get_account_restrictions)),
+      )
+      .service(
+        web::resource("/api/admin/users/{user_handle}/restrictions")
+          .name("admin_user_restrictions")
+          .route(web::post().to(api_restrict_account)),
+      )
+      .service(
+        web::resource("/api/admin/users/{user_handle}/restrictions/{restriction}")
+          .name("admin_user_restriction")
+          .route(web::delete().to(api_lift_account_restriction)),
+      )
+      .service(
+        web::resource("/api/admin/reports")
+          .name("admin_reports")
+          .route(web::get().to(api_get_admin_reports)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/reports")
+          .name("orbit_reports")
+          .route(web::get().to(api_get_orbit_reports)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/posts/{post_id}")
+          .name("orbit_post")
+          .route(web::delete().to(api_remove_orbit_post)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/posts/{post_id}/comments/{comment_id}")
+          .name("orbit_post_comment")
+          .route(web::delete().to(api_remove_orbit_comment)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/posts/{post_id}/lock")
+          .name("orbit_post_lock")
+          .route(web::put().to(api_lock_orbit_post))
+          .route(web::delete().to(api_unlock_orbit_post)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/posts/{post_id}/pin")
+          .name("orbit_post_pin")
+          .route(web::put().to(api_pin_orbit_post))
+          .route(web::delete().to(api_unpin_orbit_post)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/bans")
+          .name("orbit_bans")
+          .route(web::get().to(api_get_orbit_bans))
+          .route(web::post().to(api_ban_orbit_user)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/bans/{user_id}")
+          .name("orbit_ban")
+          .route(web::delete().to(api_unban_orbit_user)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/modlog")
+          .name("orbit_modlog")
+          .route(web::get().to(api_get_orbit_modlog)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/requests")
+          .name("orbit_join_requests")
+          .route(web::get().to(api_get_orbit_join_requests)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/requests/{user_id}")
+          .name("orbit_join_request")
+          .route(web::put().to(api_approve_orbit_join_request))
+          .route(web::delete().to(api_reject_orbit_join_request)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/invites")
+          .name("orbit_invites")
+          .route(web::get().to(api_get_orbit_invites))
+          .route(web::post().to(api_create_orbit_invite)),
+      )
+      .service(
+        web::resource("/api/orbit/{orbit_id}/invites/{user_id}")
+          .name("orbit_invite")
+          .route(web::delete().to(api_delete_orbit_invite)),
+      )
+      .service(
+        web::resource("/api/reports")
+          .name("reports")
+          .route(web::post().to(api_create_report)),
+      )
+      .service(
+        web::resource("/api/reports/{report_id}")
+          .name("report")
+          .route(web::patch().to(api_update_report)),
+      )
       .service(
         web::resource("/api/search")
           .name("search")
@@ -477,6 +792,167 @@ async fn main() -> std::io::Result<()> {
           .name("nodeinfo")
           .route(web::get().to(api_get_nodeinfo_2_1)),
       )
+      .service(
+        web::resource("/api/v1/apps")
+          .name("mastodon_apps")
+          .route(web::post().to(api_mastodon_create_app)),
+      )
+      .service(
+        web::resource("/api/v1/instance")
+          .name("mastodon_instance")
+          .route(web::get().to(api_mastodon_get_instance_v1)),
+      )
+      .service(
+        web::resource("/api/v2/instance")
+          .name("mastodon_instance_v2")
+          .route(web::get().to(api_mastodon_get_instance_v2)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/verify_credentials")
+          .name("mastodon_verify_credentials")
+          .route(web::get().to(api_mastodon_verify_credentials)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/lookup")
+          .name("mastodon_lookup_account")
+          .route(web::get().to(api_mastodon_lookup_account)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/relationships")
+          .name("mastodon_relationships")
+          .route(web::get().to(api_mastodon_get_relationships)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/{id}")
+          .name("mastodon_account")
+          .route(web::get().to(api_mastodon_get_account)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/{id}/statuses")
+          .name("mastodon_account_statuses")
+          .route(web::get().to(api_mastodon_get_account_statuses)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/{id}/followers")
+          .name("mastodon_account_followers")
+          .route(web::get().to(api_mastodon_get_account_followers)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/{id}/following")
+          .name("mastodon_account_following")
+          .route(web::get().to(api_mastodon_get_account_following)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/{id}/follow")
+          .name("mastodon_follow_account")
+          .route(web::post().to(api_mastodon_follow_account)),
+      )
+      .service(
+        web::resource("/api/v1/accounts/{id}/unfollow")
+          .name("mastodon_unfollow_account")
+          .route(web::post().to(api_mastodon_unfollow_account)),
+      )
+      .service(
+        web::resource("/api/v1/statuses")
+          .name("mastodon_statuses")
+          .route(web::post().to(api_mastodon_create_status)),
+      )
+      .service(
+        web::resource("/api/v1/statuses/{id}")
+          .name("mastodon_status")
+          .route(web::get().to(api_mastodon_get_status))
+          .route(web::delete().to(api_mastodon_delete_status)),
+      )
+      .service(
+        web::resource("/api/v1/statuses/{id}/context")
+          .name("mastodon_status_context")
+          .route(web::get().to(api_mastodon_get_status_context)),
+      )
+      .service(
+        web::resource("/api/v1/statuses/{id}/favourite")
+          .name("mastodon_favourite_status")
+          .route(web::post().to(api_mastodon_favourite_status)),
+      )
+      .service(
+        web::resource("/api/v1/statuses/{id}/unfavourite")
+          .name("mastodon_unfavourite_status")
+          .route(web::post().to(api_mastodon_unfavourite_status)),
+      )
+      .service(
+        web::resource("/api/v1/statuses/{id}/reblog")
+          .name("mastodon_reblog_status")
+          .route(web::post().to(api_mastodon_reblog_status)),
+      )
+      .service(
+        web::resource("/api/v1/statuses/{id}/unreblog")
+          .name("mastodon_unreblog_status")
+          .route(web::post().to(api_mastodon_unreblog_status)),
+      )
+      .service(
+        web::resource("/api/v1/timelines/home")
+          .name("mastodon_home_timeline")
+          .route(web::get().to(api_mastodon_home_timeline)),
+      )
+      .service(
+        web::resource("/api/v1/timelines/public")
+          .name("mastodon_public_timeline")
+          .route(web::get().to(api_mastodon_public_timeline)),
+      )
+      .service(
+        web::resource("/api/v1/timelines/tag/{tag}")
+          .name("mastodon_tag_timeline")
+          .route(web::get().to(api_mastodon_tag_timeline)),
+      )
+      .service(
+        web::resource("/api/v1/timelines/list/{id}")
+          .name("mastodon_list_timeline")
+          .route(web::get().to(api_mastodon_list_timeline)),
+      )
+      .service(
+        web::resource("/api/v1/lists")
+          .name("mastodon_lists")
+          .route(web::get().to(api_mastodon_get_lists)),
+      )
+      .service(
+        web::resource("/api/v1/lists/{id}")
+          .name("mastodon_list")
+          .route(web::get().to(api_mastodon_get_list)),
+      )
+      .service(
+        web::resource("/api/v1/notifications")
+          .name("mastodon_notifications")
+          .route(web::get().to(api_mastodon_get_notifications)),
+      )
+      .service(
+        web::resource("/api/v1/notifications/clear")
+          .name("mastodon_clear_notifications")
+          .route(web::post().to(api_mastodon_clear_notifications)),
+      )
+      .service(
+        web::resource("/api/v1/notifications/{id}")
+          .name("mastodon_notification")
+          .route(web::get().to(api_mastodon_get_notification)),
+      )
+      .service(
+        web::resource("/api/v1/notifications/{id}/dismiss")
+          .name("mastodon_dismiss_notification")
+          .route(web::post().to(api_mastodon_dismiss_notification)),
+      )
+      .service(
+        web::resource("/api/v1/media")
+          .name("mastodon_media")
+          .route(web::post().to(api_mastodon_upload_media)),
+      )
+      .service(
+        web::resource("/api/v2/media")
+          .name("mastodon_media_v2")
+          .route(web::post().to(api_mastodon_upload_media)),
+      )
+      .service(
+        web::resource("/api/v2/search")
+          .name("mastodon_search")
+          .route(web::get().to(api_mastodon_search)),
+      )
       .service(
         web::resource("/api/static/{path:.*}")
           .name("static_files")
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+  helpers::api::{relative_cdn_to_absolute_cdn_uri, relative_to_absolute_uri},
+  model::{comment_pub::CommentPub, post_event::PostEvent, user::User, user_account_pub::UserAccountPub},
+  settings::SETTINGS,
+};
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountField {
+  pub name: String,
+  pub value: String,
+  pub verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// The editable parts of a profile, only shown to the user it belongs to
+pub struct AccountSource {
+  pub note: String,
+  pub fields: Vec<AccountField>,
+  pub privacy: String,
+  pub sensitive: bool,
+  pub language: Option<String>,
+  pub follow_requests_count: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountCounts {
+  pub followers: i64,
+  pub following: i64,
+  pub statuses: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+  pub id: Uuid,
+  pub username: String,
+  pub acct: String,
+  pub display_name: String,
+  pub locked: bool,
+  pub bot: bool,
+  pub group: bool,
+  pub discoverable: bool,
+  pub created_at: DateTime<Utc>,
+  pub note: String,
+  pub url: String,
+  pub uri: String,
+  pub avatar: String,
+  pub avatar_static: String,
+  pub header: String,
+  pub header_static: String,
+  pub followers_count: i64,
+  pub following_count: i64,
+  pub statuses_count: i64,
+  pub last_status_at: Option<String>,
+  pub emojis: Vec<serde_json::Value>,
+  pub fields: Vec<AccountField>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub source: Option<AccountSource>,
+}
+
+/// How Mastodon addresses a user: local users by their handle alone, remote users as `user@host`
+pub fn account_acct(handle: &str, fediverse_id: &str, fediverse_uri: &str) -> String {
+  match fediverse_uri.starts_with("http") {
+    true => fediverse_id.trim_start_matches('@').to_string(),
+    false => handle.to_string(),
+  }
+}
+
+fn account_url(handle: &str, fediverse_uri: &str) -> String {
+  match fediverse_uri.starts_with("http") {
+    true => fediverse_uri.to_string(),
+    false => format!("{}/users/{}", SETTINGS.server.fqdn, handle),
+  }
+}
+
+/// Clients expect every account to have an avatar and header, so those without one get the instance's defaults
+fn account_images(avatar_url: &Option<String>) -> (String, String) {
+  let avatar = match avatar_url {
+    Some(avatar_url) => relative_cdn_to_absolute_cdn_uri(avatar_url),
+    None => format!("{}/static/android-chrome-192x192.png", SETTINGS.server.api_fqdn),
+  };
+
+  (avatar, format!("{}/static/images/space.png", SETTINGS.server.api_fqdn))
+}
+
+impl Account {
+  fn build(
+    id: Uuid,
+    handle: &str,
+    fediverse_id: &str,
+    fediverse_uri: &str,
+    avatar_url: &Option<String>,
+    created_at: DateTime<Utc>,
+  ) -> Account {
+    let (avatar, header) = account_images(avatar_url);
+
+    Account {
+      id,
+      username: handle.to_string(),
+      acct: account_acct(handle, fediverse_id, fediverse_uri),
+      display_name: handle.to_string(),
+      locked: false,
+      bot: false,
+      group: false,
+      discoverable: true,
+      created_at,
+      note: String::new(),
+      url: account_url(handle, fediverse_uri),
+      uri: relative_to_absolute_uri(fediverse_uri),
+      avatar: avatar.clone(),
+      avatar_static: avatar,
+      header: header.clone(),
+      header_static: header,
+      followers_count: 0,
+      following_count: 0,
+      statuses_count: 0,
+      last_status_at: None,
+      emojis: vec![],
+      fields: vec![],
+      source: None,
+    }
+  }
+
+  pub fn from_user(user: &User, counts: &AccountCounts) -> Account {
+    Account::from_account_pub(&UserAccountPub::from(user.clone()), counts)
+  }
+
+  pub fn from_account_pub(user: &UserAccountPub, counts: &AccountCounts) -> Account {
+    let links = [
+      (&user.url_1_title, &user.url_1),
+      (&user.url_2_title, &user.url_2),
+      (&user.url_3_title, &user.url_3),
+      (&user.url_4_title, &user.url_4),
+      (&user.url_5_title, &user.url_5),
+    ];
+
+    let fields = links
+      .into_iter()
+      .filter_map(|(title, url)| {
+        url.as_ref().map(|url| AccountField {
+          name: title.clone().unwrap_or_else(|| "Website".to_string()),
+          value: format!(
+            r#"<a href="{0}" rel="nofollow noopener noreferrer" target="_blank">{0}</a>"#,
+            url
+          ),
+          verified_at: None,
+        })
+      })
+      .collect();
+
+    Account {
+      locked: user.manually_approves_followers,
+      note: user.intro_html.clone().unwrap_or_default(),
+      followers_count: counts.followers,
+      following_count: counts.following,
+      statuses_count: counts.statuses,
+      fields,
+      ..Account::build(
+        user.user_id,
+        &user.handle,
+        &user.fediverse_id,
+        &user.fediverse_uri,
+        &user.avatar_url,
+        user.created_at,
+      )
+    }
+  }
+
+  /// The account signed in, which also carries what its profile was written as
+  pub fn from_own_user(user: &User, counts: &AccountCounts, follow_requests_count: i64) -> Account {
+    let account = Account::from_user(user, counts);
+
+    Account {
+      source: Some(AccountSource {
+        note: user.intro_md.clone().unwrap_or_default(),
+        fields: account.fields.clone(),
+        privacy: "public".to_string(),
+        sensitive: false,
+        language: None,
+        follow_requests_count,
+      }),
+      ..account
+    }
+  }
+
+  /// The author of a post. Posts don't carry when their author joined or how many followers they have, so the post's
+  /// own creation date stands in and the counts are left at zero.
+  pub fn from_post_author(post: &PostEvent) -> Account {
+    Account::build(
+      post.user_id,
+      &post.user_handle,
+      &post.user_fediverse_id,
+      &post.user_fediverse_uri,
+      &post.user_avatar_url,
+      post.created_at,
+    )
+  }
+
+  pub fn from_comment_author(comment: &CommentPub) -> Account {
+    Account::build(
+      comment.user_id,
+      &comment.user_handle,
+      &comment.user_fediverse_id,
+      &comment.user_fediverse_uri,
+      &comment.user_avatar_url,
+      comment.created_at,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::mastodon::account::account_acct;
+
+  #[test]
+  fn test_account_acct() {
+    assert_eq!(account_acct("alice", "@alice@orbit.example", "/user/1"), "alice");
+    assert_eq!(
+      account_acct("bob", "@bob@mastodon.example", "https://mastodon.example/users/bob"),
+      "bob@mastodon.example"
+    );
+  }
+}
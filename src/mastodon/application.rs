@@ -0,0 +1,47 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::model::app::App;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Application {
+  pub id: Uuid,
+  pub name: String,
+  pub website: Option<String>,
+  pub scopes: Vec<String>,
+  pub redirect_uri: String,
+  pub redirect_uris: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub client_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub client_secret: Option<String>,
+}
+
+impl Application {
+  /// An app as its owner sees it right after registering it, the only time its credentials are shown
+  pub fn with_credentials(app: &App) -> Application {
+    Application {
+      client_id: Some(app.client_id.clone()),
+      client_secret: Some(app.client_secret.clone()),
+      ..Application::from(app)
+    }
+  }
+}
+
+impl From<&App> for Application {
+  fn from(app: &App) -> Self {
+    Application {
+      id: app.app_id,
+      name: app.name.clone(),
+      website: match app.owner_uri.is_empty() {
+        true => None,
+        false => Some(app.owner_uri.clone()),
+      },
+      scopes: app.scopes.split_whitespace().map(|s| s.to_string()).collect(),
+      redirect_uri: app.redirect_uri.clone(),
+      redirect_uris: app.redirect_uri.split_whitespace().map(|s| s.to_string()).collect(),
+      client_id: None,
+      client_secret: None,
+    }
+  }
+}
@@ -0,0 +1,233 @@
+use serde::Serialize;
+
+use crate::{helpers::api::local_domain, settings::SETTINGS};
+
+/// Posts aren't limited in length, but clients need a limit to count down from
+pub const MAX_STATUS_CHARACTERS: i64 = 65535;
+pub const MAX_STATUS_MEDIA: usize = 4;
+/// The largest upload the multipart form parser takes
+const MAX_IMAGE_SIZE: i64 = 50 * 1024 * 1024;
+const SUPPORTED_MIME_TYPES: [&str; 2] = ["image/png", "image/jpeg"];
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusConfiguration {
+  pub max_characters: i64,
+  pub max_media_attachments: usize,
+  pub characters_reserved_per_url: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MediaConfiguration {
+  pub supported_mime_types: Vec<String>,
+  pub image_size_limit: i64,
+  pub image_matrix_limit: i64,
+  pub video_size_limit: i64,
+  pub video_frame_rate_limit: i64,
+  pub video_matrix_limit: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PollConfiguration {
+  pub max_options: i64,
+  pub max_characters_per_option: i64,
+  pub min_expiration: i64,
+  pub max_expiration: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceConfiguration {
+  pub statuses: StatusConfiguration,
+  pub media_attachments: MediaConfiguration,
+  /// Polls can't be made, so every limit is zero
+  pub polls: PollConfiguration,
+}
+
+impl Default for InstanceConfiguration {
+  fn default() -> Self {
+    InstanceConfiguration {
+      statuses: StatusConfiguration {
+        max_characters: MAX_STATUS_CHARACTERS,
+        max_media_attachments: MAX_STATUS_MEDIA,
+        characters_reserved_per_url: 0,
+      },
+      media_attachments: MediaConfiguration {
+        supported_mime_types: SUPPORTED_MIME_TYPES.iter().map(|t| t.to_string()).collect(),
+        image_size_limit: MAX_IMAGE_SIZE,
+        image_matrix_limit: 0,
+        video_size_limit: 0,
+        video_frame_rate_limit: 0,
+        video_matrix_limit: 0,
+      },
+      polls: PollConfiguration {
+        max_options: 0,
+        max_characters_per_option: 0,
+        min_expiration: 0,
+        max_expiration: 0,
+      },
+    }
+  }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceStats {
+  pub user_count: i64,
+  pub status_count: i64,
+  pub domain_count: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceUrls {
+  pub streaming_api: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceV1 {
+  pub uri: String,
+  pub title: String,
+  pub short_description: String,
+  pub description: String,
+  pub email: String,
+  pub version: String,
+  pub urls: InstanceUrls,
+  pub stats: InstanceStats,
+  pub thumbnail: Option<String>,
+  pub languages: Vec<String>,
+  pub registrations: bool,
+  pub approval_required: bool,
+  pub invites_enabled: bool,
+  pub configuration: InstanceConfiguration,
+  pub contact_account: Option<serde_json::Value>,
+  pub rules: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceUsers {
+  pub active_month: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceUsage {
+  pub users: InstanceUsers,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceThumbnail {
+  pub url: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceConfigurationV2 {
+  pub urls: InstanceUrls,
+  #[serde(flatten)]
+  pub configuration: InstanceConfiguration,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceRegistrations {
+  pub enabled: bool,
+  pub approval_required: bool,
+  pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceContact {
+  pub email: String,
+  pub account: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceV2 {
+  pub domain: String,
+  pub title: String,
+  pub version: String,
+  pub source_url: String,
+  pub description: String,
+  pub usage: InstanceUsage,
+  pub thumbnail: InstanceThumbnail,
+  pub languages: Vec<String>,
+  pub configuration: InstanceConfigurationV2,
+  pub registrations: InstanceRegistrations,
+  pub contact: InstanceContact,
+  pub rules: Vec<serde_json::Value>,
+}
+
+/// The version clients see. Clients decide what they can do from the Mastodon version, so that comes first.
+fn instance_version() -> String {
+  format!(
+    "4.0.0 (compatible; {} {})",
+    env!("CARGO_PKG_NAME"),
+    env!("CARGO_PKG_VERSION")
+  )
+}
+
+fn instance_urls() -> InstanceUrls {
+  InstanceUrls {
+    streaming_api: SETTINGS.server.api_root_fqdn.replacen("http", "ws", 1),
+  }
+}
+
+fn instance_thumbnail() -> String {
+  format!("{}/static/android-chrome-384x384.png", SETTINGS.server.api_fqdn)
+}
+
+impl InstanceV1 {
+  pub fn new(user_count: i64, status_count: i64) -> InstanceV1 {
+    InstanceV1 {
+      uri: local_domain(),
+      title: local_domain(),
+      short_description: String::new(),
+      description: String::new(),
+      email: String::new(),
+      version: instance_version(),
+      urls: instance_urls(),
+      stats: InstanceStats {
+        user_count,
+        status_count,
+        domain_count: 0,
+      },
+      thumbnail: Some(instance_thumbnail()),
+      languages: vec!["en".to_string()],
+      registrations: true,
+      approval_required: false,
+      invites_enabled: false,
+      configuration: InstanceConfiguration::default(),
+      contact_account: None,
+      rules: vec![],
+    }
+  }
+}
+
+impl InstanceV2 {
+  pub fn new(user_count: i64) -> InstanceV2 {
+    InstanceV2 {
+      domain: local_domain(),
+      title: local_domain(),
+      version: instance_version(),
+      source_url: env!("CARGO_PKG_REPOSITORY").to_string(),
+      description: String::new(),
+      usage: InstanceUsage {
+        users: InstanceUsers {
+          active_month: user_count,
+        },
+      },
+      thumbnail: InstanceThumbnail {
+        url: instance_thumbnail(),
+      },
+      languages: vec!["en".to_string()],
+      configuration: InstanceConfigurationV2 {
+        urls: instance_urls(),
+        configuration: InstanceConfiguration::default(),
+      },
+      registrations: InstanceRegistrations {
+        enabled: true,
+        approval_required: false,
+        message: None,
+      },
+      contact: InstanceContact {
+        email: String::new(),
+        account: None,
+      },
+      rules: vec![],
+    }
+  }
+}
@@ -0,0 +1,24 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::model::orbit::Orbit;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+/// The orbits a user has joined are their lists, each listing the orbit's posts
+pub struct List {
+  pub id: Uuid,
+  pub title: String,
+  pub replies_policy: String,
+  pub exclusive: bool,
+}
+
+impl From<&Orbit> for List {
+  fn from(orbit: &Orbit) -> Self {
+    List {
+      id: orbit.orbit_id,
+      title: orbit.name.clone(),
+      replies_policy: "list".to_string(),
+      exclusive: false,
+    }
+  }
+}
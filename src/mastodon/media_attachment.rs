@@ -0,0 +1,63 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{helpers::api::relative_cdn_to_absolute_cdn_uri, model::post_attachment::PostAttachment};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MediaDimensions {
+  pub width: i32,
+  pub height: i32,
+  pub size: String,
+  pub aspect: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MediaMeta {
+  pub original: MediaDimensions,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MediaAttachment {
+  pub id: Uuid,
+  #[serde(rename = "type")]
+  pub kind: String,
+  pub url: Option<String>,
+  pub preview_url: Option<String>,
+  pub remote_url: Option<String>,
+  pub text_url: Option<String>,
+  pub meta: MediaMeta,
+  pub description: Option<String>,
+  pub blurhash: Option<String>,
+}
+
+impl From<&PostAttachment> for MediaAttachment {
+  fn from(attachment: &PostAttachment) -> Self {
+    let url = attachment.uri.as_deref().map(relative_cdn_to_absolute_cdn_uri);
+    let kind = match &attachment.content_type {
+      Some(content_type) if content_type.starts_with("image/") => "image",
+      _ => "unknown",
+    };
+
+    MediaAttachment {
+      id: attachment.attachment_id,
+      kind: kind.to_string(),
+      url: url.clone(),
+      preview_url: url,
+      remote_url: None,
+      text_url: None,
+      meta: MediaMeta {
+        original: MediaDimensions {
+          width: attachment.width,
+          height: attachment.height,
+          size: format!("{}x{}", attachment.width, attachment.height),
+          aspect: match attachment.height {
+            0 => 1.0,
+            height => f64::from(attachment.width) / f64::from(height),
+          },
+        },
+      },
+      description: None,
+      blurhash: attachment.blurhash.clone(),
+    }
+  }
+}
@@ -0,0 +1,11 @@
+pub mod account;
+pub mod application;
+pub mod instance;
+pub mod list;
+pub mod media_attachment;
+pub mod notification;
+pub mod params;
+pub mod relationship;
+pub mod search;
+pub mod status;
+pub mod tag;
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::model::notification_type::NotificationType;
+
+use super::{account::Account, status::Status};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Notification {
+  pub id: Uuid,
+  #[serde(rename = "type")]
+  pub kind: String,
+  pub created_at: DateTime<Utc>,
+  pub account: Account,
+  pub status: Option<Status>,
+}
+
+/// The Mastodon type a notification is shown as. Replies are mentions as far as Mastodon is concerned, while orbit
+/// notifications have no counterpart and aren't shown at all.
+pub fn notification_kind(notification_type: &NotificationType) -> Option<&'static str> {
+  match notification_type {
+    NotificationType::Follow => Some("follow"),
+    NotificationType::FollowRequest => Some("follow_request"),
+    NotificationType::Like => Some("favourite"),
+    NotificationType::Boost => Some("reblog"),
+    NotificationType::Mention | NotificationType::Comment => Some("mention"),
+    NotificationType::OrbitJoin | NotificationType::OrbitJoinRequest | NotificationType::Unknown => None,
+  }
+}
+
+/// The notification types a Mastodon type covers
+pub fn notification_types(kind: &str) -> Vec<NotificationType> {
+  match kind {
+    "follow" => vec![NotificationType::Follow],
+    "follow_request" => vec![NotificationType::FollowRequest],
+    "favourite" => vec![NotificationType::Like],
+    "reblog" => vec![NotificationType::Boost],
+    "mention" => vec![NotificationType::Mention, NotificationType::Comment],
+    _ => vec![],
+  }
+}
+
+/// Every notification type that can be shown, for filtering out the ones that can't
+pub fn shown_notification_types() -> Vec<NotificationType> {
+  ["follow", "follow_request", "favourite", "reblog", "mention"]
+    .into_iter()
+    .flat_map(notification_types)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    mastodon::notification::{notification_kind, notification_types, shown_notification_types},
+    model::notification_type::NotificationType,
+  };
+
+  #[test]
+  fn test_notification_types_map_back() {
+    for notification_type in shown_notification_types() {
+      let kind = notification_kind(&notification_type).unwrap();
+      assert!(notification_types(kind).contains(&notification_type));
+    }
+
+    assert_eq!(notification_kind(&NotificationType::OrbitJoin), None);
+    assert!(notification_types("poll").is_empty());
+  }
+}
@@ -0,0 +1,109 @@
+use std::ops::Deref;
+
+use actix_web::{dev::Payload, error::InternalError, http::header::CONTENT_TYPE, web::Bytes, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+
+use crate::helpers::core::build_api_err;
+
+/// Parameters sent by a Mastodon client. Clients send them as JSON, as a form or in the query string depending on the
+/// client and the endpoint, with lists written as `media_ids[]=a&media_ids[]=b` outside of JSON.
+pub struct MastodonParams<T>(pub T);
+
+impl<T> Deref for MastodonParams<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+fn parse_params<T: DeserializeOwned>(req: &HttpRequest, body: &[u8]) -> Result<T, String> {
+  if body.is_empty() {
+    return serde_qs::Config::new(5, false)
+      .deserialize_str(req.query_string())
+      .map_err(|err| err.to_string());
+  }
+
+  let is_json = req
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|value| value.to_str().ok())
+    .map(|value| value.starts_with("application/json"))
+    .unwrap_or(false);
+
+  match is_json {
+    true => serde_json::from_slice(body).map_err(|err| err.to_string()),
+    false => serde_qs::Config::new(5, false)
+      .deserialize_bytes(body)
+      .map_err(|err| err.to_string()),
+  }
+}
+
+impl<T: DeserializeOwned + 'static> FromRequest for MastodonParams<T> {
+  type Error = actix_web::Error;
+  type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+  fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+    let req = req.clone();
+    let body = Bytes::from_request(&req, payload);
+
+    Box::pin(async move {
+      let body = body.await?;
+
+      parse_params(&req, &body)
+        .map(MastodonParams)
+        .map_err(|err| InternalError::from_response(err.clone(), build_api_err(400, err, None)).into())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use actix_web::test::TestRequest;
+  use serde::Deserialize;
+
+  use super::parse_params;
+
+  #[derive(Deserialize, Debug, PartialEq, Eq)]
+  struct Params {
+    status: Option<String>,
+    #[serde(default)]
+    media_ids: Vec<String>,
+  }
+
+  #[test]
+  fn test_parse_params_from_each_source() {
+    let expected = Params {
+      status: Some("hi".to_string()),
+      media_ids: vec!["a".to_string(), "b".to_string()],
+    };
+
+    let req = TestRequest::default()
+      .uri("/?status=hi&media_ids[]=a&media_ids[]=b")
+      .to_http_request();
+    assert_eq!(parse_params::<Params>(&req, b""), Ok(expected));
+
+    let req = TestRequest::default()
+      .insert_header(("Content-Type", "application/x-www-form-urlencoded"))
+      .to_http_request();
+    assert_eq!(
+      parse_params::<Params>(&req, b"status=hi&media_ids%5B%5D=a"),
+      Ok(Params {
+        status: Some("hi".to_string()),
+        media_ids: vec!["a".to_string()],
+      })
+    );
+
+    let req = TestRequest::default()
+      .insert_header(("Content-Type", "application/json"))
+      .to_http_request();
+    assert_eq!(
+      parse_params::<Params>(&req, br#"{"status":"hi"}"#),
+      Ok(Params {
+        status: Some("hi".to_string()),
+        media_ids: vec![],
+      })
+    );
+  }
+}
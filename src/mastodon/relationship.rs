@@ -0,0 +1,22 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+/// How the signed in user and another account relate to each other
+pub struct Relationship {
+  pub id: Uuid,
+  pub following: bool,
+  pub showing_reblogs: bool,
+  pub notifying: bool,
+  pub languages: Vec<String>,
+  pub followed_by: bool,
+  pub blocking: bool,
+  pub blocked_by: bool,
+  pub muting: bool,
+  pub muting_notifications: bool,
+  pub requested: bool,
+  pub requested_by: bool,
+  pub domain_blocking: bool,
+  pub endorsed: bool,
+  pub note: String,
+}
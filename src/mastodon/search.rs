@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+use super::{account::Account, status::Status, tag::Tag};
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct SearchResults {
+  pub accounts: Vec<Account>,
+  pub statuses: Vec<Status>,
+  pub hashtags: Vec<Tag>,
+}
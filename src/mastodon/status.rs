@@ -0,0 +1,231 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+  helpers::api::relative_to_absolute_uri,
+  model::{access_type::AccessType, comment_pub::CommentPub, post_event::PostEvent, post_mention::PostMention},
+  settings::SETTINGS,
+};
+
+use super::{account::Account, media_attachment::MediaAttachment, tag::Tag};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusVisibility {
+  Public,
+  Unlisted,
+  Private,
+  Direct,
+}
+
+impl From<&AccessType> for StatusVisibility {
+  fn from(access: &AccessType) -> Self {
+    match access {
+      AccessType::PublicFederated | AccessType::PublicLocal => StatusVisibility::Public,
+      AccessType::Unlisted => StatusVisibility::Unlisted,
+      _ => StatusVisibility::Private,
+    }
+  }
+}
+
+impl StatusVisibility {
+  /// What a status posted with this visibility is stored as. There are no direct messages, so those can't be posted.
+  pub fn access_type(&self) -> Option<AccessType> {
+    match self {
+      StatusVisibility::Public => Some(AccessType::PublicFederated),
+      StatusVisibility::Unlisted => Some(AccessType::Unlisted),
+      StatusVisibility::Private => Some(AccessType::FollowersOnly),
+      StatusVisibility::Direct => None,
+    }
+  }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusMention {
+  pub id: Uuid,
+  pub username: String,
+  pub url: String,
+  pub acct: String,
+}
+
+impl From<&PostMention> for StatusMention {
+  fn from(mention: &PostMention) -> Self {
+    StatusMention {
+      id: mention.user_id,
+      username: mention.handle.clone(),
+      url: relative_to_absolute_uri(&mention.fediverse_uri),
+      acct: match mention.is_external {
+        true => mention.fediverse_id.trim_start_matches('@').to_string(),
+        false => mention.handle.clone(),
+      },
+    }
+  }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Status {
+  pub id: Uuid,
+  pub uri: String,
+  pub url: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub edited_at: Option<DateTime<Utc>>,
+  pub account: Account,
+  pub content: String,
+  pub visibility: StatusVisibility,
+  pub sensitive: bool,
+  pub spoiler_text: String,
+  pub media_attachments: Vec<MediaAttachment>,
+  pub mentions: Vec<StatusMention>,
+  pub tags: Vec<Tag>,
+  pub emojis: Vec<serde_json::Value>,
+  pub reblogs_count: i64,
+  pub favourites_count: i64,
+  pub replies_count: i64,
+  pub in_reply_to_id: Option<Uuid>,
+  pub in_reply_to_account_id: Option<Uuid>,
+  pub reblog: Option<Box<Status>>,
+  pub poll: Option<serde_json::Value>,
+  pub card: Option<serde_json::Value>,
+  pub language: Option<String>,
+  pub text: Option<String>,
+  pub favourited: Option<bool>,
+  pub reblogged: Option<bool>,
+  pub muted: Option<bool>,
+  pub bookmarked: Option<bool>,
+  pub pinned: Option<bool>,
+}
+
+impl Status {
+  /// A post as its author made it. A post's title becomes its content warning, and the orbit it was made in is
+  /// shown as a tag, since clients have nowhere else to show it.
+  pub fn from_post(post: &PostEvent) -> Status {
+    let mut tags: Vec<Tag> = post.tags.iter().map(|name| Tag::new(name)).collect();
+    if let Some(shortcode) = &post.orbit_shortcode {
+      tags.push(Tag::from_orbit(shortcode));
+    }
+
+    Status {
+      id: post.post_id,
+      uri: relative_to_absolute_uri(&post.uri),
+      url: Some(format!("{}/feed/{}", SETTINGS.server.fqdn, post.post_id)),
+      created_at: post.created_at,
+      edited_at: match post.updated_at > post.created_at {
+        true => Some(post.updated_at),
+        false => None,
+      },
+      account: Account::from_post_author(post),
+      content: post.content_html.clone(),
+      visibility: StatusVisibility::from(&post.visibility),
+      sensitive: post.sensitive || post.title.is_some(),
+      spoiler_text: post.title.clone().unwrap_or_default(),
+      media_attachments: post.attachments.iter().map(MediaAttachment::from).collect(),
+      mentions: post.mentions.iter().map(StatusMention::from).collect(),
+      tags,
+      emojis: vec![],
+      reblogs_count: 0,
+      favourites_count: post.likes,
+      replies_count: post.comments,
+      in_reply_to_id: None,
+      in_reply_to_account_id: None,
+      reblog: None,
+      poll: None,
+      card: None,
+      language: None,
+      text: Some(post.content_md.clone()),
+      favourited: post.liked,
+      reblogged: None,
+      muted: None,
+      bookmarked: None,
+      pinned: Some(post.pinned_at.is_some()),
+    }
+  }
+
+  /// A post shared by `booster`. The wrapper keeps the post's ID rather than one of its own, as feeds are walked by
+  /// post ID and clients page through timelines with the IDs of the statuses they were given.
+  pub fn from_boost(post: &PostEvent, booster: Account) -> Status {
+    let reblog = Status::from_post(post);
+
+    Status {
+      account: booster,
+      content: String::new(),
+      spoiler_text: String::new(),
+      sensitive: false,
+      media_attachments: vec![],
+      mentions: vec![],
+      tags: vec![],
+      text: None,
+      pinned: None,
+      reblog: Some(Box::new(reblog.clone())),
+      ..reblog
+    }
+  }
+
+  /// Comments are replies to the post they were made on
+  pub fn from_comment(comment: &CommentPub, post: &PostEvent) -> Status {
+    Status {
+      id: comment.comment_id,
+      uri: relative_to_absolute_uri(&comment.uri),
+      url: Some(format!("{}/feed/{}", SETTINGS.server.fqdn, comment.post_id)),
+      created_at: comment.created_at,
+      edited_at: match comment.updated_at > comment.created_at {
+        true => Some(comment.updated_at),
+        false => None,
+      },
+      account: Account::from_comment_author(comment),
+      content: comment.content_html.clone(),
+      visibility: StatusVisibility::from(&post.visibility),
+      sensitive: false,
+      spoiler_text: String::new(),
+      media_attachments: vec![],
+      mentions: vec![],
+      tags: vec![],
+      emojis: vec![],
+      reblogs_count: 0,
+      favourites_count: comment.likes,
+      replies_count: 0,
+      in_reply_to_id: Some(comment.post_id),
+      in_reply_to_account_id: Some(post.user_id),
+      reblog: None,
+      poll: None,
+      card: None,
+      language: None,
+      text: Some(comment.content_md.clone()),
+      favourited: comment.liked,
+      reblogged: None,
+      muted: None,
+      bookmarked: None,
+      pinned: None,
+    }
+  }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+/// The statuses above and below one in its thread
+pub struct Context {
+  pub ancestors: Vec<Status>,
+  pub descendants: Vec<Status>,
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{mastodon::status::StatusVisibility, model::access_type::AccessType};
+
+  #[test]
+  fn test_visibility_round_trip() {
+    for visibility in [
+      StatusVisibility::Public,
+      StatusVisibility::Unlisted,
+      StatusVisibility::Private,
+    ] {
+      let access = visibility.access_type().unwrap();
+      assert_eq!(StatusVisibility::from(&access), visibility);
+    }
+
+    assert_eq!(StatusVisibility::Direct.access_type(), None);
+    assert_eq!(
+      StatusVisibility::from(&AccessType::MembersOnly),
+      StatusVisibility::Private
+    );
+  }
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+use crate::{helpers::api::tag_uri, settings::SETTINGS};
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+  pub name: String,
+  pub url: String,
+  pub history: Vec<serde_json::Value>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub following: Option<bool>,
+}
+
+impl Tag {
+  pub fn new(name: &str) -> Tag {
+    Tag {
+      name: name.to_string(),
+      url: tag_uri(name),
+      history: vec![],
+      following: None,
+    }
+  }
+
+  /// Orbits are shown as tags, linking to the orbit rather than to a tag feed
+  pub fn from_orbit(shortcode: &str) -> Tag {
+    Tag {
+      url: format!("{}/orbits/{}", SETTINGS.server.fqdn, shortcode),
+      ..Tag::new(shortcode)
+    }
+  }
+}
@@ -10,6 +10,8 @@ pub enum AccessType {
   Unlisted,
   Private,
   FollowersOnly,
+  /// Only readable by members of the orbit the post was made in
+  MembersOnly,
   PublicLocal,
   PublicFederated,
 }
@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AccountRestrictionType {
+  /// The account is hidden everywhere, can't sign in and its federation is refused
+  Suspend,
+  /// The account's posts are kept out of the global feed
+  Silence,
+  /// The account's posts are always marked as sensitive
+  ForceSensitive,
+  /// The account's posts are only visible to itself, without it being told
+  Shadow,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+/// Represents an administrator's restriction on a local or remote account, active until it expires or is lifted
+pub struct AccountRestriction {
+  pub account_restriction_id: Uuid,
+  pub user_id: Uuid,
+  pub user_fediverse_id: String,
+  pub created_by_id: Option<Uuid>,
+  pub restriction: AccountRestrictionType,
+  pub reason: String,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+impl FromRow for AccountRestriction {
+  fn from_row(row: Row) -> Option<Self> {
+    let restriction = match AccountRestrictionType::from_str(row.get("restriction")) {
+      Ok(restriction) => restriction,
+      Err(_) => return None,
+    };
+
+    Some(AccountRestriction {
+      account_restriction_id: row.get("account_restriction_id"),
+      user_id: row.get("user_id"),
+      user_fediverse_id: row.get("user_fediverse_id"),
+      created_by_id: row.get("created_by_id"),
+      restriction,
+      reason: row.get("reason"),
+      expires_at: row.get("expires_at"),
+      created_at: row.get("created_at"),
+      updated_at: row.get("updated_at"),
+    })
+  }
+}
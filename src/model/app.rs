@@ -14,6 +14,15 @@ pub struct App {
   pub redirect_uri: String,
   pub client_id: String,
   pub client_secret: String,
+  /// Space separated OAuth scopes the app may ask for
+  pub scopes: String,
+}
+
+impl App {
+  /// Apps may register several redirect URIs, separated by whitespace
+  pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+    self.redirect_uri.split_whitespace().any(|uri| uri == redirect_uri)
+  }
 }
 
 impl FromRow for App {
@@ -27,6 +36,7 @@ impl FromRow for App {
       redirect_uri: row.get("redirect_uri"),
       client_id: row.get("client_id"),
       client_secret: row.get("client_secret"),
+      scopes: row.get("scopes"),
     })
   }
 }
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
+/// Represents a single-use OAuth authorization code, bound to the app, redirect URI and scopes it was issued for
+pub struct AuthorizationCode {
+  pub code: String,
+  pub user_id: Uuid,
+  pub app_id: Uuid,
+  pub redirect_uri: String,
+  pub scopes: String,
+  pub created_at: DateTime<Utc>,
+  pub expires_at: DateTime<Utc>,
+}
+
+impl FromRow for AuthorizationCode {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(AuthorizationCode {
+      code: row.get("code"),
+      user_id: row.get("user_id"),
+      app_id: row.get("app_id"),
+      redirect_uri: row.get("redirect_uri"),
+      scopes: row.get("scopes"),
+      created_at: row.get("created_at"),
+      expires_at: row.get("expires_at"),
+    })
+  }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize)]
+/// Represents a user's block on another user, hiding each of them from the other
+pub struct Block {
+  pub block_id: Uuid,
+  pub user_id: Uuid,
+  pub blocked_user_id: Uuid,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for Block {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(Block {
+      block_id: row.get("block_id"),
+      user_id: row.get("user_id"),
+      blocked_user_id: row.get("blocked_user_id"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
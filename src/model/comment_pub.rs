@@ -13,7 +13,7 @@ use crate::{
     reference::Reference,
   },
   db::FromRow,
-  settings::SETTINGS,
+  helpers::api::relative_to_absolute_uri,
 };
 
 use super::access_type::AccessType;
@@ -37,6 +37,14 @@ pub struct CommentPub {
   pub liked: Option<bool>,
   #[serde(skip)]
   pub visibility: AccessType,
+  #[serde(skip)]
+  pub uri: String,
+  #[serde(skip)]
+  pub is_external: bool,
+  #[serde(skip)]
+  pub post_uri: String,
+  #[serde(skip)]
+  pub user_fediverse_uri: String,
 }
 
 impl FromRow for CommentPub {
@@ -55,20 +63,21 @@ impl FromRow for CommentPub {
       likes: row.get("likes"),
       liked: row.get("liked"),
       visibility: AccessType::from_str(row.get("visibility")).unwrap_or_default(),
+      uri: row.get("uri"),
+      is_external: row.get("is_external"),
+      post_uri: row.get("post_uri"),
+      user_fediverse_uri: row.get("user_fediverse_uri"),
     })
   }
 }
 
 impl ActivityConvertible for CommentPub {
   fn to_object(&self, actor: &str) -> Option<Object> {
-    let id = format!(
-      "{}/feed/{}/comments/{}",
-      SETTINGS.server.api_fqdn, self.post_id, self.comment_id
-    );
+    let id = relative_to_absolute_uri(&self.uri);
 
-    let attributed_to_uri = format!("{}/user/{}", SETTINGS.server.api_fqdn, self.user_id);
+    let attributed_to_uri = relative_to_absolute_uri(&self.user_fediverse_uri);
     let cc_uri = format!("{}/followers", actor);
-    let in_reply_to_uri = format!("{}/feed/{}", SETTINGS.server.api_fqdn, self.post_id);
+    let in_reply_to_uri = relative_to_absolute_uri(&self.post_uri);
 
     let to = match self.visibility {
       AccessType::Shadow => None,
@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::{Display, EnumString};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, Clone, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+  Pending,
+  Delivered,
+  Dead,
+}
+
+impl Default for DeliveryStatus {
+  fn default() -> Self {
+    DeliveryStatus::Pending
+  }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+/// Represents an outbound ActivityPub activity that couldn't be delivered to a remote inbox on the first attempt
+pub struct Delivery {
+  pub delivery_id: Uuid,
+  pub job_id: Option<Uuid>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+  pub inbox_uri: String,
+  pub actor_fediverse_uri: String,
+  pub activity: Value,
+  pub status: DeliveryStatus,
+  pub attempts: i32,
+  pub next_attempt_at: DateTime<Utc>,
+  pub last_error: Option<String>,
+}
+
+impl FromRow for Delivery {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(Delivery {
+      delivery_id: row.get("delivery_id"),
+      job_id: row.get("job_id"),
+      created_at: row.get("created_at"),
+      updated_at: row.get("updated_at"),
+      inbox_uri: row.get("inbox_uri"),
+      actor_fediverse_uri: row.get("actor_fediverse_uri"),
+      activity: row.get("activity"),
+      status: DeliveryStatus::from_str(row.get("status")).unwrap_or_default(),
+      attempts: row.get("attempts"),
+      next_attempt_at: row.get("next_attempt_at"),
+      last_error: row.get("last_error"),
+    })
+  }
+}
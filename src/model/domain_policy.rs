@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DomainPolicyType {
+  /// Federation with the domain is permitted, required for any domain to federate in allowlist mode
+  Allow,
+  /// All federation with the domain is refused in both directions
+  Suspend,
+  /// Activities from the domain are accepted, but kept out of the public feeds
+  Silence,
+  /// Activities from the domain are accepted, but their attachments, avatars and banners are dropped
+  RejectMedia,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+/// Represents an administrator's decision on how we federate with a remote domain and its subdomains
+pub struct DomainPolicy {
+  pub domain_policy_id: Uuid,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+  pub created_by_id: Option<Uuid>,
+  pub domain: String,
+  pub policy: DomainPolicyType,
+  pub reason: Option<String>,
+}
+
+impl DomainPolicy {
+  pub fn matches_host(&self, host: &str) -> bool {
+    host == self.domain || host.ends_with(&format!(".{}", self.domain))
+  }
+}
+
+impl FromRow for DomainPolicy {
+  fn from_row(row: Row) -> Option<Self> {
+    let policy = match DomainPolicyType::from_str(row.get("policy")) {
+      Ok(policy) => policy,
+      Err(_) => return None,
+    };
+
+    Some(DomainPolicy {
+      domain_policy_id: row.get("domain_policy_id"),
+      created_at: row.get("created_at"),
+      updated_at: row.get("updated_at"),
+      created_by_id: row.get("created_by_id"),
+      domain: row.get("domain"),
+      policy,
+      reason: row.get("reason"),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn build_policy(domain: &str) -> DomainPolicy {
+    DomainPolicy {
+      domain_policy_id: Uuid::new_v4(),
+      created_at: Utc::now(),
+      updated_at: Utc::now(),
+      created_by_id: None,
+      domain: domain.to_string(),
+      policy: DomainPolicyType::Suspend,
+      reason: None,
+    }
+  }
+
+  #[test]
+  fn test_matches_host_matches_domain_and_subdomains() {
+    let policy = build_policy("example.social");
+
+    assert!(policy.matches_host("example.social"));
+    assert!(policy.matches_host("media.example.social"));
+    assert!(!policy.matches_host("notexample.social"));
+    assert!(!policy.matches_host("example.social.evil"));
+  }
+}
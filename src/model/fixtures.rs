@@ -0,0 +1,175 @@
+//! Records for unit tests, filled in with placeholder values. Tests override the fields they care about with struct
+//! update syntax.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{
+  access_type::AccessType,
+  event_type::EventType,
+  follow::FollowRequest,
+  orbit::Orbit,
+  orbit_join_policy::OrbitJoinPolicy,
+  orbit_membership::OrbitJoinRequest,
+  post::Post,
+  post_event::PostEvent,
+  report::{Report, ReportStatus},
+  user::User,
+};
+
+pub fn user(user_id: Uuid) -> User {
+  User {
+    user_id,
+    fediverse_id: "user@127.0.0.1:8000".to_string(),
+    handle: "a".to_string(),
+    fediverse_uri: "d".to_string(),
+    avatar_url: None,
+    email: Some("b".to_string()),
+    password_hash: Some("c".to_string()),
+    is_external: false,
+    url_1: None,
+    url_2: None,
+    url_3: None,
+    url_4: None,
+    url_5: None,
+    url_1_title: None,
+    url_2_title: None,
+    url_3_title: None,
+    url_4_title: None,
+    url_5_title: None,
+    intro_md: None,
+    intro_html: None,
+    private_key: "d".to_string(),
+    public_key: "e".to_string(),
+    ext_apub_followers_uri: None,
+    ext_apub_following_uri: None,
+    ext_apub_inbox_uri: None,
+    ext_apub_outbox_uri: None,
+    ext_apub_shared_inbox_uri: None,
+    manually_approves_followers: false,
+    created_at: Utc::now(),
+    updated_at: Utc::now(),
+  }
+}
+
+pub fn post(post_id: Uuid, user_id: Uuid, orbit_id: Option<Uuid>) -> Post {
+  Post {
+    post_id,
+    user_id,
+    orbit_id,
+    uri: format!("/feed/{}", post_id),
+    is_external: false,
+    title: None,
+    content_md: "a".to_string(),
+    content_html: "a".to_string(),
+    visibility: AccessType::PublicFederated,
+    created_at: Utc::now(),
+    updated_at: Utc::now(),
+    deletion_scheduled_at: None,
+  }
+}
+
+pub fn post_event(post_id: Uuid, user_id: Uuid, visibility: AccessType) -> PostEvent {
+  PostEvent {
+    event_type: EventType::Post,
+    post_id,
+    orbit_id: None,
+    uri: format!("/feed/{}", post_id),
+    title: None,
+    content_md: "a".to_string(),
+    content_html: "a".to_string(),
+    visibility,
+    sensitive: false,
+    is_locked: false,
+    pinned_at: None,
+    created_at: Utc::now(),
+    updated_at: Utc::now(),
+    user_id,
+    user_handle: "a".to_string(),
+    user_fediverse_id: "a".to_string(),
+    user_fediverse_uri: "a".to_string(),
+    user_avatar_url: None,
+    event_user_handle: "a".to_string(),
+    event_user_fediverse_id: "a".to_string(),
+    event_user_fediverse_uri: "a".to_string(),
+    event_user_avatar_url: None,
+    likes: 0,
+    liked: None,
+    comments: 0,
+    orbit_name: None,
+    orbit_shortcode: None,
+    orbit_uri: None,
+    orbit_fediverse_uri: None,
+    orbit_avatar_uri: None,
+    attachments: vec![],
+    mentions: vec![],
+    tags: vec![],
+  }
+}
+
+pub fn orbit(orbit_id: Uuid, join_policy: OrbitJoinPolicy) -> Orbit {
+  Orbit {
+    orbit_id,
+    created_at: Utc::now(),
+    updated_at: Utc::now(),
+    shortcode: "test".to_string(),
+    name: "Test".to_string(),
+    description_md: "".to_string(),
+    description_html: "".to_string(),
+    avatar_uri: None,
+    banner_uri: None,
+    uri: "/orbits/test".to_string(),
+    fediverse_uri: format!("/orbit/{}", orbit_id),
+    fediverse_id: "o/test@localhost".to_string(),
+    private_key: "".to_string(),
+    public_key: "".to_string(),
+    is_external: false,
+    ext_apub_inbox_uri: None,
+    ext_apub_outbox_uri: None,
+    ext_apub_followers_uri: None,
+    join_policy,
+  }
+}
+
+pub fn report(moderator_orbit_id: Option<Uuid>) -> Report {
+  Report {
+    report_id: Uuid::new_v4(),
+    created_at: Utc::now(),
+    updated_at: Utc::now(),
+    reporter_id: Some(Uuid::new_v4()),
+    target_user_id: Some(Uuid::new_v4()),
+    post_id: None,
+    comment_id: None,
+    orbit_id: moderator_orbit_id,
+    moderator_orbit_id,
+    reason: "Spam".to_string(),
+    status: ReportStatus::Open,
+    resolved_by_id: None,
+    resolution_note: None,
+    is_external: false,
+    forwarded: false,
+    uri: None,
+  }
+}
+
+pub fn orbit_join_request(orbit_id: Uuid, user_id: Uuid, activity: Option<String>) -> OrbitJoinRequest {
+  OrbitJoinRequest {
+    orbit_join_request_id: Uuid::new_v4(),
+    orbit_id,
+    user_id,
+    user_fediverse_id: "user@localhost".to_string(),
+    activity,
+    created_at: Utc::now(),
+  }
+}
+
+pub fn follow_request(requester_id: Uuid, user_id: Uuid, activity: Option<String>) -> FollowRequest {
+  FollowRequest {
+    follower_id: Uuid::new_v4(),
+    user_id: requester_id,
+    user_fediverse_id: "@requester@example.com".to_string(),
+    following_user_id: user_id,
+    activity,
+    created_at: Utc::now(),
+  }
+}
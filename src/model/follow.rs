@@ -11,6 +11,7 @@ pub struct Follow {
   pub follower_id: Uuid,
   pub user_id: Uuid,
   pub following_user_id: Uuid,
+  pub is_pending: bool,
   pub created_at: DateTime<Utc>,
 }
 
@@ -20,6 +21,32 @@ impl FromRow for Follow {
       follower_id: row.get("follower_id"),
       user_id: row.get("user_id"),
       following_user_id: row.get("following_user_id"),
+      is_pending: row.get("is_pending"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// Represents a user waiting on a locked account to approve their follow
+pub struct FollowRequest {
+  pub follower_id: Uuid,
+  pub user_id: Uuid,
+  pub user_fediverse_id: String,
+  pub following_user_id: Uuid,
+  #[serde(skip)]
+  pub activity: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for FollowRequest {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(FollowRequest {
+      follower_id: row.get("follower_id"),
+      user_id: row.get("user_id"),
+      user_fediverse_id: row.get("user_fediverse_id"),
+      following_user_id: row.get("following_user_id"),
+      activity: row.get("activity"),
       created_at: row.get("created_at"),
     })
   }
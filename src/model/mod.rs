@@ -1,23 +1,44 @@
 pub mod access_type;
+pub mod account_restriction;
 pub mod app;
 pub mod args;
+pub mod authorization_code;
+pub mod block;
 pub mod comment;
 pub mod comment_pub;
+pub mod delivery;
+pub mod domain_policy;
 pub mod event;
 pub mod event_type;
+#[cfg(test)]
+pub mod fixtures;
 pub mod follow;
 pub mod job;
 pub mod like;
+pub mod mute;
+pub mod notification;
+pub mod notification_type;
 pub mod orbit;
+pub mod orbit_join_policy;
+pub mod orbit_membership;
+pub mod orbit_moderation;
 pub mod orbit_moderator;
 pub mod orbit_pub;
+pub mod page_cursor;
 pub mod post;
 pub mod post_attachment;
 pub mod post_create_request;
 pub mod post_event;
+pub mod post_mention;
 pub mod queue_job;
+pub mod report;
 pub mod response;
+pub mod search_hit;
+pub mod search_result;
+pub mod search_result_type;
 pub mod session;
+pub mod stream_message;
+pub mod stream_type;
 pub mod tombstone;
 pub mod user;
 pub mod user_account_pub;
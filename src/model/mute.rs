@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+/// Represents a user's mute on another user, hiding their posts (or only their boosts) without them being told
+pub struct UserMute {
+  pub user_mute_id: Uuid,
+  pub user_id: Uuid,
+  pub muted_user_id: Uuid,
+  pub muted_user_handle: String,
+  pub muted_user_fediverse_id: String,
+  pub boosts_only: bool,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for UserMute {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(UserMute {
+      user_mute_id: row.get("user_mute_id"),
+      user_id: row.get("user_id"),
+      muted_user_id: row.get("muted_user_id"),
+      muted_user_handle: row.get("muted_user_handle"),
+      muted_user_fediverse_id: row.get("muted_user_fediverse_id"),
+      boosts_only: row.get("boosts_only"),
+      expires_at: row.get("expires_at"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+/// Represents a user's mute on an orbit, keeping its posts out of their timelines
+pub struct OrbitMute {
+  pub orbit_mute_id: Uuid,
+  pub user_id: Uuid,
+  pub orbit_id: Uuid,
+  pub orbit_name: String,
+  pub orbit_shortcode: String,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for OrbitMute {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(OrbitMute {
+      orbit_mute_id: row.get("orbit_mute_id"),
+      user_id: row.get("user_id"),
+      orbit_id: row.get("orbit_id"),
+      orbit_name: row.get("orbit_name"),
+      orbit_shortcode: row.get("orbit_shortcode"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+/// Represents a phrase or pattern a user never wants to see in their timelines
+pub struct KeywordFilter {
+  pub keyword_filter_id: Uuid,
+  pub user_id: Uuid,
+  pub phrase: String,
+  pub is_regex: bool,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for KeywordFilter {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(KeywordFilter {
+      keyword_filter_id: row.get("keyword_filter_id"),
+      user_id: row.get("user_id"),
+      phrase: row.get("phrase"),
+      is_regex: row.get("is_regex"),
+      expires_at: row.get("expires_at"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
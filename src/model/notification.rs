@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+use super::notification_type::NotificationType;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// Represents another user's interaction with one of our users, or with something they own
+pub struct Notification {
+  pub notification_id: Uuid,
+  pub user_id: Uuid,
+  pub source_user_id: Uuid,
+  pub source_user_handle: String,
+  pub source_user_fediverse_id: String,
+  pub source_user_avatar_url: Option<String>,
+  pub notification_type: NotificationType,
+  pub post_id: Option<Uuid>,
+  pub comment_id: Option<Uuid>,
+  pub orbit_id: Option<Uuid>,
+  pub read_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+  /// Notifications sharing a key describe the same thing happening repeatedly, e.g. several users liking one post
+  pub fn group_key(&self) -> String {
+    let id_or_nil = |id: Option<Uuid>| id.unwrap_or_else(Uuid::nil);
+
+    match self.notification_type {
+      NotificationType::Like | NotificationType::Boost => format!(
+        "{}-{}-{}",
+        self.notification_type,
+        id_or_nil(self.post_id),
+        id_or_nil(self.comment_id)
+      ),
+      NotificationType::Follow | NotificationType::FollowRequest => self.notification_type.to_string(),
+      NotificationType::OrbitJoin | NotificationType::OrbitJoinRequest => {
+        format!("{}-{}", self.notification_type, id_or_nil(self.orbit_id))
+      }
+      _ => format!("{}-{}", self.notification_type, self.notification_id),
+    }
+  }
+}
+
+impl FromRow for Notification {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(Notification {
+      notification_id: row.get("notification_id"),
+      user_id: row.get("user_id"),
+      source_user_id: row.get("source_user_id"),
+      source_user_handle: row.get("source_user_handle"),
+      source_user_fediverse_id: row.get("source_user_fediverse_id"),
+      source_user_avatar_url: row.get("source_user_avatar_url"),
+      notification_type: NotificationType::from_str(row.get("notification_type")).unwrap_or_default(),
+      post_id: row.get("post_id"),
+      comment_id: row.get("comment_id"),
+      orbit_id: row.get("orbit_id"),
+      read_at: row.get("read_at"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewNotification {
+  pub user_id: Uuid,
+  pub source_user_id: Uuid,
+  pub notification_type: NotificationType,
+  pub post_id: Option<Uuid>,
+  pub comment_id: Option<Uuid>,
+  pub orbit_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Narrows down a user's notifications. Empty type lists don't filter anything out.
+pub struct NotificationFilter {
+  pub types: Vec<NotificationType>,
+  pub exclude_types: Vec<NotificationType>,
+  pub unread_only: bool,
+  pub max_id: Option<Uuid>,
+  pub since_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// Notifications collapsed by their group key, most recent first
+pub struct NotificationGroup {
+  pub group_key: String,
+  pub notification_type: NotificationType,
+  pub unread: bool,
+  pub latest_created_at: DateTime<Utc>,
+  pub notifications: Vec<Notification>,
+}
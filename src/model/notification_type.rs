@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, PartialEq, Eq, Clone, Copy)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationType {
+  Unknown,
+  Follow,
+  FollowRequest,
+  Like,
+  Comment,
+  Boost,
+  Mention,
+  OrbitJoin,
+  OrbitJoinRequest,
+}
+
+impl Default for NotificationType {
+  fn default() -> Self {
+    NotificationType::Unknown
+  }
+}
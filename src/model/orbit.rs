@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -15,7 +15,10 @@ use crate::{
   settings::SETTINGS,
 };
 
-use super::webfinger::{WebfingerRecord, WebfingerRecordLink};
+use super::{
+  orbit_join_policy::OrbitJoinPolicy,
+  webfinger::{WebfingerRecord, WebfingerRecordLink},
+};
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct Orbit {
@@ -37,6 +40,7 @@ pub struct Orbit {
   pub ext_apub_inbox_uri: Option<String>,
   pub ext_apub_outbox_uri: Option<String>,
   pub ext_apub_followers_uri: Option<String>,
+  pub join_policy: OrbitJoinPolicy,
 }
 
 impl Orbit {
@@ -75,6 +79,7 @@ impl FromRow for Orbit {
       ext_apub_inbox_uri: row.get("ext_apub_inbox_uri"),
       ext_apub_outbox_uri: row.get("ext_apub_outbox_uri"),
       ext_apub_followers_uri: row.get("ext_apub_followers_uri"),
+      join_policy: OrbitJoinPolicy::from_str(row.get("join_policy")).unwrap_or_default(),
     })
   }
 }
@@ -87,7 +92,7 @@ impl ActivityConvertible for Orbit {
       "{}/federate/activitypub/orbit/{}/inbox",
       SETTINGS.server.api_fqdn, &self.orbit_id
     );
-    let outbox_uri = format!("{}/orbit/{}/feed", SETTINGS.server.api_fqdn, &self.orbit_id);
+    let outbox_uri = format!("{}/orbit/{}/outbox", SETTINGS.server.api_fqdn, &self.orbit_id);
     let followers_uri = format!("{}/orbit/{}/members", SETTINGS.server.api_fqdn, &self.orbit_id);
     let icon = self.avatar_uri.clone().map(|avatar_url| {
       Reference::Embedded(Box::new(
@@ -132,6 +137,7 @@ impl ActivityConvertible for Orbit {
             .inbox(Some(Reference::Remote(inbox_uri)))
             .outbox(Some(Reference::Remote(outbox_uri)))
             .preferred_username(Some(self.shortcode.clone()))
+            .manually_approves_followers(Some(self.join_policy != OrbitJoinPolicy::Open))
             .build(),
         ))
         .orbit(Some(
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum OrbitJoinPolicy {
+  Open,
+  ApprovalRequired,
+  InviteOnly,
+}
+
+impl Default for OrbitJoinPolicy {
+  fn default() -> Self {
+    OrbitJoinPolicy::Open
+  }
+}
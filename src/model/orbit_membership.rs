@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// Represents a user waiting on a moderator to let them into an orbit
+pub struct OrbitJoinRequest {
+  pub orbit_join_request_id: Uuid,
+  pub orbit_id: Uuid,
+  pub user_id: Uuid,
+  pub user_fediverse_id: String,
+  #[serde(skip)]
+  pub activity: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for OrbitJoinRequest {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(OrbitJoinRequest {
+      orbit_join_request_id: row.get("orbit_join_request_id"),
+      orbit_id: row.get("orbit_id"),
+      user_id: row.get("user_id"),
+      user_fediverse_id: row.get("user_fediverse_id"),
+      activity: row.get("activity"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct OrbitInvite {
+  pub orbit_invite_id: Uuid,
+  pub orbit_id: Uuid,
+  pub user_id: Uuid,
+  pub user_fediverse_id: String,
+  pub created_by_id: Option<Uuid>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for OrbitInvite {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(OrbitInvite {
+      orbit_invite_id: row.get("orbit_invite_id"),
+      orbit_id: row.get("orbit_id"),
+      user_id: row.get("user_id"),
+      user_fediverse_id: row.get("user_fediverse_id"),
+      created_by_id: row.get("created_by_id"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
@@ -0,0 +1,108 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+  RemovePost,
+  RemoveComment,
+  BanUser,
+  UnbanUser,
+  LockPost,
+  UnlockPost,
+  PinPost,
+  UnpinPost,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// Represents a user barred from joining, posting and commenting in an orbit, until it expires or is lifted
+pub struct OrbitBan {
+  pub orbit_ban_id: Uuid,
+  pub orbit_id: Uuid,
+  pub user_id: Uuid,
+  pub user_fediverse_id: String,
+  pub created_by_id: Option<Uuid>,
+  pub reason: Option<String>,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for OrbitBan {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(OrbitBan {
+      orbit_ban_id: row.get("orbit_ban_id"),
+      orbit_id: row.get("orbit_id"),
+      user_id: row.get("user_id"),
+      user_fediverse_id: row.get("user_fediverse_id"),
+      created_by_id: row.get("created_by_id"),
+      reason: row.get("reason"),
+      expires_at: row.get("expires_at"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// Represents a single moderator action taken in an orbit
+pub struct OrbitModlogEntry {
+  pub orbit_modlog_id: Uuid,
+  pub orbit_id: Uuid,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub moderator_id: Option<Uuid>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub moderator_fediverse_id: Option<String>,
+  pub action: ModerationAction,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub target_user_id: Option<Uuid>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub target_user_fediverse_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub post_id: Option<Uuid>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub comment_id: Option<Uuid>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub reason: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+impl FromRow for OrbitModlogEntry {
+  fn from_row(row: Row) -> Option<Self> {
+    let action = match ModerationAction::from_str(row.get("action")) {
+      Ok(action) => action,
+      Err(_) => return None,
+    };
+
+    Some(OrbitModlogEntry {
+      orbit_modlog_id: row.get("orbit_modlog_id"),
+      orbit_id: row.get("orbit_id"),
+      moderator_id: row.get("moderator_id"),
+      moderator_fediverse_id: row.get("moderator_fediverse_id"),
+      action,
+      target_user_id: row.get("target_user_id"),
+      target_user_fediverse_id: row.get("target_user_fediverse_id"),
+      post_id: row.get("post_id"),
+      comment_id: row.get("comment_id"),
+      reason: row.get("reason"),
+      created_at: row.get("created_at"),
+    })
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewOrbitModlogEntry {
+  pub orbit_id: Uuid,
+  pub moderator_id: Uuid,
+  pub action: ModerationAction,
+  pub target_user_id: Option<Uuid>,
+  pub post_id: Option<Uuid>,
+  pub comment_id: Option<Uuid>,
+  pub reason: Option<String>,
+}
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio_postgres::Row;
@@ -5,7 +7,7 @@ use uuid::Uuid;
 
 use crate::db::FromRow;
 
-use super::orbit::Orbit;
+use super::{orbit::Orbit, orbit_join_policy::OrbitJoinPolicy};
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct OrbitPub {
@@ -21,10 +23,13 @@ pub struct OrbitPub {
   pub fediverse_id: String,
   pub uri: String,
   pub is_external: bool,
+  pub join_policy: OrbitJoinPolicy,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub joined: Option<bool>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub moderating: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub requested: Option<bool>,
 }
 
 impl FromRow for OrbitPub {
@@ -42,8 +47,10 @@ impl FromRow for OrbitPub {
       fediverse_id: row.get("fediverse_id"),
       uri: row.get("uri"),
       is_external: row.get("is_external"),
+      join_policy: OrbitJoinPolicy::from_str(row.get("join_policy")).unwrap_or_default(),
       joined: row.get("joined"),
       moderating: row.get("moderating"),
+      requested: row.get("requested"),
     })
   }
 }
@@ -63,8 +70,10 @@ impl From<Orbit> for OrbitPub {
       fediverse_id: orbit.fediverse_id,
       uri: orbit.uri,
       is_external: orbit.is_external,
+      join_policy: orbit.join_policy,
       joined: None,
       moderating: None,
+      requested: None,
     }
   }
 }
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+/// Where a page of a list starts, given by the ID of an item on a neighbouring page. Items are compared by when they
+/// were created: `max_id` fetches what's older than an item, `since_id` the newest of what's newer than it and `min_id`
+/// what comes immediately after it.
+pub struct PageCursor {
+  pub max_id: Option<Uuid>,
+  pub since_id: Option<Uuid>,
+  pub min_id: Option<Uuid>,
+}
+
+impl PageCursor {
+  pub fn is_empty(&self) -> bool {
+    self.max_id.is_none() && self.since_id.is_none() && self.min_id.is_none()
+  }
+
+  /// The item that everything fetched must be newer than, if any
+  pub fn lower_id(&self) -> Option<Uuid> {
+    self.min_id.or(self.since_id)
+  }
+
+  /// Whether a newest first list has to be walked oldest first to find the page, which is only the case for `min_id`
+  pub fn walks_oldest_first(&self) -> bool {
+    self.min_id.is_some()
+  }
+
+  /// Whether an oldest first list has to be walked newest first to find the page, i.e. anything but `min_id` is set
+  pub fn walks_newest_first(&self) -> bool {
+    self.min_id.is_none() && (self.max_id.is_some() || self.since_id.is_some())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_min_id_takes_precedence_over_since_id() {
+    let since_id = Uuid::new_v4();
+    let min_id = Uuid::new_v4();
+
+    let cursor = PageCursor {
+      max_id: None,
+      since_id: Some(since_id),
+      min_id: Some(min_id),
+    };
+
+    assert_eq!(cursor.lower_id(), Some(min_id));
+    assert!(cursor.walks_oldest_first());
+    assert!(!cursor.walks_newest_first());
+  }
+
+  #[test]
+  fn test_walk_direction() {
+    let max_id = PageCursor {
+      max_id: Some(Uuid::new_v4()),
+      ..Default::default()
+    };
+
+    assert!(PageCursor::default().is_empty());
+    assert!(!PageCursor::default().walks_oldest_first());
+    assert!(!PageCursor::default().walks_newest_first());
+    assert!(!max_id.is_empty());
+    assert!(!max_id.walks_oldest_first());
+    assert!(max_id.walks_newest_first());
+  }
+}
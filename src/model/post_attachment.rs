@@ -10,7 +10,8 @@ use crate::db::{FromRow, FromRowJoin};
 pub struct PostAttachment {
   pub attachment_id: Uuid,
   pub user_id: Uuid,
-  pub post_id: Uuid,
+  /// Missing while the attachment has been uploaded but the post it's for hasn't been created yet
+  pub post_id: Option<Uuid>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub uri: Option<String>,
   pub width: i32,
@@ -11,17 +11,20 @@ use crate::{
   activitypub::{
     activity_convertible::ActivityConvertible,
     collection::CollectionProps,
-    object::{Object, ObjectSource},
+    link::LinkProps,
+    object::{Object, ObjectSource, ObjectType},
     rdf_string::RdfString,
     reference::Reference,
   },
   db::{FromRow, FromRowJoin, FromRows},
-  helpers::api::relative_to_absolute_uri,
+  helpers::api::{relative_to_absolute_uri, tag_uri},
   logic::LogicErr,
   settings::SETTINGS,
 };
 
-use super::{access_type::AccessType, event_type::EventType, post_attachment::PostAttachment};
+use super::{
+  access_type::AccessType, event_type::EventType, post_attachment::PostAttachment, post_mention::PostMention,
+};
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
 pub struct PostEvent {
@@ -35,6 +38,10 @@ pub struct PostEvent {
   pub content_md: String,
   pub content_html: String,
   pub visibility: AccessType,
+  pub sensitive: bool,
+  pub is_locked: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub pinned_at: Option<DateTime<Utc>>,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
   // Foreign columns
@@ -59,6 +66,10 @@ pub struct PostEvent {
   pub orbit_fediverse_uri: Option<String>,
   pub orbit_avatar_uri: Option<String>,
   pub attachments: Vec<PostAttachment>,
+  #[serde(default)]
+  pub mentions: Vec<PostMention>,
+  #[serde(default)]
+  pub tags: Vec<String>,
 }
 
 impl FromRow for PostEvent {
@@ -71,6 +82,9 @@ impl FromRow for PostEvent {
       content_md: row.get("content_md"),
       content_html: row.get("content_html"),
       visibility: AccessType::from_str(row.get("visibility")).unwrap_or_default(),
+      sensitive: row.get("sensitive"),
+      is_locked: row.get("is_locked"),
+      pinned_at: row.get("pinned_at"),
       created_at: row.get("created_at"),
       updated_at: row.get("updated_at"),
       user_id: row.get("user_id"),
@@ -92,6 +106,8 @@ impl FromRow for PostEvent {
       orbit_fediverse_uri: row.get("orbit_fediverse_uri"),
       orbit_avatar_uri: row.get("orbit_avatar_uri"),
       attachments: vec![],
+      mentions: vec![],
+      tags: vec![],
     })
   }
 }
@@ -147,6 +163,9 @@ impl ActivityConvertible for PostEvent {
       AccessType::Unlisted => None,
       AccessType::Private => Some(Reference::Remote::<Object>(actor_uri.clone())),
       AccessType::FollowersOnly => Some(Reference::Remote::<Object>(actor_follower_feed_uri)),
+      AccessType::MembersOnly => self.orbit_fediverse_uri.as_ref().map(|orbit_fediverse_uri| {
+        Reference::Remote::<Object>(format!("{}/members", relative_to_absolute_uri(orbit_fediverse_uri)))
+      }),
       AccessType::PublicLocal => Some(Reference::Remote::<Object>(
         "https://www.w3.org/ns/activitystreams#Local".to_string(),
       )),
@@ -169,6 +188,57 @@ impl ActivityConvertible for PostEvent {
       },
     };
 
+    // Shadow posts are never addressed to the users they mention
+    let mentions = match self.visibility {
+      AccessType::Shadow => vec![],
+      _ => self.mentions.iter().collect::<Vec<_>>(),
+    };
+
+    let mention_refs: Vec<Reference<Object>> = mentions
+      .iter()
+      .map(|m| Reference::Remote(relative_to_absolute_uri(&m.fediverse_uri)))
+      .collect();
+
+    let cc = match (cc, mention_refs.is_empty()) {
+      (cc, true) => cc,
+      (None, false) => Some(Reference::Mixed(mention_refs)),
+      (Some(cc), false) => Some(Reference::Mixed([vec![cc], mention_refs].concat())),
+    };
+
+    let mention_tags = mentions.iter().map(|m| {
+      Reference::Embedded(Box::new(
+        Object::builder()
+          .kind(Some(ObjectType::Mention.to_string()))
+          .name(Some(m.acct()))
+          .link(Some(
+            LinkProps::builder()
+              .href(Some(Reference::Remote(relative_to_absolute_uri(&m.fediverse_uri))))
+              .build(),
+          ))
+          .build(),
+      ))
+    });
+
+    let hashtag_tags = self.tags.iter().map(|name| {
+      Reference::Embedded(Box::new(
+        Object::builder()
+          .kind(Some(ObjectType::Hashtag.to_string()))
+          .name(Some(format!("#{}", name)))
+          .link(Some(
+            LinkProps::builder()
+              .href(Some(Reference::Remote(tag_uri(name))))
+              .build(),
+          ))
+          .build(),
+      ))
+    });
+
+    let tags: Vec<Reference<Object>> = mention_tags.chain(hashtag_tags).collect();
+    let tag = match tags.is_empty() {
+      true => None,
+      false => Some(Reference::Mixed(tags)),
+    };
+
     let audience = match &self.orbit_fediverse_uri {
       Some(orbit_fediverse_uri) => self.orbit_shortcode.as_ref().map(|orbit_shortcode| {
         Reference::Embedded(Box::new(
@@ -244,6 +314,7 @@ impl ActivityConvertible for PostEvent {
         .replies(Some(Box::new(replies_collection)))
         .summary(summary)
         .content(Some(RdfString::Raw(self.content_html.clone())))
+        .sensitive(Some(self.sensitive))
         .source(Some(
           ObjectSource::builder()
             .content(self.content_md.clone())
@@ -252,6 +323,7 @@ impl ActivityConvertible for PostEvent {
         ))
         .published(Some(self.created_at))
         .attachment(Some(Reference::Mixed(attachment_refs)))
+        .tag(tag)
         .audience(audience)
         .build(),
     )
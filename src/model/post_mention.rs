@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::{db::FromRow, helpers::api::local_domain};
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+/// A user named in a post, with enough of their profile to link to and address them
+pub struct PostMention {
+  pub user_id: Uuid,
+  pub handle: String,
+  pub fediverse_id: String,
+  pub fediverse_uri: String,
+  pub is_external: bool,
+}
+
+impl FromRow for PostMention {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(PostMention {
+      user_id: row.get("user_id"),
+      handle: row.get("handle"),
+      fediverse_id: row.get("fediverse_id"),
+      fediverse_uri: row.get("fediverse_uri"),
+      is_external: row.get("is_external"),
+    })
+  }
+}
+
+impl PostMention {
+  /// The `@handle@domain` name the user is tagged with
+  pub fn acct(&self) -> String {
+    match self.is_external {
+      true => self.fediverse_id.clone(),
+      false => format!("@{}@{}", self.handle, local_domain()),
+    }
+  }
+}
@@ -18,9 +18,13 @@ pub enum QueueJobType {
   DeleteBoostEvents,
   DeletePost,
   UpdatePost,
+  CreateComment,
+  DeleteComment,
+  RemoveOrbitObject,
   FederateActivityPub,
   FederateActivityPubExt,
   CleanJobs,
+  RetryDeliveries,
   FetchExternalOrbitPosts,
   RefreshExternalProfiles,
   RefreshExternalProfile,
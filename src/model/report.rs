@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+  /// Filed and waiting on a moderator
+  Open,
+  /// A moderator has picked the report up, but has not yet acted on it
+  Triaged,
+  /// Action was taken against the reported content
+  Resolved,
+  /// No action was needed
+  Dismissed,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+/// Represents a complaint about a user, post, comment or orbit. Reports about content inside an orbit are handled by
+/// that orbit's moderators (`moderator_orbit_id`), everything else goes to the instance admins.
+pub struct Report {
+  pub report_id: Uuid,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+  pub reporter_id: Option<Uuid>,
+  pub target_user_id: Option<Uuid>,
+  pub post_id: Option<Uuid>,
+  pub comment_id: Option<Uuid>,
+  pub orbit_id: Option<Uuid>,
+  pub moderator_orbit_id: Option<Uuid>,
+  pub reason: String,
+  pub status: ReportStatus,
+  pub resolved_by_id: Option<Uuid>,
+  pub resolution_note: Option<String>,
+  pub is_external: bool,
+  pub forwarded: bool,
+  #[serde(skip)]
+  pub uri: Option<String>,
+}
+
+pub struct NewReport {
+  pub reporter_id: Option<Uuid>,
+  pub target_user_id: Option<Uuid>,
+  pub post_id: Option<Uuid>,
+  pub comment_id: Option<Uuid>,
+  pub orbit_id: Option<Uuid>,
+  pub moderator_orbit_id: Option<Uuid>,
+  pub reason: String,
+  pub is_external: bool,
+  pub forwarded: bool,
+  pub uri: Option<String>,
+}
+
+impl FromRow for Report {
+  fn from_row(row: Row) -> Option<Self> {
+    let status = match ReportStatus::from_str(row.get("status")) {
+      Ok(status) => status,
+      Err(_) => return None,
+    };
+
+    Some(Report {
+      report_id: row.get("report_id"),
+      created_at: row.get("created_at"),
+      updated_at: row.get("updated_at"),
+      reporter_id: row.get("reporter_id"),
+      target_user_id: row.get("target_user_id"),
+      post_id: row.get("post_id"),
+      comment_id: row.get("comment_id"),
+      orbit_id: row.get("orbit_id"),
+      moderator_orbit_id: row.get("moderator_orbit_id"),
+      reason: row.get("reason"),
+      status,
+      resolved_by_id: row.get("resolved_by_id"),
+      resolution_note: row.get("resolution_note"),
+      is_external: row.get("is_external"),
+      forwarded: row.get("forwarded"),
+      uri: row.get("uri"),
+    })
+  }
+}
@@ -9,6 +9,21 @@ pub struct ListResponse<T> {
   pub total_pages: i64,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+/// A page of a list that's walked by cursor rather than page number. Passing `next_max_id` back as `max_id` fetches
+/// the page after this one, and `prev_since_id` as `since_id` fetches anything newer. Lists shown oldest first use
+/// `next_min_id` as `min_id` instead. The same pages are linked from the response's `Link` header.
+pub struct CursorResponse<T> {
+  pub data: Vec<T>,
+  pub next_max_id: Option<Uuid>,
+  pub prev_since_id: Option<Uuid>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub next_min_id: Option<Uuid>,
+  /// Only counted when asked for with `count=true`, as counting long lists is slow
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub total_items: Option<i64>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct JobResponse {
   pub job_id: Uuid,
@@ -0,0 +1,29 @@
+use std::str::FromStr;
+
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::db::FromRow;
+
+use super::search_result_type::SearchResultType;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A ranked match for a search term, before the record it points to has been loaded
+pub struct SearchHit {
+  pub result_type: SearchResultType,
+  pub id: Uuid,
+  /// The post a comment belongs to, since comments are only ever looked up through their post
+  pub post_id: Option<Uuid>,
+  pub rank: f32,
+}
+
+impl FromRow for SearchHit {
+  fn from_row(row: Row) -> Option<Self> {
+    Some(SearchHit {
+      result_type: SearchResultType::from_str(row.get("result_type")).ok()?,
+      id: row.get("id"),
+      post_id: row.get("post_id"),
+      rank: row.get("rank"),
+    })
+  }
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+use strum::Display;
+
+use super::{comment_pub::CommentPub, orbit_pub::OrbitPub, post_event::PostEvent, user_account_pub::UserAccountPub};
+
+#[derive(Serialize, Display)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SearchResult {
+  User(UserAccountPub),
+  Orbit(OrbitPub),
+  Post(PostEvent),
+  Comment(CommentPub),
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(Deserialize, Serialize, EnumString, Display, Debug, PartialEq, Eq, Clone, Copy)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SearchResultType {
+  Post,
+  Comment,
+  User,
+  Orbit,
+}
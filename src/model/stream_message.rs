@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{event_type::EventType, stream_type::StreamType};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "message", rename_all = "snake_case")]
+/// Published by whichever process wrote a record, and relayed by every API process to its connected clients. Messages
+/// only reference the records involved, keeping them well within the payload limits of any pub/sub backend.
+pub enum StreamMessage {
+  /// A post arrived in a feed. `target_id` is the user whose home or friends feed it is, or the orbit for orbit feeds.
+  Update {
+    stream: StreamType,
+    target_id: Option<Uuid>,
+    post_id: Uuid,
+    source_user_id: Uuid,
+    event_type: EventType,
+  },
+  Delete {
+    post_id: Uuid,
+  },
+  Notification {
+    user_id: Uuid,
+    notification_id: Uuid,
+  },
+}
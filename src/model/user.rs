@@ -43,6 +43,8 @@ pub struct User {
   pub ext_apub_following_uri: Option<String>,
   pub ext_apub_inbox_uri: Option<String>,
   pub ext_apub_outbox_uri: Option<String>,
+  pub ext_apub_shared_inbox_uri: Option<String>,
+  pub manually_approves_followers: bool,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
 }
@@ -91,6 +93,8 @@ impl FromRow for User {
       ext_apub_following_uri: row.get("ext_apub_following_uri"),
       ext_apub_inbox_uri: row.get("ext_apub_inbox_uri"),
       ext_apub_outbox_uri: row.get("ext_apub_outbox_uri"),
+      ext_apub_shared_inbox_uri: row.get("ext_apub_shared_inbox_uri"),
+      manually_approves_followers: row.get("manually_approves_followers"),
       created_at: row.get("created_at"),
       updated_at: row.get("updated_at"),
     })
@@ -105,7 +109,7 @@ impl ActivityConvertible for User {
       "{}/federate/activitypub/user/{}/inbox",
       SETTINGS.server.api_fqdn, &self.user_id
     );
-    let outbox_uri = format!("{}/user/{}/feed", SETTINGS.server.api_fqdn, &self.user_id);
+    let outbox_uri = format!("{}/user/{}/outbox", SETTINGS.server.api_fqdn, &self.user_id);
     let liked_uri = format!("{}/user/{}/likes", SETTINGS.server.api_fqdn, &self.user_id);
     let followers_uri = format!("{}/user/{}/followers", SETTINGS.server.api_fqdn, &self.user_id);
     let following_uri = format!("{}/user/{}/following", SETTINGS.server.api_fqdn, &self.user_id);
@@ -143,6 +147,7 @@ impl ActivityConvertible for User {
             .outbox(Some(Reference::Remote(outbox_uri)))
             .liked(Some(Reference::Remote(liked_uri)))
             .preferred_username(Some(self.handle.clone()))
+            .manually_approves_followers(Some(self.manually_approves_followers))
             .build(),
         ))
         .key(Some(key_props))
@@ -186,6 +191,8 @@ mod tests {
       ext_apub_following_uri: None,
       ext_apub_inbox_uri: None,
       ext_apub_outbox_uri: None,
+      ext_apub_shared_inbox_uri: None,
+      manually_approves_followers: false,
       created_at: Utc::now(),
       updated_at: Utc::now(),
     };
@@ -41,6 +41,7 @@ pub struct UserAccountPub {
   pub intro_md: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub intro_html: Option<String>,
+  pub manually_approves_followers: bool,
   pub created_at: DateTime<Utc>,
 }
 
@@ -65,6 +66,7 @@ impl From<User> for UserAccountPub {
       url_5_title: u.url_5_title,
       intro_md: u.intro_md,
       intro_html: u.intro_html,
+      manually_approves_followers: u.manually_approves_followers,
       created_at: u.created_at,
     }
   }
@@ -106,6 +108,8 @@ mod tests {
       ext_apub_following_uri: None,
       ext_apub_inbox_uri: None,
       ext_apub_outbox_uri: None,
+      ext_apub_shared_inbox_uri: None,
+      manually_approves_followers: false,
       created_at: Utc::now(),
       updated_at: Utc::now(),
     };
@@ -8,6 +8,7 @@ pub struct UserStats {
   pub following_count: i64,
   pub followers_count: i64,
   pub following_user: bool,
+  pub follow_requested: bool,
   pub user_is_you: bool,
 }
 
@@ -17,6 +18,7 @@ impl FromRow for UserStats {
       following_count: row.get("following_count"),
       followers_count: row.get("followers_count"),
       following_user: row.get("following_user"),
+      follow_requested: row.get("follow_requested"),
       user_is_you: row.get("user_is_you"),
     })
   }
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 use lazy_static::lazy_static;
 use rand::distributions::{Alphanumeric, DistString};
 use serde::{Deserialize, Serialize};
@@ -7,7 +7,7 @@ use sha2::{Digest, Sha256};
 use strum::Display;
 use uuid::Uuid;
 
-use crate::{helpers::api::map_ext_err, logic::LogicErr, model::user::User, settings::SETTINGS};
+use crate::{model::user::User, settings::SETTINGS};
 
 use super::jwt_session_err::JwtSessionErr;
 
@@ -27,6 +27,8 @@ pub struct JwtContextProps {
   pub iat: DateTime<Utc>,
   pub sid: String,
   pub uid: Uuid,
+  /// The OAuth scope the session needs for the request being handled, if any
+  pub required_scope: Option<&'static str>,
 }
 
 #[derive(Debug, Display, Clone)]
@@ -48,29 +50,11 @@ pub struct JwtClaims {
 
 lazy_static! {
   pub static ref JWT_ENCODING_KEY: EncodingKey = EncodingKey::from_secret(SETTINGS.server.jwt_secret.as_bytes());
-  pub static ref JWT_DECODING_KEY: DecodingKey = DecodingKey::from_secret(SETTINGS.server.jwt_secret.as_bytes());
 }
 
 pub struct JwtFactory {}
 
 impl JwtFactory {
-  pub fn generate_jwt_short_lived(subject: &str) -> Result<String, LogicErr> {
-    let now = chrono::offset::Utc::now();
-
-    let claims = JwtClaims {
-      sub: subject.to_string(),
-      iss: SETTINGS.server.fqdn.clone(),
-      exp: (now + chrono::Duration::seconds(30)).timestamp(),
-      nbf: (now - chrono::Duration::seconds(30)).timestamp(),
-      iat: now.timestamp(),
-      sid: "none".to_string(),
-      uid: Uuid::new_v4(),
-    };
-
-    let header = Header::new(Algorithm::HS512);
-    encode(&header, &claims, &JWT_ENCODING_KEY).map_err(map_ext_err)
-  }
-
   pub fn generate_jwt_long_lived(user: &User, session_id: &Uuid) -> Result<JwtSessionToken, JwtSessionErr> {
     if user.is_external {
       // A user must sign into their home instance, not ours
@@ -108,13 +92,4 @@ impl JwtFactory {
       refresh_expiry,
     })
   }
-
-  pub fn parse_jwt_props(jwt: &str) -> Option<JwtClaims> {
-    let token = match decode::<JwtClaims>(jwt, &JWT_DECODING_KEY, &Validation::new(Algorithm::HS512)) {
-      Ok(token) => token,
-      Err(_) => return None,
-    };
-
-    Some(token.claims)
-  }
 }
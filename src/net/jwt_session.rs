@@ -9,7 +9,11 @@ use actix_web::{
   HttpMessage,
 };
 
-use super::jwt_session_inner::JwtSessionInner;
+use super::{
+  jwt::{JwtContext, JwtContextProps},
+  jwt_session_inner::JwtSessionInner,
+};
+use crate::helpers::auth::required_scope;
 
 pub struct JwtSession(Rc<JwtSessionInner>);
 
@@ -57,9 +61,20 @@ where
   forward_ready!(service);
 
   fn call(&self, req: ServiceRequest) -> Self::Future {
-    req
-      .extensions_mut()
-      .insert(self.inner.parse_jwt(req.headers().get("authorization")));
+    let jwt = match req.headers().get("authorization") {
+      None if req.path().starts_with("/api/streaming") => self.inner.parse_jwt_query(req.query_string()),
+      header => self.inner.parse_jwt(header),
+    };
+
+    let jwt = match jwt {
+      JwtContext::Valid(props) => JwtContext::Valid(JwtContextProps {
+        required_scope: required_scope(req.method(), req.path()),
+        ..props
+      }),
+      jwt => jwt,
+    };
+
+    req.extensions_mut().insert(jwt);
 
     let fut = self.service.call(req);
     Box::pin(async move { fut.await })
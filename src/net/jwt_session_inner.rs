@@ -1,6 +1,7 @@
-use actix_web::http::header::HeaderValue;
+use actix_web::{http::header::HeaderValue, web};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
 
 use super::jwt::{JwtClaims, JwtContext, JwtContextProps};
 use crate::settings::SETTINGS;
@@ -9,6 +10,11 @@ pub struct JwtSessionInner {
   decoding_key: DecodingKey,
 }
 
+#[derive(Deserialize)]
+struct AccessTokenQuery {
+  access_token: Option<String>,
+}
+
 impl From<JwtClaims> for JwtContextProps {
   fn from(claims: JwtClaims) -> Self {
     Self {
@@ -28,6 +34,7 @@ impl From<JwtClaims> for JwtContextProps {
       ),
       sid: claims.sid,
       uid: claims.uid,
+      required_scope: None,
     }
   }
 }
@@ -59,8 +66,21 @@ impl JwtSessionInner {
       return JwtContext::Invalid(None);
     }
 
-    let raw_jwt = raw_jwt_components[1];
+    self.decode_jwt(raw_jwt_components[1])
+  }
+
+  /// Browsers can't set headers on EventSource connections, so streaming clients pass their token in the query string
+  pub fn parse_jwt_query(&self, query_string: &str) -> JwtContext {
+    match web::Query::<AccessTokenQuery>::from_query(query_string) {
+      Ok(query) => match &query.access_token {
+        Some(access_token) => self.decode_jwt(access_token),
+        None => JwtContext::Invalid(None),
+      },
+      Err(_) => JwtContext::Invalid(None),
+    }
+  }
 
+  fn decode_jwt(&self, raw_jwt: &str) -> JwtContext {
     let token = match decode::<JwtClaims>(raw_jwt, &self.decoding_key, &Validation::new(Algorithm::HS512)) {
       Ok(token) => token,
       Err(err) => return JwtContext::Invalid(Some(err.to_string())),
@@ -17,6 +17,11 @@ lazy_static! {
       include_str!("../../public/html/oauth-authorize-app-err.html"),
     )
     .unwrap();
+    hb.register_template_string(
+      "oauth_authorize_code",
+      include_str!("../../public/html/oauth-authorize-code.html"),
+    )
+    .unwrap();
     hb
   };
 }
@@ -8,20 +8,23 @@ use crate::{
     document::ActivityPubDocument,
     helpers::{
       create_activitypub_ordered_collection_page, create_activitypub_ordered_collection_page_feed,
-      create_activitypub_ordered_collection_page_specific_feed,
+      create_activitypub_ordered_collection_page_specific_feed, cursor_page_links, numbered_page_links,
     },
     object::{Object, ObjectType},
     tombstone::TombstoneProps,
   },
   db::{
-    comment_repository::CommentPool, follow_repository::FollowPool, job_repository::JobPool,
-    orbit_repository::OrbitPool, post_repository::PostPool, session_repository::SessionPool,
-    tombstone_repository::TombstonePool, user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+    comment_repository::CommentPool, domain_policy_repository::DomainPolicyPool, follow_repository::FollowPool,
+    job_repository::JobPool, orbit_membership_repository::OrbitMembershipPool, orbit_repository::OrbitPool,
+    post_repository::PostPool, session_repository::SessionPool, tombstone_repository::TombstonePool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
   },
+  federation::domain_policy::{sync_domain_policies, uri_federation_allowed},
   helpers::{
     api::relative_to_absolute_uri,
     auth::query_auth,
     core::{build_api_err, build_api_not_found, map_api_err},
+    pagination::numbered_page,
     types::ACTIVITY_JSON_CONTENT_TYPE,
   },
   logic::{
@@ -32,6 +35,7 @@ use crate::{
   model::{
     access_type::AccessType,
     job::{JobStatus, NewJob},
+    page_cursor::PageCursor,
     queue_job::{QueueJob, QueueJobType},
   },
   net::{http_sig::build_origin_data, jwt::JwtContext},
@@ -67,6 +71,7 @@ pub async fn api_activitypub_get_post(
   sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
   follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
   tombstones: web::Data<TombstonePool>,
   post_id: web::Path<Uuid>,
   jwt: web::ReqData<JwtContext>,
@@ -111,6 +116,15 @@ pub async fn api_activitypub_get_post(
         return HttpResponse::Ok().json(doc);
       }
 
+      if post.visibility == AccessType::MembersOnly
+        && orbit_membership
+          .user_is_member_for_post(&post.post_id, &current_user_id)
+          .await
+          .unwrap_or(false)
+      {
+        return HttpResponse::Ok().json(doc);
+      }
+
       HttpResponse::NotFound().finish()
     }
     None => HttpResponse::NotFound().finish(),
@@ -120,30 +134,30 @@ pub async fn api_activitypub_get_post(
 pub async fn api_activitypub_get_federated_user_posts(
   posts: web::Data<PostPool>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
   user_id: web::Path<Uuid>,
 ) -> impl Responder {
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match posts.count_user_public_feed(&user_id, &None).await {
-    Ok(count) => count,
-    Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
-  };
+  let base_uri = format!("{}/user/{}/feed", SETTINGS.server.api_fqdn, user_id);
 
-  let posts = match posts
-    .fetch_user_public_feed(&user_id, &None, page_size, page * page_size)
+  let posts_page = match posts
+    .fetch_user_public_feed(&user_id, &None, &cursor, page_size, page.unwrap_or(0) * page_size)
     .await
   {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let doc = create_activitypub_ordered_collection_page_feed(
-    &format!("{}/user/{}/feed", SETTINGS.server.api_fqdn, user_id),
-    page.try_into().unwrap_or_default(),
-    page_size.try_into().unwrap_or_default(),
-    posts_count.try_into().unwrap_or_default(),
-    posts,
-  );
+  let (prev, next) = match page {
+    Some(page) => match posts.count_user_public_feed(&user_id, &None).await {
+      Ok(count) => numbered_page_links(&base_uri, page, page_size, count),
+      Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
+    },
+    None => cursor_page_links(&base_uri, &cursor, page_size, &posts_page, |p| p.post_id, false),
+  };
+
+  let doc = create_activitypub_ordered_collection_page_feed(&base_uri, prev, next, posts_page);
 
   HttpResponse::Ok()
     .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
@@ -153,64 +167,143 @@ pub async fn api_activitypub_get_federated_user_posts(
 pub async fn api_activitypub_get_federated_orbit_posts(
   posts: web::Data<PostPool>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
   orbit_id: web::Path<Uuid>,
 ) -> impl Responder {
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match posts.count_global_federated_orbit_feed(&orbit_id).await {
-    Ok(count) => count,
+  let base_uri = format!("{}/orbit/{}/feed", SETTINGS.server.api_fqdn, orbit_id);
+
+  let posts_page = match posts
+    .fetch_global_federated_orbit_feed(&orbit_id, &None, &cursor, page_size, page.unwrap_or(0) * page_size)
+    .await
+  {
+    Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let posts = match posts
-    .fetch_global_federated_orbit_feed(&orbit_id, page_size, page * page_size)
+  let (prev, next) = match page {
+    Some(page) => match posts.count_global_federated_orbit_feed(&orbit_id, &None).await {
+      Ok(count) => numbered_page_links(&base_uri, page, page_size, count),
+      Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
+    },
+    None => cursor_page_links(&base_uri, &cursor, page_size, &posts_page, |p| p.post_id, false),
+  };
+
+  let doc = create_activitypub_ordered_collection_page_feed(&base_uri, prev, next, posts_page);
+
+  HttpResponse::Ok()
+    .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
+    .json(doc)
+}
+
+pub async fn api_activitypub_get_user_outbox(
+  posts: web::Data<PostPool>,
+  users: web::Data<UserPool>,
+  query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
+  user_id: web::Path<Uuid>,
+) -> impl Responder {
+  // Remote users' outboxes are served by their own servers
+  match get_user_by_id(&user_id, &users).await {
+    Ok(user) if !user.is_external => (),
+    _ => return HttpResponse::NotFound().finish(),
+  }
+
+  let page = numbered_page(&query.page, &cursor);
+  let page_size = query.page_size.unwrap_or(20);
+  let base_uri = format!("{}/user/{}/outbox", SETTINGS.server.api_fqdn, user_id);
+
+  let posts_page = match posts
+    .fetch_user_outbox(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
     .await
   {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let doc = create_activitypub_ordered_collection_page_feed(
-    &format!("{}/orbit/{}/feed", SETTINGS.server.api_fqdn, orbit_id),
-    page.try_into().unwrap_or_default(),
-    page_size.try_into().unwrap_or_default(),
-    posts_count.try_into().unwrap_or_default(),
-    posts,
-  );
+  let (prev, next) = match page {
+    Some(page) => match posts.count_user_outbox(&user_id).await {
+      Ok(count) => numbered_page_links(&base_uri, page, page_size, count),
+      Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
+    },
+    None => cursor_page_links(&base_uri, &cursor, page_size, &posts_page, |p| p.post_id, false),
+  };
+
+  let doc = create_activitypub_ordered_collection_page_feed(&base_uri, prev, next, posts_page);
 
   HttpResponse::Ok()
     .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
     .json(doc)
 }
 
-pub async fn api_activitypub_get_federated_user_liked_posts(
+pub async fn api_activitypub_get_orbit_outbox(
   posts: web::Data<PostPool>,
+  orbits: web::Data<OrbitPool>,
   query: web::Query<PostsQuery>,
-  user_id: web::Path<Uuid>,
+  cursor: web::Query<PageCursor>,
+  orbit_id: web::Path<Uuid>,
 ) -> impl Responder {
-  let page = query.page.unwrap_or(0);
+  match orbits.fetch_orbit(&orbit_id).await {
+    Ok(Some(orbit)) if !orbit.is_external => (),
+    _ => return HttpResponse::NotFound().finish(),
+  }
+
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match posts.count_user_public_likes_feed(&user_id, &None).await {
-    Ok(count) => count,
+  let base_uri = format!("{}/orbit/{}/outbox", SETTINGS.server.api_fqdn, orbit_id);
+
+  let posts_page = match posts
+    .fetch_orbit_outbox(&orbit_id, &cursor, page_size, page.unwrap_or(0) * page_size)
+    .await
+  {
+    Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let posts = match posts
-    .fetch_user_public_likes_feed(&user_id, &None, page_size, page * page_size)
+  let (prev, next) = match page {
+    Some(page) => match posts.count_orbit_outbox(&orbit_id).await {
+      Ok(count) => numbered_page_links(&base_uri, page, page_size, count),
+      Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
+    },
+    None => cursor_page_links(&base_uri, &cursor, page_size, &posts_page, |p| p.post_id, false),
+  };
+
+  let doc = create_activitypub_ordered_collection_page_feed(&base_uri, prev, next, posts_page);
+
+  HttpResponse::Ok()
+    .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
+    .json(doc)
+}
+
+pub async fn api_activitypub_get_federated_user_liked_posts(
+  posts: web::Data<PostPool>,
+  query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
+  user_id: web::Path<Uuid>,
+) -> impl Responder {
+  let page = numbered_page(&query.page, &cursor);
+  let page_size = query.page_size.unwrap_or(20);
+  let base_uri = format!("{}/user/{}/likes", SETTINGS.server.api_fqdn, user_id);
+
+  let posts_page = match posts
+    .fetch_user_public_likes_feed(&user_id, &None, &cursor, page_size, page.unwrap_or(0) * page_size)
     .await
   {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let doc = create_activitypub_ordered_collection_page_specific_feed(
-    &format!("{}/user/{}/likes", SETTINGS.server.api_fqdn, user_id),
-    page.try_into().unwrap_or_default(),
-    page_size.try_into().unwrap_or_default(),
-    posts_count.try_into().unwrap_or_default(),
-    posts,
-    ActivityType::Like,
-  );
+  let (prev, next) = match page {
+    Some(page) => match posts.count_user_public_likes_feed(&user_id, &None).await {
+      Ok(count) => numbered_page_links(&base_uri, page, page_size, count),
+      Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
+    },
+    None => cursor_page_links(&base_uri, &cursor, page_size, &posts_page, |p| p.post_id, false),
+  };
+
+  let doc =
+    create_activitypub_ordered_collection_page_specific_feed(&base_uri, prev, next, posts_page, ActivityType::Like);
 
   HttpResponse::Ok()
     .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
@@ -221,24 +314,29 @@ pub async fn api_activitypub_get_user_followers(
   users: web::Data<UserPool>,
   user_id: web::Path<Uuid>,
   query: web::Query<FollowersQuery>,
+  cursor: web::Query<PageCursor>,
 ) -> impl Responder {
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let users_count = users.fetch_followers_count(&user_id).await;
+  let base_uri = format!("{}/user/{}/followers", SETTINGS.server.api_fqdn, user_id);
 
-  let users = match users.fetch_followers(&user_id, page_size, page * page_size).await {
+  let users_page = match users
+    .fetch_followers(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
+    .await
+  {
     Ok(users) => users,
     Err(err) => return build_api_err(500, err.to_string(), None),
   };
 
-  let doc = create_activitypub_ordered_collection_page(
-    &format!("{}/user/{}/followers", SETTINGS.server.api_fqdn, user_id),
-    page.try_into().unwrap_or_default(),
-    page_size.try_into().unwrap_or_default(),
-    users_count.try_into().unwrap_or_default(),
-    users,
-    None,
-  );
+  let (prev, next) = match page {
+    Some(page) => {
+      let users_count = users.fetch_followers_count(&user_id).await;
+      numbered_page_links(&base_uri, page, page_size, users_count)
+    }
+    None => cursor_page_links(&base_uri, &cursor, page_size, &users_page, |u| u.user_id, false),
+  };
+
+  let doc = create_activitypub_ordered_collection_page(&base_uri, prev, next, users_page, None);
 
   HttpResponse::Ok()
     .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
@@ -249,24 +347,29 @@ pub async fn api_activitypub_get_user_following(
   users: web::Data<UserPool>,
   user_id: web::Path<Uuid>,
   query: web::Query<FollowersQuery>,
+  cursor: web::Query<PageCursor>,
 ) -> impl Responder {
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let users_count = users.fetch_following_count(&user_id).await;
+  let base_uri = format!("{}/user/{}/following", SETTINGS.server.api_fqdn, user_id);
 
-  let users = match users.fetch_following(&user_id, page_size, page * page_size).await {
+  let users_page = match users
+    .fetch_following(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
+    .await
+  {
     Ok(users) => users,
     Err(err) => return build_api_err(500, err.to_string(), None),
   };
 
-  let doc = create_activitypub_ordered_collection_page(
-    &format!("{}/user/{}/following", SETTINGS.server.api_fqdn, user_id),
-    page.try_into().unwrap_or_default(),
-    page_size.try_into().unwrap_or_default(),
-    users_count.try_into().unwrap_or_default(),
-    users,
-    None,
-  );
+  let (prev, next) = match page {
+    Some(page) => {
+      let users_count = users.fetch_following_count(&user_id).await;
+      numbered_page_links(&base_uri, page, page_size, users_count)
+    }
+    None => cursor_page_links(&base_uri, &cursor, page_size, &users_page, |u| u.user_id, false),
+  };
+
+  let doc = create_activitypub_ordered_collection_page(&base_uri, prev, next, users_page, None);
 
   HttpResponse::Ok()
     .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
@@ -331,14 +434,10 @@ pub async fn api_activitypub_get_orbit_members(
     Err(err) => return build_api_err(500, err.to_string(), None),
   };
 
-  let doc = create_activitypub_ordered_collection_page(
-    &format!("{}/orbit/{}/members", SETTINGS.server.api_fqdn, orbit_id),
-    page.try_into().unwrap_or_default(),
-    page_size.try_into().unwrap_or_default(),
-    users_count.try_into().unwrap_or_default(),
-    users,
-    None,
-  );
+  let base_uri = format!("{}/orbit/{}/members", SETTINGS.server.api_fqdn, orbit_id);
+  let (prev, next) = numbered_page_links(&base_uri, page, page_size, users_count);
+
+  let doc = create_activitypub_ordered_collection_page(&base_uri, prev, next, users, None);
 
   HttpResponse::Ok()
     .insert_header(("Content-Type", ACTIVITY_JSON_CONTENT_TYPE))
@@ -368,6 +467,7 @@ pub async fn api_activitypub_get_comments(
   comments: web::Data<CommentPool>,
   posts: web::Data<PostPool>,
   query: web::Query<CommentsQuery>,
+  cursor: web::Query<PageCursor>,
   post_id: web::Path<Uuid>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -376,17 +476,43 @@ pub async fn api_activitypub_get_comments(
     None => None,
   };
 
-  match activitypub_get_comments(&posts, &comments, &post_id, &own_user_id, &query.page, &query.page_size).await {
+  match activitypub_get_comments(
+    &posts,
+    &comments,
+    &post_id,
+    &own_user_id,
+    &cursor,
+    &query.page,
+    &query.page_size,
+  )
+  .await
+  {
     Ok(response) => HttpResponse::Ok().json(response),
     Err(err) => map_api_err(err),
   }
 }
 
+async fn api_activitypub_origin_allowed(data: &serde_json::Value, domain_policies: &DomainPolicyPool) -> bool {
+  sync_domain_policies(domain_policies).await;
+
+  let actor_uri = match data.get("actor") {
+    Some(serde_json::Value::String(uri)) => Some(uri.as_str()),
+    Some(serde_json::Value::Object(actor)) => actor.get("id").and_then(|id| id.as_str()),
+    _ => None,
+  };
+
+  match actor_uri {
+    Some(uri) => uri_federation_allowed(uri),
+    None => true,
+  }
+}
+
 pub async fn api_activitypub_federate_shared_inbox(
   req: HttpRequest,
   jobs: web::Data<JobPool>,
   data: web::Json<serde_json::Value>,
   queue: web::Data<Queue>,
+  domain_policies: web::Data<DomainPolicyPool>,
 ) -> impl Responder {
   let origin_data = build_origin_data(&req);
 
@@ -394,6 +520,10 @@ pub async fn api_activitypub_federate_shared_inbox(
     return build_api_err(401, "signature".to_string(), None);
   }
 
+  if !api_activitypub_origin_allowed(&data, &domain_policies).await {
+    return build_api_err(403, "domain".to_string(), None);
+  }
+
   let job_id = match jobs
     .create(NewJob {
       created_by_id: None,
@@ -430,6 +560,7 @@ pub async fn api_activitypub_federate_user_inbox(
   data: web::Json<serde_json::Value>,
   jobs: web::Data<JobPool>,
   queue: web::Data<Queue>,
+  domain_policies: web::Data<DomainPolicyPool>,
 ) -> impl Responder {
   let origin_data = build_origin_data(&req);
 
@@ -437,6 +568,10 @@ pub async fn api_activitypub_federate_user_inbox(
     return build_api_err(401, "signature".to_string(), None);
   }
 
+  if !api_activitypub_origin_allowed(&data, &domain_policies).await {
+    return build_api_err(403, "domain".to_string(), None);
+  }
+
   let job_id = match jobs
     .create(NewJob {
       created_by_id: None,
@@ -471,6 +606,7 @@ pub async fn api_activitypub_federate_orbit_inbox(
   data: web::Json<serde_json::Value>,
   jobs: web::Data<JobPool>,
   queue: web::Data<Queue>,
+  domain_policies: web::Data<DomainPolicyPool>,
 ) -> impl Responder {
   let origin_data = build_origin_data(&req);
 
@@ -478,6 +614,10 @@ pub async fn api_activitypub_federate_orbit_inbox(
     return build_api_err(401, "signature".to_string(), None);
   }
 
+  if !api_activitypub_origin_allowed(&data, &domain_policies).await {
+    return build_api_err(403, "domain".to_string(), None);
+  }
+
   let job_id = match jobs
     .create(NewJob {
       created_by_id: None,
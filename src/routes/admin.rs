@@ -0,0 +1,178 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::{
+  db::{
+    account_restriction_repository::AccountRestrictionPool, domain_policy_repository::DomainPolicyPool,
+    session_repository::SessionPool, user_repository::UserPool,
+  },
+  helpers::{auth::require_auth, core::map_api_err},
+  logic::{
+    account_restriction::{get_account_restrictions, lift_account_restriction, restrict_account},
+    domain_policy::{delete_domain_policy, get_domain_policies, upsert_domain_policy},
+  },
+  model::{
+    account_restriction::AccountRestrictionType,
+    domain_policy::DomainPolicyType,
+    response::{ListResponse, ObjectResponse},
+  },
+  net::jwt::JwtContext,
+};
+
+#[derive(Deserialize)]
+pub struct DomainPolicyRequest {
+  pub domain: String,
+  pub policy: DomainPolicyType,
+  pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AccountRestrictionRequest {
+  pub restriction: AccountRestrictionType,
+  pub reason: String,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub async fn api_get_domain_policies(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  domain_policies: web::Data<DomainPolicyPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_domain_policies(&users, &domain_policies, &props.uid).await {
+    Ok(policies) => HttpResponse::Ok().json(ListResponse {
+      total_items: policies.len() as i64,
+      total_pages: 1,
+      page: 0,
+      data: policies,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_upsert_domain_policy(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  domain_policies: web::Data<DomainPolicyPool>,
+  req: web::Json<DomainPolicyRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match upsert_domain_policy(
+    &users,
+    &domain_policies,
+    &props.uid,
+    &req.domain,
+    &req.policy,
+    &req.reason,
+  )
+  .await
+  {
+    Ok(policy) => HttpResponse::Ok().json(ObjectResponse { data: policy }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_delete_domain_policy(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  domain_policies: web::Data<DomainPolicyPool>,
+  domain: web::Path<String>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match delete_domain_policy(&users, &domain_policies, &props.uid, &domain).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_get_account_restrictions(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  account_restrictions: web::Data<AccountRestrictionPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_account_restrictions(&users, &account_restrictions, &props.uid).await {
+    Ok(restrictions) => HttpResponse::Ok().json(ListResponse {
+      total_items: restrictions.len() as i64,
+      total_pages: 1,
+      page: 0,
+      data: restrictions,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_restrict_account(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  account_restrictions: web::Data<AccountRestrictionPool>,
+  user_handle: web::Path<String>,
+  req: web::Json<AccountRestrictionRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match restrict_account(
+    &users,
+    &account_restrictions,
+    &user_handle,
+    &req.restriction,
+    &req.reason,
+    &req.expires_at,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(restrictions) => HttpResponse::Ok().json(ListResponse {
+      total_items: restrictions.len() as i64,
+      total_pages: 1,
+      page: 0,
+      data: restrictions,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_lift_account_restriction(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  account_restrictions: web::Data<AccountRestrictionPool>,
+  path: web::Path<(String, AccountRestrictionType)>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let (user_handle, restriction) = path.into_inner();
+
+  match lift_account_restriction(&users, &account_restrictions, &user_handle, &restriction, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
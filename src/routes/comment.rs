@@ -1,17 +1,26 @@
 use crate::{
   db::{
-    comment_repository::CommentPool, follow_repository::FollowPool, post_repository::PostPool,
-    session_repository::SessionPool,
+    comment_repository::CommentPool, follow_repository::FollowPool, job_repository::JobPool,
+    notification_repository::NotificationPool, orbit_membership_repository::OrbitMembershipPool,
+    orbit_moderation_repository::OrbitModerationPool, post_repository::PostPool, session_repository::SessionPool,
+    user_repository::UserPool,
   },
   helpers::auth::{query_auth, require_auth},
-  helpers::core::{build_api_err, map_api_err},
-  logic::comment::{
-    create_comment, create_comment_like, delete_comment, delete_comment_like, get_comment, get_comments,
+  helpers::core::map_api_err,
+  helpers::pagination::{build_page_response, numbered_page, optional_count},
+  logic::{
+    comment::{
+      create_comment, create_comment_like, delete_comment, delete_comment_like, get_comment, get_comments,
+      get_comments_count,
+    },
+    LogicErr,
   },
-  model::response::ObjectResponse,
+  model::{page_cursor::PageCursor, response::ObjectResponse},
   net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
 };
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 use uuid::Uuid;
 
@@ -24,6 +33,9 @@ pub struct NewPost {
 pub struct CommentsQuery {
   pub page: Option<i64>,
   pub page_size: Option<i64>,
+  /// Whether to count every comment when walking them by cursor. Numbered pages are always counted.
+  #[serde(default)]
+  pub count: bool,
 }
 
 pub async fn api_create_comment(
@@ -31,6 +43,10 @@ pub async fn api_create_comment(
   comments: web::Data<CommentPool>,
   follows: web::Data<FollowPool>,
   posts: web::Data<PostPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
   post_id: web::Path<Uuid>,
   contents: web::Json<NewPost>,
   jwt: web::ReqData<JwtContext>,
@@ -40,15 +56,30 @@ pub async fn api_create_comment(
     Err(res) => return res,
   };
 
-  match create_comment(&posts, &follows, &comments, &post_id, &props.uid, &contents.content_md).await {
+  match create_comment(
+    &posts,
+    &follows,
+    &comments,
+    &orbit_moderation,
+    &orbit_membership,
+    &jobs,
+    &queue,
+    &post_id,
+    &props.uid,
+    &contents.content_md,
+  )
+  .await
+  {
     Ok(comment) => HttpResponse::Ok().json(ObjectResponse { data: comment }),
-    Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
+    Err(err) => map_api_err(err),
   }
 }
 
 pub async fn api_delete_comment(
   sessions: web::Data<SessionPool>,
   comments: web::Data<CommentPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
   ids: web::Path<(Uuid, Uuid)>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -57,7 +88,7 @@ pub async fn api_delete_comment(
     Err(res) => return res,
   };
 
-  match delete_comment(&comments, &ids.0, &ids.1, &props.uid).await {
+  match delete_comment(&comments, &jobs, &queue, &ids.0, &ids.1, &props.uid).await {
     Ok(_) => HttpResponse::Ok().finish(),
     Err(err) => map_api_err(err),
   }
@@ -67,16 +98,44 @@ pub async fn api_get_comments(
   sessions: web::Data<SessionPool>,
   comments: web::Data<CommentPool>,
   query: web::Query<CommentsQuery>,
+  cursor: web::Query<PageCursor>,
   post_id: web::Path<Uuid>,
   jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
 ) -> impl Responder {
   let own_user_id = match query_auth(&jwt, &sessions).await {
     Some(props) => Some(props.uid),
     None => None,
   };
 
-  match get_comments(&comments, &post_id, &own_user_id, &query.page, &query.page_size).await {
-    Ok(response) => HttpResponse::Ok().json(response),
+  let page = numbered_page(&query.page, &cursor);
+  let page_size = query.page_size.unwrap_or(20);
+  let comments_count = match optional_count(
+    page.is_some() || query.count,
+    get_comments_count(&comments, &post_id, &own_user_id),
+  )
+  .await
+  {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  // Numbered pages have always treated a post without comments as missing
+  if page.is_some() && comments_count == Some(0) {
+    return map_api_err(LogicErr::MissingRecord);
+  }
+
+  match get_comments(
+    &comments,
+    &post_id,
+    &own_user_id,
+    &cursor,
+    page_size,
+    page.unwrap_or(0) * page_size,
+  )
+  .await
+  {
+    Ok(comments) => build_page_response(&req, page, page_size, comments, |c| c.comment_id, true, comments_count),
     Err(err) => map_api_err(err),
   }
 }
@@ -103,6 +162,11 @@ pub async fn api_create_comment_like(
   comments: web::Data<CommentPool>,
   follows: web::Data<FollowPool>,
   posts: web::Data<PostPool>,
+  users: web::Data<UserPool>,
+  jobs: web::Data<JobPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
+  queue: web::Data<Queue>,
   ids: web::Path<(Uuid, Uuid)>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -111,7 +175,21 @@ pub async fn api_create_comment_like(
     Err(res) => return res,
   };
 
-  match create_comment_like(&posts, &follows, &comments, &ids.0, &ids.1, &props.uid).await {
+  match create_comment_like(
+    &posts,
+    &follows,
+    &comments,
+    &users,
+    &jobs,
+    &notifications,
+    &pubsub,
+    &queue,
+    &ids.0,
+    &ids.1,
+    &props.uid,
+  )
+  .await
+  {
     Ok(_) => HttpResponse::Created().finish(),
     Err(err) => map_api_err(err),
   }
@@ -120,6 +198,9 @@ pub async fn api_create_comment_like(
 pub async fn api_delete_comment_like(
   sessions: web::Data<SessionPool>,
   comments: web::Data<CommentPool>,
+  users: web::Data<UserPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
   ids: web::Path<(Uuid, Uuid)>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -128,7 +209,7 @@ pub async fn api_delete_comment_like(
     Err(res) => return res,
   };
 
-  match delete_comment_like(&comments, &ids.0, &ids.1, &props.uid).await {
+  match delete_comment_like(&comments, &users, &jobs, &queue, &ids.0, &ids.1, &props.uid).await {
     Ok(_) => HttpResponse::Ok().finish(),
     Err(err) => map_api_err(err),
   }
@@ -1,20 +1,38 @@
 use crate::{
   db::{
-    follow_repository::FollowPool, job_repository::JobPool, session_repository::SessionPool, user_repository::UserPool,
+    block_repository::BlockPool, follow_repository::FollowPool, job_repository::JobPool,
+    notification_repository::NotificationPool, session_repository::SessionPool, user_repository::UserPool,
   },
   helpers::auth::require_auth,
-  helpers::core::build_api_err,
-  logic::follow::{create_follow, delete_follow},
+  helpers::core::{build_api_err, map_api_err},
+  helpers::math::div_up,
+  logic::follow::{
+    accept_follow_request, create_follow, delete_follow, get_follow_requests, get_follow_requests_count,
+    reject_follow_request, FollowResult,
+  },
+  model::response::ListResponse,
   net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct FollowRequestsQuery {
+  pub page: Option<i64>,
+  pub page_size: Option<i64>,
+}
 
 pub async fn api_create_follow(
   sessions: web::Data<SessionPool>,
+  blocks: web::Data<BlockPool>,
   follows: web::Data<FollowPool>,
   users: web::Data<UserPool>,
   jobs: web::Data<JobPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
   queue: web::Data<Queue>,
   user_handle: web::Path<String>,
   jwt: web::ReqData<JwtContext>,
@@ -24,9 +42,22 @@ pub async fn api_create_follow(
     Err(res) => return res,
   };
 
-  match create_follow(&users, &follows, &jobs, &queue, &user_handle, &props.uid).await {
-    Ok(_) => HttpResponse::Created().finish(),
-    Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
+  match create_follow(
+    &users,
+    &blocks,
+    &follows,
+    &jobs,
+    &notifications,
+    &pubsub,
+    &queue,
+    &user_handle,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(FollowResult::Followed) => HttpResponse::Created().finish(),
+    Ok(FollowResult::Requested) => HttpResponse::Accepted().finish(),
+    Err(err) => map_api_err(err),
   }
 }
 
@@ -49,3 +80,71 @@ pub async fn api_delete_follow(
     Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
   }
 }
+
+pub async fn api_get_follow_requests(
+  sessions: web::Data<SessionPool>,
+  follows: web::Data<FollowPool>,
+  query: web::Query<FollowRequestsQuery>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let page = query.page.unwrap_or(0);
+  let page_size = query.page_size.unwrap_or(20);
+
+  let requests_count = match get_follow_requests_count(&follows, &props.uid).await {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  match get_follow_requests(&follows, page_size, page * page_size, &props.uid).await {
+    Ok(requests) => HttpResponse::Ok().json(ListResponse {
+      data: requests,
+      page,
+      total_items: requests_count,
+      total_pages: div_up(requests_count, page_size) + 1,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_accept_follow_request(
+  sessions: web::Data<SessionPool>,
+  follows: web::Data<FollowPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  requester_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match accept_follow_request(&follows, &jobs, &queue, &requester_id, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_reject_follow_request(
+  sessions: web::Data<SessionPool>,
+  follows: web::Data<FollowPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  requester_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match reject_follow_request(&follows, &jobs, &queue, &requester_id, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
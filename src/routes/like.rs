@@ -1,12 +1,15 @@
 use crate::{
   db::{
-    follow_repository::FollowPool, like_repository::LikePool, post_repository::PostPool,
-    session_repository::SessionPool,
+    follow_repository::FollowPool, job_repository::JobPool, like_repository::LikePool,
+    notification_repository::NotificationPool, post_repository::PostPool, session_repository::SessionPool,
+    user_repository::UserPool,
   },
   helpers::auth::require_auth,
   helpers::core::build_api_err,
   logic::like::{create_like, delete_like},
   net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
 };
 use actix_web::{web, HttpResponse, Responder};
 use uuid::Uuid;
@@ -16,6 +19,11 @@ pub async fn api_create_like(
   follows: web::Data<FollowPool>,
   posts: web::Data<PostPool>,
   likes: web::Data<LikePool>,
+  users: web::Data<UserPool>,
+  jobs: web::Data<JobPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
+  queue: web::Data<Queue>,
   post_id: web::Path<Uuid>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -24,7 +32,20 @@ pub async fn api_create_like(
     Err(res) => return res,
   };
 
-  match create_like(&posts, &follows, &likes, &post_id, &props.uid).await {
+  match create_like(
+    &posts,
+    &follows,
+    &likes,
+    &users,
+    &jobs,
+    &notifications,
+    &pubsub,
+    &queue,
+    &post_id,
+    &props.uid,
+  )
+  .await
+  {
     Ok(_) => HttpResponse::Created().finish(),
     Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
   }
@@ -32,7 +53,11 @@ pub async fn api_create_like(
 
 pub async fn api_delete_like(
   sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
   likes: web::Data<LikePool>,
+  users: web::Data<UserPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
   post_id: web::Path<Uuid>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -41,7 +66,7 @@ pub async fn api_delete_like(
     Err(res) => return res,
   };
 
-  match delete_like(&likes, &post_id, &props.uid).await {
+  match delete_like(&posts, &likes, &users, &jobs, &queue, &post_id, &props.uid).await {
     Ok(_) => HttpResponse::Ok().finish(),
     Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
   }
@@ -0,0 +1,324 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    block_repository::BlockPool, follow_repository::FollowPool, job_repository::JobPool, mute_repository::MutePool,
+    notification_repository::NotificationPool, post_repository::PostPool, session_repository::SessionPool,
+    user_repository::UserPool,
+  },
+  helpers::{
+    api::local_domain,
+    auth::{query_auth, require_auth},
+    core::{build_api_not_found, map_api_err},
+    pagination::build_link_response,
+  },
+  logic::{
+    follow::{create_follow, delete_follow},
+    LogicErr,
+  },
+  mastodon::{
+    account::{Account, AccountCounts},
+    params::MastodonParams,
+    relationship::Relationship,
+    status::Status,
+  },
+  model::{page_cursor::PageCursor, user::User},
+  net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
+};
+
+use super::{account_counts, page_limit, render_account, render_statuses, LimitQuery};
+
+#[derive(Deserialize)]
+pub struct AccountLookupQuery {
+  pub acct: String,
+}
+
+#[derive(Deserialize)]
+pub struct AccountStatusesQuery {
+  pub limit: Option<i64>,
+  /// Profiles have nothing pinned to them, only orbits do
+  #[serde(default)]
+  pub pinned: bool,
+}
+
+#[derive(Deserialize)]
+pub struct RelationshipsQuery {
+  #[serde(default)]
+  pub id: Vec<Uuid>,
+}
+
+async fn build_relationship(
+  follows: &FollowPool,
+  blocks: &BlockPool,
+  mutes: &MutePool,
+  user_id: &Uuid,
+  target_id: &Uuid,
+) -> Result<Relationship, LogicErr> {
+  let muting = mutes
+    .fetch_user_mutes(user_id)
+    .await?
+    .iter()
+    .any(|mute| &mute.muted_user_id == target_id);
+
+  Ok(Relationship {
+    id: *target_id,
+    following: follows.user_follows_user(user_id, target_id).await,
+    showing_reblogs: true,
+    followed_by: follows.user_follows_user(target_id, user_id).await,
+    // Blocks are only known to exist between two users, not which of them made it
+    blocking: blocks.block_exists_between(user_id, target_id).await,
+    muting,
+    muting_notifications: muting,
+    requested: follows.fetch_follow_request(user_id, target_id).await?.is_some(),
+    requested_by: follows.fetch_follow_request(target_id, user_id).await?.is_some(),
+    ..Default::default()
+  })
+}
+
+async fn fetch_user(users: &UserPool, id: &Uuid) -> Option<User> {
+  users.fetch_by_id(id).await.ok()
+}
+
+pub async fn api_mastodon_verify_credentials(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  follows: web::Data<FollowPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let user = match users.fetch_by_id(&props.uid).await {
+    Ok(user) => user,
+    Err(err) => return map_api_err(err),
+  };
+
+  let follow_requests_count = match follows.count_follow_requests(&props.uid).await {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  let counts = match account_counts(&users, &posts, &user.user_id).await {
+    Ok(counts) => counts,
+    Err(err) => return map_api_err(err),
+  };
+
+  HttpResponse::Ok().json(Account::from_own_user(&user, &counts, follow_requests_count))
+}
+
+pub async fn api_mastodon_get_account(
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  id: web::Path<Uuid>,
+) -> impl Responder {
+  let user = match fetch_user(&users, &id).await {
+    Some(user) => user,
+    None => return build_api_not_found(id.to_string()),
+  };
+
+  match render_account(&users, &posts, &user).await {
+    Ok(account) => HttpResponse::Ok().json(account),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_lookup_account(
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  query: web::Query<AccountLookupQuery>,
+) -> impl Responder {
+  let acct = query.acct.trim_start_matches('@');
+
+  let user = match acct.split_once('@') {
+    Some((handle, domain)) if domain == local_domain() => users.fetch_by_handle(handle).await,
+    Some(_) => users.fetch_by_fediverse_id(&format!("@{}", acct)).await,
+    None => users.fetch_by_handle(acct).await,
+  };
+
+  let user = match user {
+    Ok(Some(user)) => user,
+    Ok(None) => return build_api_not_found(acct.to_string()),
+    Err(err) => return map_api_err(err),
+  };
+
+  match render_account(&users, &posts, &user).await {
+    Ok(account) => HttpResponse::Ok().json(account),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_get_account_statuses(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  id: web::Path<Uuid>,
+  query: web::Query<AccountStatusesQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let own_user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  if query.pinned {
+    return HttpResponse::Ok().json(Vec::<Status>::new());
+  }
+
+  let posts = match posts
+    .fetch_user_public_feed(&id, &own_user_id, &cursor, page_limit(&query.limit), 0)
+    .await
+  {
+    Ok(posts) => posts,
+    Err(err) => return map_api_err(err),
+  };
+
+  build_link_response(&req, render_statuses(&users, &posts).await, |s| s.id, false)
+}
+
+pub async fn api_mastodon_get_account_followers(
+  users: web::Data<UserPool>,
+  id: web::Path<Uuid>,
+  query: web::Query<LimitQuery>,
+  cursor: web::Query<PageCursor>,
+  req: HttpRequest,
+) -> impl Responder {
+  match users.fetch_followers(&id, &cursor, query.limit(), 0).await {
+    Ok(followers) => build_link_response(
+      &req,
+      followers
+        .iter()
+        .map(|user| Account::from_user(user, &AccountCounts::default()))
+        .collect(),
+      |a| a.id,
+      false,
+    ),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_get_account_following(
+  users: web::Data<UserPool>,
+  id: web::Path<Uuid>,
+  query: web::Query<LimitQuery>,
+  cursor: web::Query<PageCursor>,
+  req: HttpRequest,
+) -> impl Responder {
+  match users.fetch_following(&id, &cursor, query.limit(), 0).await {
+    Ok(following) => build_link_response(
+      &req,
+      following
+        .iter()
+        .map(|user| Account::from_user(user, &AccountCounts::default()))
+        .collect(),
+      |a| a.id,
+      false,
+    ),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_follow_account(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  blocks: web::Data<BlockPool>,
+  follows: web::Data<FollowPool>,
+  mutes: web::Data<MutePool>,
+  jobs: web::Data<JobPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
+  queue: web::Data<Queue>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let user = match fetch_user(&users, &id).await {
+    Some(user) => user,
+    None => return build_api_not_found(id.to_string()),
+  };
+
+  if let Err(err) = create_follow(
+    &users,
+    &blocks,
+    &follows,
+    &jobs,
+    &notifications,
+    &pubsub,
+    &queue,
+    &user.handle,
+    &props.uid,
+  )
+  .await
+  {
+    return map_api_err(err);
+  }
+
+  match build_relationship(&follows, &blocks, &mutes, &props.uid, &id).await {
+    Ok(relationship) => HttpResponse::Ok().json(relationship),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_unfollow_account(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  blocks: web::Data<BlockPool>,
+  follows: web::Data<FollowPool>,
+  mutes: web::Data<MutePool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let user = match fetch_user(&users, &id).await {
+    Some(user) => user,
+    None => return build_api_not_found(id.to_string()),
+  };
+
+  if let Err(err) = delete_follow(&users, &follows, &jobs, &queue, &user.handle, &props.uid).await {
+    return map_api_err(err);
+  }
+
+  match build_relationship(&follows, &blocks, &mutes, &props.uid, &id).await {
+    Ok(relationship) => HttpResponse::Ok().json(relationship),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_get_relationships(
+  sessions: web::Data<SessionPool>,
+  follows: web::Data<FollowPool>,
+  blocks: web::Data<BlockPool>,
+  mutes: web::Data<MutePool>,
+  query: MastodonParams<RelationshipsQuery>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let mut relationships = vec![];
+  for target_id in &query.id {
+    match build_relationship(&follows, &blocks, &mutes, &props.uid, target_id).await {
+      Ok(relationship) => relationships.push(relationship),
+      Err(err) => return map_api_err(err),
+    }
+  }
+
+  HttpResponse::Ok().json(relationships)
+}
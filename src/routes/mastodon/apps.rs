@@ -0,0 +1,56 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::{
+  db::app_repository::AppPool,
+  helpers::core::map_api_err,
+  logic::app::{create_app, NewApp},
+  mastodon::{application::Application, params::MastodonParams},
+};
+
+/// Apps registered through the Mastodon API only get to read unless they ask for more, as on Mastodon
+const DEFAULT_MASTODON_SCOPES: &str = "read";
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum RedirectUris {
+  One(String),
+  Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+pub struct MastodonAppRequest {
+  pub client_name: String,
+  pub redirect_uris: RedirectUris,
+  pub scopes: Option<String>,
+  pub website: Option<String>,
+}
+
+pub async fn api_mastodon_create_app(
+  apps: web::Data<AppPool>,
+  params: MastodonParams<MastodonAppRequest>,
+) -> impl Responder {
+  let redirect_uris = match &params.redirect_uris {
+    RedirectUris::One(uris) => uris.split_whitespace().collect::<Vec<&str>>().join(" "),
+    RedirectUris::Many(uris) => uris.join(" "),
+  };
+
+  let new_app = NewApp {
+    name: params.client_name.clone(),
+    description: String::new(),
+    owner_name: params.client_name.clone(),
+    owner_uri: params.website.clone().unwrap_or_default(),
+    redirect_uri: redirect_uris,
+    scopes: Some(
+      params
+        .scopes
+        .clone()
+        .unwrap_or_else(|| DEFAULT_MASTODON_SCOPES.to_string()),
+    ),
+  };
+
+  match create_app(&apps, &new_app).await {
+    Ok(app) => HttpResponse::Ok().json(Application::with_credentials(&app)),
+    Err(err) => map_api_err(err),
+  }
+}
@@ -0,0 +1,17 @@
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::{
+  db::{post_repository::PostPool, user_repository::UserPool},
+  mastodon::instance::{InstanceV1, InstanceV2},
+};
+
+pub async fn api_mastodon_get_instance_v1(users: web::Data<UserPool>, posts: web::Data<PostPool>) -> impl Responder {
+  let user_count = users.fetch_user_count().await;
+  let status_count = posts.fetch_post_count().await;
+
+  HttpResponse::Ok().json(InstanceV1::new(user_count, status_count))
+}
+
+pub async fn api_mastodon_get_instance_v2(users: web::Data<UserPool>) -> impl Responder {
+  HttpResponse::Ok().json(InstanceV2::new(users.fetch_user_count().await))
+}
@@ -0,0 +1,51 @@
+use actix_web::{web, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+  db::{orbit_repository::OrbitPool, session_repository::SessionPool},
+  helpers::{
+    auth::require_auth,
+    core::{build_api_not_found, map_api_err},
+  },
+  mastodon::list::List,
+  net::jwt::JwtContext,
+};
+
+pub async fn api_mastodon_get_lists(
+  sessions: web::Data<SessionPool>,
+  orbits: web::Data<OrbitPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  // Lists aren't paged, so every orbit the user has joined is shown
+  let count = match orbits.count_user_orbits(&props.uid).await {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  match orbits.fetch_user_orbits(&props.uid, count, 0).await {
+    Ok(user_orbits) => HttpResponse::Ok().json(user_orbits.iter().map(List::from).collect::<Vec<List>>()),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_get_list(
+  sessions: web::Data<SessionPool>,
+  orbits: web::Data<OrbitPool>,
+  orbit_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  if let Err(res) = require_auth(&jwt, &sessions).await {
+    return res;
+  }
+
+  match orbits.fetch_orbit(&orbit_id).await {
+    Ok(Some(orbit)) => HttpResponse::Ok().json(List::from(&orbit)),
+    Ok(None) => build_api_not_found(orbit_id.to_string()),
+    Err(err) => map_api_err(err),
+  }
+}
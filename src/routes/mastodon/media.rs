@@ -0,0 +1,36 @@
+use actix_easy_multipart::{tempfile::Tempfile, MultipartForm};
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::{
+  cdn::cdn_store::Cdn,
+  db::{post_attachment_repository::PostAttachmentPool, session_repository::SessionPool},
+  helpers::{auth::require_auth, core::map_api_err},
+  logic::post::upload_attachment,
+  mastodon::media_attachment::MediaAttachment,
+  net::jwt::JwtContext,
+};
+
+#[derive(MultipartForm)]
+pub struct MediaUpload {
+  file: Tempfile,
+}
+
+/// Uploads an image ahead of the status it's for, which picks it up by its ID when it's posted
+// TODO: Clean up uploads that never end up attached to a status
+pub async fn api_mastodon_upload_media(
+  form: MultipartForm<MediaUpload>,
+  cdn: web::Data<Cdn>,
+  jwt: web::ReqData<JwtContext>,
+  sessions: web::Data<SessionPool>,
+  post_attachments: web::Data<PostAttachmentPool>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match upload_attachment(&post_attachments, &None, &props.uid, &cdn, &form.file).await {
+    Ok(attachment) => HttpResponse::Ok().json(MediaAttachment::from(&attachment)),
+    Err(err) => map_api_err(err),
+  }
+}
@@ -0,0 +1,140 @@
+pub mod accounts;
+pub mod apps;
+pub mod instance;
+pub mod lists;
+pub mod media;
+pub mod notifications;
+pub mod search;
+pub mod statuses;
+pub mod timelines;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    comment_repository::CommentPool, follow_repository::FollowPool, orbit_membership_repository::OrbitMembershipPool,
+    post_repository::PostPool, user_repository::UserPool,
+  },
+  logic::{
+    comment::get_comment,
+    post::{get_post, user_can_view_post},
+    LogicErr,
+  },
+  mastodon::{
+    account::{Account, AccountCounts},
+    status::Status,
+  },
+  model::{comment_pub::CommentPub, event_type::EventType, post_event::PostEvent, user::User},
+};
+
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 40;
+
+#[derive(Deserialize)]
+pub struct LimitQuery {
+  pub limit: Option<i64>,
+}
+
+/// How many items to show on a page, following Mastodon's defaults
+pub fn page_limit(limit: &Option<i64>) -> i64 {
+  limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+impl LimitQuery {
+  pub fn limit(&self) -> i64 {
+    page_limit(&self.limit)
+  }
+}
+
+/// Statuses are either posts or the comments made on them, which share an ID space
+pub enum StatusTarget {
+  Post(PostEvent),
+  Comment(CommentPub, PostEvent),
+}
+
+impl StatusTarget {
+  pub fn post(&self) -> &PostEvent {
+    match self {
+      StatusTarget::Post(post) => post,
+      StatusTarget::Comment(_, post) => post,
+    }
+  }
+
+  pub fn to_status(&self) -> Status {
+    match self {
+      StatusTarget::Post(post) => Status::from_post(post),
+      StatusTarget::Comment(comment, post) => Status::from_comment(comment, post),
+    }
+  }
+}
+
+/// Finds the post or comment a status ID refers to, as long as the user is allowed to see it
+pub async fn fetch_status_target(
+  posts: &PostPool,
+  comments: &CommentPool,
+  follows: &FollowPool,
+  orbit_membership: &OrbitMembershipPool,
+  id: &Uuid,
+  own_user_id: &Option<Uuid>,
+) -> Result<StatusTarget, LogicErr> {
+  if let Some(post) = get_post(id, own_user_id, posts).await? {
+    return match user_can_view_post(follows, orbit_membership, &post, own_user_id).await {
+      true => Ok(StatusTarget::Post(post)),
+      false => Err(LogicErr::MissingRecord),
+    };
+  }
+
+  let post_id = match comments.fetch_comment_post_id(id).await {
+    Some(post_id) => post_id,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  let post = match get_post(&post_id, own_user_id, posts).await? {
+    Some(post) => post,
+    None => return Err(LogicErr::MissingRecord),
+  };
+
+  if !user_can_view_post(follows, orbit_membership, &post, own_user_id).await {
+    return Err(LogicErr::MissingRecord);
+  }
+
+  let comment = get_comment(comments, &post_id, id, own_user_id).await?;
+  Ok(StatusTarget::Comment(comment, post))
+}
+
+/// Shows a feed event as a status. Boosts are shown as shared by whoever boosted the post, who has to be looked up as
+/// events only carry their fediverse ID.
+pub async fn render_status(users: &UserPool, post: &PostEvent) -> Status {
+  if post.event_type != EventType::Boost {
+    return Status::from_post(post);
+  }
+
+  match users.fetch_by_fediverse_id(&post.event_user_fediverse_id).await {
+    Ok(Some(booster)) => Status::from_boost(post, Account::from_user(&booster, &AccountCounts::default())),
+    _ => Status::from_post(post),
+  }
+}
+
+pub async fn render_statuses(users: &UserPool, posts: &[PostEvent]) -> Vec<Status> {
+  let mut statuses = vec![];
+  for post in posts {
+    statuses.push(render_status(users, post).await);
+  }
+
+  statuses
+}
+
+pub async fn account_counts(users: &UserPool, posts: &PostPool, user_id: &Uuid) -> Result<AccountCounts, LogicErr> {
+  Ok(AccountCounts {
+    followers: users.fetch_followers_count(user_id).await,
+    following: users.fetch_following_count(user_id).await,
+    statuses: posts.count_user_public_feed(user_id, &None).await?,
+  })
+}
+
+/// Shows a user with their follower, following and post counts, which is only worth doing for a single account
+pub async fn render_account(users: &UserPool, posts: &PostPool, user: &User) -> Result<Account, LogicErr> {
+  let counts = account_counts(users, posts, &user.user_id).await?;
+  Ok(Account::from_user(user, &counts))
+}
@@ -0,0 +1,184 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    comment_repository::CommentPool, notification_repository::NotificationPool, post_repository::PostPool,
+    session_repository::SessionPool, user_repository::UserPool,
+  },
+  helpers::{
+    auth::require_auth,
+    core::{build_api_not_found, map_api_err},
+    pagination::build_link_response,
+  },
+  logic::{
+    notification::{clear_notifications, dismiss_notification, get_notifications},
+    post::get_post,
+  },
+  mastodon::{
+    account::{Account, AccountCounts},
+    notification::{notification_kind, notification_types, shown_notification_types, Notification},
+    params::MastodonParams,
+    status::Status,
+  },
+  model::notification::{Notification as OrbitNotification, NotificationFilter},
+  net::jwt::JwtContext,
+};
+
+#[derive(Deserialize)]
+pub struct MastodonNotificationsQuery {
+  #[serde(default)]
+  pub types: Vec<String>,
+  #[serde(default)]
+  pub exclude_types: Vec<String>,
+  pub max_id: Option<Uuid>,
+  pub since_id: Option<Uuid>,
+  pub min_id: Option<Uuid>,
+  pub limit: Option<i64>,
+}
+
+/// Shows a notification the way Mastodon would, or nothing for the kinds it has no counterpart for
+async fn render_notification(
+  users: &UserPool,
+  posts: &PostPool,
+  comments: &CommentPool,
+  notification: &OrbitNotification,
+) -> Option<Notification> {
+  let kind = notification_kind(&notification.notification_type)?;
+  let source = users.fetch_by_id(&notification.source_user_id).await.ok()?;
+  let own_user_id = Some(notification.user_id);
+
+  let post = match &notification.post_id {
+    Some(post_id) => get_post(post_id, &own_user_id, posts).await.ok().flatten(),
+    None => None,
+  };
+
+  let status = match (&post, &notification.comment_id) {
+    (Some(post), Some(comment_id)) => comments
+      .fetch_comment(&post.post_id, comment_id, &own_user_id)
+      .await
+      .map(|comment| Status::from_comment(&comment, post)),
+    (Some(post), None) => Some(Status::from_post(post)),
+    (None, _) => None,
+  };
+
+  Some(Notification {
+    id: notification.notification_id,
+    kind: kind.to_string(),
+    created_at: notification.created_at,
+    account: Account::from_user(&source, &AccountCounts::default()),
+    status,
+  })
+}
+
+pub async fn api_mastodon_get_notifications(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  query: MastodonParams<MastodonNotificationsQuery>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let types = match query.types.is_empty() {
+    true => shown_notification_types(),
+    false => query.types.iter().flat_map(|kind| notification_types(kind)).collect(),
+  };
+
+  // Only types with no counterpart here were asked for, so there's nothing to show
+  if types.is_empty() {
+    return HttpResponse::Ok().json(Vec::<Notification>::new());
+  }
+
+  let filter = NotificationFilter {
+    types,
+    exclude_types: query
+      .exclude_types
+      .iter()
+      .flat_map(|kind| notification_types(kind))
+      .collect(),
+    unread_only: false,
+    max_id: query.max_id,
+    since_id: query.min_id.or(query.since_id),
+  };
+
+  let page = match get_notifications(&notifications, &props.uid, &filter, query.limit).await {
+    Ok(page) => page,
+    Err(err) => return map_api_err(err),
+  };
+
+  let mut rendered = vec![];
+  for notification in &page {
+    if let Some(notification) = render_notification(&users, &posts, &comments, notification).await {
+      rendered.push(notification);
+    }
+  }
+
+  build_link_response(&req, rendered, |n| n.id, false)
+}
+
+pub async fn api_mastodon_get_notification(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  notification_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let notification = match notifications.fetch_notification(&props.uid, &notification_id).await {
+    Ok(Some(notification)) => notification,
+    Ok(None) => return build_api_not_found(notification_id.to_string()),
+    Err(err) => return map_api_err(err),
+  };
+
+  match render_notification(&users, &posts, &comments, &notification).await {
+    Some(notification) => HttpResponse::Ok().json(notification),
+    None => build_api_not_found(notification_id.to_string()),
+  }
+}
+
+pub async fn api_mastodon_clear_notifications(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match clear_notifications(&notifications, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().json(serde_json::json!({})),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_dismiss_notification(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  notification_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match dismiss_notification(&notifications, &props.uid, &notification_id).await {
+    Ok(_) => HttpResponse::Ok().json(serde_json::json!({})),
+    Err(err) => map_api_err(err),
+  }
+}
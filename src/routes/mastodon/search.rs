@@ -0,0 +1,163 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    comment_repository::CommentPool, domain_policy_repository::DomainPolicyPool, follow_repository::FollowPool,
+    job_repository::JobPool, notification_repository::NotificationPool,
+    orbit_membership_repository::OrbitMembershipPool, orbit_repository::OrbitPool,
+    post_attachment_repository::PostAttachmentPool, post_mention_repository::PostMentionPool,
+    post_repository::PostPool, search_repository::SearchPool, session_repository::SessionPool, tag_repository::TagPool,
+    user_repository::UserPool,
+  },
+  federation::{activitypub::resolve::federate_resolve_uri, domain_policy::sync_domain_policies},
+  helpers::{auth::query_auth, core::map_api_err},
+  logic::{
+    post::get_post,
+    search::{load_resolved_object, search},
+  },
+  mastodon::{
+    account::{Account, AccountCounts},
+    params::MastodonParams,
+    search::SearchResults,
+    status::Status,
+    tag::Tag,
+  },
+  model::{search_result::SearchResult, search_result_type::SearchResultType},
+  net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
+};
+
+use super::page_limit;
+
+#[derive(Deserialize)]
+pub struct MastodonSearchQuery {
+  pub q: String,
+  #[serde(rename = "type")]
+  pub kind: Option<String>,
+  #[serde(default)]
+  pub resolve: bool,
+  pub limit: Option<i64>,
+  pub offset: Option<i64>,
+}
+
+/// Hashtags are searched as orbits, which are shown as tags everywhere else too
+fn search_result_type(kind: &Option<String>) -> Option<SearchResultType> {
+  match kind.as_deref() {
+    Some("accounts") => Some(SearchResultType::User),
+    Some("statuses") => Some(SearchResultType::Post),
+    Some("hashtags") => Some(SearchResultType::Orbit),
+    _ => None,
+  }
+}
+
+async fn build_search_results(
+  posts: &PostPool,
+  own_user_id: &Option<Uuid>,
+  results: Vec<SearchResult>,
+) -> SearchResults {
+  let mut search_results = SearchResults::default();
+
+  for result in results {
+    match result {
+      SearchResult::User(user) => search_results
+        .accounts
+        .push(Account::from_account_pub(&user, &AccountCounts::default())),
+      SearchResult::Orbit(orbit) => search_results.hashtags.push(Tag::from_orbit(&orbit.shortcode)),
+      SearchResult::Post(post) => search_results.statuses.push(Status::from_post(&post)),
+      SearchResult::Comment(comment) => {
+        if let Ok(Some(post)) = get_post(&comment.post_id, own_user_id, posts).await {
+          search_results.statuses.push(Status::from_comment(&comment, &post));
+        }
+      }
+    }
+  }
+
+  search_results
+}
+
+pub async fn api_mastodon_search(
+  query: MastodonParams<MastodonSearchQuery>,
+  sessions: web::Data<SessionPool>,
+  searches: web::Data<SearchPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  users: web::Data<UserPool>,
+  orbits: web::Data<OrbitPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  post_mentions: web::Data<PostMentionPool>,
+  tags: web::Data<TagPool>,
+  post_attachments: web::Data<PostAttachmentPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  domain_policies: web::Data<DomainPolicyPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let own_user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+  let term = query.q.trim();
+
+  // Fetching remote objects on someone's behalf is only done for signed in users
+  if let (true, Some(resolving_user_id)) = (query.resolve && term.starts_with("https://"), own_user_id) {
+    sync_domain_policies(&domain_policies).await;
+
+    let resolved = match federate_resolve_uri(
+      term,
+      &users,
+      &posts,
+      &post_mentions,
+      &tags,
+      &jobs,
+      &post_attachments,
+      &orbits,
+      &notifications,
+      &pubsub,
+      &queue,
+    )
+    .await
+    {
+      Ok(resolved) => resolved,
+      Err(err) => return map_api_err(err),
+    };
+
+    let result = match load_resolved_object(
+      resolved,
+      &posts,
+      &follows,
+      &orbit_membership,
+      &jobs,
+      &queue,
+      &resolving_user_id,
+    )
+    .await
+    {
+      Ok(result) => result,
+      Err(err) => return map_api_err(err),
+    };
+
+    let results = build_search_results(&posts, &own_user_id, result.into_iter().collect()).await;
+    return HttpResponse::Ok().json(results);
+  }
+
+  match search(
+    &searches,
+    &posts,
+    &comments,
+    &users,
+    &orbits,
+    term,
+    &own_user_id,
+    &search_result_type(&query.kind),
+    page_limit(&query.limit),
+    query.offset.unwrap_or(0).max(0),
+  )
+  .await
+  {
+    Ok(results) => HttpResponse::Ok().json(build_search_results(&posts, &own_user_id, results).await),
+    Err(err) => map_api_err(err),
+  }
+}
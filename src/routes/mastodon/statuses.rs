@@ -0,0 +1,520 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    account_restriction_repository::AccountRestrictionPool, comment_repository::CommentPool,
+    follow_repository::FollowPool, job_repository::JobPool, like_repository::LikePool,
+    notification_repository::NotificationPool, orbit_membership_repository::OrbitMembershipPool,
+    orbit_moderation_repository::OrbitModerationPool, post_attachment_repository::PostAttachmentPool,
+    post_mention_repository::PostMentionPool, post_repository::PostPool, session_repository::SessionPool,
+    tag_repository::TagPool, tombstone_repository::TombstonePool, user_repository::UserPool,
+  },
+  helpers::{
+    auth::{query_auth, require_auth},
+    core::{build_api_err, map_api_err},
+  },
+  logic::{
+    comment::{create_comment, create_comment_like, delete_comment, delete_comment_like, get_comments},
+    like::{create_like, delete_like},
+    post::{
+      attach_post_media, boost_post, create_post, delete_post, get_post, unboost_post, validate_post_media,
+      CreatePostResult, NewPostRequest,
+    },
+    LogicErr,
+  },
+  mastodon::{
+    account::{Account, AccountCounts},
+    instance::MAX_STATUS_MEDIA,
+    params::MastodonParams,
+    status::{Context, Status, StatusVisibility},
+  },
+  model::page_cursor::PageCursor,
+  net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
+  work_queue::queue::Queue,
+};
+
+use super::{fetch_status_target, StatusTarget};
+
+/// The most comments shown below a post in its context, which isn't paged
+const MAX_CONTEXT_COMMENTS: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct NewStatusRequest {
+  #[serde(default)]
+  pub status: String,
+  #[serde(default)]
+  pub media_ids: Vec<Uuid>,
+  pub in_reply_to_id: Option<Uuid>,
+  pub spoiler_text: Option<String>,
+  pub visibility: Option<StatusVisibility>,
+}
+
+/// Replies are made as comments on the post being replied to, or on the post a replied to comment belongs to
+async fn create_reply(
+  posts: &PostPool,
+  comments: &CommentPool,
+  follows: &FollowPool,
+  orbit_moderation: &OrbitModerationPool,
+  orbit_membership: &OrbitMembershipPool,
+  jobs: &JobPool,
+  queue: &Queue,
+  in_reply_to_id: &Uuid,
+  user_id: &Uuid,
+  content_md: &str,
+) -> Result<Status, LogicErr> {
+  let target = fetch_status_target(
+    posts,
+    comments,
+    follows,
+    orbit_membership,
+    in_reply_to_id,
+    &Some(*user_id),
+  )
+  .await?;
+  let post = target.post();
+
+  let comment = create_comment(
+    posts,
+    follows,
+    comments,
+    orbit_moderation,
+    orbit_membership,
+    jobs,
+    queue,
+    &post.post_id,
+    user_id,
+    content_md,
+  )
+  .await?;
+
+  Ok(Status::from_comment(&comment, post))
+}
+
+pub async fn api_mastodon_create_status(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  users: web::Data<UserPool>,
+  post_mentions: web::Data<PostMentionPool>,
+  tags: web::Data<TagPool>,
+  account_restrictions: web::Data<AccountRestrictionPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
+  (comments, follows, post_attachments): (
+    web::Data<CommentPool>,
+    web::Data<FollowPool>,
+    web::Data<PostAttachmentPool>,
+  ),
+  params: MastodonParams<NewStatusRequest>,
+  jwt: web::ReqData<JwtContext>,
+  queue: web::Data<Queue>,
+  jobs: web::Data<JobPool>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  if params.status.trim().is_empty() && params.media_ids.is_empty() {
+    return build_api_err(400, "Statuses need text or media".to_string(), None);
+  }
+
+  if params.media_ids.len() > MAX_STATUS_MEDIA {
+    return build_api_err(
+      400,
+      format!("Statuses can have at most {} images", MAX_STATUS_MEDIA),
+      None,
+    );
+  }
+
+  if let Some(in_reply_to_id) = &params.in_reply_to_id {
+    if !params.media_ids.is_empty() {
+      return build_api_err(400, "Replies can't have media".to_string(), None);
+    }
+
+    return match create_reply(
+      &posts,
+      &comments,
+      &follows,
+      &orbit_moderation,
+      &orbit_membership,
+      &jobs,
+      &queue,
+      in_reply_to_id,
+      &props.uid,
+      &params.status,
+    )
+    .await
+    {
+      Ok(status) => HttpResponse::Ok().json(status),
+      Err(err) => map_api_err(err),
+    };
+  }
+
+  let visibility = match params.visibility.unwrap_or(StatusVisibility::Public).access_type() {
+    Some(visibility) => visibility,
+    None => return build_api_err(400, "Direct messages aren't supported".to_string(), None),
+  };
+
+  if !params.media_ids.is_empty() {
+    if let Err(err) = validate_post_media(&post_attachments, &props.uid, &params.media_ids).await {
+      return map_api_err(err);
+    }
+  }
+
+  let req = NewPostRequest {
+    title: params.spoiler_text.clone().filter(|text| !text.trim().is_empty()),
+    content_md: params.status.clone(),
+    visibility,
+    orbit_id: None,
+    attachment_count: params.media_ids.len() as i64,
+  };
+
+  let post_id = match create_post(
+    &posts,
+    &users,
+    &post_mentions,
+    &tags,
+    &account_restrictions,
+    &orbit_moderation,
+    &orbit_membership,
+    &notifications,
+    &pubsub,
+    &jobs,
+    &queue,
+    &req,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(CreatePostResult::JobQueued { post_id, .. }) => post_id,
+    Ok(CreatePostResult::WaitingForImages(post_id)) => {
+      if let Err(err) = attach_post_media(
+        &posts,
+        &jobs,
+        &post_attachments,
+        &queue,
+        &post_id,
+        &props.uid,
+        &params.media_ids,
+      )
+      .await
+      {
+        // The post hasn't reached any feeds or been federated yet, and everything else about it goes with its row
+        if let Err(delete_err) = posts.delete_post(&post_id, &props.uid).await {
+          log::warn!(
+            "Failed to remove post {} after attaching media failed: {}",
+            post_id,
+            delete_err
+          );
+        }
+
+        return map_api_err(err);
+      }
+
+      post_id
+    }
+    Err(err) => return map_api_err(err),
+  };
+
+  match get_post(&post_id, &Some(props.uid), &posts).await {
+    Ok(Some(post)) => HttpResponse::Ok().json(Status::from_post(&post)),
+    Ok(None) => map_api_err(LogicErr::MissingRecord),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_get_status(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let own_user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &own_user_id).await {
+    Ok(target) => HttpResponse::Ok().json(target.to_status()),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_delete_status(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  tombstones: web::Data<TombstonePool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let target = match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &Some(props.uid)).await {
+    Ok(target) => target,
+    Err(err) => return map_api_err(err),
+  };
+
+  // Clients show what was deleted so that it can be drafted again
+  let result = match &target {
+    StatusTarget::Post(post) => delete_post(&posts, &jobs, &tombstones, &queue, &post.post_id, &props.uid).await,
+    StatusTarget::Comment(comment, post) => {
+      delete_comment(&comments, &jobs, &queue, &post.post_id, &comment.comment_id, &props.uid).await
+    }
+  };
+
+  match result {
+    Ok(_) => HttpResponse::Ok().json(target.to_status()),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_get_status_context(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let own_user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  let target = match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &own_user_id).await {
+    Ok(target) => target,
+    Err(err) => return map_api_err(err),
+  };
+
+  // Threads are only ever a post and the comments made on it
+  let context = match &target {
+    StatusTarget::Post(post) => {
+      let post_comments = match get_comments(
+        &comments,
+        &post.post_id,
+        &own_user_id,
+        &PageCursor::default(),
+        MAX_CONTEXT_COMMENTS,
+        0,
+      )
+      .await
+      {
+        Ok(post_comments) => post_comments,
+        Err(err) => return map_api_err(err),
+      };
+
+      Context {
+        ancestors: vec![],
+        descendants: post_comments
+          .iter()
+          .map(|comment| Status::from_comment(comment, post))
+          .collect(),
+      }
+    }
+    StatusTarget::Comment(_, post) => Context {
+      ancestors: vec![Status::from_post(post)],
+      descendants: vec![],
+    },
+  };
+
+  HttpResponse::Ok().json(context)
+}
+
+pub async fn api_mastodon_favourite_status(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  likes: web::Data<LikePool>,
+  users: web::Data<UserPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let own_user_id = Some(props.uid);
+  let target = match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &own_user_id).await {
+    Ok(target) => target,
+    Err(err) => return map_api_err(err),
+  };
+
+  let result = match &target {
+    StatusTarget::Post(post) => create_like(
+      &posts,
+      &follows,
+      &likes,
+      &users,
+      &jobs,
+      &notifications,
+      &pubsub,
+      &queue,
+      &post.post_id,
+      &props.uid,
+    )
+    .await
+    .map(|_| ()),
+    StatusTarget::Comment(comment, post) => {
+      create_comment_like(
+        &posts,
+        &follows,
+        &comments,
+        &users,
+        &jobs,
+        &notifications,
+        &pubsub,
+        &queue,
+        &post.post_id,
+        &comment.comment_id,
+        &props.uid,
+      )
+      .await
+    }
+  };
+
+  if let Err(err) = result {
+    return map_api_err(err);
+  }
+
+  match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &own_user_id).await {
+    Ok(target) => HttpResponse::Ok().json(target.to_status()),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_unfavourite_status(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  likes: web::Data<LikePool>,
+  users: web::Data<UserPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let own_user_id = Some(props.uid);
+  let target = match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &own_user_id).await {
+    Ok(target) => target,
+    Err(err) => return map_api_err(err),
+  };
+
+  let result = match &target {
+    StatusTarget::Post(post) => delete_like(&posts, &likes, &users, &jobs, &queue, &post.post_id, &props.uid).await,
+    StatusTarget::Comment(comment, post) => {
+      delete_comment_like(
+        &comments,
+        &users,
+        &jobs,
+        &queue,
+        &post.post_id,
+        &comment.comment_id,
+        &props.uid,
+      )
+      .await
+    }
+  };
+
+  if let Err(err) = result {
+    return map_api_err(err);
+  }
+
+  match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &own_user_id).await {
+    Ok(target) => HttpResponse::Ok().json(target.to_status()),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_reblog_status(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  users: web::Data<UserPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let post = match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &Some(props.uid)).await {
+    Ok(StatusTarget::Post(post)) => post,
+    Ok(StatusTarget::Comment(..)) => return build_api_err(400, "Comments can't be boosted".to_string(), None),
+    Err(err) => return map_api_err(err),
+  };
+
+  let booster = match users.fetch_by_id(&props.uid).await {
+    Ok(user) => user,
+    Err(err) => return map_api_err(err),
+  };
+
+  // The boost is made by a job, so the status returned is what it will look like once that's done
+  match boost_post(&jobs, &queue, &post.post_id, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().json(Status {
+      reblogged: Some(true),
+      ..Status::from_boost(&post, Account::from_user(&booster, &AccountCounts::default()))
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_unreblog_status(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let post = match fetch_status_target(&posts, &comments, &follows, &orbit_membership, &id, &Some(props.uid)).await {
+    Ok(StatusTarget::Post(post)) => post,
+    Ok(StatusTarget::Comment(..)) => return build_api_err(400, "Comments can't be boosted".to_string(), None),
+    Err(err) => return map_api_err(err),
+  };
+
+  match unboost_post(&jobs, &queue, &post.post_id, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().json(Status {
+      reblogged: Some(false),
+      ..Status::from_post(&post)
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
@@ -0,0 +1,102 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+  db::{post_repository::PostPool, session_repository::SessionPool, user_repository::UserPool},
+  helpers::{
+    auth::{query_auth, require_auth},
+    core::map_api_err,
+    pagination::build_link_response,
+  },
+  logic::{
+    post::{get_global_posts, get_user_posts},
+    tag::get_tag_posts,
+    LogicErr,
+  },
+  model::{page_cursor::PageCursor, post_event::PostEvent},
+  net::jwt::JwtContext,
+};
+
+use super::{render_statuses, LimitQuery};
+
+async fn build_timeline_response(
+  req: &HttpRequest,
+  users: &UserPool,
+  posts: Result<Vec<PostEvent>, LogicErr>,
+) -> HttpResponse {
+  match posts {
+    Ok(posts) => build_link_response(req, render_statuses(users, &posts).await, |s| s.id, false),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mastodon_home_timeline(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  query: web::Query<LimitQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let posts = get_user_posts(&props.uid, &cursor, query.limit(), 0, &posts).await;
+  build_timeline_response(&req, &users, posts).await
+}
+
+pub async fn api_mastodon_public_timeline(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  query: web::Query<LimitQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let own_user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  let posts = get_global_posts(&own_user_id, &cursor, query.limit(), 0, &posts).await;
+  build_timeline_response(&req, &users, posts).await
+}
+
+pub async fn api_mastodon_tag_timeline(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  tag: web::Path<String>,
+  query: web::Query<LimitQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let own_user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  let posts = get_tag_posts(&tag, &own_user_id, &cursor, query.limit(), 0, &posts).await;
+  build_timeline_response(&req, &users, posts).await
+}
+
+/// Lists are the orbits a user has joined, so a list's timeline is the orbit's feed
+pub async fn api_mastodon_list_timeline(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  orbit_id: web::Path<Uuid>,
+  query: web::Query<LimitQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let posts = posts
+    .fetch_global_federated_orbit_feed(&orbit_id, &Some(props.uid), &cursor, query.limit(), 0)
+    .await;
+  build_timeline_response(&req, &users, posts).await
+}
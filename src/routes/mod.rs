@@ -1,17 +1,27 @@
 pub mod activitypub;
+pub mod admin;
 pub mod apps;
+pub mod block;
 pub mod comment;
 pub mod follow;
 pub mod host_meta;
 pub mod job;
 pub mod like;
+pub mod mastodon;
+pub mod mute;
 pub mod nodeinfo;
+pub mod notification;
 pub mod oauth;
 pub mod orbit;
+pub mod orbit_membership;
+pub mod orbit_moderation;
 pub mod post;
 pub mod public;
 pub mod redirect;
+pub mod report;
 pub mod search;
 pub mod status;
+pub mod streaming;
+pub mod tag;
 pub mod user;
 pub mod webfinger;
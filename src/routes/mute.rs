@@ -0,0 +1,210 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    mute_repository::MutePool, orbit_repository::OrbitPool, session_repository::SessionPool, user_repository::UserPool,
+  },
+  helpers::{auth::require_auth, core::map_api_err},
+  logic::mute::{
+    create_keyword_filter, delete_keyword_filter, get_keyword_filters, get_orbit_mutes, get_user_mutes, mute_orbit,
+    mute_user, unmute_orbit, unmute_user,
+  },
+  model::response::{ListResponse, ObjectResponse},
+  net::jwt::JwtContext,
+};
+
+#[derive(Deserialize)]
+pub struct UserMuteRequest {
+  #[serde(default)]
+  pub boosts_only: bool,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub struct KeywordFilterRequest {
+  pub phrase: String,
+  #[serde(default)]
+  pub is_regex: bool,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub async fn api_get_user_mutes(
+  sessions: web::Data<SessionPool>,
+  mutes: web::Data<MutePool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_user_mutes(&mutes, &props.uid).await {
+    Ok(user_mutes) => HttpResponse::Ok().json(ListResponse {
+      total_items: user_mutes.len() as i64,
+      total_pages: 1,
+      page: 0,
+      data: user_mutes,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mute_user(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  mutes: web::Data<MutePool>,
+  user_handle: web::Path<String>,
+  req: web::Json<UserMuteRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match mute_user(
+    &users,
+    &mutes,
+    &user_handle,
+    &props.uid,
+    req.boosts_only,
+    &req.expires_at,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Created().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_unmute_user(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  mutes: web::Data<MutePool>,
+  user_handle: web::Path<String>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match unmute_user(&users, &mutes, &user_handle, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_get_orbit_mutes(
+  sessions: web::Data<SessionPool>,
+  mutes: web::Data<MutePool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_orbit_mutes(&mutes, &props.uid).await {
+    Ok(orbit_mutes) => HttpResponse::Ok().json(ListResponse {
+      total_items: orbit_mutes.len() as i64,
+      total_pages: 1,
+      page: 0,
+      data: orbit_mutes,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mute_orbit(
+  sessions: web::Data<SessionPool>,
+  orbits: web::Data<OrbitPool>,
+  mutes: web::Data<MutePool>,
+  orbit_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match mute_orbit(&orbits, &mutes, &orbit_id, &props.uid).await {
+    Ok(_) => HttpResponse::Created().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_unmute_orbit(
+  sessions: web::Data<SessionPool>,
+  mutes: web::Data<MutePool>,
+  orbit_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match unmute_orbit(&mutes, &orbit_id, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_get_keyword_filters(
+  sessions: web::Data<SessionPool>,
+  mutes: web::Data<MutePool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_keyword_filters(&mutes, &props.uid).await {
+    Ok(filters) => HttpResponse::Ok().json(ListResponse {
+      total_items: filters.len() as i64,
+      total_pages: 1,
+      page: 0,
+      data: filters,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_create_keyword_filter(
+  sessions: web::Data<SessionPool>,
+  mutes: web::Data<MutePool>,
+  req: web::Json<KeywordFilterRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match create_keyword_filter(&mutes, &props.uid, &req.phrase, req.is_regex, &req.expires_at).await {
+    Ok(filter) => HttpResponse::Created().json(ObjectResponse { data: filter }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_delete_keyword_filter(
+  sessions: web::Data<SessionPool>,
+  mutes: web::Data<MutePool>,
+  keyword_filter_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match delete_keyword_filter(&mutes, &props.uid, &keyword_filter_id).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
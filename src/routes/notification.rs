@@ -0,0 +1,196 @@
+use std::str::FromStr;
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{notification_repository::NotificationPool, session_repository::SessionPool},
+  helpers::{auth::require_auth, core::map_api_err},
+  logic::{
+    notification::{
+      clear_notifications, dismiss_notification, get_notifications, get_unread_notifications_count,
+      group_notifications, mark_notification_read, mark_notifications_read,
+    },
+    LogicErr,
+  },
+  model::{
+    notification::NotificationFilter,
+    notification_type::NotificationType,
+    response::{CursorResponse, ObjectResponse},
+  },
+  net::jwt::JwtContext,
+};
+
+#[derive(Deserialize)]
+pub struct NotificationsQuery {
+  /// Comma separated list of notification types to include
+  pub types: Option<String>,
+  /// Comma separated list of notification types to leave out
+  pub exclude_types: Option<String>,
+  #[serde(default)]
+  pub unread_only: bool,
+  pub max_id: Option<Uuid>,
+  pub since_id: Option<Uuid>,
+  pub limit: Option<i64>,
+  #[serde(default)]
+  pub grouped: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MarkNotificationsReadRequest {
+  pub max_id: Option<Uuid>,
+}
+
+fn parse_notification_types(types: &Option<String>) -> Result<Vec<NotificationType>, LogicErr> {
+  let types = match types {
+    Some(types) => types,
+    None => return Ok(vec![]),
+  };
+
+  types
+    .split(',')
+    .map(|t| t.trim())
+    .filter(|t| !t.is_empty())
+    .map(|t| {
+      NotificationType::from_str(t).map_err(|_| LogicErr::InvalidOperation(format!("Unknown notification type: {}", t)))
+    })
+    .collect()
+}
+
+pub async fn api_get_notifications(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  query: web::Query<NotificationsQuery>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let filter = match (
+    parse_notification_types(&query.types),
+    parse_notification_types(&query.exclude_types),
+  ) {
+    (Ok(types), Ok(exclude_types)) => NotificationFilter {
+      types,
+      exclude_types,
+      unread_only: query.unread_only,
+      max_id: query.max_id,
+      since_id: query.since_id,
+    },
+    (Err(err), _) | (_, Err(err)) => return map_api_err(err),
+  };
+
+  let notifications = match get_notifications(&notifications, &props.uid, &filter, query.limit).await {
+    Ok(notifications) => notifications,
+    Err(err) => return map_api_err(err),
+  };
+
+  let next_max_id = notifications.last().map(|n| n.notification_id);
+  let prev_since_id = notifications.first().map(|n| n.notification_id);
+
+  if query.grouped {
+    HttpResponse::Ok().json(CursorResponse {
+      data: group_notifications(notifications),
+      next_max_id,
+      prev_since_id,
+      next_min_id: None,
+      total_items: None,
+    })
+  } else {
+    HttpResponse::Ok().json(CursorResponse {
+      data: notifications,
+      next_max_id,
+      prev_since_id,
+      next_min_id: None,
+      total_items: None,
+    })
+  }
+}
+
+pub async fn api_get_unread_notifications_count(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_unread_notifications_count(&notifications, &props.uid).await {
+    Ok(count) => HttpResponse::Ok().json(ObjectResponse { data: count }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mark_notifications_read(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  req: Option<web::Json<MarkNotificationsReadRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let max_id = req.and_then(|req| req.max_id);
+
+  match mark_notifications_read(&notifications, &props.uid, &max_id).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_mark_notification_read(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  notification_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match mark_notification_read(&notifications, &props.uid, &notification_id).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_clear_notifications(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match clear_notifications(&notifications, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_dismiss_notification(
+  sessions: web::Data<SessionPool>,
+  notifications: web::Data<NotificationPool>,
+  notification_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match dismiss_notification(&notifications, &props.uid, &notification_id).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
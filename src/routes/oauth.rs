@@ -1,11 +1,14 @@
-use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use actix_web::{web, Either, HttpRequest, HttpResponse, Responder};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 use uuid::Uuid;
 
 use crate::{
-  db::{app_repository::AppPool, session_repository::SessionPool, user_repository::UserPool},
+  db::{
+    app_repository::AppPool, authorization_code_repository::AuthorizationCodePool, session_repository::SessionPool,
+    user_repository::UserPool,
+  },
   helpers::{
     api::{app_is_blessed, validate_referer_redirect_uris},
     auth::require_auth,
@@ -13,9 +16,11 @@ use crate::{
     html::{build_orbit_name, handle_oauth_app_body, handle_oauth_app_err, oauth_app_unwrap_result},
   },
   logic::{
+    app::{issue_authorization_code, normalize_scopes, redeem_authorization_code, scopes_allowed},
     user::{authorize_user, register_user},
     LogicErr,
   },
+  model::app::App,
   net::{
     jwt::{JwtContext, JwtFactory},
     templates::HANDLEBARS,
@@ -52,7 +57,6 @@ pub struct OAuthAuthorizeQuery {
   pub response_type: OAuthAuthorizeResponseType,
   pub client_id: String,
   pub redirect_uri: String,
-  // TODO: Support scopes when we have permission controls
   #[serde(skip_serializing_if = "Option::is_none")]
   pub scope: Option<String>,
   pub request_type: Option<OAuthAuthorizeRequestType>,
@@ -79,6 +83,13 @@ struct OAuthAuthorizeData<'a> {
   pub orbit_name: &'a str,
 }
 
+#[derive(Debug, Serialize)]
+struct OAuthAuthorizeCodeData<'a> {
+  pub app_name: &'a str,
+  pub code: &'a str,
+  pub orbit_name: &'a str,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OAuthTokenRequest {
   #[serde(skip_serializing_if = "Option::is_none")]
@@ -104,6 +115,24 @@ pub struct OAuthTokenResponse {
   pub refresh_expires_at: i64,
 }
 
+/// Apps that can't receive a redirect, e.g. command line tools, ask for the code to be shown to the user instead
+const OOB_REDIRECT_URI: &str = "urn:ietf:wg:oauth:2.0:oob";
+
+/// The scopes an app is granted, which are the ones it asked for or everything it registered for if it didn't ask
+fn granted_scopes(app: &App, requested: &Option<String>) -> Result<String, LogicErr> {
+  let requested = match requested {
+    Some(requested) => normalize_scopes(requested)?,
+    None => return Ok(app.scopes.clone()),
+  };
+
+  match scopes_allowed(&requested, &app.scopes) {
+    true => Ok(requested),
+    false => Err(LogicErr::InvalidOperation(
+      "The requested scopes were not registered for this application".to_string(),
+    )),
+  }
+}
+
 pub async fn api_oauth_authorize(
   apps: web::Data<AppPool>,
   query: web::Query<OAuthAuthorizeQuery>,
@@ -125,18 +154,22 @@ pub async fn api_oauth_authorize(
         );
       }
 
-      if app.redirect_uri != query.redirect_uri {
+      if !app.allows_redirect_uri(&query.redirect_uri) {
         return handle_oauth_app_err(
           "The provided parameters do not match the parameters set for the registered appliction",
         );
       }
 
-      if !validate_referer_redirect_uris(&req, &app.redirect_uri) {
+      if !validate_referer_redirect_uris(&req, &query.redirect_uri) {
         return handle_oauth_app_err(
           "The provided parameters do not match the parameters set for the registered appliction",
         );
       }
 
+      if granted_scopes(&app, &query.scope).is_err() {
+        return handle_oauth_app_err("The requested scopes are not allowed for this application");
+      }
+
       let body = match HANDLEBARS.render(
         "oauth_authorize",
         &OAuthAuthorizeData {
@@ -180,6 +213,7 @@ pub async fn api_oauth_authorize(
 pub async fn api_oauth_authorize_post(
   apps: web::Data<AppPool>,
   users: web::Data<UserPool>,
+  codes: web::Data<AuthorizationCodePool>,
   query: web::Query<OAuthAuthorizeQuery>,
   req: web::Form<OAuthAuthorizeRequest>,
   web_req: HttpRequest,
@@ -198,17 +232,22 @@ pub async fn api_oauth_authorize_post(
     );
   }
 
-  if app.redirect_uri != query.redirect_uri {
+  if !app.allows_redirect_uri(&query.redirect_uri) {
     return handle_oauth_app_err(
       "The provided parameters do not match the parameters set for the registered appliction",
     );
   }
 
+  let scopes = match granted_scopes(&app, &query.scope) {
+    Ok(scopes) => scopes,
+    Err(_) => return handle_oauth_app_err("The requested scopes are not allowed for this application"),
+  };
+
   let request_type = req.request_type.clone().unwrap_or(OAuthAuthorizeRequestType::Login);
 
-  let authorization_code = match request_type {
+  match request_type {
     OAuthAuthorizeRequestType::Login => match authorize_user(&req.username, &req.password, &users).await {
-      Ok(code) => code,
+      Ok(_) => {}
       Err(err) => match err {
         LogicErr::UnauthorizedError => {
           return handle_oauth_app_body(
@@ -227,7 +266,7 @@ pub async fn api_oauth_authorize_post(
       },
     },
     OAuthAuthorizeRequestType::Register => match register_user(&req.username, &req.password, &req.email, &users).await {
-      Ok(code) => code,
+      Ok(_) => {}
       Err(err) => match err {
         LogicErr::InvalidOperation(err) => {
           return handle_oauth_app_body(
@@ -247,6 +286,43 @@ pub async fn api_oauth_authorize_post(
     },
   };
 
+  let user = match users.fetch_by_handle(&req.username).await {
+    Ok(Some(user)) => user,
+    _ => {
+      return handle_oauth_app_body(
+        &app,
+        app_is_blessed(&web_req),
+        "Something went wrong, please try again later",
+      )
+    }
+  };
+
+  let authorization_code =
+    match issue_authorization_code(&codes, &user.user_id, &app, &query.redirect_uri, &scopes).await {
+      Ok(code) => code,
+      Err(_) => {
+        return handle_oauth_app_body(
+          &app,
+          app_is_blessed(&web_req),
+          "Something went wrong, please try again later",
+        )
+      }
+    };
+
+  if query.redirect_uri == OOB_REDIRECT_URI {
+    return match HANDLEBARS.render(
+      "oauth_authorize_code",
+      &OAuthAuthorizeCodeData {
+        app_name: &app.name,
+        code: &authorization_code,
+        orbit_name: &build_orbit_name(),
+      },
+    ) {
+      Ok(body) => HttpResponse::Ok().content_type("text/html; charset=utf-8").body(body),
+      Err(_) => HttpResponse::InternalServerError().finish(),
+    };
+  }
+
   HttpResponse::Found()
     .insert_header((
       "location",
@@ -258,10 +334,17 @@ pub async fn api_oauth_authorize_post(
 pub async fn api_oauth_token(
   apps: web::Data<AppPool>,
   users: web::Data<UserPool>,
+  codes: web::Data<AuthorizationCodePool>,
   sessions: web::Data<SessionPool>,
   session: web::ReqData<JwtContext>,
-  req: web::Form<OAuthTokenRequest>,
+  req: Either<web::Form<OAuthTokenRequest>, web::Json<OAuthTokenRequest>>,
 ) -> impl Responder {
+  // Mastodon clients send this as JSON as often as they send it as a form
+  let req = match req {
+    Either::Left(form) => form.into_inner(),
+    Either::Right(json) => json.into_inner(),
+  };
+
   let app = match oauth_app_unwrap_result(
     apps.fetch_by_client_id(&req.client_id).await,
     "This application is not configured correctly to authenticate with Orbit",
@@ -278,23 +361,20 @@ pub async fn api_oauth_token(
     return build_api_err(401, "Invalid client configuration".to_string(), None);
   }
 
-  if app.redirect_uri != req.redirect_uri {
+  if !app.allows_redirect_uri(&req.redirect_uri) {
     return build_api_err(401, "Invalid client configuration".to_string(), None);
   }
 
   match req.grant_type {
     OAuthGrantType::AuthorizationCode => {
       let code = req.code.clone().unwrap_or_default();
-      let claims = match JwtFactory::parse_jwt_props(&code) {
-        Some(claims) => claims,
-        None => return build_api_err(401, "Invalid authorization token".to_string(), None),
+      let code = match redeem_authorization_code(&codes, &code, &app, &req.redirect_uri).await {
+        Ok(code) => code,
+        Err(_) => return build_api_err(401, "Invalid authorization token".to_string(), None),
       };
 
-      let user = match users.fetch_by_handle(&claims.sub).await {
-        Ok(user) => match user {
-          Some(user) => user,
-          None => return build_api_err(401, "Invalid authorization token".to_string(), None),
-        },
+      let user = match users.fetch_by_id(&code.user_id).await {
+        Ok(user) => user,
         Err(_) => return build_api_err(401, "Invalid authorization token".to_string(), None),
       };
 
@@ -313,6 +393,7 @@ pub async fn api_oauth_token(
           &session.refresh_token,
           &session.access_expiry,
           &session.refresh_expiry,
+          &code.scopes,
         )
         .await
       {
@@ -324,7 +405,7 @@ pub async fn api_oauth_token(
         access_token: session.access_token,
         refresh_token: session.refresh_token,
         token_type: "Bearer",
-        scope: "".to_string(),
+        scope: code.scopes,
         created_at: Utc::now().timestamp(),
         expires_at: session.access_expiry.timestamp(),
         refresh_expires_at: session.refresh_expiry.timestamp(),
@@ -335,9 +416,24 @@ pub async fn api_oauth_token(
       Ok(session) => {
         let refresh_token = req.refresh_token.clone().unwrap_or_default();
 
-        match sessions.query_session_exists_for_refresh_token(&refresh_token).await {
-          true => {}
-          false => return build_api_err(401, "Invalid refresh token".to_string(), None),
+        let scope = match sessions.fetch_refresh_token_scopes(&refresh_token).await {
+          Some(scope) => scope,
+          None => return build_api_err(401, "Invalid refresh token".to_string(), None),
+        };
+
+        // A refreshed session can narrow its scopes, but never gain ones it wasn't granted
+        let scope = match &req.scope {
+          Some(requested) => match normalize_scopes(requested) {
+            Ok(requested) if scopes_allowed(&requested, &scope) => requested,
+            _ => {
+              return build_api_err(
+                400,
+                "The requested scopes were not granted to this session".to_string(),
+                None,
+              )
+            }
+          },
+          None => scope,
         };
 
         let user = match users.fetch_by_fediverse_id(&session.sub).await {
@@ -371,6 +467,7 @@ pub async fn api_oauth_token(
             &session.refresh_token,
             &session.access_expiry,
             &session.refresh_expiry,
+            &scope,
           )
           .await
         {
@@ -382,7 +479,7 @@ pub async fn api_oauth_token(
           access_token: session.access_token,
           refresh_token: session.refresh_token,
           token_type: "Bearer",
-          scope: "".to_string(),
+          scope,
           created_at: Utc::now().timestamp(),
           expires_at: session.access_expiry.timestamp(),
           refresh_expires_at: session.refresh_expiry.timestamp(),
@@ -12,9 +12,10 @@ use crate::{
   activitypub::object::ObjectType,
   cdn::cdn_store::Cdn,
   db::{
-    job_repository::JobPool, orbit_moderator_repository::OrbitModeratorPool, orbit_repository::OrbitPool,
-    session_repository::SessionPool, tombstone_repository::TombstonePool, user_orbit_repository::UserOrbitPool,
-    user_repository::UserPool,
+    job_repository::JobPool, notification_repository::NotificationPool,
+    orbit_membership_repository::OrbitMembershipPool, orbit_moderation_repository::OrbitModerationPool,
+    orbit_moderator_repository::OrbitModeratorPool, orbit_repository::OrbitPool, session_repository::SessionPool,
+    tombstone_repository::TombstonePool, user_orbit_repository::UserOrbitPool, user_repository::UserPool,
   },
   federation::activitypub::{FederateExtAction, FederateExtActorRef},
   helpers::{
@@ -23,13 +24,16 @@ use crate::{
     core::{build_api_err, build_api_not_found, map_api_err},
     math::div_up,
   },
+  logic::orbit_membership::{join_orbit, JoinOrbitResult},
   model::{
     job::{JobStatus, NewJob},
+    orbit_join_policy::OrbitJoinPolicy,
     queue_job::{QueueJob, QueueJobType},
     response::{ListResponse, ObjectResponse},
     user_account_pub::UserAccountPub,
   },
   net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
@@ -38,6 +42,7 @@ pub struct NewOrbitRequest {
   pub name: String,
   pub description_md: String,
   pub shortcode: Option<String>,
+  pub join_policy: Option<OrbitJoinPolicy>,
 }
 
 #[derive(Serialize)]
@@ -258,6 +263,7 @@ pub async fn api_create_orbit(
       &priv_key,
       &pub_key,
       &uri,
+      &req.join_policy.unwrap_or_default(),
     )
     .await
   {
@@ -322,6 +328,7 @@ pub async fn api_update_orbit(
       &orbit.avatar_uri,
       &orbit.banner_uri,
       false,
+      &req.join_policy.unwrap_or(orbit.join_policy),
     )
     .await
   {
@@ -432,6 +439,7 @@ pub async fn api_update_orbit_assets(
       &Some(avatar_uri),
       &Some(banner_uri),
       false,
+      &orbit.join_policy,
     )
     .await
   {
@@ -488,6 +496,10 @@ pub async fn api_join_orbit(
   sessions: web::Data<SessionPool>,
   user_orbits: web::Data<UserOrbitPool>,
   orbits: web::Data<OrbitPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
   jobs: web::Data<JobPool>,
   queue: web::Data<Queue>,
   orbit_id: web::Path<Uuid>,
@@ -506,6 +518,12 @@ pub async fn api_join_orbit(
     Err(err) => return build_api_err(500, err.to_string(), None),
   };
 
+  match orbit_moderation.user_is_banned(&orbit_id, &session.uid).await {
+    Ok(true) => return build_api_err(403, "banned".to_string(), None),
+    Ok(false) => {}
+    Err(err) => return build_api_err(500, err.to_string(), None),
+  }
+
   if orbit.is_external {
     let job_id = match jobs
       .create(NewJob {
@@ -533,11 +551,26 @@ pub async fn api_join_orbit(
       Ok(_) => {}
       Err(err) => return build_api_err(500, err.to_string(), None),
     }
+
+    return match user_orbits.create_user_orbit(&orbit_id, &session.uid).await {
+      Ok(_) => HttpResponse::Created().finish(),
+      Err(err) => build_api_err(500, err.to_string(), None),
+    };
   }
 
-  match user_orbits.create_user_orbit(&orbit_id, &session.uid).await {
-    Ok(_) => HttpResponse::Created().finish(),
-    Err(err) => build_api_err(500, err.to_string(), None),
+  match join_orbit(
+    &user_orbits,
+    &orbit_membership,
+    &notifications,
+    &pubsub,
+    &orbit,
+    &session.uid,
+  )
+  .await
+  {
+    Ok(JoinOrbitResult::Joined) => HttpResponse::Created().finish(),
+    Ok(JoinOrbitResult::Requested) => HttpResponse::Accepted().finish(),
+    Err(err) => map_api_err(err),
   }
 }
 
@@ -545,6 +578,7 @@ pub async fn api_leave_orbit(
   sessions: web::Data<SessionPool>,
   user_orbits: web::Data<UserOrbitPool>,
   orbits: web::Data<OrbitPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
   jobs: web::Data<JobPool>,
   queue: web::Data<Queue>,
   orbit_id: web::Path<Uuid>,
@@ -592,6 +626,12 @@ pub async fn api_leave_orbit(
     }
   }
 
+  // Leaving also withdraws a request to join that's still waiting on a moderator
+  match orbit_membership.delete_join_request(&orbit_id, &session.uid).await {
+    Ok(_) => {}
+    Err(err) => return build_api_err(500, err.to_string(), None),
+  }
+
   match user_orbits.delete_user_orbit(&orbit_id, &session.uid).await {
     Ok(_) => HttpResponse::Created().finish(),
     Err(err) => build_api_err(500, err.to_string(), None),
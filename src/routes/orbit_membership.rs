@@ -0,0 +1,201 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    job_repository::JobPool, orbit_membership_repository::OrbitMembershipPool,
+    orbit_moderator_repository::OrbitModeratorPool, session_repository::SessionPool,
+    user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+  },
+  helpers::{auth::require_auth, core::map_api_err, math::div_up},
+  logic::orbit_membership::{
+    approve_join_request, get_invites, get_join_requests, get_join_requests_count, invite_user, reject_join_request,
+    revoke_invite,
+  },
+  model::response::{ListResponse, ObjectResponse},
+  net::jwt::JwtContext,
+  work_queue::queue::Queue,
+};
+
+#[derive(Deserialize)]
+pub struct JoinRequestsQuery {
+  pub page: Option<i64>,
+  pub page_size: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct NewOrbitInviteRequest {
+  pub user_id: Uuid,
+}
+
+pub async fn api_get_orbit_join_requests(
+  sessions: web::Data<SessionPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  orbit_id: web::Path<Uuid>,
+  query: web::Query<JoinRequestsQuery>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let page = query.page.unwrap_or(0);
+  let page_size = query.page_size.unwrap_or(20);
+
+  let requests_count = match get_join_requests_count(&orbit_moderators, &orbit_membership, &orbit_id, &props.uid).await
+  {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  match get_join_requests(
+    &orbit_moderators,
+    &orbit_membership,
+    &orbit_id,
+    page_size,
+    page * page_size,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(requests) => HttpResponse::Ok().json(ListResponse {
+      data: requests,
+      page,
+      total_items: requests_count,
+      total_pages: div_up(requests_count, page_size) + 1,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_approve_orbit_join_request(
+  sessions: web::Data<SessionPool>,
+  user_orbits: web::Data<UserOrbitPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  ids: web::Path<(Uuid, Uuid)>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match approve_join_request(
+    &user_orbits,
+    &orbit_moderators,
+    &orbit_membership,
+    &jobs,
+    &queue,
+    &ids.0,
+    &ids.1,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_reject_orbit_join_request(
+  sessions: web::Data<SessionPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  ids: web::Path<(Uuid, Uuid)>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match reject_join_request(
+    &orbit_moderators,
+    &orbit_membership,
+    &jobs,
+    &queue,
+    &ids.0,
+    &ids.1,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_get_orbit_invites(
+  sessions: web::Data<SessionPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  orbit_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_invites(&orbit_moderators, &orbit_membership, &orbit_id, &props.uid).await {
+    Ok(invites) => HttpResponse::Ok().json(ObjectResponse { data: invites }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_create_orbit_invite(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  user_orbits: web::Data<UserOrbitPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  orbit_id: web::Path<Uuid>,
+  req: web::Json<NewOrbitInviteRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match invite_user(
+    &users,
+    &user_orbits,
+    &orbit_moderators,
+    &orbit_membership,
+    &orbit_id,
+    &req.user_id,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Created().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_delete_orbit_invite(
+  sessions: web::Data<SessionPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  ids: web::Path<(Uuid, Uuid)>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match revoke_invite(&orbit_moderators, &orbit_membership, &ids.0, &ids.1, &props.uid).await {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
@@ -0,0 +1,372 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    comment_repository::CommentPool, job_repository::JobPool, orbit_moderation_repository::OrbitModerationPool,
+    orbit_moderator_repository::OrbitModeratorPool, post_repository::PostPool, session_repository::SessionPool,
+    tombstone_repository::TombstonePool, user_orbit_repository::UserOrbitPool, user_repository::UserPool,
+  },
+  helpers::{auth::require_auth, core::map_api_err, math::div_up},
+  logic::orbit_moderation::{
+    ban_user, get_modlog, get_modlog_count, get_orbit_bans, lock_post, pin_post, remove_comment, remove_post,
+    unban_user,
+  },
+  model::response::{ListResponse, ObjectResponse},
+  net::jwt::JwtContext,
+  work_queue::queue::Queue,
+};
+
+#[derive(Deserialize)]
+pub struct ModerationRequest {
+  pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct NewOrbitBanRequest {
+  pub user_id: Uuid,
+  pub reason: Option<String>,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub struct ModlogQuery {
+  pub page: Option<i64>,
+  pub page_size: Option<i64>,
+}
+
+fn moderation_reason(req: &Option<web::Json<ModerationRequest>>) -> Option<String> {
+  req.as_ref().and_then(|req| req.reason.clone())
+}
+
+pub async fn api_remove_orbit_post(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  tombstones: web::Data<TombstonePool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  ids: web::Path<(Uuid, Uuid)>,
+  req: Option<web::Json<ModerationRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match remove_post(
+    &posts,
+    &tombstones,
+    &orbit_moderators,
+    &orbit_moderation,
+    &jobs,
+    &queue,
+    &ids.0,
+    &ids.1,
+    &moderation_reason(&req),
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_remove_orbit_comment(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  tombstones: web::Data<TombstonePool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  ids: web::Path<(Uuid, Uuid, Uuid)>,
+  req: Option<web::Json<ModerationRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match remove_comment(
+    &posts,
+    &comments,
+    &tombstones,
+    &orbit_moderators,
+    &orbit_moderation,
+    &jobs,
+    &queue,
+    &ids.0,
+    &ids.1,
+    &ids.2,
+    &moderation_reason(&req),
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+async fn set_orbit_post_locked(
+  sessions: &SessionPool,
+  posts: &PostPool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  ids: &(Uuid, Uuid),
+  is_locked: bool,
+  req: &Option<web::Json<ModerationRequest>>,
+  jwt: &web::ReqData<JwtContext>,
+) -> HttpResponse {
+  let props = match require_auth(jwt, sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match lock_post(
+    posts,
+    orbit_moderators,
+    orbit_moderation,
+    &ids.0,
+    &ids.1,
+    is_locked,
+    &moderation_reason(req),
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_lock_orbit_post(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  ids: web::Path<(Uuid, Uuid)>,
+  req: Option<web::Json<ModerationRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  set_orbit_post_locked(
+    &sessions,
+    &posts,
+    &orbit_moderators,
+    &orbit_moderation,
+    &ids,
+    true,
+    &req,
+    &jwt,
+  )
+  .await
+}
+
+pub async fn api_unlock_orbit_post(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  ids: web::Path<(Uuid, Uuid)>,
+  req: Option<web::Json<ModerationRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  set_orbit_post_locked(
+    &sessions,
+    &posts,
+    &orbit_moderators,
+    &orbit_moderation,
+    &ids,
+    false,
+    &req,
+    &jwt,
+  )
+  .await
+}
+
+async fn set_orbit_post_pinned(
+  sessions: &SessionPool,
+  posts: &PostPool,
+  orbit_moderators: &OrbitModeratorPool,
+  orbit_moderation: &OrbitModerationPool,
+  ids: &(Uuid, Uuid),
+  is_pinned: bool,
+  req: &Option<web::Json<ModerationRequest>>,
+  jwt: &web::ReqData<JwtContext>,
+) -> HttpResponse {
+  let props = match require_auth(jwt, sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match pin_post(
+    posts,
+    orbit_moderators,
+    orbit_moderation,
+    &ids.0,
+    &ids.1,
+    is_pinned,
+    &moderation_reason(req),
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_pin_orbit_post(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  ids: web::Path<(Uuid, Uuid)>,
+  req: Option<web::Json<ModerationRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  set_orbit_post_pinned(
+    &sessions,
+    &posts,
+    &orbit_moderators,
+    &orbit_moderation,
+    &ids,
+    true,
+    &req,
+    &jwt,
+  )
+  .await
+}
+
+pub async fn api_unpin_orbit_post(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  ids: web::Path<(Uuid, Uuid)>,
+  req: Option<web::Json<ModerationRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  set_orbit_post_pinned(
+    &sessions,
+    &posts,
+    &orbit_moderators,
+    &orbit_moderation,
+    &ids,
+    false,
+    &req,
+    &jwt,
+  )
+  .await
+}
+
+pub async fn api_get_orbit_bans(
+  sessions: web::Data<SessionPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  orbit_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match get_orbit_bans(&orbit_moderators, &orbit_moderation, &orbit_id, &props.uid).await {
+    Ok(bans) => HttpResponse::Ok().json(ObjectResponse { data: bans }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_ban_orbit_user(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  user_orbits: web::Data<UserOrbitPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  orbit_id: web::Path<Uuid>,
+  req: web::Json<NewOrbitBanRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match ban_user(
+    &users,
+    &user_orbits,
+    &orbit_moderators,
+    &orbit_moderation,
+    &orbit_id,
+    &req.user_id,
+    &req.reason,
+    &req.expires_at,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Created().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_unban_orbit_user(
+  sessions: web::Data<SessionPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  ids: web::Path<(Uuid, Uuid)>,
+  req: Option<web::Json<ModerationRequest>>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match unban_user(
+    &orbit_moderators,
+    &orbit_moderation,
+    &ids.0,
+    &ids.1,
+    &moderation_reason(&req),
+    &props.uid,
+  )
+  .await
+  {
+    Ok(_) => HttpResponse::Ok().finish(),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_get_orbit_modlog(
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  orbit_id: web::Path<Uuid>,
+  query: web::Query<ModlogQuery>,
+) -> impl Responder {
+  let page = query.page.unwrap_or(0);
+  let page_size = query.page_size.unwrap_or(20);
+
+  let modlog_count = match get_modlog_count(&orbit_moderation, &orbit_id).await {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  match get_modlog(&orbit_moderation, &orbit_id, page_size, page * page_size).await {
+    Ok(entries) => HttpResponse::Ok().json(ListResponse {
+      data: entries,
+      page,
+      total_items: modlog_count,
+      total_pages: div_up(modlog_count, page_size) + 1,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
@@ -1,33 +1,41 @@
 use actix_easy_multipart::{tempfile::Tempfile, MultipartForm};
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
   cdn::cdn_store::Cdn,
   db::{
-    follow_repository::FollowPool, job_repository::JobPool, orbit_repository::OrbitPool,
-    post_attachment_repository::PostAttachmentPool, post_repository::PostPool, session_repository::SessionPool,
+    account_restriction_repository::AccountRestrictionPool, follow_repository::FollowPool, job_repository::JobPool,
+    notification_repository::NotificationPool, orbit_membership_repository::OrbitMembershipPool,
+    orbit_moderation_repository::OrbitModerationPool, orbit_repository::OrbitPool,
+    post_attachment_repository::PostAttachmentPool, post_mention_repository::PostMentionPool,
+    post_repository::PostPool, session_repository::SessionPool, tag_repository::TagPool,
     tombstone_repository::TombstonePool, user_repository::UserPool,
   },
   helpers::{
     auth::{query_auth, require_auth},
     core::{build_api_err, build_api_not_found, map_api_err},
-    math::div_up,
+    pagination::{build_page_response, numbered_page, optional_count},
   },
-  logic::post::{
-    create_post, delete_post, get_global_posts, get_global_posts_count, get_post, get_user_friends_posts,
-    get_user_friends_posts_count, get_user_posts, get_user_posts_count, upload_post_files, CreatePostResult,
-    NewPostRequest, NewPostResponse,
+  logic::{
+    mention::{record_mentions, render_mentions, resolve_mentions},
+    post::{
+      boost_post, create_post, delete_post, get_global_posts, get_global_posts_count, get_post, get_user_friends_posts,
+      get_user_friends_posts_count, get_user_posts, get_user_posts_count, unboost_post, upload_post_files,
+      user_can_view_post, CreatePostResult, NewPostRequest, NewPostResponse,
+    },
+    tag::{parse_tags, record_tags, render_tags},
   },
   model::{
-    access_type::AccessType,
     job::JobStatus,
     job::NewJob,
+    page_cursor::PageCursor,
     queue_job::{QueueJob, QueueJobType},
-    response::{JobResponse, ListResponse, ObjectResponse},
+    response::{JobResponse, ObjectResponse},
   },
   net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
@@ -35,6 +43,9 @@ use crate::{
 pub struct PostsQuery {
   pub page: Option<i64>,
   pub page_size: Option<i64>,
+  /// Whether to count the whole feed when walking it by cursor. Numbered pages are always counted.
+  #[serde(default)]
+  pub count: bool,
 }
 
 #[derive(MultipartForm)]
@@ -56,7 +67,9 @@ pub async fn api_get_user_own_feed(
   sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
   jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
 ) -> impl Responder {
   let props = match require_auth(&jwt, &sessions).await {
     Ok(props) => props,
@@ -64,31 +77,28 @@ pub async fn api_get_user_own_feed(
   };
 
   let user_id = props.uid;
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match get_user_posts_count(&user_id, &posts).await {
+  let posts_count = match optional_count(page.is_some() || query.count, get_user_posts_count(&user_id, &posts)).await {
     Ok(count) => count,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let posts = match get_user_posts(&user_id, page_size, page * page_size, &posts).await {
+  let posts = match get_user_posts(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size, &posts).await {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  HttpResponse::Ok().json(ListResponse {
-    data: posts,
-    page,
-    total_items: posts_count,
-    total_pages: div_up(posts_count, page_size) + 1,
-  })
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
 }
 
 pub async fn api_get_user_friends_feed(
   sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
   jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
 ) -> impl Responder {
   let props = match require_auth(&jwt, &sessions).await {
     Ok(props) => props,
@@ -96,24 +106,24 @@ pub async fn api_get_user_friends_feed(
   };
 
   let user_id = props.uid;
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match get_user_friends_posts_count(&user_id, &posts).await {
+  let posts_count = match optional_count(
+    page.is_some() || query.count,
+    get_user_friends_posts_count(&user_id, &posts),
+  )
+  .await
+  {
     Ok(count) => count,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let posts = match get_user_friends_posts(&user_id, page_size, page * page_size, &posts).await {
+  let posts = match get_user_friends_posts(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size, &posts).await {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  HttpResponse::Ok().json(ListResponse {
-    data: posts,
-    page,
-    total_items: posts_count,
-    total_pages: div_up(posts_count, page_size) + 1,
-  })
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
 }
 
 pub async fn api_get_post(
@@ -121,6 +131,7 @@ pub async fn api_get_post(
   sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
   follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
   post_id: web::Path<Uuid>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -137,28 +148,9 @@ pub async fn api_get_post(
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  if post.visibility == AccessType::PublicFederated
-    || post.visibility == AccessType::PublicLocal
-    || post.visibility == AccessType::Unlisted
-  {
-    return HttpResponse::Ok().json(ObjectResponse { data: post });
-  }
-
-  match current_user_id {
-    Some(current_user_id) => {
-      if post.user_id == current_user_id {
-        return HttpResponse::Ok().json(ObjectResponse { data: post });
-      }
-
-      if post.visibility == AccessType::FollowersOnly
-        && follows.user_follows_poster(&post.post_id, &current_user_id).await
-      {
-        return HttpResponse::Ok().json(ObjectResponse { data: post });
-      }
-
-      HttpResponse::NotFound().finish()
-    }
-    None => HttpResponse::NotFound().finish(),
+  match user_can_view_post(&follows, &orbit_membership, &post, &current_user_id).await {
+    true => HttpResponse::Ok().json(ObjectResponse { data: post }),
+    false => HttpResponse::NotFound().finish(),
   }
 }
 
@@ -167,6 +159,7 @@ pub async fn api_get_user_post(
   sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
   follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
   ids: web::Path<(String, Uuid)>,
   jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
@@ -186,58 +179,50 @@ pub async fn api_get_user_post(
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  if post.visibility == AccessType::PublicFederated
-    || post.visibility == AccessType::PublicLocal
-    || post.visibility == AccessType::Unlisted
-  {
-    return HttpResponse::Ok().json(ObjectResponse { data: post });
-  }
-
-  match current_user_id {
-    Some(current_user_id) => {
-      if post.user_id == current_user_id {
-        return HttpResponse::Ok().json(ObjectResponse { data: post });
-      }
-
-      if post.visibility == AccessType::FollowersOnly
-        && follows.user_follows_poster(&post.post_id, &current_user_id).await
-      {
-        return HttpResponse::Ok().json(ObjectResponse { data: post });
-      }
-
-      HttpResponse::NotFound().finish()
-    }
-    None => HttpResponse::NotFound().finish(),
+  match user_can_view_post(&follows, &orbit_membership, &post, &current_user_id).await {
+    true => HttpResponse::Ok().json(ObjectResponse { data: post }),
+    false => HttpResponse::NotFound().finish(),
   }
 }
 
-pub async fn api_get_global_feed(posts: web::Data<PostPool>, query: web::Query<PostsQuery>) -> impl Responder {
-  let page = query.page.unwrap_or(0);
+pub async fn api_get_global_feed(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match get_global_posts_count(&posts).await {
+  let posts_count = match optional_count(page.is_some() || query.count, get_global_posts_count(&user_id, &posts)).await
+  {
     Ok(count) => count,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  let posts = match get_global_posts(page_size, page * page_size, &posts).await {
+  let posts = match get_global_posts(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size, &posts).await {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  HttpResponse::Ok().json(ListResponse {
-    data: posts,
-    page,
-    total_items: posts_count,
-    total_pages: div_up(posts_count, page_size) + 1,
-  })
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
 }
 
 pub async fn api_get_orbit_feed(
+  sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
   orbits: web::Data<OrbitPool>,
   orbit_shortcode: web::Path<String>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
 ) -> impl Responder {
+  let user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
   let orbit_id = match orbits.fetch_orbit_id_from_shortcode(&orbit_shortcode).await {
     Some(id) => id,
     None => match orbits.fetch_orbit_id_from_fediverse_id(&orbit_shortcode).await {
@@ -246,55 +231,61 @@ pub async fn api_get_orbit_feed(
     },
   };
 
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match posts.count_global_federated_orbit_feed(&orbit_id).await {
+  let posts_count = match optional_count(
+    page.is_some() || query.count,
+    posts.count_global_federated_orbit_feed(&orbit_id, &user_id),
+  )
+  .await
+  {
     Ok(count) => count,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
   let posts = match posts
-    .fetch_global_federated_orbit_feed(&orbit_id, page_size, page * page_size)
+    .fetch_global_federated_orbit_feed(&orbit_id, &user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
     .await
   {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  HttpResponse::Ok().json(ListResponse {
-    data: posts,
-    page,
-    total_items: posts_count,
-    total_pages: div_up(posts_count, page_size) + 1,
-  })
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
 }
 
 pub async fn api_get_orbit_feed_by_id(
+  sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
   orbit_id: web::Path<Uuid>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
 ) -> impl Responder {
-  let page = query.page.unwrap_or(0);
+  let user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match posts.count_global_federated_orbit_feed(&orbit_id).await {
+  let posts_count = match optional_count(
+    page.is_some() || query.count,
+    posts.count_global_federated_orbit_feed(&orbit_id, &user_id),
+  )
+  .await
+  {
     Ok(count) => count,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
   let posts = match posts
-    .fetch_global_federated_orbit_feed(&orbit_id, page_size, page * page_size)
+    .fetch_global_federated_orbit_feed(&orbit_id, &user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
     .await
   {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  HttpResponse::Ok().json(ListResponse {
-    data: posts,
-    page,
-    total_items: posts_count,
-    total_pages: div_up(posts_count, page_size) + 1,
-  })
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
 }
 
 pub async fn api_get_user_posts(
@@ -302,8 +293,10 @@ pub async fn api_get_user_posts(
   posts: web::Data<PostPool>,
   users: web::Data<UserPool>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
   handle: web::Path<String>,
   jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
 ) -> impl Responder {
   let user_id = match query_auth(&jwt, &sessions).await {
     Some(props) => Some(props.uid),
@@ -315,27 +308,27 @@ pub async fn api_get_user_posts(
     None => return HttpResponse::NotFound().finish(),
   };
 
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match posts.count_user_public_feed(&target_id, &user_id).await {
+  let posts_count = match optional_count(
+    page.is_some() || query.count,
+    posts.count_user_public_feed(&target_id, &user_id),
+  )
+  .await
+  {
     Ok(count) => count,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
   let posts = match posts
-    .fetch_user_public_feed(&target_id, &user_id, page_size, page * page_size)
+    .fetch_user_public_feed(&target_id, &user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
     .await
   {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  HttpResponse::Ok().json(ListResponse {
-    data: posts,
-    page,
-    total_items: posts_count,
-    total_pages: div_up(posts_count, page_size) + 1,
-  })
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
 }
 
 pub async fn api_get_user_liked_posts(
@@ -343,8 +336,10 @@ pub async fn api_get_user_liked_posts(
   posts: web::Data<PostPool>,
   users: web::Data<UserPool>,
   query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
   handle: web::Path<String>,
   jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
 ) -> impl Responder {
   let user_id = match query_auth(&jwt, &sessions).await {
     Some(props) => Some(props.uid),
@@ -356,32 +351,40 @@ pub async fn api_get_user_liked_posts(
     None => return HttpResponse::NotFound().finish(),
   };
 
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let posts_count = match posts.count_user_public_likes_feed(&target_id, &user_id).await {
+  let posts_count = match optional_count(
+    page.is_some() || query.count,
+    posts.count_user_public_likes_feed(&target_id, &user_id),
+  )
+  .await
+  {
     Ok(count) => count,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
   let posts = match posts
-    .fetch_user_public_likes_feed(&target_id, &user_id, page_size, page * page_size)
+    .fetch_user_public_likes_feed(&target_id, &user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
     .await
   {
     Ok(posts) => posts,
     Err(err) => return build_api_err(500, err.to_string(), Some(err.to_string())),
   };
 
-  HttpResponse::Ok().json(ListResponse {
-    data: posts,
-    page,
-    total_items: posts_count,
-    total_pages: div_up(posts_count, page_size) + 1,
-  })
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
 }
 
 pub async fn api_create_post(
   sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
+  users: web::Data<UserPool>,
+  post_mentions: web::Data<PostMentionPool>,
+  tags: web::Data<TagPool>,
+  account_restrictions: web::Data<AccountRestrictionPool>,
+  orbit_moderation: web::Data<OrbitModerationPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
   req: web::Json<NewPostRequest>,
   jwt: web::ReqData<JwtContext>,
   queue: web::Data<Queue>,
@@ -392,18 +395,39 @@ pub async fn api_create_post(
     Err(res) => return res,
   };
 
-  match create_post(&posts, &jobs, &queue, &req, &props.uid).await {
+  match create_post(
+    &posts,
+    &users,
+    &post_mentions,
+    &tags,
+    &account_restrictions,
+    &orbit_moderation,
+    &orbit_membership,
+    &notifications,
+    &pubsub,
+    &jobs,
+    &queue,
+    &req,
+    &props.uid,
+  )
+  .await
+  {
     Ok(result) => match result {
       CreatePostResult::WaitingForImages(post_id) => HttpResponse::Ok().json(NewPostResponse { id: post_id }),
-      CreatePostResult::JobQueued(job_id) => HttpResponse::Ok().json(JobResponse { job_id }),
+      CreatePostResult::JobQueued { job_id, .. } => HttpResponse::Ok().json(JobResponse { job_id }),
     },
-    Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
+    Err(err) => map_api_err(err),
   }
 }
 
 pub async fn api_update_post(
   sessions: web::Data<SessionPool>,
   posts: web::Data<PostPool>,
+  users: web::Data<UserPool>,
+  post_mentions: web::Data<PostMentionPool>,
+  tags: web::Data<TagPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
   req: web::Json<PostUpdateRequest>,
   post_id: web::Path<Uuid>,
   jwt: web::ReqData<JwtContext>,
@@ -428,15 +452,41 @@ pub async fn api_update_post(
     post.title = Some(title.to_owned());
   }
 
+  let mentions = match &req.content_md {
+    Some(content_md) => resolve_mentions(&users, content_md).await,
+    None => vec![],
+  };
+
+  let post_tags = req.content_md.as_deref().map(parse_tags);
+
   if let Some(content_md) = &req.content_md {
     post.content_md = content_md.to_owned();
-    post.content_html = markdown::to_html(content_md);
+    post.content_html = render_tags(
+      &render_mentions(&markdown::to_html(content_md), &mentions),
+      post_tags.as_deref().unwrap_or_default(),
+    );
   }
 
   if let Err(err) = posts.update_post_content(&post).await {
     return map_api_err(err);
   }
 
+  let mentioned_user_ids: Vec<Uuid> = mentions.iter().map(|m| m.user.user_id).collect();
+  record_mentions(
+    &post_mentions,
+    &notifications,
+    &pubsub,
+    &post.post_id,
+    &props.uid,
+    &post.visibility,
+    &mentioned_user_ids,
+  )
+  .await;
+
+  if let Some(post_tags) = &post_tags {
+    record_tags(&tags, &post.post_id, post_tags).await;
+  }
+
   let job_id = match jobs
     .create(NewJob {
       created_by_id: Some(props.uid.to_owned()),
@@ -530,27 +580,7 @@ pub async fn api_boost_post(
     Err(res) => return res,
   };
 
-  let user_id = props.uid;
-
-  let job_id = match jobs
-    .create(NewJob {
-      created_by_id: Some(user_id),
-      status: JobStatus::NotStarted,
-      record_id: Some(*post_id),
-      associated_record_id: None,
-    })
-    .await
-  {
-    Ok(id) => id,
-    Err(err) => return build_api_err(500, err.to_string(), None),
-  };
-
-  let job = QueueJob::builder()
-    .job_id(job_id)
-    .job_type(QueueJobType::CreateBoostEvents)
-    .build();
-
-  match queue.send_job(job).await {
+  match boost_post(&jobs, &queue, &post_id, &props.uid).await {
     Ok(_) => HttpResponse::Created().finish(),
     Err(err) => build_api_err(500, err.to_string(), None),
   }
@@ -568,27 +598,7 @@ pub async fn api_unboost_post(
     Err(res) => return res,
   };
 
-  let user_id = props.uid;
-
-  let job_id = match jobs
-    .create(NewJob {
-      created_by_id: Some(user_id),
-      status: JobStatus::NotStarted,
-      record_id: Some(*post_id),
-      associated_record_id: None,
-    })
-    .await
-  {
-    Ok(id) => id,
-    Err(err) => return build_api_err(500, err.to_string(), None),
-  };
-
-  let job = QueueJob::builder()
-    .job_id(job_id)
-    .job_type(QueueJobType::DeleteBoostEvents)
-    .build();
-
-  match queue.send_job(job).await {
+  match unboost_post(&jobs, &queue, &post_id, &props.uid).await {
     Ok(_) => HttpResponse::Created().finish(),
     Err(err) => build_api_err(500, err.to_string(), None),
   }
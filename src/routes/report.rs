@@ -0,0 +1,174 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+  db::{
+    comment_repository::CommentPool, job_repository::JobPool, orbit_moderator_repository::OrbitModeratorPool,
+    orbit_repository::OrbitPool, post_repository::PostPool, report_repository::ReportPool,
+    session_repository::SessionPool, user_repository::UserPool,
+  },
+  helpers::{auth::require_auth, core::map_api_err, math::div_up},
+  logic::report::{create_report, get_reports, get_reports_count, update_report_status, NewReportRequest},
+  model::{
+    report::ReportStatus,
+    response::{ListResponse, ObjectResponse},
+  },
+  net::jwt::JwtContext,
+  work_queue::queue::Queue,
+};
+
+#[derive(Deserialize)]
+pub struct ReportsQuery {
+  pub status: Option<ReportStatus>,
+  pub page: Option<i64>,
+  pub page_size: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateReportRequest {
+  pub status: ReportStatus,
+  pub resolution_note: Option<String>,
+}
+
+pub async fn api_create_report(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
+  orbits: web::Data<OrbitPool>,
+  reports: web::Data<ReportPool>,
+  jobs: web::Data<JobPool>,
+  queue: web::Data<Queue>,
+  req: web::Json<NewReportRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match create_report(
+    &users, &posts, &comments, &orbits, &reports, &jobs, &queue, &req, &props.uid,
+  )
+  .await
+  {
+    Ok(report) => HttpResponse::Created().json(ObjectResponse { data: report }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+async fn get_report_queue(
+  sessions: &SessionPool,
+  users: &UserPool,
+  orbit_moderators: &OrbitModeratorPool,
+  reports: &ReportPool,
+  moderator_orbit_id: &Option<Uuid>,
+  query: &ReportsQuery,
+  jwt: &web::ReqData<JwtContext>,
+) -> HttpResponse {
+  let props = match require_auth(jwt, sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  let page = query.page.unwrap_or(0);
+  let page_size = query.page_size.unwrap_or(20);
+
+  let reports_count = match get_reports_count(
+    users,
+    orbit_moderators,
+    reports,
+    moderator_orbit_id,
+    &query.status,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  match get_reports(
+    users,
+    orbit_moderators,
+    reports,
+    moderator_orbit_id,
+    &query.status,
+    page_size,
+    page * page_size,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(reports) => HttpResponse::Ok().json(ListResponse {
+      data: reports,
+      page,
+      total_items: reports_count,
+      total_pages: div_up(reports_count, page_size) + 1,
+    }),
+    Err(err) => map_api_err(err),
+  }
+}
+
+pub async fn api_get_admin_reports(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  reports: web::Data<ReportPool>,
+  query: web::Query<ReportsQuery>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  get_report_queue(&sessions, &users, &orbit_moderators, &reports, &None, &query, &jwt).await
+}
+
+pub async fn api_get_orbit_reports(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  reports: web::Data<ReportPool>,
+  orbit_id: web::Path<Uuid>,
+  query: web::Query<ReportsQuery>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  get_report_queue(
+    &sessions,
+    &users,
+    &orbit_moderators,
+    &reports,
+    &Some(*orbit_id),
+    &query,
+    &jwt,
+  )
+  .await
+}
+
+pub async fn api_update_report(
+  sessions: web::Data<SessionPool>,
+  users: web::Data<UserPool>,
+  orbit_moderators: web::Data<OrbitModeratorPool>,
+  reports: web::Data<ReportPool>,
+  report_id: web::Path<Uuid>,
+  req: web::Json<UpdateReportRequest>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let props = match require_auth(&jwt, &sessions).await {
+    Ok(props) => props,
+    Err(res) => return res,
+  };
+
+  match update_report_status(
+    &users,
+    &orbit_moderators,
+    &reports,
+    &report_id,
+    &req.status,
+    &req.resolution_note,
+    &props.uid,
+  )
+  .await
+  {
+    Ok(report) => HttpResponse::Ok().json(ObjectResponse { data: report }),
+    Err(err) => map_api_err(err),
+  }
+}
@@ -1,44 +1,70 @@
 use actix_web::{web, HttpResponse, Responder};
-use serde::{Deserialize, Serialize};
-use strum::Display;
+use serde::Deserialize;
 use substring::Substring;
 
 use crate::{
-  db::{job_repository::JobPool, orbit_repository::OrbitPool, user_repository::UserPool},
-  federation::activitypub::actor::{federate_orbit_group_from_webfinger, federate_user_actor_from_webfinger},
-  helpers::core::map_api_err,
+  db::{
+    comment_repository::CommentPool, domain_policy_repository::DomainPolicyPool, follow_repository::FollowPool,
+    job_repository::JobPool, notification_repository::NotificationPool,
+    orbit_membership_repository::OrbitMembershipPool, orbit_repository::OrbitPool,
+    post_attachment_repository::PostAttachmentPool, post_mention_repository::PostMentionPool,
+    post_repository::PostPool, search_repository::SearchPool, session_repository::SessionPool, tag_repository::TagPool,
+    user_repository::UserPool,
+  },
+  federation::{
+    activitypub::{
+      actor::{federate_orbit_group_from_webfinger, federate_user_actor_from_webfinger},
+      resolve::federate_resolve_uri,
+    },
+    domain_policy::sync_domain_policies,
+  },
+  helpers::{
+    auth::{query_auth, require_auth},
+    core::map_api_err,
+    math::div_up,
+  },
+  logic::search::{load_resolved_object, queue_fetch_external_orbit_posts, search, search_count},
   model::{
-    job::{JobStatus, NewJob},
-    orbit_pub::OrbitPub,
-    queue_job::{QueueJob, QueueJobType},
-    response::ListResponse,
+    orbit_pub::OrbitPub, response::ListResponse, search_result::SearchResult, search_result_type::SearchResultType,
     user_account_pub::UserAccountPub,
   },
+  net::jwt::JwtContext,
+  streaming::pubsub::PubSub,
   work_queue::queue::Queue,
 };
 
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
   pub term: String,
+  /// Limits a full-text search to one kind of result
+  #[serde(rename = "type")]
+  pub result_type: Option<SearchResultType>,
   pub page: Option<i64>,
   pub page_size: Option<i64>,
 }
 
-#[derive(Serialize, Display)]
-#[strum(serialize_all = "snake_case")]
-#[serde(rename_all = "snake_case")]
-pub enum SearchResult {
-  User(UserAccountPub),
-  Orbit(OrbitPub),
-}
-
 pub async fn api_search(
   query: web::Query<SearchQuery>,
+  sessions: web::Data<SessionPool>,
+  searches: web::Data<SearchPool>,
+  posts: web::Data<PostPool>,
+  comments: web::Data<CommentPool>,
   users: web::Data<UserPool>,
   orbits: web::Data<OrbitPool>,
+  follows: web::Data<FollowPool>,
+  orbit_membership: web::Data<OrbitMembershipPool>,
+  post_mentions: web::Data<PostMentionPool>,
+  tags: web::Data<TagPool>,
+  post_attachments: web::Data<PostAttachmentPool>,
+  notifications: web::Data<NotificationPool>,
+  pubsub: web::Data<PubSub>,
   jobs: web::Data<JobPool>,
   queue: web::Data<Queue>,
+  domain_policies: web::Data<DomainPolicyPool>,
+  jwt: web::ReqData<JwtContext>,
 ) -> impl Responder {
+  sync_domain_policies(&domain_policies).await;
+
   if query.term.starts_with('@') {
     let components: Vec<&str> = query.term.substring(1, query.term.len()).splitn(2, '@').collect();
     if components.is_empty() {
@@ -153,27 +179,7 @@ pub async fn api_search(
           {
             Ok(orbit) => match orbit {
               Some(orbit) => {
-                if let Ok(job_id) = jobs
-                  .create(NewJob {
-                    created_by_id: None,
-                    status: JobStatus::NotStarted,
-                    record_id: Some(orbit.orbit_id),
-                    associated_record_id: None,
-                  })
-                  .await
-                {
-                  let job = QueueJob::builder()
-                    .job_id(job_id)
-                    .job_type(QueueJobType::FetchExternalOrbitPosts)
-                    .build();
-
-                  match queue.send_job(job).await {
-                    Ok(_) => {}
-                    Err(err) => {
-                      log::warn!("Failed to queue job to fetch external orbit posts: {:?}", err);
-                    }
-                  };
-                }
+                queue_fetch_external_orbit_posts(&jobs, &queue, &orbit).await;
 
                 HttpResponse::Ok().json(ListResponse {
                   data: vec![SearchResult::Orbit(OrbitPub::from(orbit))],
@@ -194,7 +200,79 @@ pub async fn api_search(
         }
       }
     }
+  } else if query.term.starts_with("https://") {
+    // Fetching remote objects on someone's behalf is only done for signed in users
+    let props = match require_auth(&jwt, &sessions).await {
+      Ok(props) => props,
+      Err(res) => return res,
+    };
+
+    let resolved = match federate_resolve_uri(
+      query.term.trim(),
+      &users,
+      &posts,
+      &post_mentions,
+      &tags,
+      &jobs,
+      &post_attachments,
+      &orbits,
+      &notifications,
+      &pubsub,
+      &queue,
+    )
+    .await
+    {
+      Ok(resolved) => resolved,
+      Err(err) => return map_api_err(err),
+    };
+
+    let result =
+      match load_resolved_object(resolved, &posts, &follows, &orbit_membership, &jobs, &queue, &props.uid).await {
+        Ok(result) => result,
+        Err(err) => return map_api_err(err),
+      };
+
+    let total_items = i64::from(result.is_some());
+
+    HttpResponse::Ok().json(ListResponse {
+      data: result.into_iter().collect::<Vec<SearchResult>>(),
+      total_items,
+      total_pages: 1,
+      page: 0,
+    })
   } else {
-    HttpResponse::NotFound().finish()
+    let user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+    let page = query.page.unwrap_or(0);
+    let page_size = query.page_size.unwrap_or(20);
+    let results_count = match search_count(&searches, &query.term, &user_id, &query.result_type).await {
+      Ok(count) => count,
+      Err(err) => return map_api_err(err),
+    };
+
+    let results = match search(
+      &searches,
+      &posts,
+      &comments,
+      &users,
+      &orbits,
+      &query.term,
+      &user_id,
+      &query.result_type,
+      page_size,
+      page * page_size,
+    )
+    .await
+    {
+      Ok(results) => results,
+      Err(err) => return map_api_err(err),
+    };
+
+    HttpResponse::Ok().json(ListResponse {
+      data: results,
+      page,
+      total_items: results_count,
+      total_pages: div_up(results_count, page_size) + 1,
+    })
   }
 }
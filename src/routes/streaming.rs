@@ -0,0 +1,149 @@
+use std::{str::FromStr, time::Duration};
+
+use actix_web::{web, web::Bytes, HttpResponse, Responder};
+use futures_util::stream;
+use log::warn;
+use serde_json::json;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use uuid::Uuid;
+
+use crate::{
+  db::{notification_repository::NotificationPool, post_repository::PostPool, session_repository::SessionPool},
+  helpers::{
+    auth::{query_auth, require_auth},
+    core::map_api_err,
+  },
+  logic::{
+    streaming::{resolve_stream_message, StreamEvent, StreamSubscription},
+    LogicErr,
+  },
+  model::{stream_message::StreamMessage, stream_type::StreamType},
+  net::jwt::JwtContext,
+  streaming::hub::StreamHub,
+};
+
+/// Comment lines keep idle connections from being closed by proxies in between
+const KEEPALIVE_INTERVAL_SECS: u64 = 30;
+
+struct StreamState {
+  receiver: Receiver<StreamMessage>,
+  posts: PostPool,
+  notifications: NotificationPool,
+  subscription: StreamSubscription,
+}
+
+fn sse_frame(event: &StreamEvent) -> Result<Bytes, serde_json::Error> {
+  let (name, data) = match event {
+    StreamEvent::Update(post) => ("update", serde_json::to_string(post)?),
+    StreamEvent::Delete(post_id) => ("delete", json!({ "post_id": post_id }).to_string()),
+    StreamEvent::Notification(notification) => ("notification", serde_json::to_string(notification)?),
+  };
+
+  Ok(Bytes::from(format!("event: {}\ndata: {}\n\n", name, data)))
+}
+
+fn stream_response(
+  hub: &StreamHub,
+  posts: &PostPool,
+  notifications: &NotificationPool,
+  subscription: StreamSubscription,
+) -> HttpResponse {
+  let state = StreamState {
+    receiver: hub.subscribe(),
+    posts: posts.clone(),
+    notifications: notifications.clone(),
+    subscription,
+  };
+
+  let events = stream::unfold(state, |mut state| async move {
+    loop {
+      let message =
+        match tokio::time::timeout(Duration::from_secs(KEEPALIVE_INTERVAL_SECS), state.receiver.recv()).await {
+          Ok(Ok(message)) => message,
+          Ok(Err(RecvError::Lagged(skipped))) => {
+            warn!("Streaming client fell behind and missed {} messages", skipped);
+            continue;
+          }
+          Ok(Err(RecvError::Closed)) => return None,
+          Err(_) => return Some((Ok::<Bytes, actix_web::Error>(Bytes::from_static(b":\n\n")), state)),
+        };
+
+      let event = match resolve_stream_message(&state.posts, &state.notifications, &state.subscription, &message).await
+      {
+        Ok(Some(event)) => event,
+        Ok(None) => continue,
+        Err(err) => {
+          warn!("Failed to resolve stream message: {}", err);
+          continue;
+        }
+      };
+
+      match sse_frame(&event) {
+        Ok(frame) => return Some((Ok(frame), state)),
+        Err(err) => warn!("Failed to serialize stream event: {}", err),
+      }
+    }
+  });
+
+  HttpResponse::Ok()
+    .content_type("text/event-stream")
+    .insert_header(("Cache-Control", "no-cache"))
+    .streaming(events)
+}
+
+pub async fn api_stream(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  notifications: web::Data<NotificationPool>,
+  hub: web::Data<StreamHub>,
+  stream: web::Path<String>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let stream = match StreamType::from_str(&stream) {
+    Ok(StreamType::Orbit) | Err(_) => {
+      return map_api_err(LogicErr::InvalidOperation(format!("Unknown stream: {}", stream)))
+    }
+    Ok(stream) => stream,
+  };
+
+  let user_id = match stream {
+    StreamType::Federated => query_auth(&jwt, &sessions).await.map(|props| props.uid),
+    _ => match require_auth(&jwt, &sessions).await {
+      Ok(props) => Some(props.uid),
+      Err(res) => return res,
+    },
+  };
+
+  stream_response(
+    &hub,
+    &posts,
+    &notifications,
+    StreamSubscription {
+      stream,
+      user_id,
+      orbit_id: None,
+    },
+  )
+}
+
+pub async fn api_stream_orbit(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  notifications: web::Data<NotificationPool>,
+  hub: web::Data<StreamHub>,
+  orbit_id: web::Path<Uuid>,
+  jwt: web::ReqData<JwtContext>,
+) -> impl Responder {
+  let user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  stream_response(
+    &hub,
+    &posts,
+    &notifications,
+    StreamSubscription {
+      stream: StreamType::Orbit,
+      user_id,
+      orbit_id: Some(*orbit_id),
+    },
+  )
+}
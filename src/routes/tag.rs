@@ -0,0 +1,55 @@
+use actix_web::{web, HttpRequest, Responder};
+
+use crate::{
+  db::{post_repository::PostPool, session_repository::SessionPool},
+  helpers::{
+    auth::query_auth,
+    core::map_api_err,
+    pagination::{build_page_response, numbered_page, optional_count},
+  },
+  logic::tag::{get_tag_posts, get_tag_posts_count},
+  model::page_cursor::PageCursor,
+  net::jwt::JwtContext,
+};
+
+use super::post::PostsQuery;
+
+pub async fn api_get_tag_feed(
+  sessions: web::Data<SessionPool>,
+  posts: web::Data<PostPool>,
+  tag: web::Path<String>,
+  query: web::Query<PostsQuery>,
+  cursor: web::Query<PageCursor>,
+  jwt: web::ReqData<JwtContext>,
+  req: HttpRequest,
+) -> impl Responder {
+  let user_id = query_auth(&jwt, &sessions).await.map(|props| props.uid);
+
+  let page = numbered_page(&query.page, &cursor);
+  let page_size = query.page_size.unwrap_or(20);
+  let posts_count = match optional_count(
+    page.is_some() || query.count,
+    get_tag_posts_count(&tag, &user_id, &posts),
+  )
+  .await
+  {
+    Ok(count) => count,
+    Err(err) => return map_api_err(err),
+  };
+
+  let posts = match get_tag_posts(
+    &tag,
+    &user_id,
+    &cursor,
+    page_size,
+    page.unwrap_or(0) * page_size,
+    &posts,
+  )
+  .await
+  {
+    Ok(posts) => posts,
+    Err(err) => return map_api_err(err),
+  };
+
+  build_page_response(&req, page, page_size, posts, |p| p.post_id, false, posts_count)
+}
@@ -1,5 +1,5 @@
 use actix_easy_multipart::{tempfile::Tempfile, MultipartForm};
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use argon2::{password_hash::SaltString, Argon2, PasswordHasher};
 use rsa::rand_core::OsRng;
 use serde::{Deserialize, Serialize};
@@ -8,15 +8,18 @@ use uuid::Uuid;
 
 use crate::{
   cdn::cdn_store::Cdn,
-  db::{session_repository::SessionPool, user_repository::UserPool, user_stats_repository::UserStatsPool},
+  db::{
+    account_restriction_repository::AccountRestrictionPool, session_repository::SessionPool, user_repository::UserPool,
+    user_stats_repository::UserStatsPool,
+  },
   helpers::{
     auth::{query_auth, require_auth},
     core::{build_api_err, build_api_not_found},
-    math::div_up,
+    pagination::{build_page_response, numbered_page},
   },
   logic::user::{get_user_by_handle, get_user_by_id},
   model::{
-    response::{ListResponse, ObjectResponse},
+    account_restriction::AccountRestrictionType, page_cursor::PageCursor, response::ObjectResponse,
     user_account_pub::UserAccountPub,
   },
   net::jwt::JwtContext,
@@ -27,6 +30,9 @@ use crate::{
 pub struct FollowersQuery {
   pub page: Option<i64>,
   pub page_size: Option<i64>,
+  /// Whether to count every follower when walking the list by cursor. Numbered pages are always counted.
+  #[serde(default)]
+  pub count: bool,
 }
 
 #[derive(Deserialize, Serialize, EnumString, Display, Debug, PartialEq, Eq, Clone)]
@@ -53,6 +59,7 @@ pub struct ProfileUpdateRequest {
   pub url_3_title: Option<ProfileUpdateProp>,
   pub url_4_title: Option<ProfileUpdateProp>,
   pub url_5_title: Option<ProfileUpdateProp>,
+  pub manually_approves_followers: Option<bool>,
 }
 
 #[derive(MultipartForm)]
@@ -200,6 +207,10 @@ pub async fn api_update_profile(
     };
   }
 
+  if let Some(val) = req.manually_approves_followers {
+    user.manually_approves_followers = val;
+  }
+
   match users.update_from(&user).await {
     Ok(_) => HttpResponse::Ok().finish(),
     Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
@@ -258,19 +269,32 @@ pub async fn api_update_profile_assets(
 
   user.avatar_url = Some(avatar_uri);
 
+  if let Some(val) = req.manually_approves_followers {
+    user.manually_approves_followers = val;
+  }
+
   match users.update_from(&user).await {
     Ok(_) => HttpResponse::Ok().finish(),
     Err(err) => build_api_err(500, err.to_string(), Some(err.to_string())),
   }
 }
 
-pub async fn api_get_user_profile(users: web::Data<UserPool>, handle: web::Path<String>) -> impl Responder {
-  match get_user_by_handle(&handle, &users).await {
-    Ok(user) => match user {
-      Some(user) => HttpResponse::Ok().json(UserAccountPub::from(user)),
-      None => HttpResponse::NotFound().finish(),
-    },
-    Err(_) => HttpResponse::NotFound().finish(),
+pub async fn api_get_user_profile(
+  users: web::Data<UserPool>,
+  account_restrictions: web::Data<AccountRestrictionPool>,
+  handle: web::Path<String>,
+) -> impl Responder {
+  let user = match get_user_by_handle(&handle, &users).await {
+    Ok(Some(user)) => user,
+    _ => return HttpResponse::NotFound().finish(),
+  };
+
+  match account_restrictions
+    .user_has_restriction(&user.user_id, &AccountRestrictionType::Suspend)
+    .await
+  {
+    Ok(false) => HttpResponse::Ok().json(UserAccountPub::from(user)),
+    _ => HttpResponse::NotFound().finish(),
   }
 }
 
@@ -295,23 +319,34 @@ pub async fn api_get_user_followers(
   users: web::Data<UserPool>,
   handle: web::Path<String>,
   query: web::Query<FollowersQuery>,
+  cursor: web::Query<PageCursor>,
+  req: HttpRequest,
 ) -> impl Responder {
   let user_id = match users.fetch_id_by_handle(&handle).await {
     Some(id) => id,
     None => return build_api_not_found(handle.to_string()),
   };
 
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let users_count = users.fetch_followers_count(&user_id).await;
+  let users_count = match page.is_some() || query.count {
+    true => Some(users.fetch_followers_count(&user_id).await),
+    false => None,
+  };
 
-  match users.fetch_followers(&user_id, page_size, page * page_size).await {
-    Ok(users) => HttpResponse::Ok().json(ListResponse {
-      data: users.into_iter().map(UserAccountPub::from).collect(),
+  match users
+    .fetch_followers(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
+    .await
+  {
+    Ok(users) => build_page_response(
+      &req,
       page,
-      total_items: users_count,
-      total_pages: div_up(users_count, page_size) + 1,
-    }),
+      page_size,
+      users.into_iter().map(UserAccountPub::from).collect(),
+      |u: &UserAccountPub| u.user_id,
+      false,
+      users_count,
+    ),
     Err(err) => build_api_err(500, err.to_string(), None),
   }
 }
@@ -320,23 +355,34 @@ pub async fn api_get_user_following(
   users: web::Data<UserPool>,
   handle: web::Path<String>,
   query: web::Query<FollowersQuery>,
+  cursor: web::Query<PageCursor>,
+  req: HttpRequest,
 ) -> impl Responder {
   let user_id = match users.fetch_id_by_handle(&handle).await {
     Some(id) => id,
     None => return build_api_not_found(handle.to_string()),
   };
 
-  let page = query.page.unwrap_or(0);
+  let page = numbered_page(&query.page, &cursor);
   let page_size = query.page_size.unwrap_or(20);
-  let users_count = users.fetch_following_count(&user_id).await;
+  let users_count = match page.is_some() || query.count {
+    true => Some(users.fetch_following_count(&user_id).await),
+    false => None,
+  };
 
-  match users.fetch_following(&user_id, page_size, page * page_size).await {
-    Ok(users) => HttpResponse::Ok().json(ListResponse {
-      data: users.into_iter().map(UserAccountPub::from).collect(),
+  match users
+    .fetch_following(&user_id, &cursor, page_size, page.unwrap_or(0) * page_size)
+    .await
+  {
+    Ok(users) => build_page_response(
+      &req,
       page,
-      total_items: users_count,
-      total_pages: div_up(users_count, page_size) + 1,
-    }),
+      page_size,
+      users.into_iter().map(UserAccountPub::from).collect(),
+      |u: &UserAccountPub| u.user_id,
+      false,
+      users_count,
+    ),
     Err(err) => build_api_err(500, err.to_string(), None),
   }
 }
@@ -2,3 +2,4 @@ pub mod scheduler;
 mod task_trigger_clean_jobs_event;
 mod task_trigger_refresh_external_orbits_event;
 mod task_trigger_refresh_external_profiles_event;
+mod task_trigger_retry_deliveries_event;
@@ -6,6 +6,7 @@ use super::{
   task_trigger_clean_jobs_event::schedule_task_trigger_clean_jobs_event,
   task_trigger_refresh_external_orbits_event::schedule_task_trigger_refresh_external_orbits_event,
   task_trigger_refresh_external_profiles_event::schedule_task_trigger_refresh_external_profiles_event,
+  task_trigger_retry_deliveries_event::schedule_task_trigger_retry_deliveries_event,
 };
 
 pub struct JobScheduler {
@@ -19,6 +20,7 @@ impl JobScheduler {
     schedule_task_trigger_clean_jobs_event(&mut scheduler);
     schedule_task_trigger_refresh_external_orbits_event(&mut scheduler);
     schedule_task_trigger_refresh_external_profiles_event(&mut scheduler);
+    schedule_task_trigger_retry_deliveries_event(&mut scheduler);
 
     let handle = tokio::spawn(async move {
       loop {
@@ -33,6 +33,12 @@ pub enum AppQueueBackend {
   Sqs,
 }
 
+#[derive(Clone, Debug, Deserialize, EnumString, Display, PartialEq, Eq)]
+pub enum AppPubSubBackend {
+  Noop,
+  Postgres,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Log {
   pub level: AppLogLevel,
@@ -83,6 +89,27 @@ pub struct Queue {
   pub work_deadletter_queue: String,
   pub credentials: Option<CloudCredentials>,
   pub schedule_jobs: bool,
+  #[serde(default = "default_delivery_retry_horizon_hours")]
+  pub delivery_retry_horizon_hours: i64,
+}
+
+fn default_delivery_retry_horizon_hours() -> i64 {
+  72
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PubSub {
+  pub pubsub_backend: AppPubSubBackend,
+  pub channel: String,
+}
+
+impl Default for PubSub {
+  fn default() -> Self {
+    PubSub {
+      pubsub_backend: AppPubSubBackend::Postgres,
+      channel: "orbit_stream".to_string(),
+    }
+  }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -90,6 +117,8 @@ pub struct Application {
   pub imagemagick_exe_path: String,
   pub secure: bool,
   pub verify_external_https_certificates: bool,
+  #[serde(default)]
+  pub federation_allowlist_mode: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -100,6 +129,8 @@ pub struct Settings {
   pub env: AppEnv,
   pub cdn: Cdn,
   pub queue: Queue,
+  #[serde(default)]
+  pub pubsub: PubSub,
   pub app: Application,
 }
 
@@ -149,11 +180,14 @@ impl Settings {
         work_deadletter_queue: "work_dq".to_string(),
         credentials: None,
         schedule_jobs: true,
+        delivery_retry_horizon_hours: default_delivery_retry_horizon_hours(),
       },
+      pubsub: PubSub::default(),
       app: Application {
         imagemagick_exe_path: "convert".to_string(),
         secure: false,
         verify_external_https_certificates: false,
+        federation_allowlist_mode: false,
       },
     }
   }
@@ -0,0 +1,38 @@
+use log::error;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::model::stream_message::StreamMessage;
+
+use super::pubsub::PubSub;
+
+/// Messages buffered per connected client before a slow client starts missing them
+const STREAM_CAPACITY: usize = 256;
+const RESUBSCRIBE_DELAY_SECS: u64 = 5;
+
+/// Relays messages from the pub/sub backend to every streaming client connected to this process
+#[derive(Clone)]
+pub struct StreamHub {
+  sender: broadcast::Sender<StreamMessage>,
+}
+
+impl StreamHub {
+  pub fn new() -> StreamHub {
+    let (sender, _) = broadcast::channel(STREAM_CAPACITY);
+    StreamHub { sender }
+  }
+
+  pub fn subscribe(&self) -> broadcast::Receiver<StreamMessage> {
+    self.sender.subscribe()
+  }
+
+  pub async fn run(&self, pubsub: &PubSub) {
+    loop {
+      if let Err(err) = pubsub.subscribe(&self.sender).await {
+        error!("Lost stream subscription: {}", err);
+      }
+
+      tokio::time::sleep(Duration::from_secs(RESUBSCRIBE_DELAY_SECS)).await;
+    }
+  }
+}
@@ -0,0 +1,4 @@
+pub mod hub;
+pub mod pubsub;
+mod pubsub_backend_noop;
+mod pubsub_backend_postgres;
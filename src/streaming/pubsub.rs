@@ -0,0 +1,57 @@
+use crate::{
+  logic::LogicErr,
+  model::stream_message::StreamMessage,
+  settings::{AppPubSubBackend, SETTINGS},
+};
+
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::result::Result;
+use tokio::sync::broadcast;
+
+use super::{pubsub_backend_noop::PubSubBackendNoop, pubsub_backend_postgres::PubSubBackendPostgres};
+
+#[cfg(test)]
+use mockall::automock;
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait PubSubBackend {
+  async fn publish(&self, message: &StreamMessage) -> Result<(), LogicErr>;
+  /// Forwards every message published by any process to `sender` until the subscription is lost
+  async fn subscribe(&self, sender: &broadcast::Sender<StreamMessage>) -> Result<(), LogicErr>;
+}
+
+pub struct PubSub {
+  imp: Box<dyn PubSubBackend + Send + Sync + 'static>,
+}
+
+impl PubSub {
+  pub fn new(db: &Pool) -> PubSub {
+    match SETTINGS.pubsub.pubsub_backend {
+      AppPubSubBackend::Postgres => PubSub {
+        imp: Box::new(PubSubBackendPostgres { db: db.clone() }),
+      },
+      AppPubSubBackend::Noop => PubSub {
+        imp: Box::new(PubSubBackendNoop {}),
+      },
+    }
+  }
+
+  #[cfg(test)]
+  pub fn new_inner(inner: Box<dyn PubSubBackend + Sync + Send>) -> PubSub {
+    PubSub { imp: inner }
+  }
+
+  /// Streaming is best effort; a record that was written successfully shouldn't fail because nobody could be told
+  /// about it in real time.
+  pub async fn publish(&self, message: StreamMessage) {
+    if let Err(err) = self.imp.publish(&message).await {
+      log::warn!("Failed to publish stream message: {}", err);
+    }
+  }
+
+  pub async fn subscribe(&self, sender: &broadcast::Sender<StreamMessage>) -> Result<(), LogicErr> {
+    self.imp.subscribe(sender).await
+  }
+}
@@ -0,0 +1,20 @@
+use super::pubsub::PubSubBackend;
+use crate::{logic::LogicErr, model::stream_message::StreamMessage};
+use async_trait::async_trait;
+use log::warn;
+use tokio::sync::broadcast;
+
+pub struct PubSubBackendNoop {}
+
+#[async_trait]
+impl PubSubBackend for PubSubBackendNoop {
+  async fn publish(&self, _message: &StreamMessage) -> Result<(), LogicErr> {
+    Ok(())
+  }
+
+  async fn subscribe(&self, _sender: &broadcast::Sender<StreamMessage>) -> Result<(), LogicErr> {
+    warn!("PubSub backend is 'noop'. Streaming clients won't receive any updates.");
+    std::future::pending::<()>().await;
+    Ok(())
+  }
+}
@@ -0,0 +1,85 @@
+use super::pubsub::PubSubBackend;
+use crate::{
+  helpers::api::{map_db_err, map_ext_err},
+  logic::LogicErr,
+  model::stream_message::StreamMessage,
+  settings::SETTINGS,
+};
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use futures_util::{stream, StreamExt};
+use log::{error, warn};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio_postgres::{AsyncMessage, NoTls};
+
+pub struct PubSubBackendPostgres {
+  pub db: Pool,
+}
+
+#[async_trait]
+impl PubSubBackend for PubSubBackendPostgres {
+  async fn publish(&self, message: &StreamMessage) -> Result<(), LogicErr> {
+    let payload = serde_json::to_string(message).map_err(map_ext_err)?;
+
+    let db = self.db.get().await.map_err(map_db_err)?;
+    db.execute("SELECT pg_notify($1, $2)", &[&SETTINGS.pubsub.channel, &payload])
+      .await
+      .map_err(map_db_err)?;
+
+    Ok(())
+  }
+
+  async fn subscribe(&self, sender: &broadcast::Sender<StreamMessage>) -> Result<(), LogicErr> {
+    // LISTEN only lasts as long as the session that issued it, so it needs a connection of its own rather than one
+    // that'll be handed back to the pool
+    let (client, mut connection) = tokio_postgres::Config::new()
+      .host(&SETTINGS.database.host)
+      .port(SETTINGS.database.port)
+      .dbname(&SETTINGS.database.database)
+      .user(&SETTINGS.database.username)
+      .password(&SETTINGS.database.password)
+      .connect_timeout(Duration::from_secs(SETTINGS.database.connection_timeout.into()))
+      .connect(NoTls)
+      .await
+      .map_err(map_db_err)?;
+
+    let (payload_sender, mut payload_receiver) = mpsc::unbounded_channel::<String>();
+
+    tokio::spawn(async move {
+      let mut messages = stream::poll_fn(move |cx| connection.poll_message(cx));
+
+      while let Some(message) = messages.next().await {
+        match message {
+          Ok(AsyncMessage::Notification(notification)) => {
+            if payload_sender.send(notification.payload().to_string()).is_err() {
+              break;
+            }
+          }
+          Ok(_) => {}
+          Err(err) => {
+            error!("PubSub connection failed: {}", err);
+            break;
+          }
+        }
+      }
+    });
+
+    client
+      .batch_execute(&format!("LISTEN \"{}\"", SETTINGS.pubsub.channel))
+      .await
+      .map_err(map_db_err)?;
+
+    while let Some(payload) = payload_receiver.recv().await {
+      match serde_json::from_str::<StreamMessage>(&payload) {
+        // Sending only fails when no clients are currently connected
+        Ok(message) => {
+          let _ = sender.send(message);
+        }
+        Err(err) => warn!("Failed to deserialize stream message: {}", err),
+      }
+    }
+
+    Err(LogicErr::InternalError("PubSub connection closed".to_string()))
+  }
+}
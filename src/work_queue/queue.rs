@@ -4,6 +4,7 @@ use crate::{
   logic::LogicErr,
   model::queue_job::QueueJob,
   settings::{AppQueueBackend, SETTINGS},
+  streaming::pubsub::PubSub,
 };
 
 use async_trait::async_trait;
@@ -21,7 +22,13 @@ use mockall::automock;
 #[async_trait]
 pub trait QueueBackend {
   async fn send_job(&self, job: QueueJob) -> Result<(), LogicErr>;
-  async fn receive_jobs(&self, cdn: &Cdn, queue: &Queue, repositories: &Repositories) -> Result<(), LogicErr>;
+  async fn receive_jobs(
+    &self,
+    cdn: &Cdn,
+    pubsub: &PubSub,
+    queue: &Queue,
+    repositories: &Repositories,
+  ) -> Result<(), LogicErr>;
 }
 
 pub struct Queue {
@@ -52,7 +59,13 @@ impl Queue {
     self.imp.send_job(job).await
   }
 
-  pub async fn receive_jobs(&self, cdn: &Cdn, queue: &Queue, repositories: &Repositories) -> Result<(), LogicErr> {
-    self.imp.receive_jobs(cdn, queue, repositories).await
+  pub async fn receive_jobs(
+    &self,
+    cdn: &Cdn,
+    pubsub: &PubSub,
+    queue: &Queue,
+    repositories: &Repositories,
+  ) -> Result<(), LogicErr> {
+    self.imp.receive_jobs(cdn, pubsub, queue, repositories).await
   }
 }
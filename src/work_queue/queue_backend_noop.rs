@@ -1,5 +1,8 @@
 use super::queue::{Queue, QueueBackend};
-use crate::{cdn::cdn_store::Cdn, db::repositories::Repositories, logic::LogicErr, model::queue_job::QueueJob};
+use crate::{
+  cdn::cdn_store::Cdn, db::repositories::Repositories, logic::LogicErr, model::queue_job::QueueJob,
+  streaming::pubsub::PubSub,
+};
 use async_trait::async_trait;
 use log::warn;
 
@@ -17,7 +20,13 @@ impl QueueBackend for QueueBackendNoop {
     Ok(())
   }
 
-  async fn receive_jobs(&self, _cdn: &Cdn, _queue: &Queue, _repositories: &Repositories) -> Result<(), LogicErr> {
+  async fn receive_jobs(
+    &self,
+    _cdn: &Cdn,
+    _pubsub: &PubSub,
+    _queue: &Queue,
+    _repositories: &Repositories,
+  ) -> Result<(), LogicErr> {
     warn!("Queue backend is 'noop'. No queue jobs will be processed.");
     Ok(())
   }
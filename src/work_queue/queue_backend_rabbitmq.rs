@@ -10,6 +10,7 @@ use crate::{
     queue_job::QueueJob,
   },
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
 };
 use async_trait::async_trait;
 use futures_util::StreamExt;
@@ -92,7 +93,13 @@ impl QueueBackend for QueueBackendRabbitMQ {
     Ok(())
   }
 
-  async fn receive_jobs(&self, cdn: &Cdn, queue: &Queue, repositories: &Repositories) -> Result<(), LogicErr> {
+  async fn receive_jobs(
+    &self,
+    cdn: &Cdn,
+    pubsub: &PubSub,
+    queue: &Queue,
+    repositories: &Repositories,
+  ) -> Result<(), LogicErr> {
     let tag = Uuid::new_v4().to_string();
     let mut consumer = match RABBITMQ_WORK_CHANNEL
       .get()
@@ -166,7 +173,7 @@ impl QueueBackend for QueueBackendRabbitMQ {
         }
       }
 
-      let result = delegate_job(&queue_job, repositories, cdn, queue).await;
+      let result = delegate_job(&queue_job, repositories, cdn, pubsub, queue).await;
 
       match result {
         Ok(()) => {
@@ -8,6 +8,7 @@ use crate::{
   logic::LogicErr,
   model::{job::JobStatus, queue_job::QueueJob},
   settings::SETTINGS,
+  streaming::pubsub::PubSub,
 };
 use async_trait::async_trait;
 use log::error;
@@ -37,7 +38,13 @@ impl QueueBackend for QueueBackendSQS {
     Ok(())
   }
 
-  async fn receive_jobs(&self, cdn: &Cdn, queue: &Queue, repositories: &Repositories) -> Result<(), LogicErr> {
+  async fn receive_jobs(
+    &self,
+    cdn: &Cdn,
+    pubsub: &PubSub,
+    queue: &Queue,
+    repositories: &Repositories,
+  ) -> Result<(), LogicErr> {
     let rcv_message_output = SQS_CLIENT
       .get()
       .unwrap()
@@ -100,7 +107,7 @@ impl QueueBackend for QueueBackendSQS {
         }
       }
 
-      let result = delegate_job(&queue_job, repositories, cdn, queue).await;
+      let result = delegate_job(&queue_job, repositories, cdn, pubsub, queue).await;
 
       match result {
         Ok(()) => {
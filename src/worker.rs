@@ -11,16 +11,18 @@ mod federation;
 mod helpers;
 mod job;
 mod logic;
+mod mastodon;
 mod model;
 mod net;
 mod rabbitmq;
 mod routes;
 mod scheduled_tasks;
 mod settings;
+mod streaming;
 mod work_queue;
 mod worker_internal;
 
-use crate::worker_internal::services::{DB, QUEUE};
+use crate::worker_internal::services::{DB, PUBSUB, QUEUE};
 use aws::clients::AWSClient;
 use cdn::cdn_store::Cdn;
 use env_logger::WriteStyle;
@@ -55,7 +57,7 @@ async fn main() -> std::io::Result<()> {
   };
 
   loop {
-    match QUEUE.receive_jobs(&cdn, &QUEUE, &DB).await {
+    match QUEUE.receive_jobs(&cdn, &PUBSUB, &QUEUE, &DB).await {
       Ok(_) => {}
       Err(err) => error!("{}", err.to_string()),
     }